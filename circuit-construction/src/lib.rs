@@ -4,6 +4,8 @@
 pub mod constants;
 /// This contains the prover functions, ranging from curves definitions to prover index and proof generation
 pub mod prover;
+/// This is a gadget verifying Mina's Schnorr signatures, as signed by the `mina-signer` crate
+pub mod schnorr;
 /// This is the actual writer with all of the available functions to set up a circuit and its corresponding constraint system
 pub mod writer;
 
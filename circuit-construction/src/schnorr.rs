@@ -0,0 +1,232 @@
+use ark_ec::SWModelParameters;
+use ark_ff::{BigInteger, PrimeField, SquareRootField};
+use kimchi::circuits::polynomials::generic::GENERIC_COEFFS;
+use oracle::{
+    constants::{PlonkSpongeConstantsKimchi, SpongeConstants},
+    poseidon::{ArithmeticSponge, Sponge},
+};
+
+use crate::{
+    constants::Constants,
+    writer::{Cs, ShiftedScalar, Var},
+};
+
+/// The number of bits of the half of the `y`-coordinate of the point of a signature,
+/// decomposed to check that the coordinate is even
+const HALF_Y_BITS: usize = 253;
+
+/// The input of the random oracle of a signed message, as variables of the circuit.
+///
+/// These are the field elements of [`mina_hasher::ROInput::to_fields`] for the message:
+/// the fields appended to the input come first, and its bits come last, packed into field elements.
+/// The challenge of a signature is derived from the fields of the message, followed by
+/// the public key and the `x`-coordinate of the signature, followed by the packed bits of the message.
+pub struct Message<F> {
+    /// The field elements appended to the input
+    pub fields: Vec<Var<F>>,
+    /// The field elements packing the bits appended to the input
+    pub packed_bits: Vec<Var<F>>,
+}
+
+/// A signature, as variables of the circuit
+pub struct Signature<F> {
+    /// The `x`-coordinate of the point `R` of the signature
+    pub rx: Var<F>,
+    /// The scalar `s` of the signature, as given by [`Cs::scalar`] with a length of 255 bits
+    pub s: ShiftedScalar<F>,
+}
+
+/// Checks that `signature` is a valid Schnorr signature of `message` for `public_key`,
+/// as verified by the `mina-signer` crate with the kimchi Poseidon hasher,
+/// where `domain_string` is the domain string of the message (see [`mina_hasher::Hashable`]).
+///
+/// With `e` the challenge derived from the message, the public key `P` and `rx`,
+/// and `G` the generator of the curve `C`, the signature is valid if
+/// the point `R = s * G - e * P` is not the point at infinity, its `y`-coordinate is even,
+/// and its `x`-coordinate is `rx`.
+/// The gadget computes the challenge with `Poseidon` gates, takes the point `R` with
+/// the coordinates `rx` and `ry`, checks that it is on the curve and that `ry` is even,
+/// and checks with `VarBaseMul` and `CompleteAdd` gates that
+/// `2 * R + (2e + K) * P = 2 * (s * G) + K * P`, where `K = 2^255 + 1`,
+/// as the scalar multiplications compute `(2n + K) * P` for a scalar `n`.
+/// As the order of the curve is odd, this holds only if `R = s * G - e * P`.
+///
+/// The public key must be a point of the curve, for example a public input checked outside of the circuit.
+///
+/// The bits of the challenge are constrained to be its canonical decomposition by requiring the challenge
+/// to be smaller than `2^254`, and `ry` is checked to be even by decomposing `ry / 2` into 253 bits.
+/// A valid signature whose challenge or `ry` is larger than `2^254` is thus rejected,
+/// which happens with a negligible probability when the modulus of the field is close to `2^254`,
+/// as for Pallas.
+///
+/// # Panics
+///
+/// Will panic if the coefficient `a` of the curve is not zero, or if the domain string is longer than 20 bytes.
+pub fn verify_signature<F, C, Sys>(
+    sys: &mut Sys,
+    constants: &Constants<F>,
+    domain_string: Option<String>,
+    public_key: (Var<F>, Var<F>),
+    signature: Signature<F>,
+    message: &Message<F>,
+) where
+    F: PrimeField + SquareRootField,
+    C: SWModelParameters<BaseField = F>,
+    Sys: Cs<F>,
+{
+    assert!(
+        C::COEFF_A.is_zero(),
+        "the scalar multiplication only supports curves with a = 0"
+    );
+    let zero = sys.constant(F::zero());
+    let Signature { rx, s } = signature;
+
+    // The challenge is derived from the message, the public key and rx
+    let input: Vec<_> = message
+        .fields
+        .iter()
+        .copied()
+        .chain([public_key.0, public_key.1, rx])
+        .chain(message.packed_bits.iter().copied())
+        .collect();
+    let e = challenge(sys, constants, domain_string, &input);
+
+    // R is a point of the curve, with an even y-coordinate
+    let ry = sys.var(|| {
+        let y2 = rx.val().square() * rx.val() + C::COEFF_B;
+        // the x-coordinate of an invalid signature may not be on the curve,
+        // in which case any y-coordinate fails the checks below
+        let ry = y2.sqrt().unwrap_or_else(F::one);
+        if ry.into_repr().is_even() {
+            ry
+        } else {
+            -ry
+        }
+    });
+    assert_on_curve(sys, (rx, ry), C::COEFF_B);
+    assert_even(sys, ry);
+
+    // 2 * R + (2e + K) * P = 2 * (s * G) + K * P
+    let (gx, gy) = C::AFFINE_GENERATOR_COEFFS;
+    let generator = (sys.constant(gx), sys.constant(gy));
+    let s_g = sys.scalar_mul(zero, generator, s);
+    let e_p = sys.var_base_mul(zero, public_key, e, 1);
+    let k_p = sys.var_base_mul(zero, public_key, zero, 1);
+
+    let double_r = sys.double(zero, (rx, ry));
+    let lhs = sys.add_group(zero, double_r, e_p);
+    let double_s_g = sys.double(zero, s_g);
+    let rhs = sys.add_group(zero, double_s_g, k_p);
+
+    sys.assert_eq(lhs.0, rhs.0);
+    sys.assert_eq(lhs.1, rhs.1);
+}
+
+/// Derives the challenge of the Poseidon sponge of the kimchi hasher
+/// initialized with `domain_string` and absorbing `input`
+fn challenge<F, Sys>(
+    sys: &mut Sys,
+    constants: &Constants<F>,
+    domain_string: Option<String>,
+    input: &[Var<F>],
+) -> Var<F>
+where
+    F: PrimeField,
+    Sys: Cs<F>,
+{
+    // The initial state of the sponge only depends on the domain string
+    let mut sponge = ArithmeticSponge::<F, PlonkSpongeConstantsKimchi>::new(constants.poseidon);
+    if let Some(domain_string) = domain_string {
+        sponge.absorb(&[mina_hasher::domain_prefix_to_field(domain_string)]);
+        sponge.squeeze();
+    }
+    let mut state: Vec<_> = sponge.state.iter().map(|x| sys.constant(*x)).collect();
+
+    // Each chunk of the input is added to the state, which is then permuted
+    for chunk in input.chunks(PlonkSpongeConstantsKimchi::SPONGE_RATE) {
+        for (s, x) in state.iter_mut().zip(chunk) {
+            *s = add(sys, *s, *x);
+        }
+        state = sys.poseidon(constants, state);
+    }
+
+    state[0]
+}
+
+/// Returns the variable `x + y`, constrained with a `Generic` gate
+fn add<F: PrimeField, Sys: Cs<F>>(sys: &mut Sys, x: Var<F>, y: Var<F>) -> Var<F> {
+    let z = sys.var(|| x.val() + y.val());
+
+    let mut coeffs = [F::zero(); GENERIC_COEFFS];
+    coeffs[0] = F::one();
+    coeffs[1] = F::one();
+    coeffs[2] = -F::one();
+    sys.generic(coeffs, [Some(x), Some(y), Some(z)]);
+
+    z
+}
+
+/// Returns the variable `x * y`, constrained with a `Generic` gate
+fn mul<F: PrimeField, Sys: Cs<F>>(sys: &mut Sys, x: Var<F>, y: Var<F>) -> Var<F> {
+    let z = sys.var(|| x.val() * y.val());
+
+    let mut coeffs = [F::zero(); GENERIC_COEFFS];
+    coeffs[2] = -F::one();
+    coeffs[3] = F::one();
+    sys.generic(coeffs, [Some(x), Some(y), Some(z)]);
+
+    z
+}
+
+/// Checks that `(x, y)` is on the curve `y^2 = x^3 + b`, with three `Generic` gates
+fn assert_on_curve<F: PrimeField, Sys: Cs<F>>(sys: &mut Sys, (x, y): (Var<F>, Var<F>), b: F) {
+    let x2 = mul(sys, x, x);
+    let x3 = mul(sys, x2, x);
+
+    // y * y - x3 - b = 0
+    let mut coeffs = [F::zero(); GENERIC_COEFFS];
+    coeffs[2] = -F::one();
+    coeffs[3] = F::one();
+    coeffs[4] = -b;
+    sys.generic(coeffs, [Some(y), Some(y), Some(x3)]);
+}
+
+/// Checks that `y` is even, by decomposing `y / 2` into [HALF_Y_BITS] bits with `Generic` gates,
+/// so that `y = 2 * (y / 2)` holds over the integers
+fn assert_even<F: PrimeField, Sys: Cs<F>>(sys: &mut Sys, y: Var<F>) {
+    let half = sys.var(|| y.val() / F::from(2u64));
+
+    let mut acc: Option<Var<F>> = None;
+    for i in (0..HALF_Y_BITS).rev() {
+        let bit = sys.var(|| F::from(u64::from(half.val().into_repr().get_bit(i))));
+
+        // bit * bit - bit = 0
+        let mut coeffs = [F::zero(); GENERIC_COEFFS];
+        coeffs[0] = -F::one();
+        coeffs[3] = F::one();
+        sys.generic(coeffs, [Some(bit), Some(bit), None]);
+
+        // the bits are accumulated with the most significant bit first
+        acc = Some(match acc {
+            None => bit,
+            Some(acc) => {
+                let next = sys.var(|| acc.val().double() + bit.val());
+
+                // 2 * acc + bit - next = 0
+                let mut coeffs = [F::zero(); GENERIC_COEFFS];
+                coeffs[0] = F::from(2u64);
+                coeffs[1] = F::one();
+                coeffs[2] = -F::one();
+                sys.generic(coeffs, [Some(acc), Some(bit), Some(next)]);
+
+                next
+            }
+        });
+    }
+
+    // 2 * acc - y = 0
+    let mut coeffs = [F::zero(); GENERIC_COEFFS];
+    coeffs[0] = F::from(2u64);
+    coeffs[1] = -F::one();
+    sys.generic(coeffs, [acc, Some(y), None]);
+}
@@ -1 +1,2 @@
 mod example_proof;
+mod schnorr;
@@ -0,0 +1,176 @@
+use crate::{
+    prologue::*,
+    schnorr::{verify_signature, Message, Signature},
+    writer::WitnessGenerator,
+};
+use kimchi::{circuits::wires::COLUMNS, prover_index::ProverIndex};
+use mina_curves::pasta::{Fq, PallasParameters, Vesta};
+use mina_hasher::{Hashable, ROInput};
+use mina_signer::{Keypair, NetworkId, PubKey, Signer};
+
+type SpongeQ = DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>;
+type SpongeR = DefaultFrSponge<Fp, PlonkSpongeConstantsKimchi>;
+
+#[derive(Clone)]
+struct Transfer {
+    receiver: Fp,
+    amount: u64,
+}
+
+impl Hashable for Transfer {
+    type D = NetworkId;
+
+    fn to_roinput(&self) -> ROInput {
+        ROInput::new()
+            .append_field(self.receiver)
+            .append_u64(self.amount)
+    }
+
+    fn domain_string(network_id: NetworkId) -> Option<String> {
+        match network_id {
+            NetworkId::MAINNET => "MinaSignatureMainnet",
+            NetworkId::TESTNET => "CodaSignature",
+        }
+        .to_string()
+        .into()
+    }
+}
+
+// Verify a signature of a transfer, whose public key and random oracle input are public
+fn circuit<Sys: Cs<Fp>>(
+    constants: &Constants<Fp>,
+    signature: Option<&mina_signer::Signature>,
+    sys: &mut Sys,
+    public_input: Vec<Var<Fp>>,
+) {
+    let signature = Signature {
+        rx: sys.var(|| signature.unwrap().rx),
+        s: sys.scalar(Fq::size_in_bits(), || signature.unwrap().s),
+    };
+    let message = Message {
+        fields: vec![public_input[2]],
+        packed_bits: vec![public_input[3]],
+    };
+
+    verify_signature::<_, PallasParameters, _>(
+        sys,
+        constants,
+        Transfer::domain_string(NetworkId::TESTNET),
+        (public_input[0], public_input[1]),
+        signature,
+        &message,
+    );
+}
+
+const PUBLIC_INPUT_LENGTH: usize = 4;
+
+fn public_input(public_key: &PubKey, transfer: &Transfer) -> Vec<Fp> {
+    let fields = transfer.to_roinput().to_fields();
+    vec![
+        public_key.point().x,
+        public_key.point().y,
+        fields[0],
+        fields[1],
+    ]
+}
+
+fn prover_index(constants: &Constants<Fp>) -> ProverIndex<Vesta> {
+    let srs = {
+        let mut srs = SRS::<Vesta>::create(1 << 10);
+        srs.add_lagrange_basis(Radix2EvaluationDomain::new(srs.g.len()).unwrap());
+        Arc::new(srs)
+    };
+
+    generate_prover_index::<FpInner, _>(srs, PUBLIC_INPUT_LENGTH, |sys, p| {
+        circuit(constants, None, sys, p)
+    })
+}
+
+fn witness(
+    constants: &Constants<Fp>,
+    signature: &mina_signer::Signature,
+    public_input: &[Fp],
+) -> [Vec<Fp>; COLUMNS] {
+    let mut gen = WitnessGenerator::new(public_input);
+    let public_vars = public_input
+        .iter()
+        .map(|x| Var {
+            index: 0,
+            value: Some(*x),
+        })
+        .collect();
+    circuit(constants, Some(signature), &mut gen, public_vars);
+    gen.columns()
+}
+
+#[test]
+fn test_schnorr_signature() {
+    let constants = fp_constants();
+    let prover_index = prover_index(&constants);
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+
+    let mut rng = rand::thread_rng();
+    let keypair = Keypair::rand(&mut rng);
+    let transfer = Transfer {
+        receiver: Fp::rand(&mut rng),
+        amount: 1729000000000,
+    };
+
+    let mut signer = mina_signer::create_kimchi::<Transfer>(NetworkId::TESTNET);
+    let signature = signer.sign(&keypair, &transfer);
+    assert!(signer.verify(&signature, &keypair.public, &transfer));
+
+    let public_input = public_input(&keypair.public, &transfer);
+    let proof = prove::<Vesta, _, SpongeQ, SpongeR>(
+        &prover_index,
+        &group_map,
+        None,
+        public_input,
+        |sys, p| circuit(&constants, Some(&signature), sys, p),
+    );
+
+    let verifier_index = prover_index.verifier_index();
+    verify::<_, SpongeQ, SpongeR>(&group_map, &verifier_index, &proof).unwrap();
+}
+
+#[test]
+fn test_schnorr_signature_matches_signer() {
+    let constants = fp_constants();
+    let prover_index = prover_index(&constants);
+
+    let mut rng = rand::thread_rng();
+    let keypair = Keypair::rand(&mut rng);
+    let other_keypair = Keypair::rand(&mut rng);
+    let transfer = Transfer {
+        receiver: Fp::rand(&mut rng),
+        amount: 42,
+    };
+    let other_transfer = Transfer {
+        amount: 43,
+        ..transfer.clone()
+    };
+
+    let mut signer = mina_signer::create_kimchi::<Transfer>(NetworkId::TESTNET);
+    let signature = signer.sign(&keypair, &transfer);
+    let other_signature = mina_signer::Signature::new(signature.rx, signature.s + Fq::from(1u64));
+    assert!(signer.verify(&signature, &keypair.public, &transfer));
+
+    let cases = [
+        (&signature, &keypair.public, &transfer),
+        (&signature, &keypair.public, &other_transfer),
+        (&signature, &other_keypair.public, &transfer),
+        (&other_signature, &keypair.public, &transfer),
+    ];
+    for (signature, public_key, transfer) in cases {
+        let public_input = public_input(public_key, transfer);
+        let witness = witness(&constants, signature, &public_input);
+
+        assert_eq!(
+            signer.verify(signature, public_key, transfer),
+            prover_index
+                .cs
+                .verify::<Vesta>(&witness, &public_input)
+                .is_ok()
+        );
+    }
+}
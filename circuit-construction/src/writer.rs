@@ -468,18 +468,37 @@ pub trait Cs<F: PrimeField> {
         zero: Var<F>,
         (xt, yt): (Var<F>, Var<F>),
         scalar: ShiftedScalar<F>,
+    ) -> (Var<F>, Var<F>) {
+        self.var_base_mul(zero, (xt, yt), scalar.0, 0)
+    }
+
+    /// Performs a scalar multiplication of a point `(xt, yt)` by the 255 bits of the value of `n`,
+    /// and returns the point `(2n + 2^255 + 1) * (xt, yt)`.
+    /// This function creates 51 pairs of rows, each made of a `VarBaseMul` gate and a `Zero` gate,
+    /// whose accumulated points and scalars are chained by copy constraints.
+    ///
+    /// The `leading_zeros` most significant bits of `n`, at most 5, are constrained to be zero.
+    /// With at least one of them, `n` is smaller than `2^254`, so the bits are the only decomposition
+    /// of `n` when the modulus of the field is larger than `2^254`.
+    fn var_base_mul(
+        &mut self,
+        zero: Var<F>,
+        (xt, yt): (Var<F>, Var<F>),
+        n: Var<F>,
+        leading_zeros: usize,
     ) -> (Var<F>, Var<F>) {
         let num_bits = 255;
-        let num_row_pairs = num_bits / 5;
+        let bits_per_row = 5;
+        let num_row_pairs = num_bits / bits_per_row;
+        assert!(leading_zeros <= bits_per_row);
         let mut witness: [Vec<F>; COLUMNS] = array::from_fn(|_| vec![]);
 
         let acc0 = self.add_group(zero, (xt, yt), (xt, yt));
 
         let _ = self.var(|| {
             witness = array::from_fn(|_| vec![F::zero(); 2 * num_row_pairs]);
-            // Creates a vector of bits from the value inside the scalar, with the most significant bit upfront
-            let bits_msb: Vec<bool> = scalar
-                .0
+            // Creates a vector of bits from the value of the scalar, with the most significant bit upfront
+            let bits_msb: Vec<bool> = n
                 .val()
                 .into_repr()
                 .to_bits_le()
@@ -500,22 +519,29 @@ pub trait Cs<F: PrimeField> {
         });
 
         // For each of the pairs, it generates a VarBaseMul and a Zero gate.
-        let mut res = None;
+        let mut acc = acc0;
+        let mut n_acc = zero;
         for i in 0..num_row_pairs {
             let mut row1: [_; COLUMNS] = array::from_fn(|j| self.var(|| witness[j][2 * i]));
-            let row2: [_; COLUMNS] = array::from_fn(|j| self.var(|| witness[j][2 * i + 1]));
+            let mut row2: [_; COLUMNS] = array::from_fn(|j| self.var(|| witness[j][2 * i + 1]));
 
+            // The base point, and the point and scalar accumulated by the previous rows
             row1[0] = xt;
             row1[1] = yt;
-            if i == 0 {
-                row1[2] = acc0.0;
-                row1[3] = acc0.1;
-                row1[4] = zero;
-            }
+            row1[2] = acc.0;
+            row1[3] = acc.1;
+            row1[4] = n_acc;
             if i == num_row_pairs - 1 {
-                row1[5] = scalar.0;
-                res = Some((row2[0], row2[1]));
+                row1[5] = n;
             }
+            // The most significant bits are the first bits of the first row pair
+            if i == 0 {
+                for bit in row2.iter_mut().skip(2).take(leading_zeros) {
+                    *bit = zero;
+                }
+            }
+            acc = (row2[0], row2[1]);
+            n_acc = row1[5];
 
             self.gate(GateSpec {
                 row: row1.into_iter().map(Some).collect(),
@@ -530,7 +556,7 @@ pub trait Cs<F: PrimeField> {
             });
         }
 
-        res.unwrap()
+        acc
     }
 
     /// Creates an endoscalar multiplication gadget with `length_in_bits/4 + 1` gates.
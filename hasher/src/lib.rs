@@ -156,7 +156,14 @@ pub trait Hasher<H: Hashable> {
 }
 
 /// Transform domain prefix string to field element
-fn domain_prefix_to_field<F: PrimeField>(prefix: String) -> F {
+///
+/// This is the field element absorbed by the hashers to initialize their state
+/// (see [`Hasher::init`]).
+///
+/// # Panics
+///
+/// Will panic if the domain prefix is longer than 20 bytes.
+pub fn domain_prefix_to_field<F: PrimeField>(prefix: String) -> F {
     const MAX_DOMAIN_STRING_LEN: usize = 20;
     assert!(prefix.len() <= MAX_DOMAIN_STRING_LEN);
     let prefix = &prefix[..std::cmp::min(prefix.len(), MAX_DOMAIN_STRING_LEN)];
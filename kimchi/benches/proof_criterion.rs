@@ -1,5 +1,8 @@
-use criterion::{black_box, criterion_group, criterion_main, Criterion, SamplingMode};
-use kimchi::bench::BenchmarkCtx;
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion, SamplingMode};
+use kimchi::{
+    bench::BenchmarkCtx,
+    parallel::{Parallelism, Partitioning},
+};
 
 pub fn bench_proof_creation(c: &mut Criterion) {
     let mut group = c.benchmark_group("Proof creation");
@@ -26,5 +29,62 @@ pub fn bench_proof_creation(c: &mut Criterion) {
     );
 }
 
-criterion_group!(benches, bench_proof_creation);
+pub fn bench_proof_creation_partitioning(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Proof creation partitioning");
+    group.sample_size(10).sampling_mode(SamplingMode::Flat); // for slow benchmarks
+
+    // a short and a tall circuit, proven with the work split by rows, by columns,
+    // and with the partitioning picked from the size of the circuit
+    for num_gates in [1 << 10, 1 << 14] {
+        let mut ctx = BenchmarkCtx::new(num_gates);
+        for (name, parallelism) in [
+            ("rows", Some(Parallelism::uniform(Partitioning::Rows))),
+            ("columns", Some(Parallelism::uniform(Partitioning::Columns))),
+            ("default", None),
+        ] {
+            ctx.set_parallelism(parallelism);
+            group.bench_function(
+                format!("proof creation by {name} (SRS size 2^{})", ctx.srs_size()),
+                |b| b.iter(|| black_box(ctx.create_proof())),
+            );
+        }
+    }
+}
+
+pub fn bench_batch_verification(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Batch verification");
+    group.sample_size(10).sampling_mode(SamplingMode::Flat); // for slow benchmarks
+
+    let ctx = BenchmarkCtx::new(1 << 10);
+    let proof = ctx.create_proof();
+    let batch = vec![proof; 1000];
+
+    group.bench_function(
+        format!(
+            "verification of 1000 proofs (SRS size 2^{})",
+            ctx.srs_size()
+        ),
+        |b| {
+            b.iter_batched(
+                || batch.clone(),
+                |batch| ctx.batch_verification(black_box(batch)),
+                BatchSize::LargeInput,
+            )
+        },
+    );
+    group.bench_function(
+        format!(
+            "verification of 1000 proofs with a prepared index (SRS size 2^{})",
+            ctx.srs_size()
+        ),
+        |b| b.iter(|| ctx.batch_verification_prepared(black_box(&batch)).unwrap()),
+    );
+}
+
+criterion_group!(
+    benches,
+    bench_proof_creation,
+    bench_proof_creation_partitioning,
+    bench_batch_verification
+);
 criterion_main!(benches);
@@ -237,7 +237,7 @@ where
 
 // ------------------------------------------
 
-#[cfg(test)]
+#[cfg(all(test, feature = "prover"))]
 mod tests {
     use std::{fs, path::Path};
 
@@ -332,6 +332,10 @@ mod tests {
                 .as_ref()
                 .map(|lcs| &lcs.configuration),
             index.cs.foreign_field_add_selector_poly.is_some(),
+            index.cs.sha256_selector_polys.is_some(),
+            &index.cs.custom_gates,
+            &index.cs.boolean_columns,
+            index.cs.zk_rows,
         );
         // make sure this is present in the specification
         let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
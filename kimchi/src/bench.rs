@@ -6,9 +6,12 @@ use crate::{
         polynomials::generic::GenericGateSpec,
         wires::{Wire, COLUMNS},
     },
+    parallel::Parallelism,
     proof::ProverProof,
     prover_index::{testing::new_index_for_test, ProverIndex},
-    verifier::batch_verify,
+    verifier::{
+        batch_verify, batch_verify_prepared, verify_batch_parallel, PreparedVerifierIndex, Result,
+    },
     verifier_index::VerifierIndex,
 };
 use commitment_dlog::commitment::CommitmentCurve;
@@ -70,6 +73,12 @@ impl BenchmarkCtx {
         }
     }
 
+    /// Sets the partitioning of the work of the prover between the threads,
+    /// or picks it from the size of the circuit if `None`.
+    pub fn set_parallelism(&mut self, parallelism: Option<Parallelism>) {
+        self.index.parallelism = parallelism;
+    }
+
     /// Produces a proof
     pub fn create_proof(&self) -> ProverProof<Vesta> {
         // create witness
@@ -88,6 +97,22 @@ impl BenchmarkCtx {
             .collect();
         batch_verify::<Vesta, BaseSponge, ScalarSponge>(&self.group_map, &batch).unwrap();
     }
+
+    /// Verifies a batch of proofs with [batch_verify_prepared],
+    /// preparing the verifier index once for the whole batch.
+    pub fn batch_verification_prepared(&self, batch: &[ProverProof<Vesta>]) -> Result<()> {
+        let prepared = PreparedVerifierIndex::<_, BaseSponge>::new(&self.verifier_index);
+        batch_verify_prepared::<Vesta, BaseSponge, ScalarSponge>(&self.group_map, &prepared, batch)
+    }
+
+    /// Verifies a batch of proofs with [verify_batch_parallel].
+    pub fn batch_verification_parallel(&self, batch: &[ProverProof<Vesta>]) -> Result<()> {
+        let batch: Vec<_> = batch
+            .iter()
+            .map(|proof| (&self.verifier_index, proof))
+            .collect();
+        verify_batch_parallel::<Vesta, BaseSponge, ScalarSponge>(&self.group_map, &batch)
+    }
 }
 
 #[cfg(test)]
@@ -113,4 +138,47 @@ mod tests {
         ctx.batch_verification(vec![proof.clone()]);
         println!("proof verified in {}", start.elapsed().as_millis());
     }
+
+    #[test]
+    fn test_batch_verification_prepared() {
+        let ctx = BenchmarkCtx::new(1 << 4);
+        let mut batch: Vec<_> = (0..3).map(|_| ctx.create_proof()).collect();
+
+        let start = Instant::now();
+        ctx.batch_verification_prepared(&batch).unwrap();
+        println!("proofs verified in {}", start.elapsed().as_millis());
+
+        // an invalid proof makes the whole batch invalid
+        batch[1].ft_eval1 += Fp::from(1u64);
+        assert!(ctx.batch_verification_prepared(&batch).is_err());
+        assert!(ctx.batch_verification_prepared(&batch[..1]).is_ok());
+        assert!(ctx.batch_verification_prepared(&[]).is_ok());
+    }
+
+    #[test]
+    fn test_verify_batch_parallel() {
+        let ctx = BenchmarkCtx::new(1 << 4);
+        let mut batch: Vec<_> = (0..5).map(|_| ctx.create_proof()).collect();
+
+        let start = Instant::now();
+        ctx.batch_verification_parallel(&batch).unwrap();
+        println!("proofs verified in {}", start.elapsed().as_millis());
+
+        // an invalid proof makes the whole batch invalid
+        batch[3].ft_eval1 += Fp::from(1u64);
+        assert!(ctx.batch_verification_parallel(&batch).is_err());
+        assert!(ctx.batch_verification_parallel(&batch[..3]).is_ok());
+
+        // with several threads, the terms of the chunks are merged into a single check
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(3)
+            .build()
+            .unwrap();
+        pool.install(|| {
+            assert!(ctx.batch_verification_parallel(&batch).is_err());
+            assert!(ctx.batch_verification_parallel(&batch[..3]).is_ok());
+            batch[3].ft_eval1 -= Fp::from(1u64);
+            assert!(ctx.batch_verification_parallel(&batch).is_ok());
+        });
+    }
 }
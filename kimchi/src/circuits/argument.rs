@@ -15,7 +15,7 @@ use super::{
     gate::{CurrOrNext, GateType},
     polynomial::COLUMNS,
 };
-use CurrOrNext::{Curr, Next};
+use CurrOrNext::{Curr, Next, Prev};
 
 /// A constraint type represents a polynomial that will be part of the final equation f (the circuit equation)
 #[derive(PartialEq, Eq, Clone, Copy, Hash, Debug, Serialize, Deserialize)]
@@ -28,6 +28,8 @@ pub enum ArgumentType {
     Permutation,
     /// The lookup argument
     Lookup,
+    /// The boolean columns argument
+    BooleanColumns,
 }
 
 /// The argument environment is used to specify how the argument's constraints are
@@ -81,6 +83,11 @@ impl<F: Field, T: ExprOps<F>> ArgumentEnv<F, T> {
         T::witness(Next, col, self.data.as_ref())
     }
 
+    /// Witness cell on previous row
+    pub fn witness_prev(&self, col: usize) -> T {
+        T::witness(Prev, col, self.data.as_ref())
+    }
+
     /// Coefficient value at index idx
     pub fn coeff(&self, idx: usize) -> T {
         T::coeff(idx, self.data.as_ref())
@@ -126,6 +133,8 @@ pub struct ArgumentWitness<T> {
     pub curr: [T; COLUMNS],
     /// Witness for next row
     pub next: [T; COLUMNS],
+    /// Witness for previous row
+    pub prev: [T; COLUMNS],
 }
 
 impl<T> std::ops::Index<(CurrOrNext, usize)> for ArgumentWitness<T> {
@@ -135,6 +144,7 @@ impl<T> std::ops::Index<(CurrOrNext, usize)> for ArgumentWitness<T> {
         match idx.0 {
             Curr => &self.curr[idx.1],
             Next => &self.next[idx.1],
+            Prev => &self.prev[idx.1],
         }
     }
 }
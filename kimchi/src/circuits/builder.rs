@@ -0,0 +1,607 @@
+//! This module implements a high-level API to write circuits, as [CircuitBuilder].
+//!
+//! A circuit is described with variables ([Var]) rather than with cells of the witness:
+//! each operation creates its output variable, and lays out the gate constraining it.
+//! The same description then gives:
+//!
+//! - the gates of the circuit, in which all the cells of a variable
+//!   (and of the variables asserted equal to it) are wired together by the permutation,
+//! - the witness of the circuit, from the values of its inputs,
+//!   as each variable also records how to compute its value from the previous ones.
+//!
+//! The public inputs and outputs are laid out in the first rows, as expected by kimchi.
+//! Gadgets are composed as functions taking the builder and variables, and returning variables.
+//! Any gate can be laid out with [CircuitBuilder::gate], its cells being given as variables,
+//! and any gadget of built-in gates with [CircuitBuilder::gadget] (see [WitnessGenerator]).
+//!
+//! Values can also be looked up, with [CircuitBuilder::lookup], in the lookup tables added with
+//! [CircuitBuilder::lookup_table]. The table is selected by a variable rather than fixed by the circuit,
+//! as the table ID of a lookup gate is a cell of its witness, checked by the lookup argument:
+//! for example, the instruction of a virtual machine can select the table giving its result.
+
+use crate::{
+    circuits::{
+        constraints::{Builder, ConstraintSystem},
+        gate::{CircuitGate, GateType},
+        lookup::{lookups::LookupPattern, tables::LookupTable},
+        polynomials::generic::{GENERIC_COEFFS, GENERIC_REGISTERS},
+        wires::{Wire, COLUMNS, PERMUTS},
+        witness_generator::WitnessGenerator,
+    },
+    error::CircuitBuilderError,
+};
+use ark_ff::{PrimeField, SquareRootField};
+use std::{array, collections::HashMap};
+
+/// A variable of a circuit, created by a [CircuitBuilder]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Var(usize);
+
+/// The values of the variables computed so far, when generating the witness
+pub struct Values<'a, F>(&'a [F]);
+
+impl<'a, F: Copy> Values<'a, F> {
+    /// The value of the variable `var`
+    pub fn get(&self, var: Var) -> F {
+        self.0[var.0]
+    }
+}
+
+/// Computes the value of a variable from the values of the variables created before it
+type Computation<F> = Box<dyn Fn(&Values<F>) -> F>;
+
+/// How the value of a variable is obtained when generating the witness
+enum Value<F> {
+    /// An input of the circuit, given by the prover
+    Input,
+    /// A constant of the circuit
+    Constant(F),
+    /// A function of the values of the previous variables
+    Computed(Computation<F>),
+    /// The cell `(row, col)` of the witness of the `gadget`-th gadget
+    Gadget {
+        gadget: usize,
+        row: usize,
+        col: usize,
+    },
+    /// The value at the index `index` in the lookup table whose ID is the value of `table`
+    Lookup { table: Var, index: Var },
+}
+
+/// Generates the witness of a gadget from the values of the variables created before it
+type GadgetWitness<F> = Box<dyn Fn(&Values<F>) -> [Vec<F>; COLUMNS]>;
+
+/// A row of the circuit, whose cells are given as variables
+struct Row<F> {
+    typ: GateType,
+    coeffs: Vec<F>,
+    cells: [Option<Var>; COLUMNS],
+}
+
+/// A builder of circuits, from a description with variables (see the [module](self) documentation)
+pub struct CircuitBuilder<F> {
+    /// How to compute the value of each variable
+    values: Vec<Value<F>>,
+    /// The names of the named variables
+    names: HashMap<String, Var>,
+    /// For each variable, a variable it was asserted equal to (a union-find forest)
+    equal_to: Vec<usize>,
+    /// The public inputs, in order
+    public_inputs: Vec<Var>,
+    /// The public outputs, in order
+    public_outputs: Vec<Var>,
+    /// The rows of the circuit, after the public rows
+    rows: Vec<Row<F>>,
+    /// The row of a generic gate whose second half is free, if any
+    half_generic: Option<usize>,
+    /// The variables of the constants, which are only constrained once
+    constants: HashMap<F, Var>,
+    /// The witness generation of the gadgets laid out with [CircuitBuilder::gadget]
+    gadgets: Vec<GadgetWitness<F>>,
+    /// The lookup tables of the circuit
+    lookup_tables: Vec<LookupTable<F>>,
+    /// The row of a lookup gate with free lookups, and its number of lookups used, if any
+    lookup_row: Option<(usize, usize)>,
+}
+
+impl<F: PrimeField + SquareRootField> Default for CircuitBuilder<F> {
+    fn default() -> Self {
+        CircuitBuilder {
+            values: vec![],
+            names: HashMap::new(),
+            equal_to: vec![],
+            public_inputs: vec![],
+            public_outputs: vec![],
+            rows: vec![],
+            half_generic: None,
+            constants: HashMap::new(),
+            gadgets: vec![],
+            lookup_tables: vec![],
+            lookup_row: None,
+        }
+    }
+}
+
+impl<F: PrimeField + SquareRootField> CircuitBuilder<F> {
+    /// Creates an empty circuit
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn new_var(&mut self, value: Value<F>) -> Var {
+        let var = Var(self.values.len());
+        self.values.push(value);
+        self.equal_to.push(var.0);
+        var
+    }
+
+    fn new_named_var(&mut self, name: &str, value: Value<F>) -> Result<Var, CircuitBuilderError> {
+        if self.names.contains_key(name) {
+            return Err(CircuitBuilderError::DuplicateName(name.to_string()));
+        }
+        let var = self.new_var(value);
+        self.names.insert(name.to_string(), var);
+        Ok(var)
+    }
+
+    /// Gives the name `name` to the variable `var`, for error reporting and lookup with [CircuitBuilder::var].
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the name is already used.
+    pub fn name(&mut self, var: Var, name: &str) -> Result<(), CircuitBuilderError> {
+        if self.names.contains_key(name) {
+            return Err(CircuitBuilderError::DuplicateName(name.to_string()));
+        }
+        self.names.insert(name.to_string(), var);
+        Ok(())
+    }
+
+    /// The variable named `name`, if any
+    pub fn var(&self, name: &str) -> Option<Var> {
+        self.names.get(name).copied()
+    }
+
+    /// Creates the public input `name`, whose value is given when generating the witness.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the name is already used.
+    pub fn public_input(&mut self, name: &str) -> Result<Var, CircuitBuilderError> {
+        let var = self.new_named_var(name, Value::Input)?;
+        self.public_inputs.push(var);
+        Ok(var)
+    }
+
+    /// Creates the private input `name`, whose value is given when generating the witness.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the name is already used.
+    pub fn private_input(&mut self, name: &str) -> Result<Var, CircuitBuilderError> {
+        self.new_named_var(name, Value::Input)
+    }
+
+    /// Makes `var` a public output of the circuit, after the previous ones.
+    pub fn public_output(&mut self, var: Var) {
+        self.public_outputs.push(var);
+    }
+
+    /// Creates an unconstrained variable, whose value is computed by `value`
+    /// from the values of the previous variables, for example as a hint for a gadget.
+    /// The variable must then be constrained by some gates.
+    pub fn witness(&mut self, value: impl Fn(&Values<F>) -> F + 'static) -> Var {
+        self.new_var(Value::Computed(Box::new(value)))
+    }
+
+    /// The variable of the constant `value`
+    pub fn constant(&mut self, value: F) -> Var {
+        if let Some(var) = self.constants.get(&value) {
+            return *var;
+        }
+        let var = self.new_var(Value::Constant(value));
+        let mut coeffs = [F::zero(); GENERIC_COEFFS];
+        coeffs[0] = F::one();
+        coeffs[4] = -value;
+        self.generic(coeffs, [Some(var), None, None]);
+        self.constants.insert(value, var);
+        var
+    }
+
+    /// Lays out a generic gate with the coefficients `coeffs`, on the variables `vars`,
+    /// constraining `c_0 l + c_1 r + c_2 o + c_3 l r + c_4 = 0`.
+    /// Two generic gates are laid out per row.
+    pub fn generic(&mut self, coeffs: [F; GENERIC_COEFFS], vars: [Option<Var>; GENERIC_REGISTERS]) {
+        match self.half_generic.take() {
+            Some(row) => {
+                let row = &mut self.rows[row];
+                row.coeffs[GENERIC_COEFFS..].copy_from_slice(&coeffs);
+                row.cells[GENERIC_REGISTERS..2 * GENERIC_REGISTERS].copy_from_slice(&vars);
+            }
+            None => {
+                let mut cells = [None; COLUMNS];
+                cells[..GENERIC_REGISTERS].copy_from_slice(&vars);
+                let mut row_coeffs = coeffs.to_vec();
+                row_coeffs.resize(2 * GENERIC_COEFFS, F::zero());
+                self.half_generic = Some(self.rows.len());
+                self.rows.push(Row {
+                    typ: GateType::Generic,
+                    coeffs: row_coeffs,
+                    cells,
+                });
+            }
+        }
+    }
+
+    /// Lays out a gate of type `typ` with coefficients `coeffs`,
+    /// whose cells are given by `cells` (the cells without variable are set to zero).
+    /// Gates spanning several rows are laid out with consecutive calls.
+    pub fn gate(&mut self, typ: GateType, coeffs: Vec<F>, cells: [Option<Var>; COLUMNS]) {
+        self.rows.push(Row { typ, coeffs, cells });
+    }
+
+    /// Lays out the gadget `gadget`, and returns the variables of its output cells
+    /// (see [WitnessGenerator::output_cells]).
+    /// The variables `inputs` are wired to its input cells (see [WitnessGenerator::input_cells]),
+    /// and its witness is generated from the input computed by `input`
+    /// from the values of the previous variables.
+    /// Each cell of the gadget is a variable, and the cells wired together by the gadget
+    /// are asserted equal, so that generating the witness checks that the input given by `input`
+    /// is consistent with the values of `inputs`.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the number of `inputs` is not the number of input cells of the gadget.
+    pub fn gadget<G: WitnessGenerator<F> + 'static>(
+        &mut self,
+        gadget: G,
+        inputs: &[Var],
+        input: impl Fn(&Values<F>) -> G::Input + 'static,
+    ) -> Vec<Var> {
+        let input_cells = gadget.input_cells();
+        assert_eq!(
+            inputs.len(),
+            input_cells.len(),
+            "the gadget has {} inputs",
+            input_cells.len()
+        );
+        let output_cells = gadget.output_cells();
+        let gates = gadget.gates(0);
+
+        let index = self.gadgets.len();
+        self.gadgets
+            .push(Box::new(move |values| gadget.witness(&input(values))));
+
+        let cells: Vec<[Var; COLUMNS]> = (0..gates.len())
+            .map(|row| {
+                array::from_fn(|col| {
+                    self.new_var(Value::Gadget {
+                        gadget: index,
+                        row,
+                        col,
+                    })
+                })
+            })
+            .collect();
+
+        for (row, gate) in gates.into_iter().enumerate() {
+            for (col, wire) in gate.wires.iter().enumerate() {
+                self.assert_eq(cells[row][col], cells[wire.row][wire.col]);
+            }
+            self.gate(gate.typ, gate.coeffs, cells[row].map(Some));
+        }
+        for (var, (row, col)) in inputs.iter().zip(input_cells) {
+            self.assert_eq(*var, cells[row][col]);
+        }
+
+        output_cells
+            .into_iter()
+            .map(|(row, col)| cells[row][col])
+            .collect()
+    }
+
+    /// Adds the lookup table `table` to the circuit, whose first column holds the indexes
+    /// and second column the values looked up with [CircuitBuilder::lookup].
+    pub fn lookup_table(&mut self, table: LookupTable<F>) {
+        self.lookup_tables.push(table);
+    }
+
+    /// The lookup tables of the circuit, added with [CircuitBuilder::lookup_table]
+    pub fn lookup_tables(&self) -> &[LookupTable<F>] {
+        &self.lookup_tables
+    }
+
+    /// The value at the index `index` in the lookup table whose ID is the value of `table`,
+    /// checked with a lookup gate. The lookups into the same `table` are laid out
+    /// three per row, the free lookups of a row repeating its first lookup.
+    pub fn lookup(&mut self, table: Var, index: Var) -> Var {
+        let lookups_per_row = LookupPattern::LookupGate.max_lookups_per_row();
+        let value = self.new_var(Value::Lookup { table, index });
+        match self.lookup_row.take() {
+            Some((row, used)) if self.rows[row].cells[0] == Some(table) => {
+                let cells = &mut self.rows[row].cells;
+                cells[2 * used + 1] = Some(index);
+                cells[2 * used + 2] = Some(value);
+                if used + 1 < lookups_per_row {
+                    self.lookup_row = Some((row, used + 1));
+                }
+            }
+            _ => {
+                let mut cells = [None; COLUMNS];
+                cells[0] = Some(table);
+                for i in 0..lookups_per_row {
+                    cells[2 * i + 1] = Some(index);
+                    cells[2 * i + 2] = Some(value);
+                }
+                self.lookup_row = Some((self.rows.len(), 1));
+                self.gate(GateType::Lookup, vec![], cells);
+            }
+        }
+        value
+    }
+
+    /// The value at the index `index` in the lookup table of ID `table`, if any
+    fn table_value(&self, table: F, index: F) -> Option<F> {
+        let table = self
+            .lookup_tables
+            .iter()
+            .find(|lookup_table| lookup_table.id.to_field::<F>() == table)?;
+        let row = table.data.first()?.iter().position(|i| *i == index)?;
+        table.data.get(1).map(|values| values[row])
+    }
+
+    /// Computes `c_l l + c_r r` with a generic gate
+    fn linear(&mut self, c_l: F, l: Var, c_r: F, r: Var) -> Var {
+        let o = self.witness(move |v| c_l * v.get(l) + c_r * v.get(r));
+        self.generic(
+            [c_l, c_r, -F::one(), F::zero(), F::zero()],
+            [Some(l), Some(r), Some(o)],
+        );
+        o
+    }
+
+    /// Computes `l + r`
+    pub fn add(&mut self, l: Var, r: Var) -> Var {
+        self.linear(F::one(), l, F::one(), r)
+    }
+
+    /// Computes `l - r`
+    pub fn sub(&mut self, l: Var, r: Var) -> Var {
+        self.linear(F::one(), l, -F::one(), r)
+    }
+
+    /// Computes `c x`
+    pub fn scale(&mut self, c: F, x: Var) -> Var {
+        let o = self.witness(move |v| c * v.get(x));
+        self.generic(
+            [c, F::zero(), -F::one(), F::zero(), F::zero()],
+            [Some(x), None, Some(o)],
+        );
+        o
+    }
+
+    /// Computes `l r`
+    pub fn mul(&mut self, l: Var, r: Var) -> Var {
+        let o = self.witness(move |v| v.get(l) * v.get(r));
+        self.generic(
+            [F::zero(), F::zero(), -F::one(), F::one(), F::zero()],
+            [Some(l), Some(r), Some(o)],
+        );
+        o
+    }
+
+    /// Computes `1 - x`, the negation of the boolean `x`
+    pub fn not(&mut self, x: Var) -> Var {
+        let o = self.witness(move |v| F::one() - v.get(x));
+        self.generic(
+            [-F::one(), F::zero(), -F::one(), F::zero(), F::one()],
+            [Some(x), None, Some(o)],
+        );
+        o
+    }
+
+    /// Computes the boolean which is 1 if `x` is 0, and 0 otherwise
+    pub fn is_zero(&mut self, x: Var) -> Var {
+        // the inverse of x (or 0 if x is 0) as a hint
+        let inv = self.witness(move |v| v.get(x).inverse().unwrap_or_else(F::zero));
+        let o = self.witness(move |v| {
+            if v.get(x).is_zero() {
+                F::one()
+            } else {
+                F::zero()
+            }
+        });
+        // x inv + o - 1 = 0, so that o is 1 if x is 0
+        self.generic(
+            [F::zero(), F::zero(), F::one(), F::one(), -F::one()],
+            [Some(x), Some(inv), Some(o)],
+        );
+        // x o = 0, so that o is 0 if x is not 0
+        self.generic(
+            [F::zero(), F::zero(), F::zero(), F::one(), F::zero()],
+            [Some(x), Some(o), None],
+        );
+        o
+    }
+
+    /// Constrains `x` to be 0 or 1
+    pub fn assert_boolean(&mut self, x: Var) {
+        // x^2 - x = 0
+        self.generic(
+            [-F::one(), F::zero(), F::zero(), F::one(), F::zero()],
+            [Some(x), Some(x), None],
+        );
+    }
+
+    /// Constrains `x` and `y` to be equal, with the permutation (no gate is added)
+    pub fn assert_eq(&mut self, x: Var, y: Var) {
+        let (x, y) = (self.find(x.0), self.find(y.0));
+        self.equal_to[x] = y;
+    }
+
+    /// The representative of the variables asserted equal to the `var`-th variable
+    fn find(&self, mut var: usize) -> usize {
+        while self.equal_to[var] != var {
+            var = self.equal_to[var];
+        }
+        var
+    }
+
+    /// The number of public values, the public inputs followed by the public outputs
+    pub fn public(&self) -> usize {
+        self.public_inputs.len() + self.public_outputs.len()
+    }
+
+    /// The name of `var` for error reporting, if any
+    fn describe(&self, var: usize) -> String {
+        self.names
+            .iter()
+            .find(|(_, named)| named.0 == var)
+            .map_or_else(|| format!("#{var}"), |(name, _)| name.clone())
+    }
+
+    /// All the rows of the circuit: the public rows, then the other rows
+    fn all_rows(&self) -> impl Iterator<Item = (GateType, Vec<F>, [Option<Var>; COLUMNS])> + '_ {
+        let public = self
+            .public_inputs
+            .iter()
+            .chain(&self.public_outputs)
+            .map(|var| {
+                let mut coeffs = vec![F::zero(); 2 * GENERIC_COEFFS];
+                coeffs[0] = F::one();
+                let mut cells = [None; COLUMNS];
+                cells[0] = Some(*var);
+                (GateType::Generic, coeffs, cells)
+            });
+        public.chain(
+            self.rows
+                .iter()
+                .map(|row| (row.typ, row.coeffs.clone(), row.cells)),
+        )
+    }
+
+    /// Lowers the circuit to its gates, wiring together the cells of the variables
+    /// asserted equal to each other.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if a variable used in several cells is in a column that is not wired.
+    pub fn gates(&self) -> Result<Vec<CircuitGate<F>>, CircuitBuilderError> {
+        let mut gates: Vec<_> = self
+            .all_rows()
+            .enumerate()
+            .map(|(row, (typ, coeffs, _))| CircuitGate {
+                typ,
+                wires: array::from_fn(|col| Wire { row, col }),
+                coeffs,
+            })
+            .collect();
+
+        // the cells of each class of equal variables, in order
+        let mut classes: HashMap<usize, Vec<Wire>> = HashMap::new();
+        for (row, (_, _, cells)) in self.all_rows().enumerate() {
+            for (col, var) in cells.iter().enumerate() {
+                if let Some(var) = var {
+                    classes
+                        .entry(self.find(var.0))
+                        .or_default()
+                        .push(Wire { row, col });
+                }
+            }
+        }
+
+        // each cell is wired to the next one of its class, in a cycle
+        for (class, cells) in classes {
+            if cells.len() < 2 {
+                continue;
+            }
+            if let Some(cell) = cells.iter().find(|cell| cell.col >= PERMUTS) {
+                return Err(CircuitBuilderError::UnwiredCell(
+                    self.describe(class),
+                    cell.col,
+                ));
+            }
+            for (i, cell) in cells.iter().enumerate() {
+                gates[cell.row].wires[cell.col] = cells[(i + 1) % cells.len()];
+            }
+        }
+
+        Ok(gates)
+    }
+
+    /// Lowers the circuit to a builder of its constraint system,
+    /// with its public inputs and outputs and its lookup tables set up.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the gates cannot be created (see [CircuitBuilder::gates]).
+    pub fn constraint_system(&self) -> Result<Builder<F>, CircuitBuilderError> {
+        Ok(ConstraintSystem::create(self.gates()?)
+            .public(self.public())
+            .public_output(self.public_outputs.len())
+            .lookup(self.lookup_tables.clone()))
+    }
+
+    /// Generates the witness of the circuit, given the values of its (public and private) inputs
+    /// by name. The public values are in the first rows of the first column.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if an input is missing or unknown, if a looked up index is not in its table,
+    /// or if variables asserted equal have different values.
+    pub fn generate_witness(
+        &self,
+        inputs: &[(&str, F)],
+    ) -> Result<[Vec<F>; COLUMNS], CircuitBuilderError> {
+        let mut given = HashMap::new();
+        for (name, value) in inputs {
+            match self.names.get(*name) {
+                Some(var) if matches!(self.values[var.0], Value::Input) => {
+                    given.insert(*var, *value);
+                }
+                _ => return Err(CircuitBuilderError::UnknownInput(name.to_string())),
+            }
+        }
+
+        let mut values = Vec::with_capacity(self.values.len());
+        let mut gadget_witnesses: Vec<Option<[Vec<F>; COLUMNS]>> =
+            self.gadgets.iter().map(|_| None).collect();
+        for (i, value) in self.values.iter().enumerate() {
+            let value = match value {
+                Value::Input => *given
+                    .get(&Var(i))
+                    .ok_or_else(|| CircuitBuilderError::MissingInput(self.describe(i)))?,
+                Value::Constant(c) => *c,
+                Value::Computed(f) => f(&Values(&values)),
+                Value::Gadget { gadget, row, col } => gadget_witnesses[*gadget]
+                    .get_or_insert_with(|| self.gadgets[*gadget](&Values(&values)))[*col][*row],
+                Value::Lookup { table, index } => self
+                    .table_value(values[table.0], values[index.0])
+                    .ok_or_else(|| {
+                        CircuitBuilderError::MissingEntry(
+                            self.describe(index.0),
+                            self.describe(table.0),
+                        )
+                    })?,
+            };
+            values.push(value);
+        }
+
+        for (var, value) in values.iter().enumerate() {
+            let class = self.find(var);
+            if values[class] != *value {
+                return Err(CircuitBuilderError::NotEqual(
+                    self.describe(var),
+                    self.describe(class),
+                ));
+            }
+        }
+
+        let rows: Vec<_> = self.all_rows().map(|(_, _, cells)| cells).collect();
+        Ok(array::from_fn(|col| {
+            rows.iter()
+                .map(|cells| cells[col].map_or_else(F::zero, |var| values[var.0]))
+                .collect()
+        }))
+    }
+}
@@ -0,0 +1,221 @@
+//! This module implements unsigned integers of 128 and 256 bits and their comparisons
+//! in circuits written with a [CircuitBuilder], as [UInt128] and [UInt256].
+//!
+//! An integer is given by its limbs of [LIMB_BITS] bits, in little-endian order,
+//! each checked to be smaller than `2^64` by a `RangeCheck0` gate whose two highest limbs
+//! are wired to zero.
+//!
+//! The comparisons give boolean variables, which can be used in further constraints:
+//!
+//! - `a < b` is the borrow of the subtraction `a - b` done limb by limb:
+//!   for each limb, `a_i - b_i - c_i + 2^64 c_{i+1} = d_i` with the borrows `c_i` booleans
+//!   and the differences `d_i` checked to be smaller than `2^64`,
+//!   so that each borrow is 1 exactly when the difference of the limbs (and previous borrow) is negative,
+//! - `a > b`, `a <= b` and `a >= b` are obtained from it,
+//! - `a == b` is the product of the booleans telling if each limb of `a - b` is zero.
+
+use crate::circuits::{
+    builder::{CircuitBuilder, Values, Var},
+    gate::GateType,
+    polynomials::{generic::GENERIC_COEFFS, range_check::witness::value_to_limb},
+    wires::COLUMNS,
+};
+use ark_ff::{PrimeField, SquareRootField};
+use num_bigint::BigUint;
+use std::{array, rc::Rc};
+
+/// The number of bits of the limbs of a [UInt]
+pub const LIMB_BITS: usize = 64;
+
+/// An unsigned integer of 128 bits, as two limbs
+pub type UInt128 = UInt<2>;
+
+/// An unsigned integer of 256 bits, as four limbs
+pub type UInt256 = UInt<4>;
+
+/// An unsigned integer of `LIMBS` limbs of [LIMB_BITS] bits in a circuit
+/// (see the [module](self) documentation)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UInt<const LIMBS: usize> {
+    limbs: [Var; LIMBS],
+}
+
+impl<const LIMBS: usize> UInt<LIMBS> {
+    /// The number of bits of the integer
+    pub const BITS: usize = LIMBS * LIMB_BITS;
+
+    /// The integer whose limbs are `limbs`, in little-endian order,
+    /// each checked to be smaller than `2^64` with a range check gate
+    pub fn from_limbs<F: PrimeField + SquareRootField>(builder: &mut CircuitBuilder<F>, limbs: [Var; LIMBS]) -> Self {
+        for limb in limbs {
+            range_check_limb(builder, limb);
+        }
+        UInt { limbs }
+    }
+
+    /// The integer whose limbs are `limbs`, in little-endian order,
+    /// which must already be constrained to be smaller than `2^64`
+    pub fn from_limbs_unchecked(limbs: [Var; LIMBS]) -> Self {
+        UInt { limbs }
+    }
+
+    /// The integer whose value is computed by `value` from the values of the previous variables,
+    /// with its limbs checked to be smaller than `2^64`.
+    /// Generating the witness panics if the value has more than [UInt::BITS] bits.
+    pub fn witness<F: PrimeField + SquareRootField>(
+        builder: &mut CircuitBuilder<F>,
+        value: impl Fn(&Values<F>) -> BigUint + 'static,
+    ) -> Self {
+        let value = Rc::new(value);
+        let limbs = array::from_fn(|i| {
+            let value = value.clone();
+            builder.witness(move |v| Self::to_limbs::<F>(&value(v))[i])
+        });
+        Self::from_limbs(builder, limbs)
+    }
+
+    /// The constant integer `value`
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `value` has more than [UInt::BITS] bits.
+    pub fn constant<F: PrimeField + SquareRootField>(builder: &mut CircuitBuilder<F>, value: &BigUint) -> Self {
+        let limbs = Self::to_limbs::<F>(value).map(|limb| builder.constant(limb));
+        UInt { limbs }
+    }
+
+    /// The limbs of the integer, in little-endian order
+    pub fn limbs(&self) -> [Var; LIMBS] {
+        self.limbs
+    }
+
+    /// The value of the integer, when generating the witness
+    pub fn value<F: PrimeField>(&self, values: &Values<F>) -> BigUint {
+        self.limbs
+            .iter()
+            .rev()
+            .fold(BigUint::default(), |acc, limb| {
+                (acc << LIMB_BITS) + Into::<BigUint>::into(values.get(*limb))
+            })
+    }
+
+    /// The limbs of `value`, in little-endian order, for example as the inputs of [UInt::from_limbs]
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `value` has more than [UInt::BITS] bits.
+    pub fn to_limbs<F: PrimeField>(value: &BigUint) -> [F; LIMBS] {
+        assert!(
+            value.bits() as usize <= Self::BITS,
+            "the value has more than {} bits",
+            Self::BITS
+        );
+        let mut digits = value.iter_u64_digits();
+        array::from_fn(|_| F::from(digits.next().unwrap_or(0)))
+    }
+
+    /// The boolean which is 1 if `self < other`, and 0 otherwise
+    pub fn lt<F: PrimeField + SquareRootField>(&self, builder: &mut CircuitBuilder<F>, other: &Self) -> Var {
+        borrow(builder, &self.limbs, &other.limbs)
+    }
+
+    /// The boolean which is 1 if `self > other`, and 0 otherwise
+    pub fn gt<F: PrimeField + SquareRootField>(&self, builder: &mut CircuitBuilder<F>, other: &Self) -> Var {
+        other.lt(builder, self)
+    }
+
+    /// The boolean which is 1 if `self <= other`, and 0 otherwise
+    pub fn le<F: PrimeField + SquareRootField>(&self, builder: &mut CircuitBuilder<F>, other: &Self) -> Var {
+        let gt = self.gt(builder, other);
+        builder.not(gt)
+    }
+
+    /// The boolean which is 1 if `self >= other`, and 0 otherwise
+    pub fn ge<F: PrimeField + SquareRootField>(&self, builder: &mut CircuitBuilder<F>, other: &Self) -> Var {
+        let lt = self.lt(builder, other);
+        builder.not(lt)
+    }
+
+    /// The boolean which is 1 if `self == other`, and 0 otherwise
+    pub fn equals<F: PrimeField + SquareRootField>(&self, builder: &mut CircuitBuilder<F>, other: &Self) -> Var {
+        let equal_limbs: Vec<_> = self
+            .limbs
+            .iter()
+            .zip(&other.limbs)
+            .map(|(a, b)| {
+                let diff = builder.sub(*a, *b);
+                builder.is_zero(diff)
+            })
+            .collect();
+        equal_limbs[1..]
+            .iter()
+            .fold(equal_limbs[0], |acc, equal| builder.mul(acc, *equal))
+    }
+}
+
+/// The bits `start..end` of the value held in column `col` of a `RangeCheck0` row,
+/// from its 12-bit limbs in columns 3 to 6 down to its 2-bit crumbs in columns 7 to 14
+/// (the 12-bit limbs of the bits 64 to 88 in columns 1 and 2 are zero)
+fn range_check_limb_bits(col: usize) -> (usize, usize) {
+    if col < 7 {
+        let end = LIMB_BITS - 12 * (col - 3);
+        (end - 12, end)
+    } else {
+        let end = 16 - 2 * (col - 7);
+        (end - 2, end)
+    }
+}
+
+/// Checks that `x` is smaller than `2^64`, with a `RangeCheck0` gate
+/// whose limbs of the bits 64 to 88 are wired to zero
+fn range_check_limb<F: PrimeField + SquareRootField>(builder: &mut CircuitBuilder<F>, x: Var) {
+    let zero = builder.constant(F::zero());
+    let mut cells = [None; COLUMNS];
+    cells[0] = Some(x);
+    cells[1] = Some(zero);
+    cells[2] = Some(zero);
+    for (col, cell) in cells.iter_mut().enumerate().skip(3) {
+        let (start, end) = range_check_limb_bits(col);
+        *cell = Some(builder.witness(move |v| value_to_limb(v.get(x), start, end)));
+    }
+    builder.gate(GateType::RangeCheck0, vec![], cells);
+}
+
+/// The borrow of the subtraction `a - b` of the integers of limbs `a` and `b`,
+/// which is 1 if `a < b` and 0 otherwise
+fn borrow<F: PrimeField + SquareRootField>(builder: &mut CircuitBuilder<F>, a: &[Var], b: &[Var]) -> Var {
+    let two_to_limb = F::from(2u64).pow([LIMB_BITS as u64]);
+    let mut borrow: Option<Var> = None;
+    for (a, b) in a.iter().copied().zip(b.iter().copied()) {
+        let mut diff = builder.sub(a, b);
+        if let Some(borrow) = borrow {
+            diff = builder.sub(diff, borrow);
+        }
+
+        // the borrow is 1 if a_i < b_i + c_i
+        let borrow_in = borrow;
+        let borrow_out = builder.witness(move |v| {
+            let a: BigUint = v.get(a).into();
+            let b: BigUint = v.get(b).into();
+            let c: BigUint = borrow_in.map_or_else(F::zero, |c| v.get(c)).into();
+            if a < b + c {
+                F::one()
+            } else {
+                F::zero()
+            }
+        });
+        builder.assert_boolean(borrow_out);
+
+        // a_i - b_i - c_i + 2^64 c_{i+1} = d_i, with d_i smaller than 2^64
+        let limb = builder.witness(move |v| v.get(diff) + two_to_limb * v.get(borrow_out));
+        let mut coeffs = [F::zero(); GENERIC_COEFFS];
+        coeffs[0] = F::one();
+        coeffs[1] = two_to_limb;
+        coeffs[2] = -F::one();
+        builder.generic(coeffs, [Some(diff), Some(borrow_out), Some(limb)]);
+        range_check_limb(builder, limb);
+
+        borrow = Some(borrow_out);
+    }
+    borrow.expect("an integer has at least one limb")
+}
@@ -2,17 +2,23 @@
 use super::{gate::SelectorPolynomial, lookup::runtime_tables::RuntimeTableCfg};
 use crate::{
     circuits::{
+        custom_gate::{CustomGate, CustomGates},
         domain_constant_evaluation::DomainConstantEvaluations,
-        domains::EvaluationDomains,
+        domains::{CosetDomain, EvaluationDomains},
         gate::{CircuitGate, GateType},
-        lookup::{index::LookupConstraintSystem, tables::LookupTable},
+        lookup::{
+            index::{lookup_table_capacity, LookupConstraintSystem},
+            tables::LookupTable,
+        },
+        plan::CircuitPlan,
         polynomial::{WitnessEvals, WitnessOverDomains, WitnessShifts},
-        polynomials::permutation::{Shifts, ZK_ROWS},
-        polynomials::{foreign_field_add, range_check},
+        polynomials::permutation::{min_zk_rows, Shifts, MAX_ZK_ROWS, NON_ZK_ROWS, ZK_ROWS},
+        polynomials::{foreign_field_add, range_check, sha256},
         wires::*,
     },
     curve::KimchiCurve,
     error::SetupError,
+    parallel::prelude::*,
 };
 use ark_ff::{PrimeField, SquareRootField, Zero};
 use ark_poly::{
@@ -38,11 +44,19 @@ pub struct ConstraintSystem<F: PrimeField> {
     // ------
     /// number of public inputs
     pub public: usize,
+    /// number of public outputs, which are the last of the `public` public values
+    #[serde(default)]
+    pub public_output: usize,
     /// number of previous evaluation challenges, for recursive proving
     pub prev_challenges: usize,
     /// evaluation domains
     #[serde(bound = "EvaluationDomains<F>: Serialize + DeserializeOwned")]
     pub domain: EvaluationDomains<F>,
+    /// number of rows at the end of the domain reserved for zero-knowledge
+    pub zk_rows: u64,
+    /// whether the proofs are zero-knowledge (see [Builder::zk])
+    #[serde(default = "zk_default")]
+    pub zk: bool,
     /// circuit gates
     #[serde(bound = "CircuitGate<F>: Serialize + DeserializeOwned")]
     pub gates: Vec<CircuitGate<F>>,
@@ -126,6 +140,21 @@ pub struct ConstraintSystem<F: PrimeField> {
     #[serde(bound = "Option<SelectorPolynomial<F>>: Serialize + DeserializeOwned")]
     pub foreign_field_add_selector_poly: Option<SelectorPolynomial<F>>,
 
+    /// SHA-256 gate selector polynomials
+    #[serde(
+        bound = "[SelectorPolynomial<F>; sha256::gadget::GATE_COUNT]: Serialize + DeserializeOwned"
+    )]
+    pub sha256_selector_polys: Option<[SelectorPolynomial<F>; sha256::gadget::GATE_COUNT]>,
+
+    /// Custom gates, which are not serialized
+    #[serde(skip)]
+    pub custom_gates: CustomGates<F>,
+
+    /// Custom gate selector polynomials, in the order of the registration of the custom gates
+    #[serde(default)]
+    #[serde(bound = "Vec<SelectorPolynomial<F>>: Serialize + DeserializeOwned")]
+    pub custom_selector_polys: Vec<SelectorPolynomial<F>>,
+
     /// wire coordinate shifts
     #[serde_as(as = "[o1_utils::serialization::SerdeAs; PERMUTS]")]
     pub shift: [F; PERMUTS],
@@ -135,30 +164,70 @@ pub struct ConstraintSystem<F: PrimeField> {
     /// lookup constraint system
     #[serde(bound = "LookupConstraintSystem<F>: Serialize + DeserializeOwned")]
     pub lookup_constraint_system: Option<LookupConstraintSystem<F>>,
+    /// witness columns whose cells are all checked to be boolean, in increasing order
+    #[serde(default)]
+    pub boolean_columns: Vec<usize>,
     /// precomputes
     #[serde(skip)]
     precomputations: OnceCell<Arc<DomainConstantEvaluations<F>>>,
+    /// the cosets of `domain.d1` given by the wire coordinate shifts
+    #[serde(skip)]
+    cosets: OnceCell<Arc<[CosetDomain<F>; PERMUTS]>>,
 }
 
 /// Represents an error found when verifying a witness with a gate
-#[derive(Debug)]
-pub enum GateError {
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GateError<F> {
     /// Some connected wires have different values
     DisconnectedWires(Wire, Wire),
     /// A public gate was incorrectly connected
     IncorrectPublic(usize),
     /// A specific gate did not verify correctly
     Custom { row: usize, err: String },
+    /// A gate is not satisfied by the witness
+    Constraint(ConstraintFailure<F>),
+    /// A cell of a boolean column is neither zero nor one
+    NotBoolean(Wire),
+}
+
+/// The diagnostic of a gate that is not satisfied by a witness
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstraintFailure<F> {
+    /// The row of the gate
+    pub row: usize,
+    /// The type of the gate
+    pub typ: GateType,
+    /// The index of the first failing constraint among the constraints of the gate,
+    /// if it could be pinpointed
+    pub constraint: Option<usize>,
+    /// The values of all the constraints of the gate, which are zero when satisfied
+    /// (empty if they could not be evaluated)
+    pub values: Vec<F>,
+    /// The witness of the row
+    pub witness: [F; COLUMNS],
+    /// The error reported by the gate
+    pub err: String,
 }
 
 pub struct Builder<F: PrimeField> {
     gates: Vec<CircuitGate<F>>,
     public: usize,
+    public_output: usize,
     prev_challenges: usize,
     lookup_tables: Vec<LookupTable<F>>,
     runtime_tables: Option<Vec<RuntimeTableCfg<F>>>,
     precomputations: Option<Arc<DomainConstantEvaluations<F>>>,
     foreign_field_modulus: Option<BigUint>,
+    zk_rows: u64,
+    zk: bool,
+    custom_gates: Vec<Arc<dyn CustomGate<F>>>,
+    boolean_columns: Vec<usize>,
+}
+
+/// The value of [ConstraintSystem::zk] for the constraint systems and verifier indexes
+/// serialized before it was introduced, which were all zero-knowledge
+pub(crate) fn zk_default() -> bool {
+    true
 }
 
 /// Create selector polynomial for a circuit gate
@@ -207,10 +276,15 @@ impl<F: PrimeField> ConstraintSystem<F> {
     /// Returns a [Builder<F>]
     /// It also defaults to the following values of the builder:
     /// - `public: 0`
+    /// - `public_output: 0`
     /// - `prev_challenges: 0`
     /// - `lookup_tables: vec![]`,
     /// - `runtime_tables: None`,
     /// - `precomputations: None`,
+    /// - `zk_rows: ZK_ROWS`,
+    /// - `zk: true`,
+    /// - `custom_gates: vec![]`,
+    /// - `boolean_columns: vec![]`,
     ///
     /// How to use it:
     /// 1. Create your instance of your builder for the constraint system using `crate(gates, sponge params)`
@@ -220,17 +294,23 @@ impl<F: PrimeField> ConstraintSystem<F> {
         Builder {
             gates,
             public: 0,
+            public_output: 0,
             prev_challenges: 0,
             lookup_tables: vec![],
             runtime_tables: None,
             precomputations: None,
             foreign_field_modulus: None,
+            zk_rows: ZK_ROWS,
+            zk: true,
+            custom_gates: vec![],
+            boolean_columns: vec![],
         }
     }
 
     pub fn precomputations(&self) -> &Arc<DomainConstantEvaluations<F>> {
-        self.precomputations
-            .get_or_init(|| Arc::new(DomainConstantEvaluations::create(self.domain).unwrap()))
+        self.precomputations.get_or_init(|| {
+            Arc::new(DomainConstantEvaluations::create(self.domain, self.zk_rows).unwrap())
+        })
     }
 
     pub fn set_precomputations(&self, precomputations: Arc<DomainConstantEvaluations<F>>) {
@@ -239,62 +319,158 @@ impl<F: PrimeField> ConstraintSystem<F> {
             .expect("Precomputation has been set before");
     }
 
+    /// The number of rows of the domain that can hold gates,
+    /// the remaining `zk_rows` rows being reserved for zero-knowledge.
+    pub fn usable_rows(&self) -> usize {
+        self.domain.d1.size() - self.zk_rows as usize
+    }
+
+    /// The number of rows at the end of the domain that the prover fills with random values:
+    /// the `zk_rows` rows, or none when zero-knowledge is disabled.
+    pub fn random_rows(&self) -> u64 {
+        if self.zk {
+            self.zk_rows
+        } else {
+            0
+        }
+    }
+
+    /// The maximum number of entries of the concatenated lookup table,
+    /// including the fixed tables and the runtime tables.
+    /// The runtime tables are placed after the fixed tables,
+    /// so they never reach the rows reserved at the end of the domain,
+    /// see [lookup_table_capacity].
+    pub fn lookup_table_capacity(&self) -> usize {
+        lookup_table_capacity(self.domain.d1.size(), self.zk_rows)
+    }
+
+    /// The cosets `shift[i] * <omega>` of `domain.d1`, one per permuted column,
+    /// whose elements are the values of the cells in the permutation argument.
+    pub fn cosets(&self) -> &[CosetDomain<F>; PERMUTS] {
+        self.cosets.get_or_init(|| {
+            Arc::new(array::from_fn(|i| {
+                CosetDomain::new(self.domain.d1, self.shift[i])
+            }))
+        })
+    }
+
     /// This function verifies the consistency of the wire
     /// assignments (witness) against the constraints
     ///     witness: wire assignment witness
     ///     RETURN: verification status
+    ///
+    /// The rows are checked in parallel (see [crate::parallel]),
+    /// and the checks stop as soon as a row fails.
+    ///
+    /// # Errors
+    ///
+    /// Will give the first error found, in the order of the rows.
     pub fn verify<G: KimchiCurve<ScalarField = F>>(
         &self,
         witness: &[Vec<F>; COLUMNS],
         public: &[F],
-    ) -> Result<(), GateError> {
-        // pad the witness
+    ) -> Result<(), GateError<F>> {
+        let witness = self.pad_witness(witness);
+
+        // the failing row found first is the first in the order of the rows,
+        // whichever thread checked it
+        (0..self.gates.len())
+            .into_par_iter()
+            .find_map_first(|row| {
+                self.verify_row::<G>(row, &witness, public)
+                    .into_iter()
+                    .next()
+            })
+            .map_or(Ok(()), Err)
+    }
+
+    /// Like [ConstraintSystem::verify], but checks all the rows instead of stopping at the
+    /// first failure, and returns all the errors found, in the order of the rows.
+    pub fn verify_verbose<G: KimchiCurve<ScalarField = F>>(
+        &self,
+        witness: &[Vec<F>; COLUMNS],
+        public: &[F],
+    ) -> Vec<GateError<F>> {
+        let witness = self.pad_witness(witness);
+        let errors: Vec<Vec<_>> = (0..self.gates.len())
+            .into_par_iter()
+            .map(|row| self.verify_row::<G>(row, &witness, public))
+            .collect();
+        errors.into_iter().flatten().collect()
+    }
+
+    /// Pads the witness to the size of the domain
+    fn pad_witness(&self, witness: &[Vec<F>; COLUMNS]) -> [Vec<F>; COLUMNS] {
         let pad = vec![F::zero(); self.domain.d1.size() - witness[0].len()];
-        let witness: [Vec<F>; COLUMNS] = array::from_fn(|i| {
+        array::from_fn(|i| {
             let mut w = witness[i].to_vec();
             w.extend_from_slice(&pad);
             w
-        });
-
-        // check each rows' wiring
-        for (row, gate) in self.gates.iter().enumerate() {
-            // check if wires are connected
-            for col in 0..PERMUTS {
-                let wire = gate.wires[col];
-
-                if wire.col >= PERMUTS {
-                    return Err(GateError::Custom {
-                        row,
-                        err: format!(
-                            "a wire can only be connected to the first {} columns",
-                            PERMUTS
-                        ),
-                    });
-                }
+        })
+    }
 
-                if witness[col][row] != witness[wire.col][wire.row] {
-                    return Err(GateError::DisconnectedWires(
-                        Wire { col, row },
-                        Wire {
-                            col: wire.col,
-                            row: wire.row,
-                        },
-                    ));
-                }
+    /// Returns the errors of the wiring and of the gate at `row`
+    fn verify_row<G: KimchiCurve<ScalarField = F>>(
+        &self,
+        row: usize,
+        witness: &[Vec<F>; COLUMNS],
+        public: &[F],
+    ) -> Vec<GateError<F>> {
+        let gate = &self.gates[row];
+        let mut errors = vec![];
+
+        // check if wires are connected
+        for col in 0..PERMUTS {
+            let wire = gate.wires[col];
+
+            if wire.col >= PERMUTS {
+                errors.push(GateError::Custom {
+                    row,
+                    err: format!(
+                        "a wire can only be connected to the first {} columns",
+                        PERMUTS
+                    ),
+                });
+            } else if witness[col][row] != witness[wire.col][wire.row] {
+                errors.push(GateError::DisconnectedWires(
+                    Wire { col, row },
+                    Wire {
+                        col: wire.col,
+                        row: wire.row,
+                    },
+                ));
             }
+        }
 
-            // for public gates, only the left wire is toggled
-            if row < self.public && gate.coeffs[0] != F::one() {
-                return Err(GateError::IncorrectPublic(row));
+        // for public gates, only the left wire is toggled
+        if row < self.public && gate.coeffs[0] != F::one() {
+            errors.push(GateError::IncorrectPublic(row));
+        }
+
+        // check the cells of the boolean columns
+        for &col in &self.boolean_columns {
+            let value = witness[col][row];
+            if !value.is_zero() && !value.is_one() {
+                errors.push(GateError::NotBoolean(Wire { row, col }));
             }
+        }
 
-            // check the gate's satisfiability
-            gate.verify::<G>(row, &witness, self, public)
-                .map_err(|err| GateError::Custom { row, err })?;
+        // check the gate's satisfiability, and pinpoint the failing constraint
+        if let Err(err) = gate.verify::<G>(row, witness, self, public) {
+            let values = gate
+                .constraint_values::<G>(row, witness, self, public)
+                .unwrap_or_default();
+            errors.push(GateError::Constraint(ConstraintFailure {
+                row,
+                typ: gate.typ,
+                constraint: values.iter().position(|value| !value.is_zero()),
+                values,
+                witness: array::from_fn(|col| witness[col][row]),
+                err,
+            }));
         }
 
-        // all good!
-        Ok(())
+        errors
     }
 
     /// evaluate witness polynomials over domains
@@ -345,6 +521,16 @@ impl<F: PrimeField + SquareRootField> Builder<F> {
         self
     }
 
+    /// Set up the number of public outputs,
+    /// which are the last `public_output` of the public values set up with [Builder::public].
+    /// The circuit is expected to copy its results to the corresponding public rows,
+    /// and the prover to set them (see [crate::prover_index::ProverIndex::set_public_output]).
+    /// If not invoked, it equals `0` by default.
+    pub fn public_output(mut self, public_output: usize) -> Self {
+        self.public_output = public_output;
+        self
+    }
+
     /// Set up the number of previous challenges, used for recusive proving.
     /// If not invoked, it equals `0` by default.
     pub fn prev_challenges(mut self, prev_challenges: usize) -> Self {
@@ -399,8 +585,81 @@ impl<F: PrimeField + SquareRootField> Builder<F> {
         self
     }
 
+    /// Computes the [CircuitPlan] of the circuit being built,
+    /// which reports the size of the domain [Builder::build] will use.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if no domain of the field is large enough for the circuit.
+    pub fn plan(&self) -> Result<CircuitPlan, SetupError> {
+        CircuitPlan::create(
+            &self.gates,
+            &self.lookup_tables,
+            self.runtime_tables.as_deref(),
+            self.num_zk_rows(),
+        )
+    }
+
+    /// The number of rows reserved at the end of the domain:
+    /// `zk_rows`, or [NON_ZK_ROWS] when zero-knowledge is disabled.
+    fn num_zk_rows(&self) -> u64 {
+        if self.zk {
+            self.zk_rows
+        } else {
+            NON_ZK_ROWS
+        }
+    }
+
+    /// Set up the number of rows reserved for zero-knowledge at the end of the domain.
+    /// If not invoked, it is [ZK_ROWS] by default.
+    ///
+    /// Using more zero-knowledge rows increases the degree of the blinding polynomial,
+    /// at the cost of fewer usable rows in the domain.
+    /// The value must lie between [ZK_ROWS] and [MAX_ZK_ROWS] (both included),
+    /// and be at least `min_zk_rows(3)` if the gates of the circuit read the previous row
+    /// (see [min_zk_rows]), otherwise [Builder::build] fails.
+    /// Note that any shared precomputations must have been created with the same value.
+    pub fn zk_rows(mut self, zk_rows: u64) -> Self {
+        self.zk_rows = zk_rows;
+        self
+    }
+
+    /// Set up whether the proofs of the circuit are zero-knowledge.
+    /// If not invoked, it is `true` by default.
+    ///
+    /// Without zero-knowledge, only [NON_ZK_ROWS] rows are reserved at the end of the domain
+    /// (`zk_rows` is ignored), and the prover neither randomizes them nor blinds its commitments.
+    /// The proofs stay sound, but reveal information about the witness:
+    /// this is only meant for circuits whose witness is public anyway.
+    /// The mode is part of the digest of the verifier index.
+    pub fn zk(mut self, zk: bool) -> Self {
+        self.zk = zk;
+        self
+    }
+
+    /// Set up the custom gates of the circuit (see [crate::circuits::custom_gate]).
+    /// The `i`-th custom gate is bound to the gate type `GateType::custom(i)`.
+    /// If not invoked, it is `vec![]` by default.
+    pub fn custom_gates(mut self, custom_gates: Vec<Arc<dyn CustomGate<F>>>) -> Self {
+        self.custom_gates = custom_gates;
+        self
+    }
+
+    /// Set up the witness columns whose cells must all be boolean
+    /// (see [crate::circuits::polynomials::boolean_columns]).
+    /// Each of them is checked by a single constraint, applying to all the rows of the circuit,
+    /// including the public input rows and the padding rows.
+    /// If not invoked, it is `vec![]` by default.
+    pub fn boolean_columns(mut self, boolean_columns: Vec<usize>) -> Self {
+        self.boolean_columns = boolean_columns;
+        self
+    }
+
     /// Build the [ConstraintSystem] from a [Builder].
     pub fn build(self) -> Result<ConstraintSystem<F>, SetupError> {
+        let plan = self.plan()?;
+        let zk = self.zk;
+        let zk_rows = self.num_zk_rows();
         let mut gates = self.gates;
         let lookup_tables = self.lookup_tables;
         let runtime_tables = self.runtime_tables;
@@ -409,14 +668,39 @@ impl<F: PrimeField + SquareRootField> Builder<F> {
         // for some reason we need more than 1 gate for the circuit to work, see TODO below
         assert!(gates.len() > 1);
 
-        //~ 2. Create a domain for the circuit. That is,
+        //~ 2. If the number of zero-knowledge rows `zk_rows` is not in the range
+        //~    `ZK_ROWS..=MAX_ZK_ROWS` (3 to 7 rows), abort.
+        //~    If zero-knowledge is disabled, use `NON_ZK_ROWS` (1 row) instead.
+        if zk && !(ZK_ROWS..=MAX_ZK_ROWS).contains(&zk_rows) {
+            return Err(SetupError::ZkRows(zk_rows));
+        }
+
+        // the boolean columns are witness columns
+        let mut boolean_columns = self.boolean_columns;
+        if let Some(col) = boolean_columns.iter().find(|col| **col >= COLUMNS) {
+            return Err(SetupError::BooleanColumn(*col));
+        }
+        boolean_columns.sort_unstable();
+        boolean_columns.dedup();
+
+        // the public outputs are part of the public values
+        if self.public_output > self.public {
+            return Err(SetupError::PublicOutput {
+                output: self.public_output,
+                public: self.public,
+            });
+        }
+
+        //~ 3. Create a domain for the circuit. That is,
         //~    compute the smallest subgroup of the field that
-        //~    has order greater or equal to `n + ZK_ROWS` elements.
-        let domain = EvaluationDomains::<F>::create(gates.len() + ZK_ROWS as usize)?;
+        //~    has order greater or equal to `n + zk_rows` elements.
+        //~    If lookups are used, the domain must also fit the concatenated lookup table
+        //~    followed by a dummy entry, the final row of the lookup argument, and the `zk_rows` rows.
+        let domain = EvaluationDomains::<F>::create(plan.domain_size)?;
 
-        assert!(domain.d1.size > ZK_ROWS);
+        assert!(domain.d1.size > zk_rows);
 
-        //~ 3. Pad the circuit: add zero gates to reach the domain size.
+        //~ 4. Pad the circuit: add zero gates to reach the domain size.
         let d1_size = domain.d1.size();
         let mut padding = (gates.len()..d1_size)
             .map(|i| {
@@ -434,7 +718,24 @@ impl<F: PrimeField + SquareRootField> Builder<F> {
             circuit_gates_used.insert(gate.typ);
         });
 
-        //~ 4. sample the `PERMUTS` shifts.
+        //~ 5. Register the custom gates, and check that every custom gate of the circuit
+        //~    is bound to one of them, is followed by a zero gate if it uses two rows,
+        //~    and is not on the first row if it reads the previous row.
+        let custom_gates = CustomGates::new(self.custom_gates)?;
+        custom_gates.check_layout(&gates)?;
+
+        //~    If the gates read the previous row, the proofs open their polynomials
+        //~    at a third point $\zeta / \omega$: abort if zero-knowledge is enabled
+        //~    with fewer than `min_zk_rows(3)` (4) zero-knowledge rows.
+        let eval_points = if custom_gates.reads_prev_row() { 3 } else { 2 };
+        if zk && zk_rows < min_zk_rows(eval_points) {
+            return Err(SetupError::PrevRowZkRows {
+                zk_rows,
+                min: min_zk_rows(eval_points),
+            });
+        }
+
+        //~ 6. sample the `PERMUTS` shifts.
         let shifts = Shifts::new(&domain.d1);
 
         // Precomputations
@@ -592,6 +893,24 @@ impl<F: PrimeField + SquareRootField> Builder<F> {
             }
         };
 
+        // SHA-256 constraint selector polynomials
+        let sha256_gates = sha256::gadget::circuit_gates();
+        let sha256_selector_polys = {
+            if circuit_gates_used.is_disjoint(&sha256_gates.into_iter().collect()) {
+                None
+            } else {
+                Some(array::from_fn(|i| {
+                    selector_polynomial(sha256_gates[i], &gates, &domain)
+                }))
+            }
+        };
+
+        // Custom gate constraint selector polynomials
+        let custom_selector_polys = custom_gates
+            .gate_types()
+            .map(|gate_type| selector_polynomial(gate_type, &gates, &domain))
+            .collect();
+
         //
         // Coefficient
         // -----------
@@ -614,10 +933,10 @@ impl<F: PrimeField + SquareRootField> Builder<F> {
         // Lookup
         // ------
         let lookup_constraint_system =
-            LookupConstraintSystem::create(&gates, lookup_tables, runtime_tables, &domain)
+            LookupConstraintSystem::create(&gates, lookup_tables, runtime_tables, &domain, zk_rows)
                 .map_err(|e| SetupError::ConstraintSystem(e.to_string()))?;
 
-        let sid = shifts.map[0].clone();
+        let sid = shifts.cosets[0].elements().to_vec();
 
         // TODO: remove endo as a field
         let endo = F::zero();
@@ -628,7 +947,10 @@ impl<F: PrimeField + SquareRootField> Builder<F> {
             chacha8,
             endomul_scalar8,
             domain,
+            zk_rows,
+            zk,
             public: self.public,
+            public_output: self.public_output,
             prev_challenges: self.prev_challenges,
             sid,
             sigmal1,
@@ -644,15 +966,26 @@ impl<F: PrimeField + SquareRootField> Builder<F> {
             emull,
             range_check_selector_polys,
             foreign_field_add_selector_poly,
+            sha256_selector_polys,
+            custom_gates,
+            custom_selector_polys,
             foreign_field_modulus: self.foreign_field_modulus,
             gates,
             shift: shifts.shifts,
             endo,
             //fr_sponge_params: self.sponge_params,
             lookup_constraint_system,
+            boolean_columns,
             precomputations: domain_constant_evaluation,
+            cosets: OnceCell::new(),
         };
 
+        // the cosets were already computed to create the permutation
+        constraints
+            .cosets
+            .set(Arc::new(shifts.cosets))
+            .expect("cosets have not been set before");
+
         match self.precomputations {
             Some(t) => {
                 constraints.set_precomputations(t);
@@ -0,0 +1,292 @@
+//! This module implements the registration of custom gates.
+//!
+//! A custom gate is a gate defined outside of kimchi, by an implementation of the [CustomGate] trait
+//! giving its constraints, their degree, and the number of witness rows the gate uses.
+//! The custom gates of a circuit are registered on the builder of the constraint system
+//! (see [crate::circuits::constraints::Builder::custom_gates]), which binds the `i`-th of them
+//! to the gate type returned by [GateType::custom], that is [GateType::Custom0] for the first one,
+//! [GateType::Custom1] for the second one, and so on.
+//! The circuit then uses these gate types for the rows of the custom gates.
+//!
+//! The custom gates are resolved when the index is created:
+//! their constraints are added to the linearization and to the quotient polynomial,
+//! each of them having its own selector polynomial, exactly like the built-in gates.
+//!
+//! Note that the custom gates are not serialized with the constraint system.
+
+use std::{fmt, sync::Arc};
+
+use ark_ff::PrimeField;
+
+use crate::{
+    alphas::Alphas,
+    circuits::{
+        argument::{Argument, ArgumentEnv, ArgumentType},
+        constraints::ConstraintSystem,
+        expr::prologue::*,
+        gate::{CircuitGate, CircuitGateError, CircuitGateResult, GateType},
+        polynomials::varbasemul::VarbaseMul,
+        wires::COLUMNS,
+    },
+    curve::KimchiCurve,
+    error::CustomGateError,
+    linearization::linearization_columns,
+};
+
+/// The number of custom gates that can be registered on a constraint system
+pub const CUSTOM_GATE_SLOTS: usize = 8;
+
+/// The maximum degree of the constraints of a custom gate.
+/// Multiplied by their selector polynomial, the constraints must fit in the domain `d8`.
+pub const MAX_CUSTOM_GATE_DEGREE: u64 = 7;
+
+/// The gate types the custom gates are bound to, in the order of their registration
+const CUSTOM_GATE_TYPES: [GateType; CUSTOM_GATE_SLOTS] = [
+    GateType::Custom0,
+    GateType::Custom1,
+    GateType::Custom2,
+    GateType::Custom3,
+    GateType::Custom4,
+    GateType::Custom5,
+    GateType::Custom6,
+    GateType::Custom7,
+];
+
+impl GateType {
+    /// Returns the gate type the custom gate registered at position `slot` is bound to
+    pub fn custom(slot: usize) -> Option<GateType> {
+        CUSTOM_GATE_TYPES.get(slot).copied()
+    }
+
+    /// Returns the position of the custom gate bound to this gate type,
+    /// or `None` if this is not a custom gate type
+    pub fn custom_slot(&self) -> Option<usize> {
+        CUSTOM_GATE_TYPES.iter().position(|typ| typ == self)
+    }
+}
+
+/// A gate defined outside of kimchi.
+///
+/// The constraints of the gate are given twice: as expressions, used by the prover and the verifier,
+/// and evaluated on the witness, used to check a witness against the circuit.
+/// Both can be obtained from a single function generic over [ExprOps](crate::circuits::expr::constraints::ExprOps),
+/// as done by the built-in gates with [Argument::constraint_checks].
+///
+/// Like the built-in gates, a custom gate can access the witness of its row and of the next row,
+/// and the constants of [ConstantExpr](crate::circuits::expr::ConstantExpr).
+/// It can also read the witness of the previous row, except on the first row of the circuit;
+/// the proofs of the circuit then open their polynomials at a third point,
+/// which requires more zero-knowledge rows
+/// (see [min_zk_rows](crate::circuits::polynomials::permutation::min_zk_rows)).
+/// Its constraints cannot read the coefficients of its row though:
+/// multiplied by the selector polynomial of the gate, they could not be linearized,
+/// as neither the selector nor the coefficients are evaluated in the proof.
+pub trait CustomGate<F: PrimeField>: Send + Sync {
+    /// The name of the gate, used in error messages
+    fn name(&self) -> &str;
+
+    /// The number of constraints of the gate,
+    /// which cannot be larger than the number of constraints of the `VarBaseMul` gate
+    fn constraint_count(&self) -> u32;
+
+    /// The degree of the constraints of the gate (in the witness cells),
+    /// between 1 and [MAX_CUSTOM_GATE_DEGREE]
+    fn degree(&self) -> u64;
+
+    /// The number of witness rows used by the gate:
+    /// 1 if its constraints only use the current row,
+    /// 2 if they also use the next row, which must then be a zero gate
+    fn rows(&self) -> usize;
+
+    /// The constraints of the gate, as expressions
+    fn constraints(&self, env: &ArgumentEnv<F, E<F>>) -> Vec<E<F>>;
+
+    /// The constraints of the gate, evaluated on the witness
+    fn constraint_checks(&self, env: &ArgumentEnv<F, F>) -> Vec<F>;
+}
+
+/// Returns `true` if the constraints of `gate` read the previous row of the witness
+fn reads_prev_row<F: PrimeField>(gate: &dyn CustomGate<F>) -> bool {
+    gate.constraints(&ArgumentEnv::default())
+        .iter()
+        .any(E::reads_prev_row)
+}
+
+/// The custom gates registered on a constraint system
+#[derive(Clone)]
+pub struct CustomGates<F: PrimeField>(Vec<Arc<dyn CustomGate<F>>>);
+
+impl<F: PrimeField> Default for CustomGates<F> {
+    fn default() -> Self {
+        CustomGates(vec![])
+    }
+}
+
+impl<F: PrimeField> fmt::Debug for CustomGates<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.0.iter().map(|gate| gate.name()))
+            .finish()
+    }
+}
+
+impl<F: PrimeField> CustomGates<F> {
+    /// Registers the custom gates `gates`, bound to the gate types `GateType::custom(i)`.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if there are more than [CUSTOM_GATE_SLOTS] gates,
+    /// if one of them does not match its declared number of constraints, degree, or rows,
+    /// or if its constraints cannot be linearized.
+    pub fn new(gates: Vec<Arc<dyn CustomGate<F>>>) -> Result<Self, CustomGateError> {
+        if gates.len() > CUSTOM_GATE_SLOTS {
+            return Err(CustomGateError::TooManyGates(gates.len()));
+        }
+
+        for gate in &gates {
+            let name = gate.name().to_string();
+
+            let count = gate.constraint_count();
+            let max = VarbaseMul::<F>::CONSTRAINTS;
+            if count > max {
+                return Err(CustomGateError::TooManyConstraints { name, count, max });
+            }
+
+            let constraints = gate.constraints(&ArgumentEnv::default());
+            if constraints.len() != count as usize {
+                return Err(CustomGateError::ConstraintCount {
+                    name,
+                    declared: count,
+                    actual: constraints.len(),
+                });
+            }
+
+            let declared = gate.degree();
+            if !(1..=MAX_CUSTOM_GATE_DEGREE).contains(&declared) {
+                return Err(CustomGateError::UnsupportedDegree(name, declared));
+            }
+            // with a domain of size 1 and no zero-knowledge rows,
+            // the degree of an expression is its degree in the cells
+            let actual = constraints
+                .iter()
+                .map(|c| c.degree(1, 0))
+                .max()
+                .unwrap_or(0);
+            if actual != declared {
+                return Err(CustomGateError::Degree {
+                    name,
+                    declared,
+                    actual,
+                });
+            }
+
+            // the constraints are multiplied by the selector of the gate, which is not evaluated,
+            // so they must be linear in the other columns that are not evaluated
+            let evaluated = linearization_columns::<F>(None);
+            if constraints.iter().any(|c| {
+                (index(GateType::Custom0) * c.clone())
+                    .linearize(evaluated.clone())
+                    .is_err()
+            }) {
+                return Err(CustomGateError::Linearization(name));
+            }
+
+            if !(1..=2).contains(&gate.rows()) {
+                return Err(CustomGateError::UnsupportedRows(name, gate.rows()));
+            }
+        }
+
+        Ok(CustomGates(gates))
+    }
+
+    /// The number of registered custom gates
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if no custom gate is registered
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the custom gate bound to the gate type `typ`, if any
+    pub fn get(&self, typ: GateType) -> Option<&Arc<dyn CustomGate<F>>> {
+        typ.custom_slot().and_then(|slot| self.0.get(slot))
+    }
+
+    /// Returns `true` if the constraints of one of the registered custom gates
+    /// read the previous row of the witness
+    pub fn reads_prev_row(&self) -> bool {
+        self.0.iter().any(|gate| reads_prev_row(gate.as_ref()))
+    }
+
+    /// The gate types the registered custom gates are bound to
+    pub fn gate_types(&self) -> impl Iterator<Item = GateType> {
+        CUSTOM_GATE_TYPES.into_iter().take(self.0.len())
+    }
+
+    /// Checks the layout of the custom gates in the circuit `gates`:
+    /// all the custom gate types must be bound to a registered custom gate,
+    /// the gates using two rows must be followed by a zero gate,
+    /// and the gates reading the previous row cannot be on the first row
+    /// (whose previous row would be the last row of the domain, a zero-knowledge row).
+    ///
+    /// # Errors
+    ///
+    /// Will give error if one of these conditions does not hold.
+    pub fn check_layout(&self, gates: &[CircuitGate<F>]) -> Result<(), CustomGateError> {
+        for (row, gate) in gates.iter().enumerate() {
+            if gate.typ.custom_slot().is_none() {
+                continue;
+            }
+            let custom = self
+                .get(gate.typ)
+                .ok_or(CustomGateError::Unregistered(gate.typ, row))?;
+            if custom.rows() == 2
+                && !matches!(gates.get(row + 1), Some(next) if next.typ == GateType::Zero)
+            {
+                return Err(CustomGateError::NextRow(custom.name().to_string(), row));
+            }
+            if row == 0 && reads_prev_row(custom.as_ref()) {
+                return Err(CustomGateError::PrevRow(custom.name().to_string(), row));
+            }
+        }
+        Ok(())
+    }
+
+    /// Get the combined constraints of the custom gate bound to `typ`
+    ///
+    /// # Panics
+    ///
+    /// Will panic if no custom gate is bound to `typ`.
+    pub fn circuit_gate_constraints(&self, typ: GateType, alphas: &Alphas<F>) -> E<F> {
+        let gate = self
+            .get(typ)
+            .unwrap_or_else(|| panic!("no custom gate is bound to {typ:?}"));
+        let constraints = gate.constraints(&ArgumentEnv::default());
+        let alphas = alphas.get_exponents(ArgumentType::Gate(typ), gate.constraint_count());
+        index(typ) * E::combine_constraints(alphas, constraints)
+    }
+}
+
+impl<F: PrimeField> CircuitGate<F> {
+    /// Verifies the witness of a custom gate against its constraints
+    ///
+    /// # Errors
+    ///
+    /// Will give error if `self.typ` is not bound to a custom gate registered on `cs`,
+    /// or if one of the constraints is not satisfied.
+    pub fn verify_custom<G: KimchiCurve<ScalarField = F>>(
+        &self,
+        row: usize,
+        witness: &[Vec<F>; COLUMNS],
+        cs: &ConstraintSystem<F>,
+    ) -> CircuitGateResult<()> {
+        if cs.custom_gates.get(self.typ).is_none() {
+            return Err(CircuitGateError::InvalidCircuitGateType(self.typ));
+        }
+
+        // the constraints of the gate are checked with the other gates
+        self.verify_witness::<G>(row, witness, cs, &[])
+    }
+}
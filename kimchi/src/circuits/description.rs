@@ -0,0 +1,134 @@
+//! This module implements a JSON export of constraint systems, describing their gates,
+//! wiring, coefficients and lookup tables, so that the circuit a verifier index corresponds to
+//! can be inspected by external tooling and compared across versions.
+
+use crate::circuits::{
+    constraints::ConstraintSystem,
+    gate::CircuitGate,
+    lookup::{runtime_tables::RuntimeTableSpec, tables::TableRegistry},
+    wires::PERMUTS,
+};
+use ark_ff::PrimeField;
+use ark_poly::{univariate::DensePolynomial as DP, EvaluationDomain};
+use num_bigint::BigUint;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_with::serde_as;
+
+/// The lookup tables of a constraint system, as committed to in its verifier index
+#[serde_as]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LookupTablesDescription<F: PrimeField> {
+    /// The IDs of the lookup tables, with their origin (built-in, fixed or runtime)
+    pub table_registry: TableRegistry,
+    /// The columns of the concatenated lookup table, with one entry per row of the domain
+    #[serde_as(as = "Vec<Vec<o1_utils::serialization::SerdeAs>>")]
+    pub table: Vec<Vec<F>>,
+    /// The table ID of each row of the concatenated lookup table,
+    /// if some lookup table has a non-zero ID
+    #[serde_as(as = "Option<Vec<o1_utils::serialization::SerdeAs>>")]
+    pub table_ids: Option<Vec<F>>,
+    /// The runtime tables, with their IDs and lengths
+    pub runtime_tables: Option<Vec<RuntimeTableSpec>>,
+    /// The offset of the runtime tables within the concatenated lookup table
+    pub runtime_table_offset: Option<usize>,
+}
+
+/// The description of a constraint system: everything that defines its circuit,
+/// without the polynomials derived from it.
+#[serde_as]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CircuitDescription<F: PrimeField> {
+    /// number of public inputs
+    pub public: usize,
+    /// number of public outputs, which are the last of the `public` public values
+    pub public_output: usize,
+    /// number of previous evaluation challenges, for recursive proving
+    pub prev_challenges: usize,
+    /// size of the domain
+    pub domain_size: usize,
+    /// number of rows at the end of the domain reserved for zero-knowledge
+    pub zk_rows: u64,
+    /// circuit gates, with their types, wiring and coefficients
+    #[serde(bound = "CircuitGate<F>: Serialize + DeserializeOwned")]
+    pub gates: Vec<CircuitGate<F>>,
+    /// wire coordinate shifts
+    #[serde_as(as = "[o1_utils::serialization::SerdeAs; PERMUTS]")]
+    pub shift: [F; PERMUTS],
+    /// coefficient for the group endomorphism
+    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
+    pub endo: F,
+    /// foreign field modulus
+    pub foreign_field_modulus: Option<BigUint>,
+    /// names of the custom gates, in the order of their registration
+    pub custom_gates: Vec<String>,
+    /// witness columns whose cells are all checked to be boolean
+    pub boolean_columns: Vec<usize>,
+    /// lookup tables, if the circuit uses lookups
+    #[serde(bound = "LookupTablesDescription<F>: Serialize + DeserializeOwned")]
+    pub lookup: Option<LookupTablesDescription<F>>,
+}
+
+impl<F: PrimeField> CircuitDescription<F> {
+    /// Exports the description to JSON
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the description cannot be serialized, which does not happen for JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("the description is serializable to JSON")
+    }
+
+    /// Loads a description exported by [CircuitDescription::to_json] or [ConstraintSystem::to_json]
+    ///
+    /// # Errors
+    ///
+    /// Will give error if `json` is not the JSON export of a description.
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+}
+
+impl<F: PrimeField> ConstraintSystem<F> {
+    /// The description of the circuit of this constraint system:
+    /// its gates, wiring, coefficients and lookup tables
+    pub fn description(&self) -> CircuitDescription<F> {
+        let d1 = self.domain.d1;
+
+        let lookup = self.lookup_constraint_system.as_ref().map(|lcs| {
+            let evals = |poly: &DP<F>| poly.evaluate_over_domain_by_ref(d1).evals;
+            LookupTablesDescription {
+                table_registry: lcs.table_registry.clone(),
+                table: lcs.lookup_table.iter().map(evals).collect(),
+                table_ids: lcs.table_ids.as_ref().map(evals),
+                runtime_tables: lcs.runtime_tables.clone(),
+                runtime_table_offset: lcs.runtime_table_offset,
+            }
+        });
+
+        CircuitDescription {
+            public: self.public,
+            public_output: self.public_output,
+            prev_challenges: self.prev_challenges,
+            domain_size: d1.size(),
+            zk_rows: self.zk_rows,
+            gates: self.gates.clone(),
+            shift: self.shift,
+            endo: self.endo,
+            foreign_field_modulus: self.foreign_field_modulus.clone(),
+            custom_gates: self
+                .custom_gates
+                .gate_types()
+                .filter_map(|typ| self.custom_gates.get(typ))
+                .map(|gate| gate.name().to_string())
+                .collect(),
+            boolean_columns: self.boolean_columns.clone(),
+            lookup,
+        }
+    }
+
+    /// Exports the description of the circuit of this constraint system to JSON
+    /// (see [ConstraintSystem::description]), which can be loaded with [CircuitDescription::from_json]
+    pub fn to_json(&self) -> String {
+        self.description().to_json()
+    }
+}
@@ -53,6 +53,43 @@ impl<F: FftField> EvaluationDomains<F> {
     }
 }
 
+/// The coset `shift * <omega>` of an evaluation domain of generator `omega`.
+/// Its elements `shift * omega^i` are computed once and cached,
+/// as they are used repeatedly by the permutation argument.
+#[derive(Debug, Clone)]
+pub struct CosetDomain<F> {
+    shift: F,
+    elements: Vec<F>,
+}
+
+impl<F: FftField> CosetDomain<F> {
+    /// Creates the coset `shift * <omega>` of `domain`.
+    pub fn new(domain: Domain<F>, shift: F) -> Self {
+        let elements = domain.elements().map(|elm| shift * elm).collect();
+        CosetDomain { shift, elements }
+    }
+
+    /// The element multiplying the domain to obtain the coset.
+    pub fn shift(&self) -> F {
+        self.shift
+    }
+
+    /// The number of elements of the coset.
+    pub fn size(&self) -> usize {
+        self.elements.len()
+    }
+
+    /// The element `shift * omega^i`.
+    pub fn element(&self, i: usize) -> F {
+        self.elements[i % self.elements.len()]
+    }
+
+    /// The elements `shift * omega^i` of the coset, in order.
+    pub fn elements(&self) -> &[F] {
+        &self.elements
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -72,4 +109,21 @@ mod tests {
             println!("d1 = {:?}", d.d1.group_gen);
         }
     }
+
+    #[test]
+    fn test_coset_domain() {
+        let domain = Domain::<Fp>::new(16).unwrap();
+        let shift = Fp::from(7u32);
+        let coset = CosetDomain::new(domain, shift);
+
+        assert_eq!(coset.shift(), shift);
+        assert_eq!(coset.size(), domain.size());
+        for (i, elm) in domain.elements().enumerate() {
+            assert_eq!(coset.element(i), shift * elm);
+            assert_eq!(coset.elements()[i], shift * elm);
+        }
+
+        // indices wrap around, as powers of the generator do
+        assert_eq!(coset.element(domain.size() + 3), coset.element(3));
+    }
 }
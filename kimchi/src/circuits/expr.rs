@@ -3,9 +3,10 @@ use crate::{
         domains::EvaluationDomains,
         gate::{CurrOrNext, GateType},
         lookup::{index::LookupSelectors, lookups::LookupPattern},
-        polynomials::permutation::eval_vanishes_on_last_4_rows,
+        polynomials::permutation::eval_vanishes_on_zero_knowledge_and_previous_rows,
         wires::COLUMNS,
     },
+    parallel::prelude::*,
     proof::ProofEvaluations,
 };
 use ark_ff::{FftField, Field, One, PrimeField, Zero};
@@ -15,16 +16,16 @@ use ark_poly::{
 use itertools::Itertools;
 use num_bigint::BigUint;
 use o1_utils::{FieldHelpers, ForeignElement};
-use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::ops::{Add, AddAssign, Mul, Neg, Sub};
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
+    hash::Hash,
     ops::MulAssign,
 };
 use std::{fmt, iter::FromIterator};
 use thiserror::Error;
-use CurrOrNext::{Curr, Next};
+use CurrOrNext::{Curr, Next, Prev};
 
 use self::constraints::ExprOps;
 
@@ -50,6 +51,7 @@ pub enum ExprError {
 }
 
 /// The collection of constants required to evaluate an `Expr`.
+#[derive(Clone)]
 pub struct Constants<F: 'static> {
     /// The challenge alpha from the PLONK IOP.
     pub alpha: F,
@@ -66,11 +68,14 @@ pub struct Constants<F: 'static> {
     pub mds: &'static Vec<Vec<F>>,
     /// The modulus for foreign field operations
     pub foreign_field_modulus: Option<BigUint>,
+    /// The number of zero-knowledge rows at the end of the domain
+    pub zk_rows: u64,
 }
 
 /// The polynomials specific to the lookup argument.
 ///
 /// All are evaluations over the D8 domain
+#[derive(Clone)]
 pub struct LookupEnvironment<'a, F: FftField> {
     /// The sorted lookup table polynomials.
     pub sorted: &'a Vec<Evaluations<F, D<F>>>,
@@ -95,8 +100,8 @@ pub struct Environment<'a, F: FftField> {
     pub witness: &'a [Evaluations<F, D<F>>; COLUMNS],
     /// The coefficient column polynomials
     pub coefficient: &'a [Evaluations<F, D<F>>; COLUMNS],
-    /// The polynomial which vanishes on the last 4 elements of the domain.
-    pub vanishes_on_last_4_rows: &'a Evaluations<F, D<F>>,
+    /// The polynomial which vanishes on the last `zk_rows + 1` elements of the domain.
+    pub vanishes_on_zero_knowledge_and_previous_rows: &'a Evaluations<F, D<F>>,
     /// The permutation aggregation polynomial.
     pub z: &'a Evaluations<F, D<F>>,
     /// The index selector polynomials.
@@ -222,11 +227,11 @@ impl Column {
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 /// A type representing a variable which can appear in a constraint. It specifies a column
-/// and a relative position (Curr or Next)
+/// and a relative position (Curr, Next, or Prev for the witness columns)
 pub struct Variable {
     /// The column of this variable
     pub col: Column,
-    /// The row (Curr of Next) of this variable
+    /// The row (Curr, Next or Prev) of this variable
     pub row: CurrOrNext,
 }
 
@@ -240,6 +245,7 @@ impl Variable {
         match self.row {
             Curr => col,
             Next => format!("\\tilde{{{col}}}"),
+            Prev => format!("\\hat{{{col}}}"),
         }
     }
 
@@ -248,11 +254,12 @@ impl Variable {
         match self.row {
             Curr => format!("Curr({col})"),
             Next => format!("Next({col})"),
+            Prev => format!("Prev({col})"),
         }
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 /// An arithmetic expression over
 ///
 /// - the operations *, +, -, ^
@@ -413,8 +420,71 @@ impl Cache {
     }
 }
 
+/// The default number of rows evaluated together by [Expr::evaluations_chunked]
+pub const DEFAULT_EVALUATION_CHUNK_SIZE: usize = 1 << 12;
+
+/// Statistics of the memoization cache of [Expr::evaluations_chunked]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EvaluationCacheStats {
+    /// The number of evaluations of cached sub-expressions that were reused
+    pub hits: usize,
+    /// The number of evaluations of cached sub-expressions that were computed
+    pub misses: usize,
+}
+
+impl AddAssign for EvaluationCacheStats {
+    fn add_assign(&mut self, other: Self) {
+        self.hits += other.hits;
+        self.misses += other.misses;
+    }
+}
+
+/// The evaluations of cached sub-expressions, keyed by sub-expression and chunk of rows
+struct EvaluationCache<F> {
+    evals: HashMap<(CacheId, usize), Vec<F>>,
+    stats: EvaluationCacheStats,
+}
+
+impl<F> Default for EvaluationCache<F> {
+    fn default() -> Self {
+        EvaluationCache {
+            evals: HashMap::new(),
+            stats: EvaluationCacheStats::default(),
+        }
+    }
+}
+
+impl<F> EvaluationCache<F> {
+    /// Drops the evaluations of the other chunks, which will not be used again.
+    fn start_chunk(&mut self, chunk: usize) {
+        self.evals.retain(|(_, c), _| *c == chunk);
+    }
+}
+
+/// The rows of a chunk, and what is needed to evaluate an expression on them
+struct ChunkContext<'a, 'b, F: FftField> {
+    env: &'b Environment<'a, F>,
+    domain: Domain,
+    chunk: usize,
+    rows: std::ops::Range<usize>,
+    lagrange: &'b HashMap<i32, Evaluations<F, D<F>>>,
+}
+
+impl<'a, 'b, F: FftField> ChunkContext<'a, 'b, F> {
+    /// The evaluations of the chunk, read from `evals` (over `domain`) shifted by `shift` rows.
+    fn sub_evals(&self, evals: &Evaluations<F, D<F>>, domain: Domain, shift: usize) -> Vec<F> {
+        let scale = (domain as usize) / (self.domain as usize);
+        assert!(scale != 0);
+        let len = evals.evals.len();
+        self.rows
+            .clone()
+            .map(|i| evals.evals[(scale * i + (domain as usize) * shift) % len])
+            .collect()
+    }
+}
+
 /// A binary operation
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Op2 {
     Add,
     Mul,
@@ -436,7 +506,8 @@ impl Op2 {
 /// variables
 ///
 /// - `Cell(v)` for `v : Variable`
-/// - VanishesOnLast4Rows
+/// - VanishesOnZeroKnowledgeAndPreviousRows, which vanishes on the `zk_rows` zero-knowledge rows
+///   and on the row before them
 /// - UnnormalizedLagrangeBasis(i) for `i : i32`
 ///
 /// This represents a PLONK "custom constraint", which enforces that
@@ -449,7 +520,7 @@ pub enum Expr<C> {
     Double(Box<Expr<C>>),
     Square(Box<Expr<C>>),
     BinOp(Op2, Box<Expr<C>>, Box<Expr<C>>),
-    VanishesOnLast4Rows,
+    VanishesOnZeroKnowledgeAndPreviousRows,
     /// UnnormalizedLagrangeBasis(i) is
     /// (x^n - 1) / (x - omega^i)
     UnnormalizedLagrangeBasis(i32),
@@ -476,16 +547,25 @@ pub enum PolishToken<F> {
     Add,
     Mul,
     Sub,
-    VanishesOnLast4Rows,
+    VanishesOnZeroKnowledgeAndPreviousRows,
     UnnormalizedLagrangeBasis(i32),
     Store,
     Load(usize),
 }
 
 impl Variable {
+    /// Evaluates the variable from the evaluations at `zeta`, at `zeta * omega`,
+    /// and optionally at `zeta / omega`, of which only the witness columns are used
     fn evaluate<F: Field>(&self, evals: &[ProofEvaluations<F>]) -> Result<F, ExprError> {
-        let evals = &evals[self.row.shift()];
         use Column::*;
+        let evals = match self.row {
+            Curr => &evals[0],
+            Next => &evals[1],
+            Prev => match (self.col, evals.get(2)) {
+                (Witness(_), Some(evals)) => evals,
+                _ => return Err(ExprError::MissingEvaluation(self.col, self.row)),
+            },
+        };
         let l = evals
             .lookup
             .as_ref()
@@ -534,7 +614,9 @@ impl<F: FftField> PolishToken<F> {
                         stack.push(ForeignElement::<F, 3>::from_biguint(modulus.clone())[*i])
                     }
                 }
-                VanishesOnLast4Rows => stack.push(eval_vanishes_on_last_4_rows(d, pt)),
+                VanishesOnZeroKnowledgeAndPreviousRows => stack.push(
+                    eval_vanishes_on_zero_knowledge_and_previous_rows(d, c.zk_rows, pt),
+                ),
                 UnnormalizedLagrangeBasis(i) => {
                     stack.push(unnormalized_lagrange_basis(&d, *i, &pt))
                 }
@@ -595,25 +677,174 @@ impl<C> Expr<C> {
         Expr::Constant(c)
     }
 
-    fn degree(&self, d1_size: u64) -> u64 {
+    pub(crate) fn degree(&self, d1_size: u64, zk_rows: u64) -> u64 {
         use Expr::*;
         match self {
-            Double(x) => x.degree(d1_size),
+            Double(x) => x.degree(d1_size, zk_rows),
             Constant(_) => 0,
-            VanishesOnLast4Rows => 4,
+            VanishesOnZeroKnowledgeAndPreviousRows => zk_rows + 1,
             UnnormalizedLagrangeBasis(_) => d1_size,
             Cell(_) => d1_size,
-            Square(x) => 2 * x.degree(d1_size),
-            BinOp(Op2::Mul, x, y) => (*x).degree(d1_size) + (*y).degree(d1_size),
+            Square(x) => 2 * x.degree(d1_size, zk_rows),
+            BinOp(Op2::Mul, x, y) => (*x).degree(d1_size, zk_rows) + (*y).degree(d1_size, zk_rows),
             BinOp(Op2::Add, x, y) | BinOp(Op2::Sub, x, y) => {
-                std::cmp::max((*x).degree(d1_size), (*y).degree(d1_size))
+                std::cmp::max((*x).degree(d1_size, zk_rows), (*y).degree(d1_size, zk_rows))
+            }
+            Pow(e, d) => d * e.degree(d1_size, zk_rows),
+            Cache(_, e) => e.degree(d1_size, zk_rows),
+        }
+    }
+
+    /// Whether the expression reads the previous row of the witness
+    pub(crate) fn reads_prev_row(&self) -> bool {
+        use Expr::*;
+        match self {
+            Cell(Variable { row, .. }) => matches!(row, Prev),
+            Constant(_) | VanishesOnZeroKnowledgeAndPreviousRows | UnnormalizedLagrangeBasis(_) => {
+                false
             }
-            Pow(e, d) => d * e.degree(d1_size),
-            Cache(_, e) => e.degree(d1_size),
+            Double(x) | Square(x) | Pow(x, _) | Cache(_, x) => x.reads_prev_row(),
+            BinOp(_, x, y) => x.reads_prev_row() || y.reads_prev_row(),
         }
     }
 }
 
+/// A node of an expression, identified by the nodes of its operands (see [SharedNodes])
+#[derive(PartialEq, Eq, Hash)]
+enum NodeKey<'e, C> {
+    Constant(&'e C),
+    Cell(Variable),
+    Double(usize),
+    Square(usize),
+    BinOp(Op2, usize, usize),
+    VanishesOnZeroKnowledgeAndPreviousRows,
+    UnnormalizedLagrangeBasis(i32),
+    Pow(usize, u64),
+    Cache(CacheId, usize),
+}
+
+/// The distinct sub-expressions of an expression, with the identical ones merged
+struct SharedNodes<'e, C> {
+    ids: HashMap<NodeKey<'e, C>, usize>,
+    /// A sub-expression of each node, and the nodes of its operands
+    nodes: Vec<(&'e Expr<C>, Vec<usize>)>,
+    /// The number of distinct nodes each node is an operand of
+    uses: Vec<usize>,
+    /// The first id not used by the cached sub-expressions of the expression
+    next_cache_id: usize,
+}
+
+impl<'e, C: Clone + Eq + Hash> SharedNodes<'e, C> {
+    fn new() -> Self {
+        SharedNodes {
+            ids: HashMap::new(),
+            nodes: vec![],
+            uses: vec![],
+            next_cache_id: 0,
+        }
+    }
+
+    /// Returns the node of `e`, creating it (and the nodes of its operands) if new.
+    fn intern(&mut self, e: &'e Expr<C>) -> usize {
+        use Expr::*;
+        let (key, operands) = match e {
+            Constant(c) => (NodeKey::Constant(c), vec![]),
+            Cell(v) => (NodeKey::Cell(*v), vec![]),
+            Double(x) => {
+                let x = self.intern(x);
+                (NodeKey::Double(x), vec![x])
+            }
+            Square(x) => {
+                let x = self.intern(x);
+                (NodeKey::Square(x), vec![x])
+            }
+            BinOp(op, x, y) => {
+                let x = self.intern(x);
+                let y = self.intern(y);
+                (NodeKey::BinOp(op.clone(), x, y), vec![x, y])
+            }
+            VanishesOnZeroKnowledgeAndPreviousRows => {
+                (NodeKey::VanishesOnZeroKnowledgeAndPreviousRows, vec![])
+            }
+            UnnormalizedLagrangeBasis(i) => (NodeKey::UnnormalizedLagrangeBasis(*i), vec![]),
+            Pow(x, p) => {
+                let x = self.intern(x);
+                (NodeKey::Pow(x, *p), vec![x])
+            }
+            Cache(id, x) => {
+                self.next_cache_id = std::cmp::max(self.next_cache_id, id.0 + 1);
+                let x = self.intern(x);
+                (NodeKey::Cache(*id, x), vec![x])
+            }
+        };
+
+        if let Some(node) = self.ids.get(&key) {
+            return *node;
+        }
+        let node = self.nodes.len();
+        for operand in &operands {
+            self.uses[*operand] += 1;
+        }
+        self.nodes.push((e, operands));
+        self.uses.push(0);
+        self.ids.insert(key, node);
+        node
+    }
+
+    /// Rebuilds the expression of `node`, with the nodes used several times cached.
+    fn rebuild(&mut self, node: usize, shared: &mut HashMap<usize, Expr<C>>) -> Expr<C> {
+        use Expr::*;
+        if let Some(e) = shared.get(&node) {
+            return e.clone();
+        }
+
+        let (e, operands) = self.nodes[node].clone();
+        let mut operand = |i: usize| Box::new(self.rebuild(operands[i], shared));
+        let rebuilt = match e {
+            Double(_) => Double(operand(0)),
+            Square(_) => Square(operand(0)),
+            BinOp(op, _, _) => BinOp(op.clone(), operand(0), operand(1)),
+            Pow(_, p) => Pow(operand(0), *p),
+            Cache(id, _) => Cache(*id, operand(0)),
+            Constant(_)
+            | Cell(_)
+            | VanishesOnZeroKnowledgeAndPreviousRows
+            | UnnormalizedLagrangeBasis(_) => e.clone(),
+        };
+
+        // the leaves are cheap to evaluate, and the cached nodes are already shared
+        let is_shared = match rebuilt {
+            Constant(_)
+            | Cell(_)
+            | VanishesOnZeroKnowledgeAndPreviousRows
+            | UnnormalizedLagrangeBasis(_) => false,
+            Cache(_, _) => false,
+            _ => self.uses[node] > 1,
+        };
+        if !is_shared {
+            return rebuilt;
+        }
+        let id = CacheId(self.next_cache_id);
+        self.next_cache_id += 1;
+        let rebuilt = Cache(id, Box::new(rebuilt));
+        shared.insert(node, rebuilt.clone());
+        rebuilt
+    }
+}
+
+impl<C: Clone + Eq + Hash> Expr<C> {
+    /// Caches the sub-expressions of this expression that occur several times (see [Cache]),
+    /// so that they are evaluated once by [Expr::evaluations] and [Expr::evaluations_chunked].
+    ///
+    /// The cached sub-expressions already present are kept as they are,
+    /// and the new ones are given ids that they do not use.
+    pub fn share_subexpressions(&self) -> Self {
+        let mut nodes = SharedNodes::new();
+        let root = nodes.intern(self);
+        nodes.rebuild(root, &mut HashMap::new())
+    }
+}
+
 impl<F> fmt::Display for Expr<ConstantExpr<F>>
 where
     F: PrimeField,
@@ -1229,8 +1460,8 @@ impl<F: FftField> Expr<ConstantExpr<F>> {
                 c.to_polish_(res);
             }
             Expr::Cell(v) => res.push(PolishToken::Cell(*v)),
-            Expr::VanishesOnLast4Rows => {
-                res.push(PolishToken::VanishesOnLast4Rows);
+            Expr::VanishesOnZeroKnowledgeAndPreviousRows => {
+                res.push(PolishToken::VanishesOnZeroKnowledgeAndPreviousRows);
             }
             Expr::UnnormalizedLagrangeBasis(i) => {
                 res.push(PolishToken::UnnormalizedLagrangeBasis(*i));
@@ -1272,7 +1503,7 @@ impl<F: FftField> Expr<ConstantExpr<F>> {
             Square(x) => x.evaluate_constants_(c).square(),
             Constant(x) => Constant(x.value(c)),
             Cell(v) => Cell(*v),
-            VanishesOnLast4Rows => VanishesOnLast4Rows,
+            VanishesOnZeroKnowledgeAndPreviousRows => VanishesOnZeroKnowledgeAndPreviousRows,
             UnnormalizedLagrangeBasis(i) => UnnormalizedLagrangeBasis(*i),
             BinOp(Op2::Add, x, y) => x.evaluate_constants_(c) + y.evaluate_constants_(c),
             BinOp(Op2::Mul, x, y) => x.evaluate_constants_(c) * y.evaluate_constants_(c),
@@ -1321,7 +1552,9 @@ impl<F: FftField> Expr<ConstantExpr<F>> {
                 let y = (*y).evaluate_(d, pt, evals, c)?;
                 Ok(x - y)
             }
-            VanishesOnLast4Rows => Ok(eval_vanishes_on_last_4_rows(d, pt)),
+            VanishesOnZeroKnowledgeAndPreviousRows => Ok(
+                eval_vanishes_on_zero_knowledge_and_previous_rows(d, c.zk_rows, pt),
+            ),
             UnnormalizedLagrangeBasis(i) => Ok(unnormalized_lagrange_basis(&d, *i, &pt)),
             Cell(v) => v.evaluate(evals),
             Cache(_, e) => e.evaluate_(d, pt, evals, c),
@@ -1337,6 +1570,17 @@ impl<F: FftField> Expr<ConstantExpr<F>> {
     pub fn evaluations<'a>(&self, env: &Environment<'a, F>) -> Evaluations<F, D<F>> {
         self.evaluate_constants(env).evaluations(env)
     }
+
+    /// Compute the polynomial corresponding to this expression, in evaluation form,
+    /// `chunk_size` rows at a time (see [Expr::evaluations_chunked]).
+    pub fn evaluations_chunked<'a>(
+        &self,
+        env: &Environment<'a, F>,
+        chunk_size: usize,
+    ) -> (Evaluations<F, D<F>>, EvaluationCacheStats) {
+        self.evaluate_constants(env)
+            .evaluations_chunked(env, chunk_size)
+    }
 }
 
 enum Either<A, B> {
@@ -1346,40 +1590,48 @@ enum Either<A, B> {
 
 impl<F: FftField> Expr<F> {
     /// Evaluate an expression into a field element.
-    pub fn evaluate(&self, d: D<F>, pt: F, evals: &[ProofEvaluations<F>]) -> Result<F, ExprError> {
+    pub fn evaluate(
+        &self,
+        d: D<F>,
+        pt: F,
+        zk_rows: u64,
+        evals: &[ProofEvaluations<F>],
+    ) -> Result<F, ExprError> {
         use Expr::*;
         match self {
             Constant(x) => Ok(*x),
-            Pow(x, p) => Ok(x.evaluate(d, pt, evals)?.pow(&[*p as u64])),
-            Double(x) => x.evaluate(d, pt, evals).map(|x| x.double()),
-            Square(x) => x.evaluate(d, pt, evals).map(|x| x.square()),
+            Pow(x, p) => Ok(x.evaluate(d, pt, zk_rows, evals)?.pow(&[*p as u64])),
+            Double(x) => x.evaluate(d, pt, zk_rows, evals).map(|x| x.double()),
+            Square(x) => x.evaluate(d, pt, zk_rows, evals).map(|x| x.square()),
             BinOp(Op2::Mul, x, y) => {
-                let x = (*x).evaluate(d, pt, evals)?;
-                let y = (*y).evaluate(d, pt, evals)?;
+                let x = (*x).evaluate(d, pt, zk_rows, evals)?;
+                let y = (*y).evaluate(d, pt, zk_rows, evals)?;
                 Ok(x * y)
             }
             BinOp(Op2::Add, x, y) => {
-                let x = (*x).evaluate(d, pt, evals)?;
-                let y = (*y).evaluate(d, pt, evals)?;
+                let x = (*x).evaluate(d, pt, zk_rows, evals)?;
+                let y = (*y).evaluate(d, pt, zk_rows, evals)?;
                 Ok(x + y)
             }
             BinOp(Op2::Sub, x, y) => {
-                let x = (*x).evaluate(d, pt, evals)?;
-                let y = (*y).evaluate(d, pt, evals)?;
+                let x = (*x).evaluate(d, pt, zk_rows, evals)?;
+                let y = (*y).evaluate(d, pt, zk_rows, evals)?;
                 Ok(x - y)
             }
-            VanishesOnLast4Rows => Ok(eval_vanishes_on_last_4_rows(d, pt)),
+            VanishesOnZeroKnowledgeAndPreviousRows => Ok(
+                eval_vanishes_on_zero_knowledge_and_previous_rows(d, zk_rows, pt),
+            ),
             UnnormalizedLagrangeBasis(i) => Ok(unnormalized_lagrange_basis(&d, *i, &pt)),
             Cell(v) => v.evaluate(evals),
-            Cache(_, e) => e.evaluate(d, pt, evals),
+            Cache(_, e) => e.evaluate(d, pt, zk_rows, evals),
         }
     }
 
-    /// Compute the polynomial corresponding to this expression, in evaluation form.
-    pub fn evaluations<'a>(&self, env: &Environment<'a, F>) -> Evaluations<F, D<F>> {
+    /// The smallest domain on which the evaluations of this expression determine it.
+    fn evaluation_domain(&self, env: &Environment<F>) -> Domain {
         let d1_size = env.domain.d1.size;
-        let deg = self.degree(d1_size);
-        let d = if deg <= d1_size {
+        let deg = self.degree(d1_size, env.constants.zk_rows);
+        if deg <= d1_size {
             Domain::D1
         } else if deg <= 4 * d1_size {
             Domain::D4
@@ -1387,7 +1639,12 @@ impl<F: FftField> Expr<F> {
             Domain::D8
         } else {
             panic!("constraint had degree {deg} > d8 ({})", 8 * d1_size);
-        };
+        }
+    }
+
+    /// Compute the polynomial corresponding to this expression, in evaluation form.
+    pub fn evaluations<'a>(&self, env: &Environment<'a, F>) -> Evaluations<F, D<F>> {
+        let d = self.evaluation_domain(env);
 
         let mut cache = HashMap::new();
 
@@ -1417,6 +1674,146 @@ impl<F: FftField> Expr<F> {
         }
     }
 
+    /// Compute the polynomial corresponding to this expression, in evaluation form,
+    /// `chunk_size` rows at a time.
+    ///
+    /// Contrary to [Expr::evaluations], which materializes every intermediate result over the
+    /// whole domain, the rows are split into chunks evaluated in parallel, and the evaluations
+    /// of the cached sub-expressions (see [Cache]) are memoized per chunk.
+    /// This bounds the memory used by intermediate results to a few chunks per thread.
+    ///
+    /// Also returns statistics on the memoization cache, for profiling.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `chunk_size` is zero, or if the degree of the expression is too high.
+    pub fn evaluations_chunked<'a>(
+        &self,
+        env: &Environment<'a, F>,
+        chunk_size: usize,
+    ) -> (Evaluations<F, D<F>>, EvaluationCacheStats) {
+        assert!(chunk_size > 0, "chunk size must be positive");
+        let d = self.evaluation_domain(env);
+        let domain = get_domain(d, env);
+
+        // the Lagrange basis polynomials are computed over the whole domain only once
+        let mut lagrange_indices = HashSet::new();
+        self.lagrange_indices(&mut lagrange_indices);
+        let lagrange = lagrange_indices
+            .into_iter()
+            .map(|i| (i, unnormalized_lagrange_evals(env.l0_1, i, d, env)))
+            .collect();
+
+        let size = domain.size();
+        let chunks: Vec<_> = (0..size)
+            .step_by(chunk_size)
+            .enumerate()
+            .map(|(chunk, start)| (chunk, start..std::cmp::min(start + chunk_size, size)))
+            .collect();
+
+        let results: Vec<_> = chunks
+            .into_par_iter()
+            .map_init(EvaluationCache::default, |cache, (chunk, rows)| {
+                cache.start_chunk(chunk);
+                let ctx = ChunkContext {
+                    env,
+                    domain: d,
+                    chunk,
+                    rows,
+                    lagrange: &lagrange,
+                };
+                let evals = self.chunk_evaluations(&ctx, cache);
+                (evals, std::mem::take(&mut cache.stats))
+            })
+            .collect();
+
+        let mut stats = EvaluationCacheStats::default();
+        let mut evals = Vec::with_capacity(size);
+        for (chunk_evals, chunk_stats) in results {
+            evals.extend(chunk_evals);
+            stats += chunk_stats;
+        }
+
+        (Evaluations::from_vec_and_domain(evals, domain), stats)
+    }
+
+    /// Collects the indices of the Lagrange basis polynomials used by this expression.
+    fn lagrange_indices(&self, acc: &mut HashSet<i32>) {
+        match self {
+            Expr::UnnormalizedLagrangeBasis(i) => {
+                acc.insert(*i);
+            }
+            Expr::Double(x) | Expr::Square(x) | Expr::Pow(x, _) | Expr::Cache(_, x) => {
+                x.lagrange_indices(acc)
+            }
+            Expr::BinOp(_, x, y) => {
+                x.lagrange_indices(acc);
+                y.lagrange_indices(acc);
+            }
+            Expr::Constant(_) | Expr::Cell(_) | Expr::VanishesOnZeroKnowledgeAndPreviousRows => (),
+        }
+    }
+
+    /// Evaluates this expression on the rows of a chunk.
+    fn chunk_evaluations(&self, ctx: &ChunkContext<F>, cache: &mut EvaluationCache<F>) -> Vec<F> {
+        match self {
+            Expr::Constant(x) => vec![*x; ctx.rows.len()],
+            Expr::Cell(Variable { col, row }) => match ctx.env.get_column(col) {
+                None => vec![F::zero(); ctx.rows.len()],
+                Some(evals) => {
+                    ctx.sub_evals(evals, col.domain(), row.rotation(ctx.env.domain.d1.size()))
+                }
+            },
+            Expr::VanishesOnZeroKnowledgeAndPreviousRows => ctx.sub_evals(
+                ctx.env.vanishes_on_zero_knowledge_and_previous_rows,
+                Domain::D8,
+                0,
+            ),
+            Expr::UnnormalizedLagrangeBasis(i) => ctx.lagrange[i].evals[ctx.rows.clone()].to_vec(),
+            Expr::Double(x) => {
+                let mut evals = x.chunk_evaluations(ctx, cache);
+                evals.iter_mut().for_each(|x| {
+                    x.double_in_place();
+                });
+                evals
+            }
+            Expr::Square(x) => {
+                let mut evals = x.chunk_evaluations(ctx, cache);
+                evals.iter_mut().for_each(|x| {
+                    x.square_in_place();
+                });
+                evals
+            }
+            Expr::Pow(x, p) => {
+                let mut evals = x.chunk_evaluations(ctx, cache);
+                evals.iter_mut().for_each(|x| *x = x.pow(&[*p]));
+                evals
+            }
+            Expr::BinOp(op, x, y) => {
+                let mut evals = x.chunk_evaluations(ctx, cache);
+                let y = y.chunk_evaluations(ctx, cache);
+                let zipped = evals.iter_mut().zip(y);
+                match op {
+                    Op2::Add => zipped.for_each(|(x, y)| *x += y),
+                    Op2::Sub => zipped.for_each(|(x, y)| *x -= y),
+                    Op2::Mul => zipped.for_each(|(x, y)| *x *= y),
+                }
+                evals
+            }
+            Expr::Cache(id, e) => {
+                let key = (*id, ctx.chunk);
+                if let Some(evals) = cache.evals.get(&key) {
+                    cache.stats.hits += 1;
+                    return evals.clone();
+                }
+                cache.stats.misses += 1;
+                let evals = e.chunk_evaluations(ctx, cache);
+                cache.evals.insert(key, evals.clone());
+                evals
+            }
+        }
+    }
+
     fn evaluations_helper<'a, 'b>(
         &self,
         cache: &'b mut HashMap<CacheId, EvalResult<'a, F>>,
@@ -1494,10 +1891,10 @@ impl<F: FftField> Expr<F> {
                     }
                 }
             }
-            Expr::VanishesOnLast4Rows => EvalResult::SubEvals {
+            Expr::VanishesOnZeroKnowledgeAndPreviousRows => EvalResult::SubEvals {
                 domain: Domain::D8,
                 shift: 0,
-                evals: env.vanishes_on_last_4_rows,
+                evals: env.vanishes_on_zero_knowledge_and_previous_rows,
             },
             Expr::Constant(x) => EvalResult::Constant(*x),
             Expr::UnnormalizedLagrangeBasis(i) => EvalResult::Evals {
@@ -1513,7 +1910,7 @@ impl<F: FftField> Expr<F> {
                 };
                 EvalResult::SubEvals {
                     domain: col.domain(),
-                    shift: row.shift(),
+                    shift: row.rotation(env.domain.d1.size()),
                     evals,
                 }
             }
@@ -1576,6 +1973,19 @@ impl<F: FftField> Linearization<Expr<ConstantExpr<F>>> {
     }
 }
 
+impl<F> Linearization<Vec<PolishToken<F>>> {
+    /// Whether the linearization reads the previous row of the witness,
+    /// in which case the proofs open their polynomials at `zeta / omega` as well
+    pub fn reads_prev_row(&self) -> bool {
+        self.index_terms
+            .iter()
+            .map(|(_, tokens)| tokens)
+            .chain(std::iter::once(&self.constant_term))
+            .flatten()
+            .any(|token| matches!(token, PolishToken::Cell(Variable { row: Prev, .. })))
+    }
+}
+
 impl<F: FftField> Linearization<Vec<PolishToken<F>>> {
     /// Given a linearization and an environment, compute the polynomial corresponding to the
     /// linearization, in evaluation form.
@@ -1650,13 +2060,15 @@ impl<F: One> Expr<F> {
     }
 }
 
-type Monomials<F> = HashMap<Vec<Variable>, Expr<F>>;
+// The monomials are ordered, so that the linearization does not depend
+// on the iteration order of a hash map, and is the same for the same constraints
+type Monomials<F> = BTreeMap<Vec<Variable>, Expr<F>>;
 
 fn mul_monomials<F: Neg<Output = F> + Clone + One + Zero + PartialEq>(
     e1: &Monomials<F>,
     e2: &Monomials<F>,
 ) -> Monomials<F> {
-    let mut res: HashMap<_, Expr<F>> = HashMap::new();
+    let mut res: Monomials<F> = BTreeMap::new();
     for (m1, c1) in e1.iter() {
         for (m2, c2) in e2.iter() {
             let mut m = m1.clone();
@@ -1684,15 +2096,15 @@ impl<F: Neg<Output = F> + Clone + One + Zero + PartialEq> Expr<F> {
             Cell(v) => evaluated.contains(&v.col),
             Double(x) => x.is_constant(evaluated),
             BinOp(_, x, y) => x.is_constant(evaluated) && y.is_constant(evaluated),
-            VanishesOnLast4Rows => true,
+            VanishesOnZeroKnowledgeAndPreviousRows => true,
             UnnormalizedLagrangeBasis(_) => true,
             Cache(_, x) => x.is_constant(evaluated),
         }
     }
 
-    fn monomials(&self, ev: &HashSet<Column>) -> HashMap<Vec<Variable>, Expr<F>> {
+    fn monomials(&self, ev: &HashSet<Column>) -> Monomials<F> {
         let sing = |v: Vec<Variable>, c: Expr<F>| {
-            let mut h = HashMap::new();
+            let mut h = BTreeMap::new();
             h.insert(v, c);
             h
         };
@@ -1725,11 +2137,13 @@ impl<F: Neg<Output = F> + Clone + One + Zero + PartialEq> Expr<F> {
                 acc
             }
             Double(e) => {
-                HashMap::from_iter(e.monomials(ev).into_iter().map(|(m, c)| (m, c.double())))
+                BTreeMap::from_iter(e.monomials(ev).into_iter().map(|(m, c)| (m, c.double())))
             }
             Cache(_, e) => e.monomials(ev),
             UnnormalizedLagrangeBasis(i) => constant(UnnormalizedLagrangeBasis(*i)),
-            VanishesOnLast4Rows => constant(VanishesOnLast4Rows),
+            VanishesOnZeroKnowledgeAndPreviousRows => {
+                constant(VanishesOnZeroKnowledgeAndPreviousRows)
+            }
             Constant(c) => constant(Constant(c.clone())),
             Cell(var) => sing(vec![*var], Constant(F::one())),
             BinOp(Op2::Add, e1, e2) => {
@@ -1796,7 +2210,7 @@ impl<F: Neg<Output = F> + Clone + One + Zero + PartialEq> Expr<F> {
         &self,
         evaluated: HashSet<Column>,
     ) -> Result<Linearization<Expr<F>>, ExprError> {
-        let mut res: HashMap<Column, Expr<F>> = HashMap::new();
+        let mut res: BTreeMap<Column, Expr<F>> = BTreeMap::new();
         let mut constant_term: Expr<F> = Self::zero();
         let monomials = self.monomials(&evaluated);
 
@@ -1809,7 +2223,7 @@ impl<F: Neg<Output = F> + Clone + One + Zero + PartialEq> Expr<F> {
             } else if unevaluated.len() == 1 {
                 let var = unevaluated.remove(0);
                 match var.row {
-                    Next => {
+                    Next | Prev => {
                         return Err(ExprError::MissingEvaluation(var.col, var.row));
                     }
                     Curr => {
@@ -1827,7 +2241,7 @@ impl<F: Neg<Output = F> + Clone + One + Zero + PartialEq> Expr<F> {
                         // with the above that moves v out of the map with .remove and
                         // into v + c.
                         //
-                        // I'm not sure if there's a way to do it with the HashMap API
+                        // I'm not sure if there's a way to do it with the map API
                         // without calling remove.
                     }
                 }
@@ -2167,7 +2581,9 @@ where
             Constant(x) => x.ocaml(),
             Cell(v) => format!("cell({})", v.ocaml()),
             UnnormalizedLagrangeBasis(i) => format!("unnormalized_lagrange_basis({})", *i),
-            VanishesOnLast4Rows => "vanishes_on_last_4_rows".to_string(),
+            VanishesOnZeroKnowledgeAndPreviousRows => {
+                "vanishes_on_zero_knowledge_and_previous_rows".to_string()
+            }
             BinOp(Op2::Add, x, y) => format!("({} + {})", x.ocaml(cache), y.ocaml(cache)),
             BinOp(Op2::Mul, x, y) => format!("({} * {})", x.ocaml(cache), y.ocaml(cache)),
             BinOp(Op2::Sub, x, y) => format!("({} - {})", x.ocaml(cache), y.ocaml(cache)),
@@ -2208,7 +2624,9 @@ where
             Constant(x) => x.latex(),
             Cell(v) => v.latex(),
             UnnormalizedLagrangeBasis(i) => format!("unnormalized\\_lagrange\\_basis({})", *i),
-            VanishesOnLast4Rows => "vanishes\\_on\\_last\\_4\\_rows".to_string(),
+            VanishesOnZeroKnowledgeAndPreviousRows => {
+                "vanishes\\_on\\_zero\\_knowledge\\_and\\_previous\\_rows".to_string()
+            }
             BinOp(Op2::Add, x, y) => format!("({} + {})", x.latex(cache), y.latex(cache)),
             BinOp(Op2::Mul, x, y) => format!("({} \\cdot {})", x.latex(cache), y.latex(cache)),
             BinOp(Op2::Sub, x, y) => format!("({} - {})", x.latex(cache), y.latex(cache)),
@@ -2230,7 +2648,9 @@ where
             Constant(x) => x.text(),
             Cell(v) => v.text(),
             UnnormalizedLagrangeBasis(i) => format!("unnormalized_lagrange_basis({})", *i),
-            VanishesOnLast4Rows => "vanishes_on_last_4_rows".to_string(),
+            VanishesOnZeroKnowledgeAndPreviousRows => {
+                "vanishes_on_zero_knowledge_and_previous_rows".to_string()
+            }
             BinOp(Op2::Add, x, y) => format!("({} + {})", x.text(cache), y.text(cache)),
             BinOp(Op2::Mul, x, y) => format!("({} * {})", x.text(cache), y.text(cache)),
             BinOp(Op2::Sub, x, y) => format!("({} - {})", x.text(cache), y.text(cache)),
@@ -2453,6 +2873,11 @@ pub fn witness_next<F>(i: usize) -> E<F> {
     witness(i, CurrOrNext::Next)
 }
 
+/// Same as [witness] but for the previous row.
+pub fn witness_prev<F>(i: usize) -> E<F> {
+    witness(i, CurrOrNext::Prev)
+}
+
 /// Handy function to quickly create an expression for a gate.
 pub fn index<F>(g: GateType) -> E<F> {
     E::<F>::cell(Column::Index(g), CurrOrNext::Curr)
@@ -2464,7 +2889,7 @@ pub fn coeff<F>(i: usize) -> E<F> {
 
 /// You can import this module like `use kimchi::circuits::expr::prologue::*` to obtain a number of handy aliases and helpers
 pub mod prologue {
-    pub use super::{coeff, constant, index, witness, witness_curr, witness_next, E};
+    pub use super::{coeff, constant, index, witness, witness_curr, witness_next, witness_prev, E};
 }
 
 #[cfg(test)]
@@ -2481,6 +2906,7 @@ pub mod test {
         curve::KimchiCurve,
     };
     use ark_ff::UniformRand;
+    use ark_poly::UVPolynomial;
     use mina_curves::pasta::{Fp, Vesta};
     use rand::{prelude::StdRng, SeedableRng};
     use std::array;
@@ -2537,10 +2963,13 @@ pub mod test {
                 endo_coefficient: one,
                 mds: &Vesta::sponge_params().mds,
                 foreign_field_modulus: None,
+                zk_rows: ZK_ROWS,
             },
             witness: &domain_evals.d8.this.w,
             coefficient: &constraint_system.coefficients8,
-            vanishes_on_last_4_rows: &constraint_system.precomputations().vanishes_on_last_4_rows,
+            vanishes_on_zero_knowledge_and_previous_rows: &constraint_system
+                .precomputations()
+                .vanishes_on_zero_knowledge_and_previous_rows,
             z: &domain_evals.d8.this.z,
             l0_1: l0_1(constraint_system.domain.d1),
             domain: constraint_system.domain,
@@ -2552,6 +2981,143 @@ pub mod test {
         expr.evaluations(&env);
     }
 
+    #[test]
+    fn test_evaluations_chunked() {
+        let rng = &mut StdRng::from_seed([42u8; 32]);
+        let gates = (0..20)
+            .map(|row| {
+                CircuitGate::create_generic_gadget(
+                    Wire::new(row),
+                    GenericGateSpec::Const(1u32.into()),
+                    None,
+                )
+            })
+            .collect();
+        let constraint_system = ConstraintSystem::fp_for_testing(gates);
+
+        let n = constraint_system.domain.d1.size();
+        let witness_cols: [_; COLUMNS] = array::from_fn(|_| DensePolynomial::rand(n - 1, rng));
+        let permutation = DensePolynomial::rand(n - 1, rng);
+        let domain_evals = constraint_system.evaluate(&witness_cols, &permutation);
+
+        let env = Environment {
+            constants: Constants {
+                alpha: Fp::rand(rng),
+                beta: Fp::rand(rng),
+                gamma: Fp::rand(rng),
+                joint_combiner: None,
+                endo_coefficient: Fp::rand(rng),
+                mds: &Vesta::sponge_params().mds,
+                foreign_field_modulus: None,
+                zk_rows: ZK_ROWS,
+            },
+            witness: &domain_evals.d8.this.w,
+            coefficient: &constraint_system.coefficients8,
+            vanishes_on_zero_knowledge_and_previous_rows: &constraint_system
+                .precomputations()
+                .vanishes_on_zero_knowledge_and_previous_rows,
+            z: &domain_evals.d8.this.z,
+            l0_1: l0_1(constraint_system.domain.d1),
+            domain: constraint_system.domain,
+            index: HashMap::new(),
+            lookup: None,
+        };
+
+        // a cached sub-expression used three times
+        let mut cache = Cache::default();
+        let x = cache.cache(witness_curr(0) * witness_next(1) + E::constant(ConstantExpr::Alpha));
+        let expr = x.clone() * x.clone() - x * witness_curr(2)
+            + E::UnnormalizedLagrangeBasis(1) * witness_curr(3)
+            + E::VanishesOnZeroKnowledgeAndPreviousRows * E::cell(Column::Z, CurrOrNext::Next);
+
+        let expected = expr.evaluations(&env);
+        for chunk_size in [1, 7, 64, 1 << 20] {
+            let (evals, stats) = expr.evaluations_chunked(&env, chunk_size);
+            assert_eq!(evals.evals, expected.evals);
+
+            let chunks = (expected.evals.len() + chunk_size - 1) / chunk_size;
+            assert_eq!(
+                stats,
+                EvaluationCacheStats {
+                    hits: 2 * chunks,
+                    misses: chunks
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn test_share_subexpressions() {
+        let rng = &mut StdRng::from_seed([42u8; 32]);
+        let gates = (0..20)
+            .map(|row| {
+                CircuitGate::create_generic_gadget(
+                    Wire::new(row),
+                    GenericGateSpec::Const(1u32.into()),
+                    None,
+                )
+            })
+            .collect();
+        let constraint_system = ConstraintSystem::fp_for_testing(gates);
+
+        let n = constraint_system.domain.d1.size();
+        let witness_cols: [_; COLUMNS] = array::from_fn(|_| DensePolynomial::rand(n - 1, rng));
+        let permutation = DensePolynomial::rand(n - 1, rng);
+        let domain_evals = constraint_system.evaluate(&witness_cols, &permutation);
+
+        let env = Environment {
+            constants: Constants {
+                alpha: Fp::rand(rng),
+                beta: Fp::rand(rng),
+                gamma: Fp::rand(rng),
+                joint_combiner: None,
+                endo_coefficient: Fp::rand(rng),
+                mds: &Vesta::sponge_params().mds,
+                foreign_field_modulus: None,
+                zk_rows: ZK_ROWS,
+            },
+            witness: &domain_evals.d8.this.w,
+            coefficient: &constraint_system.coefficients8,
+            vanishes_on_zero_knowledge_and_previous_rows: &constraint_system
+                .precomputations()
+                .vanishes_on_zero_knowledge_and_previous_rows,
+            z: &domain_evals.d8.this.z,
+            l0_1: l0_1(constraint_system.domain.d1),
+            domain: constraint_system.domain,
+            index: HashMap::new(),
+            lookup: None,
+        };
+
+        // a sub-expression used twice, next to an already cached one
+        let mut cache = Cache::default();
+        let x = cache.cache(witness_curr(0) + witness_curr(1));
+        let y = || witness_curr(2) * witness_next(3) + E::constant(ConstantExpr::Beta);
+        let expr = y() * x.clone() - y().square() + x;
+
+        let shared = expr.share_subexpressions();
+        assert_ne!(shared, expr);
+        assert_eq!(shared.share_subexpressions(), shared);
+        assert_eq!(
+            shared.degree(n as u64, ZK_ROWS),
+            expr.degree(n as u64, ZK_ROWS)
+        );
+
+        let expected = expr.evaluations(&env);
+        assert_eq!(shared.evaluations(&env).evals, expected.evals);
+
+        let chunk_size = 64;
+        let chunks = (expected.evals.len() + chunk_size - 1) / chunk_size;
+        let (evals, stats) = shared.evaluations_chunked(&env, chunk_size);
+        assert_eq!(evals.evals, expected.evals);
+        assert_eq!(
+            stats,
+            EvaluationCacheStats {
+                hits: 2 * chunks,
+                misses: 2 * chunks
+            }
+        );
+    }
+
     #[test]
     fn test_unnormalized_lagrange_basis() {
         let domain = EvaluationDomains::<Fp>::create(2usize.pow(10) + ZK_ROWS as usize)
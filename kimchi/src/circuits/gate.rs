@@ -5,8 +5,9 @@ use crate::{
         argument::{Argument, ArgumentEnv},
         constraints::ConstraintSystem,
         polynomials::{
-            chacha, complete_add, endomul_scalar, endosclmul, foreign_field_add, poseidon,
-            range_check, turshi, varbasemul,
+            chacha, complete_add, endomul_scalar, endosclmul, foreign_field_add,
+            generic::{GENERIC_COEFFS, GENERIC_REGISTERS},
+            poseidon, range_check, sha256, turshi, varbasemul,
         },
         wires::*,
     },
@@ -19,13 +20,17 @@ use num_traits::cast::ToPrimitive;
 use o1_utils::hasher::CryptoDigest;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
-use std::io::{Result as IoResult, Write};
+use std::{
+    array,
+    io::{Result as IoResult, Write},
+};
 use thiserror::Error;
 
 use super::{argument::ArgumentWitness, expr};
 
 /// A row accessible from a given row, corresponds to the fact that we open all polynomials
-/// at `zeta` **and** `omega * zeta`.
+/// at `zeta` **and** `omega * zeta`, and the witness polynomials at `zeta / omega`
+/// when a gate reads the previous row.
 #[repr(C)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 #[cfg_attr(
@@ -37,16 +42,34 @@ use super::{argument::ArgumentWitness, expr};
 pub enum CurrOrNext {
     Curr,
     Next,
+    Prev,
 }
 
 impl CurrOrNext {
     /// Compute the offset corresponding to the `CurrOrNext` value.
     /// - `Curr.shift() == 0`
     /// - `Next.shift() == 1`
+    ///
+    /// # Panics
+    ///
+    /// Will panic for `Prev`, whose offset is negative (see [CurrOrNext::rotation]).
     pub fn shift(&self) -> usize {
         match self {
             CurrOrNext::Curr => 0,
             CurrOrNext::Next => 1,
+            CurrOrNext::Prev => panic!("the previous row is not a forward shift"),
+        }
+    }
+
+    /// Compute the offset of the row in a domain of size `n`, whose rows wrap around:
+    /// - `Curr.rotation(n) == 0`
+    /// - `Next.rotation(n) == 1`
+    /// - `Prev.rotation(n) == n - 1`
+    pub fn rotation(&self, n: usize) -> usize {
+        match self {
+            CurrOrNext::Curr => 0,
+            CurrOrNext::Next => 1,
+            CurrOrNext::Prev => n - 1,
         }
     }
 }
@@ -109,6 +132,25 @@ pub enum GateType {
     RangeCheck1 = 17,
     ForeignFieldAdd = 25,
     //ForeignFieldMul = 26,
+    /// SHA-256 (27-34)
+    Sha256Word = 27,
+    Sha256SmallSigma0 = 28,
+    Sha256SmallSigma1 = 29,
+    Sha256BigSigma0 = 30,
+    Sha256BigSigma1 = 31,
+    Sha256Ch = 32,
+    Sha256Maj = 33,
+    Sha256Spread = 34,
+    /// Custom gates (35-42), bound to the gates registered on the constraint system
+    /// (see [crate::circuits::custom_gate])
+    Custom0 = 35,
+    Custom1 = 36,
+    Custom2 = 37,
+    Custom3 = 38,
+    Custom4 = 39,
+    Custom5 = 40,
+    Custom6 = 41,
+    Custom7 = 42,
 }
 
 /// Selector polynomial
@@ -153,13 +195,16 @@ pub enum CircuitGateError {
     /// Failed to get witness for row
     #[error("Failed to get {0:?} witness for row {1}")]
     FailedToGetWitnessForRow(GateType, usize),
+    /// Value not found in the lookup table
+    #[error("Invalid {0:?} lookup at row {1}")]
+    InvalidLookup(GateType, usize),
 }
 
 /// Gate result
 pub type CircuitGateResult<T> = std::result::Result<T, CircuitGateError>;
 
 #[serde_as]
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 /// A single gate in a circuit.
 pub struct CircuitGate<F: PrimeField> {
     /// type of the gate
@@ -233,6 +278,13 @@ impl<F: PrimeField> CircuitGate<F> {
             ForeignFieldAdd => self
                 .verify_foreign_field_add::<G>(row, witness, cs)
                 .map_err(|e| e.to_string()),
+            Sha256Word | Sha256SmallSigma0 | Sha256SmallSigma1 | Sha256BigSigma0
+            | Sha256BigSigma1 | Sha256Ch | Sha256Maj | Sha256Spread => self
+                .verify_sha256::<G>(row, witness, cs)
+                .map_err(|e| e.to_string()),
+            Custom0 | Custom1 | Custom2 | Custom3 | Custom4 | Custom5 | Custom6 | Custom7 => self
+                .verify_custom::<G>(row, witness, cs)
+                .map_err(|e| e.to_string()),
         }
     }
 
@@ -244,21 +296,8 @@ impl<F: PrimeField> CircuitGate<F> {
         cs: &ConstraintSystem<F>,
         _public: &[F],
     ) -> CircuitGateResult<()> {
-        // Grab the relevant part of the witness
-        let argument_witness = self.argument_witness(row, witness)?;
-        // Set up the constants.  Note that alpha, beta, gamma and joint_combiner
-        // are one because this function is not running the prover.
-        let constants = expr::Constants::<F> {
-            alpha: F::one(),
-            beta: F::one(),
-            gamma: F::one(),
-            joint_combiner: Some(F::one()),
-            endo_coefficient: cs.endo,
-            mds: &G::sponge_params().mds,
-            foreign_field_modulus: cs.foreign_field_modulus.clone(),
-        };
-        // Create the argument environment for the constraints over field elements
-        let env = ArgumentEnv::<F, F>::create(argument_witness, self.coeffs.clone(), constants);
+        // Set up the argument environment for the constraints over field elements
+        let env = self.argument_env::<G>(row, witness, cs)?;
 
         // Check the wiring (i.e. copy constraints) for this gate
         // Note: Gates can operated on row Curr or Curr and Next.
@@ -283,7 +322,92 @@ impl<F: PrimeField> CircuitGate<F> {
         }
 
         // Perform witness verification on each constraint for this gate
-        let results = match self.typ {
+        let results = self.constraint_checks(&env, cs)?;
+
+        // Check for failed constraints
+        for (i, result) in results.iter().enumerate() {
+            if !result.is_zero() {
+                // Pinpoint failed constraint
+                return Err(CircuitGateError::Constraint(self.typ, i));
+            }
+        }
+
+        // TODO: implement generic plookup witness verification
+
+        Ok(())
+    }
+
+    /// Evaluates the constraints of the gate on the witness at `row`,
+    /// the constraints being satisfied when all the values are zero.
+    /// Unlike [CircuitGate::verify_witness], this covers the two equations of the generic gate,
+    /// where the public input of the row (if any) is taken from `public`.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the witness of the row cannot be read, or if the gate is unknown.
+    pub fn constraint_values<G: KimchiCurve<ScalarField = F>>(
+        &self,
+        row: usize,
+        witness: &[Vec<F>; COLUMNS],
+        cs: &ConstraintSystem<F>,
+        public: &[F],
+    ) -> CircuitGateResult<Vec<F>> {
+        if self.typ == GateType::Generic {
+            let get = |i: usize| self.coeffs.get(i).copied().unwrap_or_else(F::zero);
+            let values = (0..2)
+                .map(|half| {
+                    let (c, w) = (half * GENERIC_COEFFS, half * GENERIC_REGISTERS);
+                    let (l, r, o) = (witness[w][row], witness[w + 1][row], witness[w + 2][row]);
+                    let public = match half {
+                        0 => public.get(row).copied().unwrap_or_else(F::zero),
+                        _ => F::zero(),
+                    };
+                    get(c) * l + get(c + 1) * r + get(c + 2) * o + get(c + 3) * l * r + get(c + 4)
+                        - public
+                })
+                .collect();
+            return Ok(values);
+        }
+
+        let env = self.argument_env::<G>(row, witness, cs)?;
+        self.constraint_checks(&env, cs)
+    }
+
+    // Create the argument environment of the gate at the given row
+    fn argument_env<G: KimchiCurve<ScalarField = F>>(
+        &self,
+        row: usize,
+        witness: &[Vec<F>; COLUMNS],
+        cs: &ConstraintSystem<F>,
+    ) -> CircuitGateResult<ArgumentEnv<F, F>> {
+        // Grab the relevant part of the witness
+        let argument_witness = self.argument_witness(row, witness)?;
+        // Set up the constants.  Note that alpha, beta, gamma and joint_combiner
+        // are one because this function is not running the prover.
+        let constants = expr::Constants::<F> {
+            alpha: F::one(),
+            beta: F::one(),
+            gamma: F::one(),
+            joint_combiner: Some(F::one()),
+            endo_coefficient: cs.endo,
+            mds: &G::sponge_params().mds,
+            foreign_field_modulus: cs.foreign_field_modulus.clone(),
+            zk_rows: cs.zk_rows,
+        };
+        Ok(ArgumentEnv::<F, F>::create(
+            argument_witness,
+            self.coeffs.clone(),
+            constants,
+        ))
+    }
+
+    // Evaluate the constraints of the gate in the given environment
+    fn constraint_checks(
+        &self,
+        env: &ArgumentEnv<F, F>,
+        cs: &ConstraintSystem<F>,
+    ) -> CircuitGateResult<Vec<F>> {
+        Ok(match self.typ {
             GateType::Zero => {
                 vec![]
             }
@@ -291,45 +415,57 @@ impl<F: PrimeField> CircuitGate<F> {
                 // TODO: implement the verification for the generic gate
                 vec![]
             }
-            GateType::Poseidon => poseidon::Poseidon::constraint_checks(&env),
-            GateType::CompleteAdd => complete_add::CompleteAdd::constraint_checks(&env),
-            GateType::VarBaseMul => varbasemul::VarbaseMul::constraint_checks(&env),
-            GateType::EndoMul => endosclmul::EndosclMul::constraint_checks(&env),
-            GateType::EndoMulScalar => endomul_scalar::EndomulScalar::constraint_checks(&env),
-            GateType::ChaCha0 => chacha::ChaCha0::constraint_checks(&env),
-            GateType::ChaCha1 => chacha::ChaCha1::constraint_checks(&env),
-            GateType::ChaCha2 => chacha::ChaCha2::constraint_checks(&env),
-            GateType::ChaChaFinal => chacha::ChaChaFinal::constraint_checks(&env),
+            GateType::Poseidon => poseidon::Poseidon::constraint_checks(env),
+            GateType::CompleteAdd => complete_add::CompleteAdd::constraint_checks(env),
+            GateType::VarBaseMul => varbasemul::VarbaseMul::constraint_checks(env),
+            GateType::EndoMul => endosclmul::EndosclMul::constraint_checks(env),
+            GateType::EndoMulScalar => endomul_scalar::EndomulScalar::constraint_checks(env),
+            GateType::ChaCha0 => chacha::ChaCha0::constraint_checks(env),
+            GateType::ChaCha1 => chacha::ChaCha1::constraint_checks(env),
+            GateType::ChaCha2 => chacha::ChaCha2::constraint_checks(env),
+            GateType::ChaChaFinal => chacha::ChaChaFinal::constraint_checks(env),
             GateType::Lookup => {
                 // TODO: implement the verification for the lookup gate
                 vec![]
             }
-            GateType::CairoClaim => turshi::Claim::constraint_checks(&env),
-            GateType::CairoInstruction => turshi::Instruction::constraint_checks(&env),
-            GateType::CairoFlags => turshi::Flags::constraint_checks(&env),
-            GateType::CairoTransition => turshi::Transition::constraint_checks(&env),
-            GateType::RangeCheck0 => {
-                range_check::circuitgates::RangeCheck0::constraint_checks(&env)
+            GateType::CairoClaim => turshi::Claim::constraint_checks(env),
+            GateType::CairoInstruction => turshi::Instruction::constraint_checks(env),
+            GateType::CairoFlags => turshi::Flags::constraint_checks(env),
+            GateType::CairoTransition => turshi::Transition::constraint_checks(env),
+            GateType::RangeCheck0 => range_check::circuitgates::RangeCheck0::constraint_checks(env),
+            GateType::RangeCheck1 => range_check::circuitgates::RangeCheck1::constraint_checks(env),
+            GateType::ForeignFieldAdd => {
+                foreign_field_add::circuitgates::ForeignFieldAdd::constraint_checks(env)
             }
-            GateType::RangeCheck1 => {
-                range_check::circuitgates::RangeCheck1::constraint_checks(&env)
+            GateType::Sha256Word => sha256::circuitgates::Sha256Word::constraint_checks(env),
+            GateType::Sha256SmallSigma0 => {
+                sha256::circuitgates::Sha256SmallSigma0::constraint_checks(env)
             }
-            GateType::ForeignFieldAdd => {
-                foreign_field_add::circuitgates::ForeignFieldAdd::constraint_checks(&env)
+            GateType::Sha256SmallSigma1 => {
+                sha256::circuitgates::Sha256SmallSigma1::constraint_checks(env)
             }
-        };
-
-        // Check for failed constraints
-        for (i, result) in results.iter().enumerate() {
-            if !result.is_zero() {
-                // Pinpoint failed constraint
-                return Err(CircuitGateError::Constraint(self.typ, i));
+            GateType::Sha256BigSigma0 => {
+                sha256::circuitgates::Sha256BigSigma0::constraint_checks(env)
             }
-        }
-
-        // TODO: implement generic plookup witness verification
-
-        Ok(())
+            GateType::Sha256BigSigma1 => {
+                sha256::circuitgates::Sha256BigSigma1::constraint_checks(env)
+            }
+            GateType::Sha256Ch => sha256::circuitgates::Sha256Ch::constraint_checks(env),
+            GateType::Sha256Maj => sha256::circuitgates::Sha256Maj::constraint_checks(env),
+            GateType::Sha256Spread => sha256::circuitgates::Sha256Spread::constraint_checks(env),
+            GateType::Custom0
+            | GateType::Custom1
+            | GateType::Custom2
+            | GateType::Custom3
+            | GateType::Custom4
+            | GateType::Custom5
+            | GateType::Custom6
+            | GateType::Custom7 => cs
+                .custom_gates
+                .get(self.typ)
+                .ok_or(CircuitGateError::InvalidCircuitGateType(self.typ))?
+                .constraint_checks(env),
+        })
     }
 
     // Return the part of the witness relevant to this gate at the given row offset
@@ -353,10 +489,18 @@ impl<F: PrimeField> CircuitGate<F> {
         } else {
             [F::zero(); COLUMNS]
         };
+        // the row before the first one is the last row of the domain,
+        // which the gates reading the previous row cannot be laid out on
+        let witness_prev: [F; COLUMNS] = if row > 0 {
+            array::from_fn(|col| witness[col][row - 1])
+        } else {
+            [F::zero(); COLUMNS]
+        };
 
         Ok(ArgumentWitness::<F> {
             curr: witness_curr,
             next: witness_next,
+            prev: witness_prev,
         })
     }
 }
@@ -16,7 +16,7 @@ use rand::Rng;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use std::collections::HashMap;
-use CurrOrNext::{Curr, Next};
+use CurrOrNext::{Curr, Next, Prev};
 
 use super::runtime_tables;
 
@@ -24,9 +24,12 @@ use super::runtime_tables;
 pub const CONSTRAINTS: u32 = 7;
 
 /// The number of random values to append to columns for zero-knowledge.
-pub const ZK_ROWS: usize = 3;
+#[deprecated(
+    note = "the number of zero-knowledge rows is configurable, use the `zk_rows` of the constraint system, or `permutation::ZK_ROWS` for the default"
+)]
+pub const ZK_ROWS: usize = crate::circuits::polynomials::permutation::ZK_ROWS as usize;
 
-/// Pad with zeroes and then add 3 random elements in the last two
+/// Pad with zeroes and then add `zk_rows` random elements in the last
 /// rows for zero knowledge.
 ///
 /// # Panics
@@ -35,13 +38,15 @@ pub const ZK_ROWS: usize = 3;
 pub fn zk_patch<R: Rng + ?Sized, F: FftField>(
     mut e: Vec<F>,
     d: D<F>,
+    zk_rows: u64,
     rng: &mut R,
 ) -> Evaluations<F, D<F>> {
     let n = d.size();
     let k = e.len();
-    assert!(k <= n - ZK_ROWS);
-    e.extend((0..((n - ZK_ROWS) - k)).map(|_| F::zero()));
-    e.extend((0..ZK_ROWS).map(|_| F::rand(rng)));
+    let zk_rows = zk_rows as usize;
+    assert!(k <= n - zk_rows);
+    e.extend((0..((n - zk_rows) - k)).map(|_| F::zero()));
+    e.extend((0..zk_rows).map(|_| F::rand(rng)));
     Evaluations::<F, D<F>>::from_vec_and_domain(e, d)
 }
 
@@ -90,6 +95,7 @@ pub fn sorted<F: PrimeField>(
     dummy_lookup_value: F,
     joint_lookup_table_d8: &Evaluations<F, D<F>>,
     d1: D<F>,
+    zk_rows: u64,
     gates: &[CircuitGate<F>],
     witness: &[Vec<F>; COLUMNS],
     joint_combiner: F,
@@ -102,7 +108,7 @@ pub fn sorted<F: PrimeField>(
     let n = d1.size();
     let mut counts: HashMap<&F, usize> = HashMap::new();
 
-    let lookup_rows = n - ZK_ROWS - 1;
+    let lookup_rows = n - zk_rows as usize - 1;
     let by_row = lookup_info.by_row(gates);
     let max_lookups_per_row = lookup_info.max_per_row;
 
@@ -133,6 +139,7 @@ pub fn sorted<F: PrimeField>(
                 let row = match pos.row {
                     Curr => i,
                     Next => i + 1,
+                    Prev => i - 1,
                 };
                 witness[pos.column][row]
             };
@@ -231,6 +238,8 @@ pub fn aggregation<R, F>(
     dummy_lookup_value: F,
     joint_lookup_table_d8: &Evaluations<F, D<F>>,
     d1: D<F>,
+    zk_rows: u64,
+    random_rows: u64,
     gates: &[CircuitGate<F>],
     witness: &[Vec<F>; COLUMNS],
     joint_combiner: &F,
@@ -246,7 +255,7 @@ where
     F: PrimeField,
 {
     let n = d1.size();
-    let lookup_rows = n - ZK_ROWS - 1;
+    let lookup_rows = n - zk_rows as usize - 1;
     let beta1: F = F::one() + beta;
     let gammabeta1 = gamma * beta1;
     let mut lookup_aggreg = vec![F::one()];
@@ -292,6 +301,7 @@ where
                     let row = match pos.row {
                         Curr => i,
                         Next => i + 1,
+                        Prev => i - 1,
                     };
                     witness[pos.column][row]
                 };
@@ -318,11 +328,11 @@ where
             lookup_aggreg[i + 1] *= prev;
         });
 
-    let res = zk_patch(lookup_aggreg, d1, rng);
+    let res = zk_patch(lookup_aggreg, d1, random_rows, rng);
 
     // check that the final evaluation is equal to 1
     if cfg!(debug_assertions) {
-        let final_val = res.evals[d1.size() - (ZK_ROWS + 1)];
+        let final_val = res.evals[d1.size() - (zk_rows as usize + 1)];
         if final_val != F::one() {
             panic!("aggregation incorrect: {}", final_val);
         }
@@ -356,20 +366,20 @@ pub struct LookupConfiguration<F: FftField> {
 /// # Panics
 ///
 /// Will panic if single `element` length is bigger than `max_per_row` length.
-pub fn constraints<F: FftField>(configuration: &LookupConfiguration<F>) -> Vec<E<F>> {
-    // Something important to keep in mind is that the last 2 rows of
+pub fn constraints<F: FftField>(configuration: &LookupConfiguration<F>, zk_rows: u64) -> Vec<E<F>> {
+    // Something important to keep in mind is that the last `zk_rows` rows of
     // all columns will have random values in them to maintain zero-knowledge.
     //
     // Another important thing to note is that there are no lookups permitted
-    // in the 3rd to last row.
+    // in the row just before them (the 4th to last row, with the default 3 zk rows).
     //
     // This is because computing the lookup-product requires
     // num_lookup_rows + 1
     // rows, so we need to have
-    // num_lookup_rows + 1 = n - 2 (the last 2 being reserved for the zero-knowledge random
-    // values) and thus
+    // num_lookup_rows + 1 = n - zk_rows (the last zk_rows being reserved for the
+    // zero-knowledge random values) and thus
     //
-    // num_lookup_rows = n - 3
+    // num_lookup_rows = n - zk_rows - 1
     let lookup_info = &configuration.lookup_info;
 
     let column = |col: Column| E::cell(col, Curr);
@@ -528,12 +538,12 @@ pub fn constraints<F: FftField>(configuration: &LookupConfiguration<F>) -> Vec<E
     let aggreg_equation = E::cell(Column::LookupAggreg, Next) * denominator
         - E::cell(Column::LookupAggreg, Curr) * numerator;
 
-    let final_lookup_row: i32 = -(ZK_ROWS as i32) - 1;
+    let final_lookup_row: i32 = -(zk_rows as i32) - 1;
 
     let mut res = vec![
-        // the accumulator except for the last 4 rows
+        // the accumulator except for the last `zk_rows + 1` rows
         // (contains the zk-rows and the last value of the accumulator)
-        E::VanishesOnLast4Rows * aggreg_equation,
+        E::VanishesOnZeroKnowledgeAndPreviousRows * aggreg_equation,
         // the initial value of the accumulator
         E::UnnormalizedLagrangeBasis(0) * (E::cell(Column::LookupAggreg, Curr) - E::one()),
         // Check that the final value of the accumulator is 1
@@ -578,6 +588,7 @@ pub fn verify<F: PrimeField, I: Iterator<Item = F>, TABLE: Fn() -> I>(
     lookup_table: TABLE,
     lookup_table_entries: usize,
     d1: D<F>,
+    zk_rows: u64,
     gates: &[CircuitGate<F>],
     witness: &[Vec<F>; COLUMNS],
     joint_combiner: &F,
@@ -589,7 +600,7 @@ pub fn verify<F: PrimeField, I: Iterator<Item = F>, TABLE: Fn() -> I>(
         .iter()
         .for_each(|s| assert_eq!(d1.size, s.domain().size));
     let n = d1.size();
-    let lookup_rows = n - ZK_ROWS - 1;
+    let lookup_rows = n - zk_rows as usize - 1;
 
     // Check that the (desnakified) sorted table is
     // 1. Sorted
@@ -649,6 +660,7 @@ pub fn verify<F: PrimeField, I: Iterator<Item = F>, TABLE: Fn() -> I>(
             let row = match pos.row {
                 Curr => i,
                 Next => i + 1,
+                Prev => i - 1,
             };
             witness[pos.column][row]
         };
@@ -4,10 +4,9 @@ use crate::circuits::{
     gate::CircuitGate,
     lookup::{
         constraints::LookupConfiguration,
-        lookups::{JointLookup, LookupInfo, LookupPattern},
-        tables::LookupTable,
+        lookups::{JointLookup, LookupInfo, LookupPattern, LookupsUsed},
+        tables::{LookupTable, TableId, TableKind, TableRegistry},
     },
-    polynomials::permutation::ZK_ROWS,
 };
 use ark_ff::{FftField, PrimeField, SquareRootField};
 use ark_poly::{
@@ -15,7 +14,6 @@ use ark_poly::{
     Radix2EvaluationDomain as D,
 };
 use itertools::repeat_n;
-use o1_utils::field_helpers::i32_to_field;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_with::serde_as;
 use std::iter;
@@ -33,6 +31,14 @@ pub enum LookupError {
     },
     #[error("The table with id 0 must have an entry of all zeros")]
     TableIDZeroMustHaveZeroEntry,
+    #[error("The table ID {0} is reserved for the built-in tables")]
+    ReservedTableId(TableId),
+    #[error("The table ID {id} of a {kind:?} table is already used by a {registered:?} table")]
+    TableIdCollision {
+        id: TableId,
+        registered: TableKind,
+        kind: TableKind,
+    },
 }
 
 /// Lookup selectors
@@ -46,6 +52,9 @@ pub struct LookupSelectors<T> {
     pub lookup_gate: Option<T>,
     /// RangeCheckGate pattern lookup selector
     pub range_check_gate: Option<T>,
+    /// Sha256 pattern lookup selector
+    #[serde(default = "Option::default")]
+    pub sha256: Option<T>,
 }
 
 #[serde_as]
@@ -59,6 +68,9 @@ struct LookupSelectorsSerdeAs<F: FftField> {
     pub lookup_gate: Option<E<F, D<F>>>,
     #[serde_as(as = "Option<o1_utils::serialization::SerdeAs>")]
     pub range_check_gate: Option<E<F, D<F>>>,
+    #[serde(default)]
+    #[serde_as(as = "Option<o1_utils::serialization::SerdeAs>")]
+    pub sha256: Option<E<F, D<F>>>,
 }
 
 impl<F: FftField> serde_with::SerializeAs<LookupSelectors<E<F, D<F>>>>
@@ -73,6 +85,7 @@ impl<F: FftField> serde_with::SerializeAs<LookupSelectors<E<F, D<F>>>>
             chacha_final: val.chacha_final.clone(),
             lookup_gate: val.lookup_gate.clone(),
             range_check_gate: val.range_check_gate.clone(),
+            sha256: val.sha256.clone(),
         };
         repr.serialize(serializer)
     }
@@ -90,12 +103,14 @@ impl<'de, F: FftField> serde_with::DeserializeAs<'de, LookupSelectors<E<F, D<F>>
             chacha_final,
             lookup_gate,
             range_check_gate,
+            sha256,
         } = LookupSelectorsSerdeAs::deserialize(deserializer)?;
         Ok(LookupSelectors {
             chacha,
             chacha_final,
             lookup_gate,
             range_check_gate,
+            sha256,
         })
     }
 }
@@ -109,6 +124,7 @@ impl<T> std::ops::Index<LookupPattern> for LookupSelectors<T> {
             LookupPattern::ChaChaFinal => &self.chacha_final,
             LookupPattern::LookupGate => &self.lookup_gate,
             LookupPattern::RangeCheckGate => &self.range_check_gate,
+            LookupPattern::Sha256 => &self.sha256,
         }
     }
 }
@@ -120,6 +136,7 @@ impl<T> std::ops::IndexMut<LookupPattern> for LookupSelectors<T> {
             LookupPattern::ChaChaFinal => &mut self.chacha_final,
             LookupPattern::LookupGate => &mut self.lookup_gate,
             LookupPattern::RangeCheckGate => &mut self.range_check_gate,
+            LookupPattern::Sha256 => &mut self.sha256,
         }
     }
 }
@@ -131,6 +148,7 @@ impl<T> LookupSelectors<T> {
             chacha_final,
             lookup_gate,
             range_check_gate,
+            sha256,
         } = self;
         // This closure isn't really redundant -- it shields the parameter from a copy -- but
         // clippy isn't smart enough to figure that out..
@@ -141,6 +159,7 @@ impl<T> LookupSelectors<T> {
             chacha_final: chacha_final.map(f),
             lookup_gate: lookup_gate.map(f),
             range_check_gate: range_check_gate.map(f),
+            sha256: sha256.map(f),
         }
     }
 
@@ -150,6 +169,7 @@ impl<T> LookupSelectors<T> {
             chacha_final: self.chacha_final.as_ref(),
             lookup_gate: self.lookup_gate.as_ref(),
             range_check_gate: self.range_check_gate.as_ref(),
+            sha256: self.sha256.as_ref(),
         }
     }
 }
@@ -188,11 +208,23 @@ pub struct LookupConstraintSystem<F: FftField> {
     /// The offset of the runtime table within the concatenated table
     pub runtime_table_offset: Option<usize>,
 
+    /// The IDs of the lookup tables (built-in, fixed, and runtime tables)
+    #[serde(default)]
+    pub table_registry: TableRegistry,
+
     /// Configuration for the lookup constraint.
     #[serde(bound = "LookupConfiguration<F>: Serialize + DeserializeOwned")]
     pub configuration: LookupConfiguration<F>,
 }
 
+/// The maximum number of entries of the concatenated lookup table (fixed and runtime tables)
+/// in a domain of size `domain_size`.
+/// The rows after the table are reserved for the dummy entry, for the row asserting that the
+/// final product of the lookup argument is 1, and for the `zk_rows` zero-knowledge rows.
+pub fn lookup_table_capacity(domain_size: usize, zk_rows: u64) -> usize {
+    domain_size.saturating_sub(zk_rows as usize + 2)
+}
+
 impl<F: PrimeField + SquareRootField> LookupConstraintSystem<F> {
     /// Create the `LookupConstraintSystem`.
     ///
@@ -204,11 +236,12 @@ impl<F: PrimeField + SquareRootField> LookupConstraintSystem<F> {
         lookup_tables: Vec<LookupTable<F>>,
         runtime_tables: Option<Vec<RuntimeTableCfg<F>>>,
         domain: &EvaluationDomains<F>,
+        zk_rows: u64,
     ) -> Result<Option<Self>, LookupError> {
         //~ 1. If no lookup is used in the circuit, do not create a lookup index
         match LookupInfo::create_from_gates(gates, runtime_tables.is_some()) {
             None => Ok(None),
-            Some(lookup_info) => {
+            Some(mut lookup_info) => {
                 let lookup_used = match lookup_info.lookup_used() {
                     Some(lookup_used) => lookup_used,
                     None => return Ok(None),
@@ -216,16 +249,32 @@ impl<F: PrimeField + SquareRootField> LookupConstraintSystem<F> {
                 let d1_size = domain.d1.size();
 
                 // The maximum number of entries that can be provided across all tables.
-                // Since we do not assert the lookup constraint on the final `ZK_ROWS` rows, and
+                // Since we do not assert the lookup constraint on the final `zk_rows` rows, and
                 // because the row before is used to assert that the lookup argument's final
                 // product is 1, we cannot use those rows to store any values.
-                let max_num_entries = d1_size - (ZK_ROWS as usize) - 1;
+                let max_num_entries = d1_size - (zk_rows as usize) - 1;
+                let capacity = lookup_table_capacity(d1_size, zk_rows);
 
                 //~ 2. Get the lookup selectors and lookup tables (TODO: how?)
                 let (lookup_selectors, gate_lookup_tables) =
                     lookup_info.selector_polynomials_and_tables(domain, gates);
 
-                //~ 3. Concatenate runtime lookup tables with the ones used by gates
+                //~ 3. Register the IDs of the tables used by gates, of the fixed tables,
+                //~    and of the runtime tables. The fixed and runtime tables of the circuit
+                //~    must not use an ID reserved for the built-in tables, and no two tables
+                //~    can share an ID.
+                let mut table_registry = TableRegistry::default();
+                for table in &gate_lookup_tables {
+                    table_registry.register(table.id, TableKind::BuiltIn)?;
+                }
+                for table in &lookup_tables {
+                    table_registry.register(table.id, TableKind::Fixed)?;
+                }
+                for table in runtime_tables.iter().flatten() {
+                    table_registry.register(table.id(), TableKind::Runtime)?;
+                }
+
+                //~ 4. Concatenate runtime lookup tables with the ones used by gates
                 let mut lookup_tables: Vec<_> = gate_lookup_tables
                     .into_iter()
                     .chain(lookup_tables.into_iter())
@@ -248,6 +297,15 @@ impl<F: PrimeField + SquareRootField> LookupConstraintSystem<F> {
                             runtime_len += t.len();
                         }
 
+                        // the runtime tables must not overlap with the final rows,
+                        // where the prover writes random values for zero-knowledge
+                        if runtime_table_offset + runtime_len > capacity {
+                            return Err(LookupError::LookupTableTooLong {
+                                length: runtime_table_offset + runtime_len,
+                                maximum_allowed: capacity,
+                            });
+                        }
+
                         // compute the runtime selector
                         let runtime_selector = {
                             let mut evals = Vec::with_capacity(d1_size);
@@ -261,8 +319,8 @@ impl<F: PrimeField + SquareRootField> LookupConstraintSystem<F> {
                                     .take(d1_size - runtime_table_offset - runtime_len),
                             );
 
-                            // although the last ZK_ROWS are fine
-                            for e in evals.iter_mut().rev().take(ZK_ROWS as usize) {
+                            // although the last zk_rows are fine
+                            for e in evals.iter_mut().rev().take(zk_rows as usize) {
                                 *e = F::zero();
                             }
 
@@ -282,12 +340,6 @@ impl<F: PrimeField + SquareRootField> LookupConstraintSystem<F> {
                                 Custom { id, first_column } => (*id, first_column.clone()),
                             };
 
-                            // record if table ID 0 is used in one of the runtime tables
-                            // note: the check later will still force you to have a fixed table with ID 0
-                            if id == 0 {
-                                has_table_id_0 = true;
-                            }
-
                             // important: we still need a placeholder column to make sure that
                             // if all other tables have a single column
                             // we don't use the second table as table ID column.
@@ -302,7 +354,7 @@ impl<F: PrimeField + SquareRootField> LookupConstraintSystem<F> {
                         (None, None)
                     };
 
-                //~ 4. Get the highest number of columns `max_table_width`
+                //~ 5. Get the highest number of columns `max_table_width`
                 //~    that a lookup table can have.
                 let max_table_width = lookup_tables
                     .iter()
@@ -310,10 +362,26 @@ impl<F: PrimeField + SquareRootField> LookupConstraintSystem<F> {
                     .max()
                     .unwrap_or(0);
 
-                //~ 5. Create the concatenated table of all the fixed lookup tables.
+                //~    The columns of the tables are combined with the powers of the joint combiner,
+                //~    and the table IDs with the power following the widest table or lookup,
+                //~    so that a fixed table wider than the lookups of the gates
+                //~    does not collide with the table IDs.
+                //~    A table with several columns also requires a random joint combiner.
+                lookup_info.max_joint_size =
+                    std::cmp::max(lookup_info.max_joint_size, max_table_width as u32);
+                let lookup_used = if max_table_width > 1 {
+                    LookupsUsed::Joint
+                } else {
+                    lookup_used
+                };
+
+                //~ 6. Create the concatenated table of all the fixed lookup tables.
                 //~    It will be of height the size of the domain,
                 //~    and of width the maximum width of any of the lookup tables.
                 //~    In addition, create an additional column to store all the tables' table IDs.
+                //~    The concatenated table is held by a single table polynomial per column:
+                //~    tables are not split across several table polynomials,
+                //~    so the domain must be large enough to fit all of them.
                 //~
                 //~    For example, if you have a table with ID 0
                 //~
@@ -360,7 +428,7 @@ impl<F: PrimeField + SquareRootField> LookupConstraintSystem<F> {
                 for table in &lookup_tables {
                     let table_len = table.data[0].len();
 
-                    if table.id == 0 {
+                    if table.id.value() == 0 {
                         has_table_id_0 = true;
                         if table.has_zero_entry() {
                             has_table_id_0_with_zero_entry = true;
@@ -371,7 +439,7 @@ impl<F: PrimeField + SquareRootField> LookupConstraintSystem<F> {
 
                     //~~ - Update the corresponding entries in a table id vector (of size the domain as well)
                     //~    with the table ID of the table.
-                    let table_id: F = i32_to_field(table.id);
+                    let table_id: F = table.id.to_field();
                     table_ids.extend(repeat_n(table_id, table_len));
 
                     //~~ - Copy the entries from the table to new rows in the corresponding columns of the concatenated table.
@@ -394,11 +462,11 @@ impl<F: PrimeField + SquareRootField> LookupConstraintSystem<F> {
                     return Err(LookupError::TableIDZeroMustHaveZeroEntry);
                 }
 
-                // Note: we use `>=` here to leave space for the dummy value.
-                if lookup_table[0].len() >= max_num_entries {
+                // Note: the capacity leaves space for the dummy value.
+                if lookup_table[0].len() > capacity {
                     return Err(LookupError::LookupTableTooLong {
                         length: lookup_table[0].len(),
-                        maximum_allowed: max_num_entries - 1,
+                        maximum_allowed: capacity,
                     });
                 }
 
@@ -409,15 +477,15 @@ impl<F: PrimeField + SquareRootField> LookupConstraintSystem<F> {
                     table_id: F::zero(),
                 };
 
-                //~ 6. Pad the end of the concatened table with the dummy value.
+                //~ 7. Pad the end of the concatened table with the dummy value.
                 lookup_table
                     .iter_mut()
                     .for_each(|col| col.extend(repeat_n(F::zero(), max_num_entries - col.len())));
 
-                //~ 7. Pad the end of the table id vector with 0s.
+                //~ 8. Pad the end of the table id vector with 0s.
                 table_ids.extend(repeat_n(F::zero(), max_num_entries - table_ids.len()));
 
-                //~ 8. pre-compute polynomial and evaluation form for the look up tables
+                //~ 9. pre-compute polynomial and evaluation form for the look up tables
                 let mut lookup_table_polys: Vec<DP<F>> = vec![];
                 let mut lookup_table8: Vec<E<F, D<F>>> = vec![];
                 for col in lookup_table {
@@ -427,7 +495,7 @@ impl<F: PrimeField + SquareRootField> LookupConstraintSystem<F> {
                     lookup_table8.push(eval);
                 }
 
-                //~ 9. pre-compute polynomial and evaluation form for the table IDs,
+                //~ 10. pre-compute polynomial and evaluation form for the table IDs,
                 //~    only if a table with an ID different from zero was used.
                 let (table_ids, table_ids8) = if non_zero_table_id {
                     let table_ids: DP<F> =
@@ -451,6 +519,7 @@ impl<F: PrimeField + SquareRootField> LookupConstraintSystem<F> {
                     runtime_selector,
                     runtime_tables,
                     runtime_table_offset,
+                    table_registry,
                     configuration: LookupConfiguration {
                         lookup_used,
                         lookup_info,
@@ -3,13 +3,12 @@ use crate::circuits::{
     gate::{CircuitGate, CurrOrNext, GateType},
     lookup::index::LookupSelectors,
     lookup::tables::{
-        combine_table_entry, get_table, GateLookupTable, LookupTable, RANGE_CHECK_TABLE_ID,
-        XOR_TABLE_ID,
+        combine_table_entry, get_table, GateLookupTable, LookupTable, TableId,
+        RANGE_CHECK_TABLE_ID, SHA256_SPREAD_TABLE_ID, XOR_TABLE_ID,
     },
 };
 use ark_ff::{Field, One, PrimeField, Zero};
 use ark_poly::{EvaluationDomain, Evaluations as E, Radix2EvaluationDomain as D};
-use o1_utils::field_helpers::i32_to_field;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::ops::{Mul, Neg};
@@ -197,7 +196,7 @@ impl<F: Copy> SingleLookup<F> {
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub enum LookupTableID {
     /// Look up the value from the given fixed table ID
-    Constant(i32),
+    Constant(TableId),
     /// Look up the value in the table with ID given by the value in the witness column
     WitnessColumn(usize),
 }
@@ -206,9 +205,9 @@ pub enum LookupTableID {
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct JointLookup<SingleLookup, LookupTableID> {
     /// The ID for the table associated with this lookup.
-    /// Positive IDs are intended to be used for the fixed tables associated with individual gates,
-    /// with negative IDs reserved for gates defined by the particular constraint system to avoid
-    /// accidental collisions.
+    /// Non-negative IDs are reserved for the fixed tables associated with individual gates,
+    /// with negative IDs used for the tables defined by the particular constraint system to avoid
+    /// accidental collisions (see [TableId]).
     pub table_id: LookupTableID,
     pub entry: Vec<SingleLookup>,
 }
@@ -244,7 +243,7 @@ impl<F: Copy> JointLookup<SingleLookup<F>, LookupTableID> {
         K: From<u64>,
     {
         let table_id = match self.table_id {
-            LookupTableID::Constant(table_id) => i32_to_field(table_id),
+            LookupTableID::Constant(table_id) => table_id.to_field(),
             LookupTableID::WitnessColumn(column) => eval(LocalPosition {
                 row: CurrOrNext::Curr,
                 column,
@@ -283,13 +282,17 @@ pub enum LookupPattern {
     ChaChaFinal,
     LookupGate,
     RangeCheckGate,
+    Sha256,
 }
 
 impl LookupPattern {
     /// Returns the maximum number of lookups per row that are used by the pattern.
     pub fn max_lookups_per_row(&self) -> usize {
         match self {
-            LookupPattern::ChaCha | LookupPattern::ChaChaFinal | LookupPattern::RangeCheckGate => 4,
+            LookupPattern::ChaCha
+            | LookupPattern::ChaChaFinal
+            | LookupPattern::RangeCheckGate
+            | LookupPattern::Sha256 => 4,
             LookupPattern::LookupGate => 3,
         }
     }
@@ -298,7 +301,7 @@ impl LookupPattern {
     pub fn max_joint_size(&self) -> u32 {
         match self {
             LookupPattern::ChaCha | LookupPattern::ChaChaFinal => 3,
-            LookupPattern::LookupGate => 2,
+            LookupPattern::LookupGate | LookupPattern::Sha256 => 2,
             LookupPattern::RangeCheckGate => 1,
         }
     }
@@ -390,6 +393,25 @@ impl LookupPattern {
                     })
                     .collect()
             }
+            LookupPattern::Sha256 => {
+                (0..4)
+                    .map(|i| {
+                        // each lookup checks that s = spread(n), for a 4-bit value n
+                        //
+                        // 0 1 2 3 4 5 6 7 8 9 10 11 12 13 14
+                        // - - - - - - - n s n s  n  s  n  s
+                        let value = curr_row(7 + 2 * i);
+                        let spread = curr_row(8 + 2 * i);
+                        let l = |loc: LocalPosition| SingleLookup {
+                            value: vec![(F::one(), loc)],
+                        };
+                        JointLookup {
+                            table_id: LookupTableID::Constant(SHA256_SPREAD_TABLE_ID),
+                            entry: vec![l(value), l(spread)],
+                        }
+                    })
+                    .collect()
+            }
         }
     }
 
@@ -399,6 +421,7 @@ impl LookupPattern {
             LookupPattern::ChaCha | LookupPattern::ChaChaFinal => Some(GateLookupTable::Xor),
             LookupPattern::LookupGate => None,
             LookupPattern::RangeCheckGate => Some(GateLookupTable::RangeCheck),
+            LookupPattern::Sha256 => Some(GateLookupTable::Sha256Spread),
         }
     }
 
@@ -411,6 +434,11 @@ impl LookupPattern {
             (ChaChaFinal, Curr | Next) => Some(LookupPattern::ChaChaFinal),
             (Lookup, Curr) => Some(LookupPattern::LookupGate),
             (RangeCheck0, Curr) | (RangeCheck1, Curr | Next) => Some(LookupPattern::RangeCheckGate),
+            (
+                Sha256Word | Sha256SmallSigma0 | Sha256SmallSigma1 | Sha256BigSigma0
+                | Sha256BigSigma1 | Sha256Spread,
+                Curr | Next,
+            ) => Some(LookupPattern::Sha256),
             _ => None,
         }
     }
@@ -429,6 +457,7 @@ impl GateType {
             LookupPattern::ChaChaFinal,
             LookupPattern::LookupGate,
             LookupPattern::RangeCheckGate,
+            LookupPattern::Sha256,
         ]
     }
 }
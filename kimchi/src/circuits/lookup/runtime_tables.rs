@@ -1,41 +1,61 @@
 //! Runtime tables are tables (or arrays) that can be produced during proof creation.
 //! The setup has to prepare for their presence using [`RuntimeTableCfg`].
-//! At proving time, the prover can use [`RuntimeTable`] to specify the actual tables.
+//! At proving time, the prover can use [`RuntimeTable`] to specify the actual tables,
+//! in any order.
+//!
+//! For example, a circuit reading from a memory whose content is only known at proving time
+//! configures an indexed runtime table with one entry per address,
+//! `RuntimeTableCfg::indexed(id, size)`, and looks up `(address, value)` pairs in it.
+//! Each proof then gives the snapshot of the memory as `RuntimeTable::new(id, values)`:
+//! the prover commits to it in the proof, and the verifier checks the lookups against the commitment.
 
 use crate::circuits::{
     expr::{prologue::*, Column},
     gate::CurrOrNext,
+    lookup::tables::TableId,
 };
 use ark_ff::Field;
 use serde::{Deserialize, Serialize};
 
 /// The specification of a runtime table.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct RuntimeTableSpec {
     /// The table ID.
-    pub id: i32,
+    pub id: TableId,
     /// The number of entries contained in the runtime table.
     pub len: usize,
 }
 
 /// Use this type at setup time, to list all the runtime tables.
 ///
-/// Note: care must be taken as table IDs can collide with IDs of other types of lookup tables.
+/// Note: like the fixed tables of the circuit, the runtime tables must use IDs
+/// outside of the range reserved for the built-in tables (see [TableId]).
+#[derive(Debug, Clone)]
 pub enum RuntimeTableCfg<F> {
     /// An indexed runtime table has a counter (starting at zero) in its first column.
     Indexed(RuntimeTableSpec),
     /// A custom runtime table can contain arbitrary values in its first column.
     Custom {
         /// The table ID.
-        id: i32,
+        id: TableId,
         /// The content of the first column of the runtime table.
         first_column: Vec<F>,
     },
 }
 
 impl<F> RuntimeTableCfg<F> {
+    /// Configures an indexed runtime table of `len` entries.
+    pub fn indexed(id: TableId, len: usize) -> Self {
+        RuntimeTableCfg::Indexed(RuntimeTableSpec { id, len })
+    }
+
+    /// Configures a custom runtime table, with the content `first_column` of its first column.
+    pub fn custom(id: TableId, first_column: Vec<F>) -> Self {
+        RuntimeTableCfg::Custom { id, first_column }
+    }
+
     /// Returns the ID of the runtime table.
-    pub fn id(&self) -> i32 {
+    pub fn id(&self) -> TableId {
         use RuntimeTableCfg::{Custom, Indexed};
         match self {
             Indexed(cfg) => cfg.id,
@@ -80,11 +100,38 @@ impl<F> From<RuntimeTableCfg<F>> for RuntimeTableSpec {
 #[derive(Debug, Clone)]
 pub struct RuntimeTable<F> {
     /// The table id.
-    pub id: i32,
+    pub id: TableId,
     /// A single column.
     pub data: Vec<F>,
 }
 
+impl<F> RuntimeTable<F> {
+    /// Creates the runtime table `id`, with the content `data` of its second column.
+    pub fn new(id: TableId, data: Vec<F>) -> Self {
+        RuntimeTable { id, data }
+    }
+}
+
+/// Orders the runtime tables `tables` as in the configuration `specs`,
+/// or returns `None` if they do not match the configuration:
+/// each configured table must be given exactly once, with the configured length.
+pub fn order_as_configured<'a, F>(
+    specs: &[RuntimeTableSpec],
+    tables: &'a [RuntimeTable<F>],
+) -> Option<Vec<&'a RuntimeTable<F>>> {
+    if tables.len() != specs.len() {
+        return None;
+    }
+    specs
+        .iter()
+        .map(|spec| {
+            let mut matching = tables.iter().filter(|table| table.id == spec.id);
+            let table = matching.next()?;
+            (matching.next().is_none() && table.data.len() == spec.len).then_some(table)
+        })
+        .collect()
+}
+
 /// Returns the constraints related to the runtime tables.
 pub fn constraints<F>() -> Vec<E<F>>
 where
@@ -1,16 +1,63 @@
 use ark_ff::{FftField, One, Zero};
 use commitment_dlog::PolyComm;
+use o1_utils::field_helpers::i32_to_field;
 use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt, ops::Neg};
+
+use super::index::LookupError;
 
 pub mod range_check;
+pub mod sha256;
 pub mod xor;
 
+/// The ID of a lookup table.
+///
+/// The non-negative IDs are reserved for the built-in tables used by the gates of kimchi,
+/// the tables defined by a circuit (fixed or runtime tables) must use negative IDs.
+/// This way, the tables of a circuit cannot collide with the tables that kimchi adds
+/// when one of its gates uses them.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct TableId(i32);
+
+impl TableId {
+    /// Creates a table ID from its raw value
+    pub const fn new(id: i32) -> Self {
+        TableId(id)
+    }
+
+    /// Returns the raw value of the table ID
+    pub const fn value(self) -> i32 {
+        self.0
+    }
+
+    /// Returns `true` if the ID is in the range reserved for the built-in tables
+    pub const fn is_built_in(self) -> bool {
+        self.0 >= 0
+    }
+
+    /// Returns the table ID as a field element, as it appears in the table ID column
+    /// of the concatenated table and in the lookups
+    pub fn to_field<F: From<u64> + Neg<Output = F>>(self) -> F {
+        i32_to_field(self.0)
+    }
+}
+
+impl fmt::Display for TableId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 //~ spec:startcode
 /// The table ID associated with the XOR lookup table.
-pub const XOR_TABLE_ID: i32 = 0;
+pub const XOR_TABLE_ID: TableId = TableId(0);
 
 /// The range check table ID.
-pub const RANGE_CHECK_TABLE_ID: i32 = 1;
+pub const RANGE_CHECK_TABLE_ID: TableId = TableId(1);
+
+/// The table ID associated with the SHA-256 spread lookup table.
+pub const SHA256_SPREAD_TABLE_ID: TableId = TableId(2);
 //~ spec:endcode
 
 /// Enumerates the different 'fixed' lookup tables used by individual gates
@@ -18,12 +65,77 @@ pub const RANGE_CHECK_TABLE_ID: i32 = 1;
 pub enum GateLookupTable {
     Xor,
     RangeCheck,
+    Sha256Spread,
+}
+
+impl GateLookupTable {
+    /// Returns the ID of the table
+    pub const fn id(self) -> TableId {
+        match self {
+            GateLookupTable::Xor => XOR_TABLE_ID,
+            GateLookupTable::RangeCheck => RANGE_CHECK_TABLE_ID,
+            GateLookupTable::Sha256Spread => SHA256_SPREAD_TABLE_ID,
+        }
+    }
+}
+
+/// The origin of a lookup table
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TableKind {
+    /// A built-in table, used by some gates of kimchi
+    BuiltIn,
+    /// A fixed table of the circuit
+    Fixed,
+    /// A runtime table of the circuit
+    Runtime,
+}
+
+/// The registry of the lookup tables of a constraint system, indexed by their IDs
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableRegistry(BTreeMap<TableId, TableKind>);
+
+impl TableRegistry {
+    /// Registers the table `id` of kind `kind`.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the ID is already registered,
+    /// or if a table of the circuit uses an ID reserved for the built-in tables.
+    pub fn register(&mut self, id: TableId, kind: TableKind) -> Result<(), LookupError> {
+        if kind != TableKind::BuiltIn && id.is_built_in() {
+            return Err(LookupError::ReservedTableId(id));
+        }
+        if let Some(&registered) = self.0.get(&id) {
+            return Err(LookupError::TableIdCollision {
+                id,
+                registered,
+                kind,
+            });
+        }
+        self.0.insert(id, kind);
+        Ok(())
+    }
+
+    /// Returns the kind of the table `id`, if it is registered
+    pub fn get(&self, id: TableId) -> Option<TableKind> {
+        self.0.get(&id).copied()
+    }
+
+    /// Returns `true` if the table `id` is registered
+    pub fn contains(&self, id: TableId) -> bool {
+        self.0.contains_key(&id)
+    }
+
+    /// Iterates over the registered tables, by increasing ID
+    pub fn iter(&self) -> impl Iterator<Item = (TableId, TableKind)> + '_ {
+        self.0.iter().map(|(id, kind)| (*id, *kind))
+    }
 }
 
 /// A table of values that can be used for a lookup, along with the ID for the table.
 #[derive(Debug, Clone)]
 pub struct LookupTable<F> {
-    pub id: i32,
+    pub id: TableId,
     pub data: Vec<Vec<F>>,
 }
 
@@ -63,6 +175,7 @@ pub fn get_table<F: FftField>(table_name: GateLookupTable) -> LookupTable<F> {
     match table_name {
         GateLookupTable::Xor => xor::xor_table(),
         GateLookupTable::RangeCheck => range_check::range_check_table(),
+        GateLookupTable::Sha256Spread => sha256::sha256_spread_table(),
     }
 }
 
@@ -0,0 +1,43 @@
+//! SHA-256 spread table
+
+use crate::circuits::lookup::tables::{LookupTable, SHA256_SPREAD_TABLE_ID};
+use ark_ff::Field;
+
+//~ The lookup table for the "spread" form of 4-bit values used by the SHA-256 gates.
+//~ The spread form of a value is obtained by interleaving a zero bit before each of its bits,
+//~ so that the $i$-th bit of `n` ends up at position $2i$ of `spread(n)`.
+//~ Each row of the table is a pair `(n, spread(n))` for `n` in `[0, 2^4)`.
+//~
+//~ As with the XOR table, the table is reversed so that `(0, 0)` is its last entry.
+
+/// The number of bits of the values in the SHA-256 spread table
+pub const SHA256_SPREAD_BITS: u32 = 4;
+
+/// Returns the spread form of the `u32` value `x`,
+/// that is the value whose bit `2i` is the bit `i` of `x` and whose odd bits are zero.
+pub fn spread(x: u32) -> u64 {
+    (0..32).fold(0u64, |acc, i| acc | (((x as u64 >> i) & 1) << (2 * i)))
+}
+
+/// Returns the SHA-256 spread lookup table
+///
+/// # Panics
+///
+/// Will panic if `data` is invalid.
+pub fn sha256_spread_table<F: Field>() -> LookupTable<F> {
+    let mut data = vec![vec![]; 2];
+
+    for n in 0u32..(1 << SHA256_SPREAD_BITS) {
+        data[0].push(F::from(n));
+        data[1].push(F::from(spread(n)));
+    }
+
+    for r in &mut data {
+        r.reverse();
+        assert!(r[r.len() - 1].is_zero());
+    }
+    LookupTable {
+        id: SHA256_SPREAD_TABLE_ID,
+        data,
+    }
+}
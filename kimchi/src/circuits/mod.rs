@@ -2,14 +2,27 @@
 pub mod macros;
 
 pub mod argument;
+#[cfg(feature = "prover")]
+pub mod builder;
+#[cfg(feature = "prover")]
+pub mod compare;
 pub mod constraints;
+pub mod custom_gate;
+pub mod description;
 pub mod domain_constant_evaluation;
 pub mod domains;
 pub mod expr;
 pub mod gate;
 pub mod lookup;
+pub mod plan;
 pub mod polynomial;
 pub mod polynomials;
 pub mod scalars;
 mod serialization_helper;
+#[cfg(feature = "prover")]
+pub mod sponge;
 pub mod wires;
+#[cfg(feature = "prover")]
+pub mod witness_diff;
+#[cfg(feature = "prover")]
+pub mod witness_generator;
@@ -0,0 +1,140 @@
+//! This module implements a planning API for circuits,
+//! which reports the size of the domain a circuit requires without building its constraint system.
+
+use crate::{
+    circuits::{
+        gate::{CircuitGate, CurrOrNext},
+        lookup::{
+            lookups::{LookupInfo, LookupPattern},
+            runtime_tables::RuntimeTableCfg,
+            tables::{get_table, LookupTable},
+        },
+        polynomials::permutation::ZK_ROWS,
+    },
+    error::SetupError,
+};
+use ark_ff::PrimeField;
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain as D};
+use std::collections::HashSet;
+
+/// The part of a circuit that dictates the size of its domain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitingFactor {
+    /// The gates, followed by the zero-knowledge rows.
+    Gates,
+    /// The concatenated lookup table (fixed and runtime tables), followed by
+    /// the dummy entry, the final row of the lookup argument and the zero-knowledge rows.
+    /// The tables are not split across several table polynomials,
+    /// so a table longer than the gates grows the domain.
+    LookupTables,
+}
+
+/// The row usage of a circuit, and the size of the domain it requires.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CircuitPlan {
+    /// The number of gates in the circuit
+    pub gates: usize,
+    /// The number of rows reserved for zero-knowledge at the end of the domain
+    pub zk_rows: usize,
+    /// The number of entries of the concatenated lookup table,
+    /// including the tables used by gates and the runtime tables (0 if no lookup is used)
+    pub lookup_table_entries: usize,
+    /// The number of rows required by the gates
+    pub gate_rows: usize,
+    /// The number of rows required by the lookup argument (0 if no lookup is used)
+    pub lookup_rows: usize,
+    /// The size of the smallest domain fitting the circuit
+    pub domain_size: usize,
+    /// What dictates the size of the domain
+    pub limiting_factor: LimitingFactor,
+}
+
+impl CircuitPlan {
+    /// Computes the plan of a circuit made of `gates`,
+    /// using the given lookup tables, runtime tables, and number of zero-knowledge rows.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if no domain of the field is large enough for the circuit.
+    pub fn create<F: PrimeField>(
+        gates: &[CircuitGate<F>],
+        lookup_tables: &[LookupTable<F>],
+        runtime_tables: Option<&[RuntimeTableCfg<F>]>,
+        zk_rows: u64,
+    ) -> Result<Self, SetupError> {
+        let zk_rows = zk_rows as usize;
+        let gate_rows = gates.len() + zk_rows;
+
+        // the lookup tables are only taken into account if a gate uses lookups,
+        // see [crate::circuits::lookup::index::LookupConstraintSystem::create]
+        let lookup_table_entries = LookupInfo::create_from_gates(gates, runtime_tables.is_some())
+            .and_then(|lookup_info| lookup_info.lookup_used())
+            .map(|_| {
+                let mut gate_tables = HashSet::new();
+                for gate in gates {
+                    for row in [CurrOrNext::Curr, CurrOrNext::Next] {
+                        if let Some(table) =
+                            LookupPattern::from_gate(gate.typ, row).and_then(|p| p.table())
+                        {
+                            gate_tables.insert(table);
+                        }
+                    }
+                }
+
+                let gate_entries: usize = gate_tables
+                    .into_iter()
+                    .map(|table| get_table::<F>(table).len())
+                    .sum();
+                let fixed_entries: usize = lookup_tables.iter().map(LookupTable::len).sum();
+                let runtime_entries: usize = runtime_tables
+                    .unwrap_or_default()
+                    .iter()
+                    .map(RuntimeTableCfg::len)
+                    .sum();
+
+                gate_entries + fixed_entries + runtime_entries
+            });
+
+        // the concatenated table must be strictly shorter than `n - zk_rows - 1`,
+        // to leave space for the dummy value
+        let lookup_rows = lookup_table_entries.map_or(0, |entries| entries + zk_rows + 2);
+        let lookup_table_entries = lookup_table_entries.unwrap_or(0);
+
+        let (rows, limiting_factor) = if lookup_rows > gate_rows {
+            (lookup_rows, LimitingFactor::LookupTables)
+        } else {
+            (gate_rows, LimitingFactor::Gates)
+        };
+
+        let domain_size = D::<F>::compute_size_of_domain(rows).ok_or(
+            SetupError::DomainCreation("could not compute size of domain"),
+        )?;
+
+        Ok(CircuitPlan {
+            gates: gates.len(),
+            zk_rows,
+            lookup_table_entries,
+            gate_rows,
+            lookup_rows,
+            domain_size,
+            limiting_factor,
+        })
+    }
+
+    /// The number of rows of the domain left unused by the circuit.
+    pub fn free_rows(&self) -> usize {
+        self.domain_size - std::cmp::max(self.gate_rows, self.lookup_rows)
+    }
+}
+
+/// Computes the plan of a circuit made of `gates`,
+/// without lookup tables other than the ones used by the gates,
+/// and with the default number of zero-knowledge rows.
+/// Use [crate::circuits::constraints::Builder::plan] for other configurations.
+///
+/// # Errors
+///
+/// Will give error if no domain of the field is large enough for the circuit.
+pub fn plan_circuit<F: PrimeField>(gates: &[CircuitGate<F>]) -> Result<CircuitPlan, SetupError> {
+    CircuitPlan::create(gates, &[], None, ZK_ROWS)
+}
@@ -0,0 +1,586 @@
+//! This module lays out bitwise operations on 32-bit and 64-bit words, and rotations of 16-bit words.
+
+use ark_ff::PrimeField;
+
+use crate::circuits::{
+    gate::CircuitGate,
+    polynomial::COLUMNS,
+    polynomials::sha256::gadget::{Layout, Var, Word},
+};
+#[cfg(feature = "prover")]
+use crate::circuits::witness_generator::WitnessGenerator;
+
+//~ The bitwise gadget does not define gates of its own: like the Keccak-256 gadget, it is built from the
+//~ 32-bit word gates of the SHA-256 gadget and their spread lookup table, together with generic gates.
+//~ Each word is decomposed by a `Sha256Word` gate, which checks that it is smaller than $2^{32}$
+//~ and gives its spread form, that is the word with a zero bit inserted before each of its bits.
+//~
+//~ The bitwise operations on two words `a` and `b` are extracted from the sum $spread(a) + spread(b)$,
+//~ whose even bits are $a \oplus b$ and whose odd bits are $a \wedge b$:
+//~
+//~ * `XOR` and `AND` are the even and odd parts of the sum, decomposed by `Sha256Spread` gates,
+//~ * `OR` is the sum of these parts, as $a \vee b = (a \oplus b) + (a \wedge b)$,
+//~ * `NOT` is computed with a generic gate, as $\neg a = (2^{32} - 1) - a$.
+//~
+//~ The shift to the left by `k` bits of a word `x` is checked with a generic gate, as
+//~ $2^k \cdot x = 2^{32} \cdot h + l$
+//~ where `h` and `l` are words decomposed by `Sha256Word` gates.
+//~ As both sides are smaller than $2^{64}$, this equation holds over the integers,
+//~ so `l` is `x << k` and `h` is `x >> (32 - k)`.
+//~ The shifts to the right and the rotations are obtained from the same decomposition.
+//~
+//~ A 64-bit word is represented by its low and high 32-bit words,
+//~ on which the operations are done with the carries between the two halves.
+//~
+//~ A 16-bit word `x` is represented by a 32-bit word, with the additional check that
+//~ $2^{16} \cdot x$ is a word decomposed by a `Sha256Word` gate.
+//~ Its rotation to the left by `k` bits is obtained from the shift of `x` by $16 + k$ bits,
+//~ whose high word is `x >> (16 - k)` and whose low word is `(x << k) mod 2^16` shifted by 16 bits.
+//~
+//~ The rotations are available for any of these word sizes with `rotl` and `rotr`,
+//~ as needed by the ARX ciphers and hash functions.
+
+/// A 32-bit word laid out by a [Bitwise] gadget
+#[derive(Clone, Copy, Debug)]
+pub struct Word32(Word);
+
+impl Word32 {
+    /// The value of the word
+    pub fn value(&self) -> u32 {
+        self.0.word.value as u32
+    }
+
+    /// The cell `(row, column)`, with the row relative to the start of the gadget,
+    /// holding the word in its `Sha256Word` gate
+    pub fn cell(&self) -> (usize, usize) {
+        (self.0.word.row, self.0.word.col)
+    }
+}
+
+/// A 16-bit word laid out by a [Bitwise] gadget, as a 32-bit word checked to be smaller than `2^16`
+#[derive(Clone, Copy, Debug)]
+pub struct Word16(Word32);
+
+impl Word16 {
+    /// The value of the word
+    pub fn value(&self) -> u16 {
+        self.0.value() as u16
+    }
+
+    /// The cell `(row, column)`, with the row relative to the start of the gadget,
+    /// holding the word in its `Sha256Word` gate
+    pub fn cell(&self) -> (usize, usize) {
+        self.0.cell()
+    }
+}
+
+/// A 64-bit word laid out by a [Bitwise] gadget, given by its low and high 32-bit words
+#[derive(Clone, Copy, Debug)]
+pub struct Word64 {
+    /// The low 32 bits of the word
+    pub low: Word32,
+    /// The high 32 bits of the word
+    pub high: Word32,
+}
+
+impl Word64 {
+    /// The value of the word
+    pub fn value(&self) -> u64 {
+        (self.high.value() as u64) << 32 | self.low.value() as u64
+    }
+}
+
+/// A word of [RotatableWord::BITS] bits laid out by a [Bitwise] gadget,
+/// which can be rotated by [Bitwise::rotl] and [Bitwise::rotr]
+pub trait RotatableWord<F: PrimeField>: Copy {
+    /// The size of the word in bits
+    const BITS: u32;
+
+    /// Decomposes an input word, given by its `BITS` low bits of `value`
+    fn input(gadget: &mut Bitwise<F>, value: u64) -> Self;
+
+    /// The value of the word
+    fn to_u64(&self) -> u64;
+
+    /// The rotation to the left of the word by `k` bits, for `k < BITS`
+    fn rotate_left(self, gadget: &mut Bitwise<F>, k: u32) -> Self;
+}
+
+impl<F: PrimeField> RotatableWord<F> for Word16 {
+    const BITS: u32 = 16;
+
+    fn input(gadget: &mut Bitwise<F>, value: u64) -> Self {
+        gadget.input16(value as u16)
+    }
+
+    fn to_u64(&self) -> u64 {
+        self.value() as u64
+    }
+
+    fn rotate_left(self, gadget: &mut Bitwise<F>, k: u32) -> Self {
+        gadget.rotl16(self, k)
+    }
+}
+
+impl<F: PrimeField> RotatableWord<F> for Word32 {
+    const BITS: u32 = 32;
+
+    fn input(gadget: &mut Bitwise<F>, value: u64) -> Self {
+        gadget.input32(value as u32)
+    }
+
+    fn to_u64(&self) -> u64 {
+        self.value() as u64
+    }
+
+    fn rotate_left(self, gadget: &mut Bitwise<F>, k: u32) -> Self {
+        gadget.rotl32(self, k)
+    }
+}
+
+impl<F: PrimeField> RotatableWord<F> for Word64 {
+    const BITS: u32 = 64;
+
+    fn input(gadget: &mut Bitwise<F>, value: u64) -> Self {
+        gadget.input64(value)
+    }
+
+    fn to_u64(&self) -> u64 {
+        self.value()
+    }
+
+    fn rotate_left(self, gadget: &mut Bitwise<F>, k: u32) -> Self {
+        gadget.rotl64(self, k)
+    }
+}
+
+/// Lays out the gates and the witness of bitwise operations on words.
+///
+/// The gates do not depend on the values of the words, so the gadget laid out
+/// with any inputs gives the gates of the circuit, and with the actual inputs gives its witness.
+pub struct Bitwise<F: PrimeField> {
+    start_row: usize,
+    layout: Layout<F>,
+}
+
+impl<F: PrimeField> Bitwise<F> {
+    /// Starts a gadget at the row `start_row`
+    pub fn new(start_row: usize) -> Self {
+        Bitwise {
+            start_row,
+            layout: Layout::new(start_row),
+        }
+    }
+
+    /// The row after the gates laid out so far
+    pub fn next_row(&self) -> usize {
+        self.start_row + self.layout.gates.len()
+    }
+
+    /// Outputs tuple (`circuit_gates`, `witness`) of the operations laid out,
+    /// where the witness starts at the first row of the gadget
+    pub fn finish(self) -> (Vec<CircuitGate<F>>, [Vec<F>; COLUMNS]) {
+        (self.layout.gates, self.layout.witness)
+    }
+
+    /// Decomposes an input 16-bit word
+    pub fn input16(&mut self, value: u16) -> Word16 {
+        let x = self.input32(value as u32);
+        self.check16(x);
+        Word16(x)
+    }
+
+    /// Decomposes an input word
+    pub fn input32(&mut self, value: u32) -> Word32 {
+        Word32(self.layout.input(value))
+    }
+
+    /// Decomposes an input 64-bit word
+    pub fn input64(&mut self, value: u64) -> Word64 {
+        Word64 {
+            low: self.input32(value as u32),
+            high: self.input32((value >> 32) as u32),
+        }
+    }
+
+    /// The rotation to the left of the word `x`, of any size, by `k` bits
+    pub fn rotl<W: RotatableWord<F>>(&mut self, x: W, k: u32) -> W {
+        x.rotate_left(self, k % W::BITS)
+    }
+
+    /// The rotation to the right of the word `x`, of any size, by `k` bits
+    pub fn rotr<W: RotatableWord<F>>(&mut self, x: W, k: u32) -> W {
+        self.rotl(x, W::BITS - k % W::BITS)
+    }
+
+    /// The rotation to the left of the 16-bit word `x` by `k` bits
+    pub fn rotl16(&mut self, x: Word16, k: u32) -> Word16 {
+        let k = k % 16;
+        if k == 0 {
+            return x;
+        }
+        let (high, low) = self.shift(x.0, 16 + k);
+        let low = self.shr32(low, 16);
+        Word16(self.combine(low, high))
+    }
+
+    /// The rotation to the right of the 16-bit word `x` by `k` bits
+    pub fn rotr16(&mut self, x: Word16, k: u32) -> Word16 {
+        self.rotl16(x, 16 - k % 16)
+    }
+
+    /// The word `a ^ b`
+    pub fn xor32(&mut self, a: Word32, b: Word32) -> Word32 {
+        let (even, _) = self.spread_sum(a, b);
+        Word32(self.layout.reduce(even))
+    }
+
+    /// The word `a & b`
+    pub fn and32(&mut self, a: Word32, b: Word32) -> Word32 {
+        let (_, odd) = self.spread_sum(a, b);
+        Word32(self.layout.reduce(odd))
+    }
+
+    /// The word `a | b`
+    pub fn or32(&mut self, a: Word32, b: Word32) -> Word32 {
+        let (even, odd) = self.spread_sum(a, b);
+        let sum = self.layout.add(&[even, odd], 0);
+        Word32(self.layout.reduce(sum))
+    }
+
+    /// The word `!a`
+    pub fn not32(&mut self, a: Word32) -> Word32 {
+        let ones = u32::MAX as u64;
+        let value = ones - a.0.word.value;
+        let not = self.layout.linear(
+            (-F::one(), a.0.word),
+            (F::zero(), a.0.word),
+            F::from(ones),
+            value,
+        );
+        Word32(self.layout.reduce(not))
+    }
+
+    /// The word `x << k`, for `k < 32`
+    pub fn shl32(&mut self, x: Word32, k: u32) -> Word32 {
+        assert!(k < 32, "shift of a 32-bit word by {k} bits");
+        if k == 0 {
+            return x;
+        }
+        self.shift(x, k).1
+    }
+
+    /// The word `x >> k`, for `k < 32`
+    pub fn shr32(&mut self, x: Word32, k: u32) -> Word32 {
+        assert!(k < 32, "shift of a 32-bit word by {k} bits");
+        if k == 0 {
+            return x;
+        }
+        self.shift(x, 32 - k).0
+    }
+
+    /// The rotation to the left of the word `x` by `k` bits
+    pub fn rotl32(&mut self, x: Word32, k: u32) -> Word32 {
+        let k = k % 32;
+        if k == 0 {
+            return x;
+        }
+        let (high, low) = self.shift(x, k);
+        self.combine(low, high)
+    }
+
+    /// The rotation to the right of the word `x` by `k` bits
+    pub fn rotr32(&mut self, x: Word32, k: u32) -> Word32 {
+        self.rotl32(x, 32 - k % 32)
+    }
+
+    /// The 64-bit word `a ^ b`
+    pub fn xor64(&mut self, a: Word64, b: Word64) -> Word64 {
+        Word64 {
+            low: self.xor32(a.low, b.low),
+            high: self.xor32(a.high, b.high),
+        }
+    }
+
+    /// The 64-bit word `a & b`
+    pub fn and64(&mut self, a: Word64, b: Word64) -> Word64 {
+        Word64 {
+            low: self.and32(a.low, b.low),
+            high: self.and32(a.high, b.high),
+        }
+    }
+
+    /// The 64-bit word `a | b`
+    pub fn or64(&mut self, a: Word64, b: Word64) -> Word64 {
+        Word64 {
+            low: self.or32(a.low, b.low),
+            high: self.or32(a.high, b.high),
+        }
+    }
+
+    /// The 64-bit word `!a`
+    pub fn not64(&mut self, a: Word64) -> Word64 {
+        Word64 {
+            low: self.not32(a.low),
+            high: self.not32(a.high),
+        }
+    }
+
+    /// The 64-bit word `x << k`, for `k < 64`
+    pub fn shl64(&mut self, x: Word64, k: u32) -> Word64 {
+        assert!(k < 64, "shift of a 64-bit word by {k} bits");
+        if k == 0 {
+            return x;
+        }
+        if k >= 32 {
+            return Word64 {
+                low: self.zero(x.low),
+                high: self.shl32(x.low, k - 32),
+            };
+        }
+        let (carry, low) = self.shift(x.low, k);
+        let (_, high) = self.shift(x.high, k);
+        Word64 {
+            low,
+            high: self.combine(high, carry),
+        }
+    }
+
+    /// The 64-bit word `x >> k`, for `k < 64`
+    pub fn shr64(&mut self, x: Word64, k: u32) -> Word64 {
+        assert!(k < 64, "shift of a 64-bit word by {k} bits");
+        if k == 0 {
+            return x;
+        }
+        if k >= 32 {
+            return Word64 {
+                low: self.shr32(x.high, k - 32),
+                high: self.zero(x.high),
+            };
+        }
+        let (low, _) = self.shift(x.low, 32 - k);
+        let (high, carry) = self.shift(x.high, 32 - k);
+        Word64 {
+            low: self.combine(low, carry),
+            high,
+        }
+    }
+
+    /// The rotation to the left of the 64-bit word `x` by `k` bits
+    pub fn rotl64(&mut self, x: Word64, k: u32) -> Word64 {
+        let k = k % 64;
+        let x = if k >= 32 {
+            Word64 {
+                low: x.high,
+                high: x.low,
+            }
+        } else {
+            x
+        };
+        let k = k % 32;
+        if k == 0 {
+            return x;
+        }
+        let (low_carry, low) = self.shift(x.low, k);
+        let (high_carry, high) = self.shift(x.high, k);
+        Word64 {
+            low: self.combine(low, high_carry),
+            high: self.combine(high, low_carry),
+        }
+    }
+
+    /// The rotation to the right of the 64-bit word `x` by `k` bits
+    pub fn rotr64(&mut self, x: Word64, k: u32) -> Word64 {
+        self.rotl64(x, 64 - k % 64)
+    }
+
+    // Decomposes the sum of the spread forms of `a` and `b` into its even and odd parts,
+    // that is into `a ^ b` and `a & b`
+    fn spread_sum(&mut self, a: Word32, b: Word32) -> (Var, Var) {
+        let sum = self.layout.add(&[a.0.spread, b.0.spread], 0);
+        self.layout.decompose(sum)
+    }
+
+    // Splits `2^k x`, for `0 < k < 32`, into its high and low words,
+    // that is `x >> (32 - k)` and `x << k`
+    fn shift(&mut self, x: Word32, k: u32) -> (Word32, Word32) {
+        let value = x.value();
+        let (_, high) = self.layout.word_gate((value >> (32 - k)) as u64);
+        let (_, low) = self.layout.word_gate((value << k) as u64);
+        let shifted = self.layout.linear(
+            (F::from(1u64 << k), x.0.word),
+            (-F::from(1u64 << 32), high.word),
+            F::zero(),
+            low.word.value,
+        );
+        self.layout.copy(shifted, low.word.row, low.word.col);
+        (Word32(high), Word32(low))
+    }
+
+    // Checks that the word `x` is smaller than `2^16`, as `2^16 x` is a word
+    fn check16(&mut self, x: Word32) {
+        let value = (x.value() as u64) << 16;
+        let (row, _) = self.layout.word_gate(value);
+        let shifted = self.layout.linear(
+            (F::from(1u64 << 16), x.0.word),
+            (F::zero(), x.0.word),
+            F::zero(),
+            value,
+        );
+        self.layout.copy(shifted, row, 0);
+    }
+
+    // The word `a + b`, where `a` and `b` have no bits in common
+    fn combine(&mut self, a: Word32, b: Word32) -> Word32 {
+        let sum = self.layout.add(&[a.0.word, b.0.word], 0);
+        Word32(self.layout.reduce(sum))
+    }
+
+    // The zero word, constrained with a generic gate on any cell `x`
+    fn zero(&mut self, x: Word32) -> Word32 {
+        let zero = self
+            .layout
+            .linear((F::zero(), x.0.word), (F::zero(), x.0.word), F::zero(), 0);
+        Word32(self.layout.reduce(zero))
+    }
+}
+
+/// A bitwise operation laid out by a [BitwiseGadget]
+#[cfg(feature = "prover")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BitwiseOp {
+    /// `a ^ b`
+    Xor,
+    /// `a & b`
+    And,
+    /// `a | b`
+    Or,
+    /// `!a`
+    Not,
+    /// `a << k`, for `k` smaller than the size of the words
+    Shl(u32),
+    /// `a >> k`, for `k` smaller than the size of the words
+    Shr(u32),
+    /// The rotation to the left of `a` by `k` bits
+    Rotl(u32),
+    /// The rotation to the right of `a` by `k` bits
+    Rotr(u32),
+}
+
+#[cfg(feature = "prover")]
+impl BitwiseOp {
+    /// The number of words the operation is applied to
+    pub fn arity(&self) -> usize {
+        match self {
+            BitwiseOp::Xor | BitwiseOp::And | BitwiseOp::Or => 2,
+            _ => 1,
+        }
+    }
+}
+
+/// A bitwise operation on words of `bits` bits, as a [WitnessGenerator] whose input is
+/// the values of the words the operation is applied to (see [BitwiseOp::arity]).
+/// Its input cells are the cells of these words, and its output cells the cells of the result,
+/// where a 64-bit word is held by the cells of its low and high 32-bit words.
+///
+/// Only the rotations are available on 16-bit words.
+#[cfg(feature = "prover")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BitwiseGadget {
+    /// The operation
+    pub op: BitwiseOp,
+    /// The size of the words, 16, 32 or 64 bits
+    pub bits: u32,
+}
+
+/// The cells `(row, column)` of the inputs and of the output of a [BitwiseGadget]
+#[cfg(feature = "prover")]
+type BitwiseCells = (Vec<(usize, usize)>, Vec<(usize, usize)>);
+
+#[cfg(feature = "prover")]
+impl BitwiseGadget {
+    // Lays out the operation from the row `start_row` on the words of `values`,
+    // and returns the gadget with the cells of the inputs and of the output
+    fn lay_out<F: PrimeField>(
+        &self,
+        start_row: usize,
+        values: &[u64],
+    ) -> (Bitwise<F>, BitwiseCells) {
+        assert_eq!(
+            values.len(),
+            self.op.arity(),
+            "the operation {:?} is applied to {} words",
+            self.op,
+            self.op.arity()
+        );
+        let mut gadget = Bitwise::new(start_row);
+        let cells = match self.bits {
+            16 => {
+                let x = gadget.input16(values[0] as u16);
+                let output = match self.op {
+                    BitwiseOp::Rotl(k) => gadget.rotl16(x, k),
+                    BitwiseOp::Rotr(k) => gadget.rotr16(x, k),
+                    op => panic!("the operation {op:?} is not available on 16-bit words"),
+                };
+                (vec![x.cell()], vec![output.cell()])
+            }
+            32 => {
+                let xs: Vec<_> = values.iter().map(|v| gadget.input32(*v as u32)).collect();
+                let output = match self.op {
+                    BitwiseOp::Xor => gadget.xor32(xs[0], xs[1]),
+                    BitwiseOp::And => gadget.and32(xs[0], xs[1]),
+                    BitwiseOp::Or => gadget.or32(xs[0], xs[1]),
+                    BitwiseOp::Not => gadget.not32(xs[0]),
+                    BitwiseOp::Shl(k) => gadget.shl32(xs[0], k),
+                    BitwiseOp::Shr(k) => gadget.shr32(xs[0], k),
+                    BitwiseOp::Rotl(k) => gadget.rotl32(xs[0], k),
+                    BitwiseOp::Rotr(k) => gadget.rotr32(xs[0], k),
+                };
+                (xs.iter().map(Word32::cell).collect(), vec![output.cell()])
+            }
+            64 => {
+                let xs: Vec<_> = values.iter().map(|v| gadget.input64(*v)).collect();
+                let output = match self.op {
+                    BitwiseOp::Xor => gadget.xor64(xs[0], xs[1]),
+                    BitwiseOp::And => gadget.and64(xs[0], xs[1]),
+                    BitwiseOp::Or => gadget.or64(xs[0], xs[1]),
+                    BitwiseOp::Not => gadget.not64(xs[0]),
+                    BitwiseOp::Shl(k) => gadget.shl64(xs[0], k),
+                    BitwiseOp::Shr(k) => gadget.shr64(xs[0], k),
+                    BitwiseOp::Rotl(k) => gadget.rotl64(xs[0], k),
+                    BitwiseOp::Rotr(k) => gadget.rotr64(xs[0], k),
+                };
+                let cells = |x: &Word64| [x.low.cell(), x.high.cell()];
+                (xs.iter().flat_map(cells).collect(), cells(&output).to_vec())
+            }
+            bits => panic!("bitwise operations on {bits}-bit words are not available"),
+        };
+        (gadget, cells)
+    }
+}
+
+#[cfg(feature = "prover")]
+impl<F: PrimeField> WitnessGenerator<F> for BitwiseGadget {
+    type Input = Vec<u64>;
+
+    fn gates(&self, start_row: usize) -> Vec<CircuitGate<F>> {
+        // the gates do not depend on the values of the words
+        let (gadget, _) = self.lay_out::<F>(start_row, &[0; 2][..self.op.arity()]);
+        gadget.finish().0
+    }
+
+    fn input_cells(&self) -> Vec<(usize, usize)> {
+        let (_, (inputs, _)) = self.lay_out::<F>(0, &[0; 2][..self.op.arity()]);
+        inputs
+    }
+
+    fn output_cells(&self) -> Vec<(usize, usize)> {
+        let (_, (_, output)) = self.lay_out::<F>(0, &[0; 2][..self.op.arity()]);
+        output
+    }
+
+    fn witness(&self, values: &Vec<u64>) -> [Vec<F>; COLUMNS] {
+        let (gadget, _) = self.lay_out::<F>(0, values);
+        gadget.finish().1
+    }
+}
@@ -0,0 +1,3 @@
+//! Bitwise gadget module
+
+pub mod gadget;
@@ -0,0 +1,63 @@
+//! This module implements the boolean columns argument.
+
+use ark_ff::FftField;
+
+use crate::{
+    alphas::Alphas,
+    circuits::{
+        argument::ArgumentType,
+        expr::{prologue::*, Column},
+        gate::CurrOrNext,
+    },
+};
+
+//~ A witness column can be declared boolean at the level of the constraint system
+//~ (see `Builder::boolean_columns`), in which case all its cells are checked to be zero or one
+//~ by a single constraint, instead of a degree-2 gate constraint per cell.
+//~
+//~ The constraint of a boolean column $w$ is
+//~
+//~ $$v(x) \cdot w(x) \cdot (w(x) - 1) = 0$$
+//~
+//~ where $v$ is the polynomial vanishing on the last `zk_rows + 1` rows of the domain
+//~ (the rows of the witness that are randomized for zero-knowledge).
+//~ It does not depend on a selector, so it applies to every row of the circuit:
+//~ the cells of the public inputs (in column 0) and the padding rows must also be boolean.
+//~
+//~ The constraints of the boolean columns are combined with their own powers of $\alpha$,
+//~ registered after those of the lookup argument.
+//~ As their only columns are the witness columns, which are evaluated at $\zeta$ in every proof,
+//~ they only contribute to the constant term of the linearization,
+//~ and do not add any commitment or evaluation to the proof.
+//~
+//~ **Soundness.** If a cell of a boolean column, at a row $i$ not reserved for zero-knowledge,
+//~ is neither zero nor one, the constraint of the column does not vanish at $\omega^i$.
+//~ The combination of all the constraints with the powers of the challenge $\alpha$ then vanishes
+//~ at $\omega^i$ for fewer values of $\alpha$ than the number of powers of $\alpha$ of the circuit,
+//~ and otherwise is not divisible by the vanishing polynomial of the domain,
+//~ so that the check of the quotient at $\zeta$ fails except with a probability bounded
+//~ by the Schwartz-Zippel lemma, as for the constraints of the gates.
+
+/// The constraints of the boolean columns `columns`, one for each column
+pub fn constraints<F: FftField>(columns: &[usize]) -> Vec<E<F>> {
+    columns
+        .iter()
+        .map(|col| {
+            let w = E::cell(Column::Witness(*col), CurrOrNext::Curr);
+            E::VanishesOnZeroKnowledgeAndPreviousRows * (w.clone() * w.clone() - w)
+        })
+        .collect()
+}
+
+/// The constraints of the boolean columns `columns`, combined with their powers of alpha
+///
+/// # Panics
+///
+/// Will panic if the powers of alpha of the boolean columns are not registered.
+pub fn combined_constraints<F: FftField>(columns: &[usize], alphas: &Alphas<F>) -> E<F> {
+    let constraints = constraints(columns);
+    let constraints_len = u32::try_from(constraints.len())
+        .expect("we always expect a relatively low amount of constraints");
+    let alphas = alphas.get_exponents(ArgumentType::BooleanColumns, constraints_len);
+    E::combine_constraints(alphas, constraints)
+}
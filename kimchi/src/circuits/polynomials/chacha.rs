@@ -147,6 +147,14 @@ use crate::circuits::{
     expr::constraints::{boolean, ExprOps},
     gate::{CurrOrNext, GateType},
 };
+#[cfg(feature = "prover")]
+use crate::circuits::{
+    gate::{CircuitGate, Connect},
+    wires::{Wire, COLUMNS},
+    witness_generator::WitnessGenerator,
+};
+#[cfg(feature = "prover")]
+use std::array;
 use ark_ff::{FftField, Field, PrimeField};
 
 //
@@ -319,8 +327,8 @@ pub mod testing {
         gs
     }
 
-    const CHACHA20_ROTATIONS: [u32; 4] = [16, 12, 8, 7];
-    const CHACHA20_QRS: [[usize; 4]; 8] = [
+    pub(super) const CHACHA20_ROTATIONS: [u32; 4] = [16, 12, 8, 7];
+    pub(super) const CHACHA20_QRS: [[usize; 4]; 8] = [
         [0, 4, 8, 12],
         [1, 5, 9, 13],
         [2, 6, 10, 14],
@@ -464,6 +472,106 @@ pub mod testing {
     }
 }
 
+/// The 20 rounds of the ChaCha permutation of a state of 16 words, as a [WitnessGenerator]
+/// whose input is the state. Its input cells are the cells where each word of the state
+/// is first read, and its output cells the cells of the words of the permuted state
+/// (without the final addition of the input state of the ChaCha20 block function).
+///
+/// Each word read by a line of a quarter round is wired to the cell where it was last written.
+#[cfg(feature = "prover")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChaCha20Gadget;
+
+/// The wiring of a [ChaCha20Gadget]: the input cells, the pairs of cells copied from one to the other,
+/// and the output cells
+#[cfg(feature = "prover")]
+type ChaCha20Wiring = (
+    Vec<(usize, usize)>,
+    Vec<((usize, usize), (usize, usize))>,
+    Vec<(usize, usize)>,
+);
+
+#[cfg(feature = "prover")]
+impl ChaCha20Gadget {
+    // Follows the cells holding the words of the state through the lines of the quarter rounds
+    fn wiring() -> ChaCha20Wiring {
+        let mut inputs: [Option<(usize, usize)>; 16] = [None; 16];
+        let mut current: [Option<(usize, usize)>; 16] = [None; 16];
+        let mut copies = vec![];
+        let mut row = 0;
+
+        let mut line = |x: usize, y: usize, z: usize, k: u32| {
+            for (word, col) in [(x, 0), (y, 1), (z, 2)] {
+                match current[word] {
+                    Some(cell) => copies.push((cell, (row, col))),
+                    None => inputs[word] = Some((row, col)),
+                }
+            }
+            current[x] = Some((row + 1, 0));
+            // the last line of a quarter round is followed by a `ChaChaFinal` gate,
+            // which holds the rotated word
+            if k == 7 {
+                current[y] = Some((row + 2, 0));
+                row += 4;
+            } else {
+                current[y] = Some((row + 1, 1));
+                row += 2;
+            }
+        };
+        for _ in 0..10 {
+            for [a, b, c, d] in testing::CHACHA20_QRS {
+                line(a, d, b, testing::CHACHA20_ROTATIONS[0]);
+                line(c, b, d, testing::CHACHA20_ROTATIONS[1]);
+                line(a, d, b, testing::CHACHA20_ROTATIONS[2]);
+                line(c, b, d, testing::CHACHA20_ROTATIONS[3]);
+            }
+        }
+
+        let cells = |cells: [Option<(usize, usize)>; 16]| {
+            cells
+                .into_iter()
+                .map(|cell| cell.expect("every word of the state is used"))
+                .collect()
+        };
+        (cells(inputs), copies, cells(current))
+    }
+}
+
+#[cfg(feature = "prover")]
+impl<F: PrimeField> WitnessGenerator<F> for ChaCha20Gadget {
+    type Input = [u32; 16];
+
+    fn gates(&self, start_row: usize) -> Vec<CircuitGate<F>> {
+        let mut gates: Vec<_> = testing::chacha20_gates()
+            .into_iter()
+            .enumerate()
+            .map(|(i, typ)| CircuitGate {
+                typ,
+                wires: Wire::new(start_row + i),
+                coeffs: vec![],
+            })
+            .collect();
+        let (_, copies, _) = Self::wiring();
+        for (cell_pre, cell_new) in copies {
+            gates.connect_cell_pair(cell_pre, cell_new);
+        }
+        gates
+    }
+
+    fn input_cells(&self) -> Vec<(usize, usize)> {
+        Self::wiring().0
+    }
+
+    fn output_cells(&self) -> Vec<(usize, usize)> {
+        Self::wiring().2
+    }
+
+    fn witness(&self, state: &[u32; 16]) -> [Vec<F>; COLUMNS] {
+        let rows = testing::chacha20_rows::<F>(state.to_vec());
+        array::from_fn(|col| rows.iter().map(|row| row[col]).collect())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -472,7 +580,7 @@ mod tests {
         circuits::{
             expr::{Column, Constants, PolishToken},
             lookup::lookups::{LookupInfo, LookupPattern},
-            wires::*,
+            polynomials::permutation::ZK_ROWS,
         },
         curve::KimchiCurve,
         proof::{LookupEvaluations, ProofEvaluations},
@@ -571,6 +679,7 @@ mod tests {
             endo_coefficient: F::zero(),
             mds: &Vesta::sponge_params().mds,
             foreign_field_modulus: None,
+            zk_rows: ZK_ROWS,
         };
 
         assert_eq!(
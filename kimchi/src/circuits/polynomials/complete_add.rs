@@ -19,7 +19,11 @@ use crate::circuits::{
     gate::{CircuitGate, GateType},
     wires::COLUMNS,
 };
+#[cfg(feature = "prover")]
+use crate::circuits::{wires::Wire, witness_generator::WitnessGenerator};
 use ark_ff::{Field, PrimeField};
+#[cfg(feature = "prover")]
+use std::array;
 use std::marker::PhantomData;
 
 /// This enforces that
@@ -296,3 +300,77 @@ impl<F: PrimeField> CircuitGate<F> {
         Ok(())
     }
 }
+
+/// The complete addition of two points `(x1, y1)` and `(x2, y2)`, as a [WitnessGenerator]
+/// whose input is the pair of points. Its input cells are `x1, y1, x2, y2`,
+/// and its output cells `x3, y3`.
+/// If the points are opposite, the result is the point at infinity, flagged by `inf`,
+/// and `(x3, y3)` is left to the doubling of the first point.
+#[cfg(feature = "prover")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CompleteAddGadget;
+
+#[cfg(feature = "prover")]
+impl<F: PrimeField> WitnessGenerator<F> for CompleteAddGadget {
+    type Input = ((F, F), (F, F));
+
+    fn gates(&self, start_row: usize) -> Vec<CircuitGate<F>> {
+        vec![CircuitGate {
+            typ: GateType::CompleteAdd,
+            wires: Wire::new(start_row),
+            coeffs: vec![],
+        }]
+    }
+
+    fn rows(&self) -> usize {
+        1
+    }
+
+    fn input_cells(&self) -> Vec<(usize, usize)> {
+        (0..4).map(|col| (0, col)).collect()
+    }
+
+    fn output_cells(&self) -> Vec<(usize, usize)> {
+        vec![(0, 4), (0, 5)]
+    }
+
+    fn witness(&self, &((x1, y1), (x2, y2)): &((F, F), (F, F))) -> [Vec<F>; COLUMNS] {
+        let same_x = x1 == x2;
+        let inf = same_x && y1 != y2;
+        let s = if same_x {
+            // 2 * s * y1 = 3 * x1^2
+            let x1_squared = x1.square();
+            (x1_squared.double() + x1_squared) / y1.double()
+        } else {
+            // (x2 - x1) * s = y2 - y1
+            (y2 - y1) / (x2 - x1)
+        };
+        let x3 = s.square() - x1 - x2;
+        let y3 = s * (x1 - x3) - y1;
+        let inf_z = if inf {
+            (y2 - y1).inverse().unwrap()
+        } else {
+            F::zero()
+        };
+        let x21_inv = if same_x {
+            F::zero()
+        } else {
+            (x2 - x1).inverse().unwrap()
+        };
+
+        let row = [
+            x1,
+            y1,
+            x2,
+            y2,
+            x3,
+            y3,
+            F::from(inf),
+            F::from(same_x),
+            s,
+            inf_z,
+            x21_inv,
+        ];
+        array::from_fn(|col| vec![row.get(col).copied().unwrap_or_else(F::zero)])
+    }
+}
@@ -11,6 +11,8 @@ use crate::{
     },
     curve::KimchiCurve,
 };
+#[cfg(feature = "prover")]
+use crate::circuits::{gate::Connect, wires::Wire, witness_generator::WitnessGenerator};
 use ark_ff::{BitIteratorLE, Field, PrimeField};
 use std::array;
 use std::marker::PhantomData;
@@ -279,6 +281,67 @@ pub fn gen_witness<F: PrimeField + std::fmt::Display>(
     a * endo_scalar + b
 }
 
+/// The conversion of a scalar of `num_bits` bits into the endo scalar `a * endo_scalar + b`,
+/// as a [WitnessGenerator] whose input is the scalar (see [`gen_witness`]).
+/// Its input cell is the scalar `n` recomposed from the crumbs in the last row,
+/// and its output cells the values `a` and `b` in the last row.
+///
+/// The rows are wired together: each row starts from the values `n`, `a` and `b` of the previous one.
+/// The initial values of the first row (`0`, `2` and `2`) are set in the witness,
+/// but they are not constrained by the gadget.
+#[cfg(feature = "prover")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EndoMulScalarGadget<F> {
+    /// The endo scalar coefficient
+    pub endo_scalar: F,
+    /// The number of bits of the scalar, a multiple of 16
+    pub num_bits: usize,
+}
+
+#[cfg(feature = "prover")]
+impl<F: PrimeField> WitnessGenerator<F> for EndoMulScalarGadget<F> {
+    type Input = F;
+
+    fn gates(&self, start_row: usize) -> Vec<CircuitGate<F>> {
+        let rows = WitnessGenerator::<F>::rows(self);
+        let mut gates: Vec<_> = (0..rows)
+            .map(|i| CircuitGate {
+                typ: GateType::EndoMulScalar,
+                wires: Wire::new(start_row + i),
+                coeffs: vec![],
+            })
+            .collect();
+        for row in 1..rows {
+            // n8 -> n0, a8 -> a0, b8 -> b0
+            for (prev_col, col) in [(1, 0), (4, 2), (5, 3)] {
+                gates.connect_cell_pair((row - 1, prev_col), (row, col));
+            }
+        }
+        gates
+    }
+
+    fn rows(&self) -> usize {
+        self.num_bits / 16
+    }
+
+    fn input_cells(&self) -> Vec<(usize, usize)> {
+        let row = WitnessGenerator::<F>::rows(self) - 1;
+        vec![(row, 1)]
+    }
+
+    fn output_cells(&self) -> Vec<(usize, usize)> {
+        let row = WitnessGenerator::<F>::rows(self) - 1;
+        vec![(row, 4), (row, 5)]
+    }
+
+    fn witness(&self, scalar: &F) -> [Vec<F>; COLUMNS] {
+        let rows = WitnessGenerator::<F>::rows(self);
+        let mut witness = array::from_fn(|_| Vec::with_capacity(rows));
+        gen_witness(&mut witness, *scalar, self.endo_scalar, self.num_bits);
+        witness
+    }
+}
+
 fn c_func<F: Field>(x: F) -> F {
     let zero = F::zero();
     let one = F::one();
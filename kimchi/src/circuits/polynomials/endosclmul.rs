@@ -17,7 +17,11 @@ use crate::{
     curve::KimchiCurve,
     proof::ProofEvaluations,
 };
+#[cfg(feature = "prover")]
+use crate::circuits::{gate::Connect, wires::Wire, witness_generator::WitnessGenerator};
 use ark_ff::{Field, PrimeField};
+#[cfg(feature = "prover")]
+use std::array;
 use std::marker::PhantomData;
 
 //~ We implement custom gate constraints for short Weierstrass curve
@@ -147,6 +151,7 @@ impl<F: PrimeField> CircuitGate<F> {
             mds: &G::sponge_params().mds,
             endo_coefficient: cs.endo,
             foreign_field_modulus: None,
+            zk_rows: cs.zk_rows,
         };
 
         let evals: [ProofEvaluations<G::ScalarField>; 2] = [
@@ -366,3 +371,69 @@ pub fn gen_witness<F: Field + std::fmt::Display>(
 
     EndoMulResult { acc, n: n_acc }
 }
+
+/// The endomorphism optimised scalar multiplication by a scalar of `num_bits` bits,
+/// as a [WitnessGenerator] whose input is the base, the bits of the scalar
+/// (most significant bit first) and the initial accumulator (see [`gen_witness`]).
+/// Its input cells are the base and the initial accumulator,
+/// and its output cells the final accumulator and the scalar `n` recomposed from the bits,
+/// in the `Zero` row following the `EndoMul` rows.
+///
+/// The bits are not in the columns of the permutation, so the scalar can only be
+/// constrained through its output cell. Each `EndoMul` row is wired to the base of the first one.
+/// The partial scalar of the first row is zero in the witness,
+/// but it is not constrained by the gadget.
+#[cfg(feature = "prover")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EndoMulGadget<F> {
+    /// The coefficient of the endomorphism of the curve
+    pub endo: F,
+    /// The number of bits of the scalar, a multiple of 4
+    pub num_bits: usize,
+}
+
+#[cfg(feature = "prover")]
+impl<F: PrimeField> WitnessGenerator<F> for EndoMulGadget<F> {
+    type Input = ((F, F), Vec<bool>, (F, F));
+
+    fn gates(&self, start_row: usize) -> Vec<CircuitGate<F>> {
+        let chunks = self.num_bits / 4;
+        let mut gates: Vec<_> = (0..chunks)
+            .map(|i| CircuitGate::create_endomul(Wire::new(start_row + i)))
+            .chain(std::iter::once(CircuitGate::zero(Wire::new(
+                start_row + chunks,
+            ))))
+            .collect();
+        for row in 1..chunks {
+            gates.connect_cell_pair((0, 0), (row, 0));
+            gates.connect_cell_pair((0, 1), (row, 1));
+        }
+        gates
+    }
+
+    fn rows(&self) -> usize {
+        self.num_bits / 4 + 1
+    }
+
+    fn input_cells(&self) -> Vec<(usize, usize)> {
+        vec![(0, 0), (0, 1), (0, 4), (0, 5)]
+    }
+
+    fn output_cells(&self) -> Vec<(usize, usize)> {
+        let row = self.num_bits / 4;
+        vec![(row, 4), (row, 5), (row, 6)]
+    }
+
+    fn witness(&self, (base, bits, acc0): &((F, F), Vec<bool>, (F, F))) -> [Vec<F>; COLUMNS] {
+        assert_eq!(
+            bits.len(),
+            self.num_bits,
+            "the scalar is not of {} bits",
+            self.num_bits
+        );
+        let rows = WitnessGenerator::<F>::rows(self);
+        let mut w = array::from_fn(|_| vec![F::zero(); rows]);
+        gen_witness(&mut w, 0, self.endo, *base, bits, *acc0);
+        w
+    }
+}
@@ -1,11 +1,13 @@
 //! This module computes the witness of a foreign field addition circuit.
 
 use crate::circuits::{
+    gate::CircuitGate,
     polynomial::COLUMNS,
     polynomials::range_check::{
         self,
         witness::{extend_witness, handle_standard_witness_cell, CopyWitnessCell, ZeroWitnessCell},
     },
+    witness_generator::WitnessGenerator,
 };
 use ark_ff::{Field, PrimeField};
 use num_bigint::BigUint;
@@ -354,3 +356,55 @@ fn handle_ffadd_rows<F: PrimeField>(
         WitnessCell::Ignore => (),
     }
 }
+
+/// The chain of foreign field additions and subtractions `opcodes` modulo `modulus`,
+/// as a [WitnessGenerator] whose input is the list of the operands.
+/// Its input cells are the limbs of the operands, and its output cells the limbs of the result,
+/// in the range checks of the gadget (see [CircuitGate::create_foreign_field_add]).
+/// The constraint system must be built with the foreign field modulus `modulus`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ForeignFieldAddGadget {
+    /// The operations of the chain
+    pub opcodes: Vec<FFOps>,
+    /// The modulus of the foreign field
+    pub modulus: BigUint,
+}
+
+impl ForeignFieldAddGadget {
+    /// The first row of the range check of the operand `i` (or of the result, for the last one)
+    fn range_check_row(i: usize) -> usize {
+        if i == 0 {
+            0
+        } else {
+            8 * i - 4
+        }
+    }
+
+    /// The cells of the limbs of the range check starting at `row`
+    fn limb_cells(row: usize) -> [(usize, usize); 3] {
+        [(row, 0), (row + 1, 0), (row + 2, 0)]
+    }
+}
+
+impl<F: PrimeField> WitnessGenerator<F> for ForeignFieldAddGadget {
+    type Input = Vec<BigUint>;
+
+    fn gates(&self, start_row: usize) -> Vec<CircuitGate<F>> {
+        CircuitGate::create_foreign_field_add(start_row, self.opcodes.len()).1
+    }
+
+    fn input_cells(&self) -> Vec<(usize, usize)> {
+        (0..=self.opcodes.len())
+            .flat_map(|i| Self::limb_cells(Self::range_check_row(i)))
+            .collect()
+    }
+
+    fn output_cells(&self) -> Vec<(usize, usize)> {
+        // the range check of the result of the last operation follows that of its right operand
+        Self::limb_cells(Self::range_check_row(self.opcodes.len()) + 4).to_vec()
+    }
+
+    fn witness(&self, inputs: &Vec<BigUint>) -> [Vec<F>; COLUMNS] {
+        create_witness(inputs, &self.opcodes, self.modulus.clone())
+    }
+}
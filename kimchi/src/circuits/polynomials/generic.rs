@@ -39,11 +39,13 @@ use crate::circuits::{
     polynomial::COLUMNS,
     wires::GateWires,
 };
+#[cfg(feature = "prover")]
+use crate::circuits::{wires::Wire, witness_generator::WitnessGenerator};
+use crate::parallel::prelude::*;
 use ark_ff::{FftField, PrimeField, Zero};
 use ark_poly::{
     univariate::DensePolynomial, EvaluationDomain, Evaluations, Radix2EvaluationDomain as D,
 };
-use rayon::prelude::*;
 use std::array;
 
 /// Number of constraints produced by the gate.
@@ -65,6 +67,7 @@ pub const DOUBLE_GENERIC_REGISTERS: usize = GENERIC_REGISTERS * 2;
 
 /// The different type of computation that are possible with a generic gate.
 /// This type is useful to create a generic gate via the [`CircuitGate::create_generic_gadget`] function.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum GenericGateSpec<F> {
     /// Add two values.
     Add {
@@ -162,6 +165,120 @@ impl<F: PrimeField> CircuitGate<F> {
     }
 }
 
+/// A row of one or two generic gates, as a [WitnessGenerator] whose inputs are the operands
+/// of its gates, in order: the left and right registers of an addition or a multiplication,
+/// the register of a public gate, and none for a constant.
+/// Its outputs are the output registers of the additions and multiplications,
+/// and the registers of the constants and public gates.
+#[cfg(feature = "prover")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GenericGadget<F> {
+    /// The first generic gate
+    pub gate1: GenericGateSpec<F>,
+    /// The optional second generic gate, which cannot be a public gate
+    pub gate2: Option<GenericGateSpec<F>>,
+}
+
+#[cfg(feature = "prover")]
+impl<F> GenericGadget<F> {
+    /// The gates of the row, with the column of their first register
+    fn specs(&self) -> impl Iterator<Item = (usize, &GenericGateSpec<F>)> {
+        std::iter::once((0, &self.gate1))
+            .chain(self.gate2.iter().map(|spec| (GENERIC_REGISTERS, spec)))
+    }
+
+    /// The cells of the row made of the registers `registers` of each gate,
+    /// as selected by `registers` from the specification of the gate
+    fn cells(
+        &self,
+        registers: impl Fn(&GenericGateSpec<F>) -> &'static [usize],
+    ) -> Vec<(usize, usize)> {
+        self.specs()
+            .flat_map(|(first, spec)| registers(spec).iter().map(move |reg| (0, first + reg)))
+            .collect()
+    }
+}
+
+#[cfg(feature = "prover")]
+impl<F: PrimeField> WitnessGenerator<F> for GenericGadget<F> {
+    type Input = Vec<F>;
+
+    fn gates(&self, start_row: usize) -> Vec<CircuitGate<F>> {
+        vec![CircuitGate::create_generic_gadget(
+            Wire::new(start_row),
+            self.gate1,
+            self.gate2,
+        )]
+    }
+
+    fn rows(&self) -> usize {
+        1
+    }
+
+    fn input_cells(&self) -> Vec<(usize, usize)> {
+        self.cells(|spec| match spec {
+            GenericGateSpec::Add { .. } | GenericGateSpec::Mul { .. } => &[0, 1],
+            GenericGateSpec::Pub => &[0],
+            GenericGateSpec::Const(_) => &[],
+        })
+    }
+
+    fn output_cells(&self) -> Vec<(usize, usize)> {
+        self.cells(|spec| match spec {
+            GenericGateSpec::Add { .. } | GenericGateSpec::Mul { .. } => &[2],
+            GenericGateSpec::Const(_) | GenericGateSpec::Pub => &[0],
+        })
+    }
+
+    /// # Panics
+    ///
+    /// Will panic if `input` does not have one value per input cell,
+    /// or if the output coefficient of an addition or a multiplication is zero.
+    fn witness(&self, input: &Vec<F>) -> [Vec<F>; COLUMNS] {
+        let inputs = WitnessGenerator::<F>::input_cells(self).len();
+        assert_eq!(
+            input.len(),
+            inputs,
+            "the generic gates have {inputs} inputs"
+        );
+
+        let mut row = [F::zero(); COLUMNS];
+        let mut input = input.iter().copied();
+        for (first, spec) in self.specs() {
+            let registers = &mut row[first..first + GENERIC_REGISTERS];
+            let output_coeff = |coeff: Option<F>| {
+                coeff
+                    .unwrap_or_else(|| -F::one())
+                    .inverse()
+                    .expect("the output coefficient of a generic gate is not zero")
+            };
+            match *spec {
+                GenericGateSpec::Add {
+                    left_coeff,
+                    right_coeff,
+                    output_coeff: coeff,
+                } => {
+                    let (l, r) = (input.next().unwrap(), input.next().unwrap());
+                    let sum = left_coeff.unwrap_or_else(F::one) * l
+                        + right_coeff.unwrap_or_else(F::one) * r;
+                    registers.copy_from_slice(&[l, r, -sum * output_coeff(coeff)]);
+                }
+                GenericGateSpec::Mul {
+                    output_coeff: coeff,
+                    mul_coeff,
+                } => {
+                    let (l, r) = (input.next().unwrap(), input.next().unwrap());
+                    let product = mul_coeff.unwrap_or_else(F::one) * l * r;
+                    registers.copy_from_slice(&[l, r, -product * output_coeff(coeff)]);
+                }
+                GenericGateSpec::Const(cst) => registers[0] = cst,
+                GenericGateSpec::Pub => registers[0] = input.next().unwrap(),
+            }
+        }
+        row.map(|cell| vec![cell])
+    }
+}
+
 // -------------------------------------------------
 
 //~ The constraints:
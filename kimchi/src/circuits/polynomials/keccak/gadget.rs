@@ -0,0 +1,296 @@
+//! This module obtains the gates of a Keccak-256 circuit.
+
+use ark_ff::PrimeField;
+use std::array;
+
+use crate::circuits::{
+    gate::CircuitGate,
+    lookup::tables::sha256::spread,
+    polynomials::sha256::{
+        circuitgates::SPREAD_ONES,
+        gadget::{Layout, Var, Word},
+    },
+};
+
+//~ The Keccak-256 gadget does not define gates of its own: it is built from the
+//~ 32-bit word gates of the SHA-256 gadget and their spread lookup table, together with generic gates.
+//~
+//~ The 64-bit lanes of the Keccak state are *interleaved*, that is each of them is represented by
+//~ the 32-bit words made of its even bits and of its odd bits.
+//~ A lane `L` is the spread sum `spread(E) + 2 spread(O)` of its even and odd words `E` and `O`,
+//~ so the lanes of the message are split into their words by a `Sha256Spread` decomposition,
+//~ and the lanes of the digest are recombined with a generic gate.
+//~
+//~ The bitwise operations of the Keccak-f permutation are then computed on the words:
+//~
+//~ * the XOR of up to three words is the even part of the sum of their spread forms,
+//~ * $\neg b \wedge c$ is the odd part of $spread(\neg b) + spread(c)$,
+//~   with $spread(\neg b) = spread(2^{32} - 1) - spread(b)$,
+//~ * a rotation of a lane by `2m` (resp. `2m + 1`) bits rotates its even and odd words by `m` bits
+//~   (resp. swaps them and rotates them by `m + 1` and `m` bits).
+//~
+//~ The rotation to the left by `k` bits of a word `x` is checked with a generic gate, as
+//~ $4^k \cdot spread(x) = (2^{64} - 1) \cdot q + spread(y)$
+//~ where `q` and `y` are words decomposed by `Sha256Word` gates.
+//~ As all the spread forms are smaller than $2^{63}$, this equation holds over the integers,
+//~ and $spread(y)$ is $4^k \cdot spread(x)$ modulo $2^{64} - 1$, that is the spread form of the rotated word.
+
+/// The number of lanes of the Keccak state
+pub const STATE_LANES: usize = 25;
+
+/// The number of lanes absorbed by each block of Keccak-256 (its rate is 1088 bits)
+pub const RATE_LANES: usize = 17;
+
+/// The number of lanes of a Keccak-256 digest
+pub const DIGEST_LANES: usize = 4;
+
+/// The number of rounds of the Keccak-f[1600] permutation
+pub const ROUNDS: usize = 24;
+
+/// The round constants of the Keccak-f[1600] permutation
+pub const ROUND_CONSTANTS: [u64; ROUNDS] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808A,
+    0x8000000080008000,
+    0x000000000000808B,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008A,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000A,
+    0x000000008000808B,
+    0x800000000000008B,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800A,
+    0x800000008000000A,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+/// The rotation offsets of the lanes, indexed by `x + 5 y`
+pub const ROTATION_OFFSETS: [u32; STATE_LANES] = [
+    0, 1, 62, 28, 27, //
+    36, 44, 6, 55, 20, //
+    3, 10, 43, 25, 39, //
+    41, 45, 15, 21, 8, //
+    18, 2, 61, 56, 14,
+];
+
+/// The column holding the input lanes of the gadget
+pub const INPUT_LANE_COL: usize = 3;
+
+/// The row (relative to the start of the gadget) holding, in column [INPUT_LANE_COL],
+/// the lane `j` of the padded message, that is the lane `j % RATE_LANES` of the block `j / RATE_LANES`
+pub const fn input_lane_row(j: usize) -> usize {
+    4 * j
+}
+
+/// The cell `(row, column)`, with the row relative to the end of the gadget,
+/// holding the lane `i` of the digest
+pub const fn digest_lane_cell_from_end(i: usize) -> (usize, usize) {
+    (2 - i / 2, 2 + 3 * (i % 2))
+}
+
+/// Returns the even and odd words of a lane
+pub fn interleave(lane: u64) -> [u32; 2] {
+    array::from_fn(|h| (0..32).fold(0, |acc, i| acc | (((lane >> (2 * i + h)) & 1) as u32) << i))
+}
+
+impl<F: PrimeField> CircuitGate<F> {
+    /// Create the gates of the Keccak-256 hash of a padded message of `blocks` blocks
+    ///     Inputs the starting row and the number of blocks
+    ///     Outputs tuple (`next_row`, `circuit_gates`) where
+    ///       `next_row`      - next row after this gadget
+    ///       `circuit_gates` - vector of circuit gates comprising this gadget
+    ///
+    /// The lanes of the padded message are in the cells given by [input_lane_row] and [INPUT_LANE_COL],
+    /// and the lanes of the digest in the cells given by [digest_lane_cell_from_end].
+    /// The gadget does not check the padding of the message.
+    pub fn create_keccak256(start_row: usize, blocks: usize) -> (usize, Vec<Self>) {
+        let mut layout = Layout::new(start_row);
+        layout.keccak256(&vec![[0; RATE_LANES]; blocks]);
+        (start_row + layout.gates.len(), layout.gates)
+    }
+}
+
+/// A lane, given by its even and odd words
+type Lane = [Word; 2];
+
+impl<F: PrimeField> Layout<F> {
+    /// Lays out the Keccak-256 hash of the padded message `blocks`,
+    /// and returns its digest
+    pub(crate) fn keccak256(&mut self, blocks: &[[u64; RATE_LANES]]) -> [u64; DIGEST_LANES] {
+        // the input lanes are decomposed first, so that they are at known rows
+        let parts: Vec<(Var, Var)> = blocks
+            .iter()
+            .flatten()
+            .map(|lane| {
+                let (_, even, odd) = self.spread_decomposition(*lane);
+                (even, odd)
+            })
+            .collect();
+        let lanes: Vec<Lane> = parts
+            .into_iter()
+            .map(|(even, odd)| [self.reduce(even), self.reduce(odd)])
+            .collect();
+
+        // absorb
+        let mut state: Vec<Option<Lane>> = vec![None; STATE_LANES];
+        for block in lanes.chunks(RATE_LANES) {
+            for (i, lane) in block.iter().enumerate() {
+                state[i] = Some(match state[i] {
+                    None => *lane,
+                    Some(current) => array::from_fn(|h| self.xor(&[current[h], lane[h]], 0)),
+                });
+            }
+            state = self.keccak_f(state).into_iter().map(Some).collect();
+        }
+
+        // squeeze, with the digest lanes in the last generic gates of the gadget
+        self.flush_generic();
+        array::from_fn(|i| {
+            let [even, odd] = state[i].expect("the state is initialized by the permutation");
+            let value = even.spread.value + 2 * odd.spread.value;
+            self.linear(
+                (F::one(), even.spread),
+                (F::from(2u64), odd.spread),
+                F::zero(),
+                value,
+            )
+            .value
+        })
+    }
+
+    // The Keccak-f[1600] permutation, where the lanes that are `None` are zero
+    fn keccak_f(&mut self, state: Vec<Option<Lane>>) -> Vec<Lane> {
+        let mut state = state;
+        let mut lanes = vec![];
+        for constant in ROUND_CONSTANTS {
+            lanes = self.round(&state, constant);
+            state = lanes.iter().copied().map(Some).collect();
+        }
+        lanes
+    }
+
+    // A round of the Keccak-f[1600] permutation
+    fn round(&mut self, state: &[Option<Lane>], constant: u64) -> Vec<Lane> {
+        // theta
+        let mut columns = vec![];
+        for x in 0..5 {
+            let column: Vec<Lane> = (0..5).filter_map(|y| state[x + 5 * y]).collect();
+            let lane: Lane =
+                array::from_fn(|h| self.xor_many(&column.iter().map(|l| l[h]).collect::<Vec<_>>()));
+            columns.push(lane);
+        }
+        let mut theta = vec![];
+        for x in 0..5 {
+            let rotated = self.rotl_lane(columns[(x + 1) % 5], 1);
+            let previous = columns[(x + 4) % 5];
+            let d: Lane = array::from_fn(|h| self.xor(&[previous[h], rotated[h]], 0));
+            theta.push(d);
+        }
+        let state: Vec<Lane> = (0..STATE_LANES)
+            .map(|i| match state[i] {
+                None => theta[i % 5],
+                Some(lane) => array::from_fn(|h| self.xor(&[lane[h], theta[i % 5][h]], 0)),
+            })
+            .collect();
+
+        // rho and pi
+        let mut rotated = vec![None; STATE_LANES];
+        for x in 0..5 {
+            for y in 0..5 {
+                let i = x + 5 * y;
+                rotated[y + 5 * ((2 * x + 3 * y) % 5)] =
+                    Some(self.rotl_lane(state[i], ROTATION_OFFSETS[i]));
+            }
+        }
+        let rotated: Vec<Lane> = rotated.into_iter().flatten().collect();
+
+        // chi and iota
+        let constant = interleave(constant);
+        (0..STATE_LANES)
+            .map(|i| {
+                let (x, y) = (i % 5, i / 5);
+                let b = rotated[(x + 1) % 5 + 5 * y];
+                let c = rotated[(x + 2) % 5 + 5 * y];
+                array::from_fn(|h| {
+                    let and_not = self.and_not(b[h], c[h]);
+                    let constant = if i == 0 { spread(constant[h]) } else { 0 };
+                    self.xor(&[rotated[i][h], and_not], constant)
+                })
+            })
+            .collect()
+    }
+
+    // The XOR of up to three words and of the spread form `constant`
+    fn xor(&mut self, terms: &[Word], constant: u64) -> Word {
+        let terms: Vec<Var> = terms.iter().map(|word| word.spread).collect();
+        let sum = self.add(&terms, constant);
+        let (even, _) = self.decompose(sum);
+        self.reduce(even)
+    }
+
+    // The XOR of the words `terms`
+    fn xor_many(&mut self, terms: &[Word]) -> Word {
+        let mut acc = terms[0];
+        for chunk in terms[1..].chunks(2) {
+            let mut xor_terms = vec![acc];
+            xor_terms.extend(chunk);
+            acc = self.xor(&xor_terms, 0);
+        }
+        acc
+    }
+
+    // The word `!b & c`
+    fn and_not(&mut self, b: Word, c: Word) -> Word {
+        let value = SPREAD_ONES - b.spread.value + c.spread.value;
+        let sum = self.linear(
+            (-F::one(), b.spread),
+            (F::one(), c.spread),
+            F::from(SPREAD_ONES),
+            value,
+        );
+        let (_, odd) = self.decompose(sum);
+        self.reduce(odd)
+    }
+
+    // The rotation to the left of the word `x` by `k` bits
+    fn rotl(&mut self, x: Word, k: u32) -> Word {
+        let k = k % 32;
+        if k == 0 {
+            return x;
+        }
+
+        let value = x.word.value as u32;
+        let (_, high) = self.word_gate((value >> (32 - k)) as u64);
+        let rotated = value.rotate_left(k);
+        let spread_rotated = self.linear(
+            (F::from(1u64 << (2 * k)), x.spread),
+            (-F::from(u64::MAX), high.spread),
+            F::zero(),
+            spread(rotated),
+        );
+        let (_, word) = self.word_gate(rotated as u64);
+        self.copy(spread_rotated, word.spread.row, word.spread.col);
+        word
+    }
+
+    // The rotation to the left of an interleaved lane by `n` bits
+    fn rotl_lane(&mut self, [even, odd]: Lane, n: u32) -> Lane {
+        let m = n / 2;
+        if n % 2 == 0 {
+            [self.rotl(even, m), self.rotl(odd, m)]
+        } else {
+            [self.rotl(odd, m + 1), self.rotl(even, m)]
+        }
+    }
+}
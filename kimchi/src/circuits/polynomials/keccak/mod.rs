@@ -0,0 +1,5 @@
+//! Keccak gadget module
+
+pub mod gadget;
+#[cfg(feature = "prover")]
+pub mod witness;
@@ -0,0 +1,135 @@
+//! Keccak-256 witness computation
+
+use ark_ff::PrimeField;
+
+use crate::circuits::{
+    gate::CircuitGate, polynomial::COLUMNS, polynomials::sha256::gadget::Layout,
+    witness_generator::WitnessGenerator,
+};
+
+use super::gadget::{
+    digest_lane_cell_from_end, input_lane_row, DIGEST_LANES, INPUT_LANE_COL, RATE_LANES,
+    ROTATION_OFFSETS, ROUND_CONSTANTS, STATE_LANES,
+};
+
+/// The number of bytes absorbed by each block of Keccak-256
+pub const RATE_BYTES: usize = 8 * RATE_LANES;
+
+/// Pads `message` and splits it into the blocks of lanes absorbed by Keccak-256
+pub fn pad_message(message: &[u8]) -> Vec<[u64; RATE_LANES]> {
+    let mut bytes = message.to_vec();
+    bytes.push(0x01);
+    while bytes.len() % RATE_BYTES != 0 {
+        bytes.push(0);
+    }
+    *bytes.last_mut().unwrap() |= 0x80;
+
+    bytes
+        .chunks(RATE_BYTES)
+        .map(|block| {
+            let mut lanes = [0; RATE_LANES];
+            for (lane, chunk) in lanes.iter_mut().zip(block.chunks(8)) {
+                *lane = u64::from_le_bytes(chunk.try_into().unwrap());
+            }
+            lanes
+        })
+        .collect()
+}
+
+/// Computes the Keccak-f[1600] permutation of `state`
+pub fn keccak_f(state: &mut [u64; STATE_LANES]) {
+    for constant in ROUND_CONSTANTS {
+        // theta
+        let columns: Vec<u64> = (0..5)
+            .map(|x| (0..5).fold(0, |acc, y| acc ^ state[x + 5 * y]))
+            .collect();
+        for (i, lane) in state.iter_mut().enumerate() {
+            let x = i % 5;
+            *lane ^= columns[(x + 4) % 5] ^ columns[(x + 1) % 5].rotate_left(1);
+        }
+
+        // rho and pi
+        let mut rotated = [0; STATE_LANES];
+        for x in 0..5 {
+            for y in 0..5 {
+                let i = x + 5 * y;
+                rotated[y + 5 * ((2 * x + 3 * y) % 5)] = state[i].rotate_left(ROTATION_OFFSETS[i]);
+            }
+        }
+
+        // chi and iota
+        for i in 0..STATE_LANES {
+            let (x, y) = (i % 5, i / 5);
+            state[i] = rotated[i] ^ (!rotated[(x + 1) % 5 + 5 * y] & rotated[(x + 2) % 5 + 5 * y]);
+        }
+        state[0] ^= constant;
+    }
+}
+
+/// Computes the Keccak-256 hash of `message`
+pub fn keccak256(message: &[u8]) -> [u8; 32] {
+    let mut state = [0; STATE_LANES];
+    for block in pad_message(message) {
+        for (lane, input) in state.iter_mut().zip(block) {
+            *lane ^= input;
+        }
+        keccak_f(&mut state);
+    }
+
+    let mut digest = [0; 32];
+    for (chunk, lane) in digest.chunks_mut(8).zip(state) {
+        chunk.copy_from_slice(&lane.to_le_bytes());
+    }
+    digest
+}
+
+/// Create the witness of the Keccak-256 hash of `message`,
+/// for the gadget created with as many blocks as `pad_message(message)`
+pub fn create_keccak256_witness<F: PrimeField>(message: &[u8]) -> [Vec<F>; COLUMNS] {
+    let mut layout = Layout::new(0);
+    layout.keccak256(&pad_message(message));
+    layout.witness
+}
+
+/// The Keccak-256 hash of a message padded to `blocks` blocks, as a [WitnessGenerator]
+/// whose input is the message. Its input cells are the lanes of the padded message,
+/// and its output cells the lanes of the digest (see [CircuitGate::create_keccak256]).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Keccak256Gadget {
+    /// The number of blocks of the padded message
+    pub blocks: usize,
+}
+
+impl<F: PrimeField> WitnessGenerator<F> for Keccak256Gadget {
+    type Input = Vec<u8>;
+
+    fn gates(&self, start_row: usize) -> Vec<CircuitGate<F>> {
+        CircuitGate::create_keccak256(start_row, self.blocks).1
+    }
+
+    fn input_cells(&self) -> Vec<(usize, usize)> {
+        (0..self.blocks * RATE_LANES)
+            .map(|j| (input_lane_row(j), INPUT_LANE_COL))
+            .collect()
+    }
+
+    fn output_cells(&self) -> Vec<(usize, usize)> {
+        let rows = WitnessGenerator::<F>::rows(self);
+        (0..DIGEST_LANES)
+            .map(|i| {
+                let (row, col) = digest_lane_cell_from_end(i);
+                (rows - row, col)
+            })
+            .collect()
+    }
+
+    fn witness(&self, message: &Vec<u8>) -> [Vec<F>; COLUMNS] {
+        assert_eq!(
+            pad_message(message).len(),
+            self.blocks,
+            "the padded message is not of {} blocks",
+            self.blocks
+        );
+        create_keccak256_witness(message)
+    }
+}
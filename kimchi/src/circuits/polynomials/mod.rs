@@ -1,11 +1,16 @@
+pub mod bitwise;
+pub mod boolean_columns;
 pub mod chacha;
 pub mod complete_add;
 pub mod endomul_scalar;
 pub mod endosclmul;
 pub mod foreign_field_add;
 pub mod generic;
+pub mod keccak;
 pub mod permutation;
 pub mod poseidon;
 pub mod range_check;
+pub mod select;
+pub mod sha256;
 pub mod turshi;
 pub mod varbasemul;
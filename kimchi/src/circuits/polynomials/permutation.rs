@@ -43,10 +43,12 @@
 use crate::{
     circuits::{
         constraints::ConstraintSystem,
-        polynomial::WitnessOverDomains,
+        domains::CosetDomain,
+        polynomial::WitnessShifts,
         wires::{Wire, COLUMNS, PERMUTS},
     },
     error::ProverError,
+    parallel::{prelude::*, Partitioning},
     proof::ProofEvaluations,
 };
 use ark_ff::{FftField, PrimeField, SquareRootField, Zero};
@@ -62,60 +64,94 @@ use std::array;
 
 /// Number of constraints produced by the argument.
 pub const CONSTRAINTS: u32 = 3;
-pub const ZK_ROWS: u64 = 3;
+/// The default number of rows at the end of the domain reserved for zero-knowledge,
+/// which is the minimum for the proofs opening their polynomials at `zeta` and `zeta * omega`
+/// (see [min_zk_rows]).
+pub const ZK_ROWS: u64 = min_zk_rows(2);
+/// The number of rows at the end of the domain reserved when zero-knowledge is disabled
+/// (see [ConstraintSystem::zk]): the accumulator of the permutation argument ends on the last row,
+/// as the argument cannot wrap around the domain.
+pub const NON_ZK_ROWS: u64 = 1;
+/// The largest supported number of zero-knowledge rows.
+///
+/// The permutation constraint has degree `8(n - 1) + zk_rows` (where `n` is the size of the domain),
+/// so that the quotient polynomial only fits in its [PERMUTS] chunks as long as `zk_rows < 8`.
+pub const MAX_ZK_ROWS: u64 = 7;
+
+/// The minimal number of zero-knowledge rows for the proofs that open their polynomials
+/// at `eval_points` points.
+///
+/// Each opening reveals one evaluation of every polynomial, so that the witness and the
+/// permutation polynomials need at least as many random values on the zero-knowledge rows
+/// as there are points. The permutation polynomial is only random on the last `zk_rows - 1` rows,
+/// as its value on the first zero-knowledge row ends the permutation argument.
+/// Hence [ZK_ROWS] rows for `zeta` and `zeta * omega`, and one more row
+/// when the gates of the circuit read the previous row, which is opened at `zeta / omega`.
+pub const fn min_zk_rows(eval_points: u64) -> u64 {
+    eval_points + 1
+}
+
 /// Evaluates the polynomial
-/// (x - w^{n - 4}) (x - w^{n - 3}) * (x - w^{n - 2}) * (x - w^{n - 1})
-pub fn eval_vanishes_on_last_4_rows<F: FftField>(domain: D<F>, x: F) -> F {
-    let w4 = domain.group_gen.pow(&[domain.size - (ZK_ROWS + 1)]);
-    let w3 = domain.group_gen * w4;
-    let w2 = domain.group_gen * w3;
-    let w1 = domain.group_gen * w2;
-    (x - w1) * (x - w2) * (x - w3) * (x - w4)
+/// (x - w^{n - zk_rows - 1}) * ... * (x - w^{n - 1}),
+/// which vanishes on the `zk_rows` zero-knowledge rows and on the row before them
+/// (the last 4 rows with the default [ZK_ROWS])
+pub fn eval_vanishes_on_zero_knowledge_and_previous_rows<F: FftField>(
+    domain: D<F>,
+    zk_rows: u64,
+    x: F,
+) -> F {
+    eval_vanishes_on_last_rows(domain, zk_rows + 1, x)
 }
 
 /// The polynomial
-/// (x - w^{n - 4}) (x - w^{n - 3}) * (x - w^{n - 2}) * (x - w^{n - 1})
-pub fn vanishes_on_last_4_rows<F: FftField>(domain: D<F>) -> DensePolynomial<F> {
-    let x = DensePolynomial::from_coefficients_slice(&[F::zero(), F::one()]);
-    let c = |a: F| DensePolynomial::from_coefficients_slice(&[a]);
-    let w4 = domain.group_gen.pow(&[domain.size - (ZK_ROWS + 1)]);
-    let w3 = domain.group_gen * w4;
-    let w2 = domain.group_gen * w3;
-    let w1 = domain.group_gen * w2;
-    &(&(&x - &c(w1)) * &(&x - &c(w2))) * &(&(&x - &c(w3)) * &(&x - &c(w4)))
+/// (x - w^{n - zk_rows - 1}) * ... * (x - w^{n - 1}),
+/// which vanishes on the `zk_rows` zero-knowledge rows and on the row before them
+/// (the last 4 rows with the default [ZK_ROWS])
+pub fn vanishes_on_zero_knowledge_and_previous_rows<F: FftField>(
+    domain: D<F>,
+    zk_rows: u64,
+) -> DensePolynomial<F> {
+    vanishes_on_last_rows(domain, zk_rows + 1)
 }
 
 /// Returns the end of the circuit, which is used for introducing zero-knowledge in the permutation polynomial
-pub fn zk_w3<F: FftField>(domain: D<F>) -> F {
-    domain.group_gen.pow(&[domain.size - (ZK_ROWS)])
+pub fn zk_w3<F: FftField>(domain: D<F>, zk_rows: u64) -> F {
+    domain.group_gen.pow(&[domain.size - zk_rows])
 }
 
 /// Evaluates the polynomial
-/// (x - w^{n - 3}) * (x - w^{n - 2}) * (x - w^{n - 1})
-pub fn eval_zk_polynomial<F: FftField>(domain: D<F>, x: F) -> F {
-    let w3 = zk_w3(domain);
-    let w2 = domain.group_gen * w3;
-    let w1 = domain.group_gen * w2;
-    (x - w1) * (x - w2) * (x - w3)
+/// (x - w^{n - zk_rows}) * ... * (x - w^{n - 1})
+pub fn eval_zk_polynomial<F: FftField>(domain: D<F>, zk_rows: u64, x: F) -> F {
+    eval_vanishes_on_last_rows(domain, zk_rows, x)
 }
 
-/// Computes the zero-knowledge polynomial for blinding the permutation polynomial: `(x-w^{n-k})(x-w^{n-k-1})...(x-w^n)`.
-/// Currently, we use k = 3 for 2 blinding factors,
+/// Computes the zero-knowledge polynomial for blinding the permutation polynomial: `(x-w^{n-k})(x-w^{n-k+1})...(x-w^{n-1})`.
+/// By default, we use k = [ZK_ROWS] = 3 for 2 blinding factors,
 /// see <https://www.plonk.cafe/t/noob-questions-plonk-paper/73>
-pub fn zk_polynomial<F: FftField>(domain: D<F>) -> DensePolynomial<F> {
-    let w3 = zk_w3(domain);
-    let w2 = domain.group_gen * w3;
-    let w1 = domain.group_gen * w2;
-
-    // (x-w3)(x-w2)(x-w1) =
-    // x^3 - x^2(w1+w2+w3) + x(w1w2+w1w3+w2w3) - w1w2w3
-    let w1w2 = w1 * w2;
-    DensePolynomial::from_coefficients_slice(&[
-        -w1w2 * w3,                   // 1
-        w1w2 + (w1 * w3) + (w3 * w2), // x
-        -w1 - w2 - w3,                // x^2
-        F::one(),                     // x^3
-    ])
+pub fn zk_polynomial<F: FftField>(domain: D<F>, zk_rows: u64) -> DensePolynomial<F> {
+    vanishes_on_last_rows(domain, zk_rows)
+}
+
+/// Evaluates the polynomial vanishing on the last `rows` elements of the domain.
+fn eval_vanishes_on_last_rows<F: FftField>(domain: D<F>, rows: u64, x: F) -> F {
+    let mut w = domain.group_gen.pow(&[domain.size - rows]);
+    let mut res = F::one();
+    for _ in 0..rows {
+        res *= x - w;
+        w *= domain.group_gen;
+    }
+    res
+}
+
+/// The polynomial vanishing on the last `rows` elements of the domain.
+fn vanishes_on_last_rows<F: FftField>(domain: D<F>, rows: u64) -> DensePolynomial<F> {
+    let mut w = domain.group_gen.pow(&[domain.size - rows]);
+    let mut res = DensePolynomial::from_coefficients_slice(&[F::one()]);
+    for _ in 0..rows {
+        res = &res * &DensePolynomial::from_coefficients_slice(&[-w, F::one()]);
+        w *= domain.group_gen;
+    }
+    res
 }
 
 /// Shifts represent the shifts required in the permutation argument of PLONK.
@@ -123,10 +159,10 @@ pub fn zk_polynomial<F: FftField>(domain: D<F>) -> DensePolynomial<F> {
 pub struct Shifts<F> {
     /// The coefficients `k` (in the Plonk paper) that create a coset when multiplied with the generator of our domain.
     pub(crate) shifts: [F; PERMUTS],
-    /// A matrix that maps all cells coordinates `{col, row}` to their shifted field element.
+    /// The cosets that map all cells coordinates `{col, row}` to their shifted field element.
     /// For example the cell `{col:2, row:1}` will map to `omega * k2`,
-    /// which lives in `map[2][1]`
-    pub(crate) map: [Vec<F>; PERMUTS],
+    /// which is `cosets[2].element(1)`
+    pub(crate) cosets: [CosetDomain<F>; PERMUTS],
 }
 
 impl<F> Shifts<F>
@@ -152,11 +188,10 @@ where
         }
 
         // create a map of cells to their shifted value
-        let map: [Vec<F>; PERMUTS] =
-            array::from_fn(|i| domain.elements().map(|elm| shifts[i] * elm).collect());
+        let cosets = array::from_fn(|i| CosetDomain::new(*domain, shifts[i]));
 
         //
-        Self { shifts, map }
+        Self { shifts, cosets }
     }
 
     /// retrieve the shifts
@@ -186,12 +221,14 @@ where
 
     /// Returns the field element that represents a position
     pub(crate) fn cell_to_field(&self, &Wire { row, col }: &Wire) -> F {
-        self.map[col][row]
+        self.cosets[col].element(row)
     }
 }
 
 impl<F: PrimeField> ConstraintSystem<F> {
-    /// permutation quotient poly contribution computation
+    /// permutation quotient poly contribution computation,
+    /// from the evaluations `witness_d8` of the witness over the domain d8.
+    /// With [Partitioning::Columns], the terms of the products are computed concurrently.
     ///
     /// # Errors
     ///
@@ -203,11 +240,12 @@ impl<F: PrimeField> ConstraintSystem<F> {
     #[allow(clippy::type_complexity)]
     pub fn perm_quot(
         &self,
-        lagrange: &WitnessOverDomains<F>,
+        witness_d8: &WitnessShifts<F>,
         beta: F,
         gamma: F,
         z: &DensePolynomial<F>,
         mut alphas: impl Iterator<Item = F>,
+        partitioning: Partitioning,
     ) -> Result<(Evaluations<F, D<F>>, DensePolynomial<F>), ProverError> {
         let alpha0 = alphas.next().expect("missing power of alpha");
         let alpha1 = alphas.next().expect("missing power of alpha");
@@ -245,27 +283,55 @@ impl<F: PrimeField> ConstraintSystem<F> {
         //~ $$
         //~
         let perm = {
+            let poly_x_d1 = &self.precomputations().poly_x_d1;
+            let shift_term = |(witness, shift): (&Evaluations<F, D<F>>, &F)| {
+                &(witness + gamma) + &poly_x_d1.scale(beta * shift)
+            };
+            let sigma_term = |(witness, sigma): (&Evaluations<F, D<F>>, &Evaluations<F, D<F>>)| {
+                witness + &(gamma + &sigma.scale(beta))
+            };
+
             // shifts = z(x) *
             // (w[0](x) + gamma + x * beta * shift[0]) *
             // (w[1](x) + gamma + x * beta * shift[1]) * ...
             // (w[6](x) + gamma + x * beta * shift[6])
             // in evaluation form in d8
-            let mut shifts = lagrange.d8.this.z.clone();
-            for (witness, shift) in lagrange.d8.this.w.iter().zip(self.shift.iter()) {
-                let term =
-                    &(witness + gamma) + &self.precomputations().poly_x_d1.scale(beta * shift);
-                shifts = &shifts * &term;
-            }
-
+            //
             // sigmas = z(x * w) *
             // (w8[0] + gamma + sigma[0] * beta) *
             // (w8[1] + gamma + sigma[1] * beta) * ...
             // (w8[6] + gamma + sigma[6] * beta)
             // in evaluation form in d8
-            let mut sigmas = lagrange.d8.next.z.clone();
-            for (witness, sigma) in lagrange.d8.this.w.iter().zip(self.sigmal8.iter()) {
-                let term = witness + &(gamma + &sigma.scale(beta));
-                sigmas = &sigmas * &term;
+            let mut shifts = witness_d8.this.z.clone();
+            let mut sigmas = witness_d8.next.z.clone();
+            let witness = &witness_d8.this.w[..PERMUTS];
+            match partitioning {
+                Partitioning::Rows => {
+                    for term in witness.iter().zip(self.shift.iter()).map(shift_term) {
+                        shifts = &shifts * &term;
+                    }
+                    for term in witness.iter().zip(self.sigmal8.iter()).map(sigma_term) {
+                        sigmas = &sigmas * &term;
+                    }
+                }
+                Partitioning::Columns => {
+                    let shift_terms: Vec<_> = witness
+                        .par_iter()
+                        .zip(self.shift[..].par_iter())
+                        .map(shift_term)
+                        .collect();
+                    let sigma_terms: Vec<_> = witness
+                        .par_iter()
+                        .zip(self.sigmal8[..].par_iter())
+                        .map(sigma_term)
+                        .collect();
+                    for term in &shift_terms {
+                        shifts = &shifts * term;
+                    }
+                    for term in &sigma_terms {
+                        sigmas = &sigmas * term;
+                    }
+                }
             }
 
             &(&shifts - &sigmas).scale(alpha0) * &self.precomputations().zkpl
@@ -294,9 +360,9 @@ impl<F: PrimeField> ConstraintSystem<F> {
                 return Err(ProverError::Permutation("first division rest"));
             }
 
-            // accumulator end := (z(x) - 1) / (x - sid[n-3])
+            // accumulator end := (z(x) - 1) / (x - sid[n-zk_rows])
             let denominator = DensePolynomial::from_coefficients_slice(&[
-                -self.sid[self.domain.d1.size() - 3],
+                -self.sid[self.domain.d1.size() - self.zk_rows as usize],
                 F::one(),
             ]);
             let (bnd2, res) = DenseOrSparsePolynomial::divide_with_q_and_r(
@@ -392,6 +458,7 @@ impl<F: PrimeField> ConstraintSystem<F> {
         rng: &mut (impl RngCore + CryptoRng),
     ) -> Result<DensePolynomial<F>, ProverError> {
         let n = self.domain.d1.size();
+        let zk_rows = self.zk_rows as usize;
 
         // only works if first element is 1
         assert_eq!(self.domain.d1.elements().next(), Some(F::one()));
@@ -404,7 +471,7 @@ impl<F: PrimeField> ConstraintSystem<F> {
 
         let mut z = vec![F::one(); n];
 
-        //~ For $i = 0, \cdot, n - 4$, where $n$ is the size of the domain,
+        //~ For $i = 0, \cdot, n - zk\_rows - 1$, where $n$ is the size of the domain,
         //~ evaluations are computed as:
         //~
         //~ $$z(g^{i+1}) = z_1 / z_2$$
@@ -438,7 +505,7 @@ impl<F: PrimeField> ConstraintSystem<F> {
         //~ $$
         //~
         //~
-        for j in 0..n - 3 {
+        for j in 0..n - zk_rows {
             z[j + 1] = witness
                 .iter()
                 .zip(self.sigmal1.iter())
@@ -446,27 +513,30 @@ impl<F: PrimeField> ConstraintSystem<F> {
                 .fold(F::one(), |x, y| x * y);
         }
 
-        ark_ff::fields::batch_inversion::<F>(&mut z[1..=n - 3]);
+        ark_ff::fields::batch_inversion::<F>(&mut z[1..=n - zk_rows]);
 
-        for j in 0..n - 3 {
+        let cosets = self.cosets();
+        for j in 0..n - zk_rows {
             let x = z[j];
             z[j + 1] *= witness
                 .iter()
-                .zip(self.shift.iter())
-                .map(|(w, s)| w[j] + (self.sid[j] * beta * s) + gamma)
+                .zip(cosets.iter())
+                .map(|(w, coset)| w[j] + (coset.elements()[j] * beta) + gamma)
                 .fold(x, |z, y| z * y);
         }
 
-        //~ If computed correctly, we should have $z(g^{n-3}) = 1$.
+        //~ If computed correctly, we should have $z(g^{n-zk\_rows}) = 1$.
         //~
-        if z[n - 3] != F::one() {
+        if z[n - zk_rows] != F::one() {
             return Err(ProverError::Permutation("final value"));
         };
 
-        //~ Finally, randomize the last `EVAL_POINTS` evaluations $z(g^{n-2})$ and $z(g^{n-1})$,
+        //~ Finally, randomize the last `zk_rows - 1` evaluations $z(g^{n-zk\_rows+1}), \cdots, z(g^{n-1})$
+        //~ (that is, $z(g^{n-2})$ and $z(g^{n-1})$ with the default 3 zero-knowledge rows),
         //~ in order to add zero-knowledge to the protocol.
-        z[n - 2] = F::rand(rng);
-        z[n - 1] = F::rand(rng);
+        for e in z.iter_mut().skip(n - zk_rows + 1) {
+            *e = F::rand(rng);
+        }
 
         let res = Evaluations::<F, D<F>>::from_vec_and_domain(z, self.domain.d1).interpolate();
         Ok(res)
@@ -25,6 +25,8 @@
 //~ ```
 //~
 
+#[cfg(feature = "prover")]
+use crate::circuits::witness_generator::WitnessGenerator;
 use crate::{
     circuits::{
         argument::{Argument, ArgumentEnv, ArgumentType},
@@ -273,6 +275,54 @@ pub fn generate_witness<F: Field>(
     }
 }
 
+/// The Poseidon permutation with the parameters `params`, as a [WitnessGenerator]
+/// whose input is the initial state, in the first cells of its first row,
+/// and whose outputs are the final state, in the first cells of its last (`Zero`) row
+#[cfg(feature = "prover")]
+#[derive(Clone, Copy, Debug)]
+pub struct PoseidonGadget<F: Field + 'static> {
+    /// The parameters of the permutation
+    pub params: &'static ArithmeticSpongeParams<F>,
+}
+
+#[cfg(feature = "prover")]
+impl<F: PrimeField> WitnessGenerator<F> for PoseidonGadget<F> {
+    type Input = [F; SPONGE_WIDTH];
+
+    fn gates(&self, start_row: usize) -> Vec<CircuitGate<F>> {
+        let first_and_last_row = [
+            Wire::new(start_row),
+            Wire::new(start_row + POS_ROWS_PER_HASH),
+        ];
+        CircuitGate::create_poseidon_gadget(
+            start_row,
+            first_and_last_row,
+            &self.params.round_constants,
+        )
+        .0
+    }
+
+    fn rows(&self) -> usize {
+        POS_ROWS_PER_HASH + 1
+    }
+
+    fn input_cells(&self) -> Vec<(usize, usize)> {
+        (0..SPONGE_WIDTH).map(|col| (0, col)).collect()
+    }
+
+    fn output_cells(&self) -> Vec<(usize, usize)> {
+        (0..SPONGE_WIDTH)
+            .map(|col| (POS_ROWS_PER_HASH, col))
+            .collect()
+    }
+
+    fn witness(&self, input: &[F; SPONGE_WIDTH]) -> [Vec<F>; COLUMNS] {
+        let mut witness = std::array::from_fn(|_| vec![F::zero(); POS_ROWS_PER_HASH + 1]);
+        generate_witness(0, self.params, &mut witness, *input);
+        witness
+    }
+}
+
 /// An equation of the form `(curr | next)[i] = round(curr[j])`
 struct RoundEquation {
     pub source: usize,
@@ -186,10 +186,13 @@ impl<F: PrimeField> CircuitGate<F> {
                     endo_coefficient: cs.endo,
                     mds: &G::sponge_params().mds,
                     foreign_field_modulus: None,
+                    zk_rows: cs.zk_rows,
                 },
                 witness: &witness_evals.d8.this.w,
                 coefficient: &cs.coefficients8,
-                vanishes_on_last_4_rows: &cs.precomputations().vanishes_on_last_4_rows,
+                vanishes_on_zero_knowledge_and_previous_rows: &cs
+                    .precomputations()
+                    .vanishes_on_zero_knowledge_and_previous_rows,
                 z: &witness_evals.d8.this.z,
                 l0_1: l0_1(cs.domain.d1),
                 domain: cs.domain,
@@ -312,6 +315,7 @@ fn set_up_lookup_env_data<F: PrimeField>(
         dummy_lookup_value,
         &joint_lookup_table_d8,
         cs.domain.d1,
+        cs.zk_rows,
         &cs.gates,
         witness,
         joint_combiner,
@@ -323,7 +327,7 @@ fn set_up_lookup_env_data<F: PrimeField>(
     // Randomize the last `EVALS` rows in each of the sorted polynomials in order to add zero-knowledge to the protocol.
     let sorted: Vec<_> = sorted
         .into_iter()
-        .map(|chunk| lookup::constraints::zk_patch(chunk, cs.domain.d1, rng))
+        .map(|chunk| lookup::constraints::zk_patch(chunk, cs.domain.d1, cs.random_rows(), rng))
         .collect();
 
     let sorted_coeffs: Vec<_> = sorted.iter().map(|e| e.clone().interpolate()).collect();
@@ -337,6 +341,8 @@ fn set_up_lookup_env_data<F: PrimeField>(
         dummy_lookup_value,
         &joint_lookup_table_d8,
         cs.domain.d1,
+        cs.zk_rows,
+        cs.random_rows(),
         &cs.gates,
         witness,
         &joint_combiner,
@@ -2,4 +2,5 @@
 
 pub mod circuitgates;
 pub mod gadget;
+#[cfg(feature = "prover")]
 pub mod witness;
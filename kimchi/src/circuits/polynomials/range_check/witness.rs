@@ -4,7 +4,9 @@ use ark_ff::PrimeField;
 use o1_utils::{FieldHelpers, ForeignElement};
 use std::array;
 
-use crate::circuits::polynomial::COLUMNS;
+use crate::circuits::{
+    gate::CircuitGate, polynomial::COLUMNS, witness_generator::WitnessGenerator,
+};
 
 /// Witness cell for range check gadget
 pub enum WitnessCell {
@@ -160,7 +162,10 @@ const fn range_check_row(row: usize) -> [WitnessCell; COLUMNS] {
 
 /// transforms a field to a limb from a start bit to an end bit
 pub fn value_to_limb<F: PrimeField>(fe: F, start: usize, end: usize) -> F {
-    F::from_bits(&fe.to_bits()[start..end]).expect("failed to deserialize field bits")
+    let bits = fe
+        .to_bits_range(start, end)
+        .expect("limb out of the bits of the field");
+    F::from_bits(&bits).expect("failed to deserialize field bits")
 }
 
 /// handles range-check witness cells
@@ -229,3 +234,57 @@ pub fn extend_witness<F: PrimeField>(witness: &mut [Vec<F>; COLUMNS], fe: Foreig
         witness[col].extend(limbs_witness[col].iter())
     }
 }
+
+/// The range check of one 88-bit value, as a [WitnessGenerator] whose input is the value,
+/// in column 0 of its only row
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RangeCheckGadget;
+
+impl<F: PrimeField> WitnessGenerator<F> for RangeCheckGadget {
+    type Input = F;
+
+    fn gates(&self, start_row: usize) -> Vec<CircuitGate<F>> {
+        CircuitGate::create_range_check(start_row).1
+    }
+
+    fn input_cells(&self) -> Vec<(usize, usize)> {
+        vec![(0, 0)]
+    }
+
+    fn output_cells(&self) -> Vec<(usize, usize)> {
+        vec![]
+    }
+
+    fn witness(&self, input: &F) -> [Vec<F>; COLUMNS] {
+        let mut witness = create_witness(*input);
+        for column in &mut witness {
+            column.truncate(1);
+        }
+        witness
+    }
+}
+
+/// The range check of three 88-bit values, as a [WitnessGenerator] whose input is the values,
+/// in column 0 of its first three rows
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MultiRangeCheckGadget;
+
+impl<F: PrimeField> WitnessGenerator<F> for MultiRangeCheckGadget {
+    type Input = [F; 3];
+
+    fn gates(&self, start_row: usize) -> Vec<CircuitGate<F>> {
+        CircuitGate::create_multi_range_check(start_row).1
+    }
+
+    fn input_cells(&self) -> Vec<(usize, usize)> {
+        vec![(0, 0), (1, 0), (2, 0)]
+    }
+
+    fn output_cells(&self) -> Vec<(usize, usize)> {
+        vec![]
+    }
+
+    fn witness(&self, [v0, v1, v2]: &[F; 3]) -> [Vec<F>; COLUMNS] {
+        create_multi_witness(*v0, *v1, *v2)
+    }
+}
@@ -0,0 +1,307 @@
+//! This module obtains the gates of the conditional select and multiplexer gadgets.
+
+use ark_ff::PrimeField;
+use std::array;
+
+use crate::circuits::{
+    gate::{CircuitGate, Connect, GateType},
+    polynomial::COLUMNS,
+    polynomials::generic::GENERIC_COEFFS,
+    wires::Wire,
+};
+
+//~ The select and multiplexer gadgets are made of generic gates, packed two per row.
+//~
+//~ A multiplexer over `n` values selects the value at a given index, which is decomposed into
+//~ its $k = \lceil \log_2 n \rceil$ bits $b_0, \ldots, b_{k-1}$:
+//~
+//~ * each bit is checked to be boolean with a generic gate $b_j^2 - b_j = 0$,
+//~ * the index is recomposed from its bits with $k - 1$ generic gates,
+//~ * the value is selected by a binary tree of selects, where the level `j` of the tree
+//~   depends on the bit $b_j$.
+//~
+//~ A select of $x_1$ if $b$ is one and of $x_0$ otherwise is checked with three generic gates:
+//~
+//~ * $d = x_1 - x_0$,
+//~ * $m = b \cdot d$,
+//~ * $out = m + x_0$.
+//~
+//~ When `n` is not a power of two, the values are padded with the last one,
+//~ and the selects between two copies of the last value are skipped.
+//~ The index is not range checked: any index from `n` to $2^k - 1$ selects the last value.
+//~
+//~ The conditional select gadget is the multiplexer over two values, whose index is the condition.
+//~ It takes two rows:
+//~
+//~ | row |  0  |  1  |  2  |  3    |  4    |  5    |
+//~ |:---:|:---:|:---:|:---:|:-----:|:-----:|:-----:|
+//~ |  0  | $b$ | $b$ |     | $x_1$ | $x_0$ | $d$   |
+//~ |  1  | $b$ | $d$ | $m$ | $m$   | $x_0$ | $out$ |
+
+/// The number of rows of the conditional select gadget
+pub const SELECT_ROWS: usize = 2;
+
+/// The cell `(row, column)`, with the row relative to the start of the gadget,
+/// holding the condition of a select
+pub const CONDITION_CELL: (usize, usize) = (0, 0);
+
+/// The cell `(row, column)` holding the value selected when the condition is one
+pub const IF_TRUE_CELL: (usize, usize) = (0, 3);
+
+/// The cell `(row, column)` holding the value selected when the condition is zero
+pub const IF_FALSE_CELL: (usize, usize) = (0, 4);
+
+/// The cell `(row, column)` holding the output of a select
+pub const SELECT_OUTPUT_CELL: (usize, usize) = (1, 5);
+
+/// The number of bits of the index of a multiplexer over `n` values
+pub fn mux_index_bits(n: usize) -> usize {
+    (usize::BITS - (n - 1).leading_zeros()) as usize
+}
+
+/// The cells `(row, column)`, with the rows relative to the start of the gadget,
+/// of the inputs and of the output of a multiplexer
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MuxCells {
+    /// The cell holding the index
+    pub index: (usize, usize),
+    /// The cells holding the values
+    pub values: Vec<(usize, usize)>,
+    /// The cell holding the selected value
+    pub output: (usize, usize),
+}
+
+/// Returns the cells of the inputs and of the output of a multiplexer over `n` values
+pub fn mux_cells(n: usize) -> MuxCells {
+    let k = mux_index_bits(n);
+    let tree = selection_tree(n);
+    let first = first_select_half(k);
+
+    let index = if k == 1 {
+        half_cell(0, 0)
+    } else {
+        half_cell(2 * k - 2, 2)
+    };
+    let values = (0..n)
+        .map(|i| {
+            tree.iter()
+                .enumerate()
+                .find_map(|(s, select)| {
+                    let h = first + 3 * s;
+                    if select.if_true == Node::Value(i) {
+                        Some(half_cell(h, 0))
+                    } else if select.if_false == Node::Value(i) {
+                        Some(half_cell(h, 1))
+                    } else {
+                        None
+                    }
+                })
+                .expect("every value is an input of a select")
+        })
+        .collect();
+    let output = half_cell(first + 3 * tree.len() - 1, 2);
+
+    MuxCells {
+        index,
+        values,
+        output,
+    }
+}
+
+impl<F: PrimeField> CircuitGate<F> {
+    /// Create the gates of a conditional select
+    ///     Inputs the starting row
+    ///     Outputs tuple (`next_row`, `circuit_gates`) where
+    ///       `next_row`      - next row after this gadget
+    ///       `circuit_gates` - vector of circuit gates comprising this gadget
+    ///
+    /// The inputs and the output are in the cells [CONDITION_CELL], [IF_TRUE_CELL],
+    /// [IF_FALSE_CELL] and [SELECT_OUTPUT_CELL].
+    pub fn create_select(start_row: usize) -> (usize, Vec<Self>) {
+        Self::create_mux(start_row, 2)
+    }
+
+    /// Create the gates of a multiplexer over `n` values
+    ///     Inputs the starting row and the number of values
+    ///     Outputs tuple (`next_row`, `circuit_gates`) where
+    ///       `next_row`      - next row after this gadget
+    ///       `circuit_gates` - vector of circuit gates comprising this gadget
+    ///
+    /// The inputs and the output are in the cells given by [mux_cells].
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `n` is smaller than 2.
+    pub fn create_mux(start_row: usize, n: usize) -> (usize, Vec<Self>) {
+        let (gates, _) = layout(start_row, 0, &vec![F::zero(); n]);
+        (start_row + gates.len(), gates)
+    }
+}
+
+// A node of the selection tree of a multiplexer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Node {
+    Value(usize),
+    Select(usize),
+}
+
+// A select of the selection tree, depending on the bit `bit` of the index
+struct Select {
+    bit: usize,
+    if_true: Node,
+    if_false: Node,
+}
+
+// The selects of the binary tree selecting one of `n` values, in the order they are laid out
+fn selection_tree(n: usize) -> Vec<Select> {
+    let mut tree = vec![];
+    let mut level: Vec<Node> = (0..n).map(Node::Value).collect();
+    for bit in 0..mux_index_bits(n) {
+        if level.len() % 2 == 1 {
+            level.push(level[level.len() - 1]);
+        }
+        level = level
+            .chunks(2)
+            .map(|pair| {
+                if pair[0] == pair[1] {
+                    pair[0]
+                } else {
+                    tree.push(Select {
+                        bit,
+                        if_true: pair[1],
+                        if_false: pair[0],
+                    });
+                    Node::Select(tree.len() - 1)
+                }
+            })
+            .collect();
+    }
+    tree
+}
+
+// The first generic gate of the selects, after the checks of the `k` bits and the recomposition of the index
+fn first_select_half(k: usize) -> usize {
+    2 * k - 1
+}
+
+// The cell holding the register `i` of the generic gate `h`
+fn half_cell(h: usize, i: usize) -> (usize, usize) {
+    (h / 2, 3 * (h % 2) + i)
+}
+
+/// Lays out the gates and the witness of a multiplexer selecting the value at `index` in `values`
+pub(crate) fn layout<F: PrimeField>(
+    start_row: usize,
+    index: usize,
+    values: &[F],
+) -> (Vec<CircuitGate<F>>, [Vec<F>; COLUMNS]) {
+    let n = values.len();
+    assert!(n >= 2, "a multiplexer selects among at least two values");
+    let k = mux_index_bits(n);
+    let tree = selection_tree(n);
+    let first = first_select_half(k);
+    let rows = (first + 3 * tree.len() + 1) / 2;
+
+    let mut layout = MuxLayout {
+        gates: (0..rows)
+            .map(|row| CircuitGate {
+                typ: GateType::Generic,
+                wires: Wire::new(start_row + row),
+                coeffs: vec![F::zero(); 2 * GENERIC_COEFFS],
+            })
+            .collect(),
+        witness: array::from_fn(|_| vec![F::zero(); rows]),
+        value_cells: vec![None; n],
+        outputs: vec![],
+    };
+    let one = F::one();
+    let zero = F::zero();
+
+    // the bits of the index are boolean
+    let bits: Vec<F> = (0..k).map(|j| F::from((index >> j) as u64 & 1)).collect();
+    for (j, b) in bits.iter().enumerate() {
+        layout.half(j, [-one, zero, zero, one, zero], [*b, *b, zero]);
+        layout.copy(half_cell(j, 0), half_cell(j, 1));
+    }
+
+    // the index is recomposed from its bits
+    let mut acc = (half_cell(0, 0), bits[0]);
+    for (j, &bit) in bits.iter().enumerate().skip(1) {
+        let h = k - 1 + j;
+        let power = F::from(1u64 << j);
+        let value = acc.1 + power * bit;
+        layout.half(h, [one, power, -one, zero, zero], [acc.1, bit, value]);
+        layout.copy(acc.0, half_cell(h, 0));
+        layout.copy(half_cell(j, 0), half_cell(h, 1));
+        acc = (half_cell(h, 2), value);
+    }
+
+    // the value is selected by the tree of selects
+    for (s, select) in tree.iter().enumerate() {
+        let h = first + 3 * s;
+        let x1 = layout.input(values, select.if_true, half_cell(h, 0));
+        let x0 = layout.input(values, select.if_false, half_cell(h, 1));
+        let b = bits[select.bit];
+
+        let d = x1 - x0;
+        layout.half(h, [one, -one, -one, zero, zero], [x1, x0, d]);
+
+        let m = b * d;
+        layout.half(h + 1, [zero, zero, -one, one, zero], [b, d, m]);
+        layout.copy(half_cell(select.bit, 0), half_cell(h + 1, 0));
+        layout.copy(half_cell(h, 2), half_cell(h + 1, 1));
+
+        layout.half(h + 2, [one, one, -one, zero, zero], [m, x0, m + x0]);
+        layout.copy(half_cell(h + 1, 2), half_cell(h + 2, 0));
+        layout.copy(half_cell(h, 1), half_cell(h + 2, 1));
+
+        layout.outputs.push((half_cell(h + 2, 2), m + x0));
+    }
+
+    (layout.gates, layout.witness)
+}
+
+// The generic gates and the witness of a multiplexer
+struct MuxLayout<F: PrimeField> {
+    gates: Vec<CircuitGate<F>>,
+    witness: [Vec<F>; COLUMNS],
+    // the first cell holding each value
+    value_cells: Vec<Option<(usize, usize)>>,
+    // the output cells of the selects laid out so far, with their values
+    outputs: Vec<((usize, usize), F)>,
+}
+
+impl<F: PrimeField> MuxLayout<F> {
+    // Sets the coefficients and the registers of the generic gate `h`
+    fn half(&mut self, h: usize, coeffs: [F; GENERIC_COEFFS], registers: [F; 3]) {
+        let (row, col) = half_cell(h, 0);
+        let offset = col / 3 * GENERIC_COEFFS;
+        self.gates[row].coeffs[offset..offset + GENERIC_COEFFS].copy_from_slice(&coeffs);
+        for (i, value) in registers.into_iter().enumerate() {
+            self.witness[col + i][row] = value;
+        }
+    }
+
+    // Sets the cell `cell` to the input `node` of a select, and returns its value
+    fn input(&mut self, values: &[F], node: Node, cell: (usize, usize)) -> F {
+        match node {
+            Node::Value(i) => {
+                match self.value_cells[i] {
+                    None => self.value_cells[i] = Some(cell),
+                    Some(first) => self.copy(first, cell),
+                }
+                values[i]
+            }
+            Node::Select(s) => {
+                let (output, value) = self.outputs[s];
+                self.copy(output, cell);
+                value
+            }
+        }
+    }
+
+    // Copies the cell `from` to the cell `to`
+    fn copy(&mut self, from: (usize, usize), to: (usize, usize)) {
+        self.gates.connect_cell_pair(from, to);
+    }
+}
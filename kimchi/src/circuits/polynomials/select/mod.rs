@@ -0,0 +1,5 @@
+//! Conditional select and multiplexer gadget module
+
+pub mod gadget;
+#[cfg(feature = "prover")]
+pub mod witness;
@@ -0,0 +1,68 @@
+//! Conditional select and multiplexer witness computation
+
+use ark_ff::PrimeField;
+
+use crate::circuits::{
+    gate::CircuitGate, polynomial::COLUMNS, witness_generator::WitnessGenerator,
+};
+
+use super::gadget::{layout, mux_cells};
+
+/// Create the witness of a conditional select of `if_true` if `condition` holds,
+/// and of `if_false` otherwise
+pub fn create_select_witness<F: PrimeField>(
+    condition: bool,
+    if_true: F,
+    if_false: F,
+) -> [Vec<F>; COLUMNS] {
+    create_mux_witness(condition as usize, &[if_false, if_true])
+}
+
+/// Create the witness of a multiplexer selecting the value at `index` in `values`
+///
+/// # Panics
+///
+/// Will panic if `index` is out of the range of `values`, or if there are less than two values.
+pub fn create_mux_witness<F: PrimeField>(index: usize, values: &[F]) -> [Vec<F>; COLUMNS] {
+    assert!(
+        index < values.len(),
+        "the index of a multiplexer is out of range"
+    );
+    layout(0, index, values).1
+}
+
+/// The multiplexer over `n` values, as a [WitnessGenerator] whose input is the index
+/// and the values, and whose inputs cells are those of the index and of the values (see [mux_cells]).
+/// The conditional select is the multiplexer over two values.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MuxGadget {
+    /// The number of values
+    pub n: usize,
+}
+
+impl<F: PrimeField> WitnessGenerator<F> for MuxGadget {
+    type Input = (usize, Vec<F>);
+
+    fn gates(&self, start_row: usize) -> Vec<CircuitGate<F>> {
+        CircuitGate::create_mux(start_row, self.n).1
+    }
+
+    fn input_cells(&self) -> Vec<(usize, usize)> {
+        let cells = mux_cells(self.n);
+        std::iter::once(cells.index).chain(cells.values).collect()
+    }
+
+    fn output_cells(&self) -> Vec<(usize, usize)> {
+        vec![mux_cells(self.n).output]
+    }
+
+    fn witness(&self, (index, values): &Self::Input) -> [Vec<F>; COLUMNS] {
+        assert_eq!(
+            values.len(),
+            self.n,
+            "a multiplexer needs {} values",
+            self.n
+        );
+        create_mux_witness(*index, values)
+    }
+}
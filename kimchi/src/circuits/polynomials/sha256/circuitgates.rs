@@ -0,0 +1,550 @@
+//! SHA-256 gates
+
+use std::marker::PhantomData;
+
+use crate::circuits::{
+    argument::{Argument, ArgumentEnv, ArgumentType},
+    expr::constraints::{boolean, ExprOps},
+    gate::GateType,
+};
+use ark_ff::PrimeField;
+
+//~ The SHA-256 gates implement the operations of the SHA-256 compression function
+//~ on 32-bit words, using the *spread* representation of words:
+//~ the spread form of a word `x` is the 64-bit value `spread(x)` whose bit $2i$ is the bit $i$ of `x`,
+//~ and whose odd bits are zero.
+//~ Adding up to three spread values never overflows into the next even bit,
+//~ so for a sum $S = spread(x) + spread(y) + spread(z)$ the even bits of $S$ are the bits of $x \oplus y \oplus z$,
+//~ while the odd bits of $S$ are the bits of $maj(x, y, z)$ (and of $x \wedge y$ if $z = 0$).
+//~
+//~ The link between a value and its spread form is enforced 4 bits at a time,
+//~ with lookups in the SHA-256 spread table containing the pairs `(n, spread(n))` for `n` in `[0, 16)`.
+//~ All the gates that use lookups perform four of them on their row and four on the next row,
+//~ each lookup reading a pair of cells in columns `(7, 8)`, `(9, 10)`, `(11, 12)` and `(13, 14)`.
+//~ Hence these gates must be followed by a `Zero` gate.
+//~
+//~ A word `x` is decomposed into the nybbles $n_0, \ldots, n_7$ (from the least significant one),
+//~ with spread forms $s_0, \ldots, s_7$, laid out as follows:
+//~
+//~ | row  | 7     | 8     | 9     | 10    | 11    | 12    | 13    | 14    |
+//~ | ---- | ----- | ----- | ----- | ----- | ----- | ----- | ----- | ----- |
+//~ | Curr | $n_0$ | $s_0$ | $n_1$ | $s_1$ | $n_2$ | $s_2$ | $n_3$ | $s_3$ |
+//~ | Next | $n_4$ | $s_4$ | $n_5$ | $s_5$ | $n_6$ | $s_6$ | $n_7$ | $s_7$ |
+//~
+//~ We define the following gates:
+//~
+//~ * `Sha256Word` reduces a sum of words modulo $2^{32}$ and computes the spread form of the result.
+//~ * `Sha256SmallSigma0`, `Sha256SmallSigma1`, `Sha256BigSigma0` and `Sha256BigSigma1`
+//~   compute the spread sum of the three shifted words whose XOR is the corresponding sigma function.
+//~ * `Sha256Ch` and `Sha256Maj` compute the spread sums from which the choice and majority functions are extracted.
+//~ * `Sha256Spread` decomposes a spread sum into its even and odd bits.
+//~
+//~ The additions of words are done with generic gates, and the results of the spread sums
+//~ are extracted with `Sha256Spread` gates.
+
+/// The number of 4-bit nybbles in a 32-bit word
+pub const NYBBLES: usize = 8;
+
+/// The spread form of `2^32 - 1`
+pub const SPREAD_ONES: u64 = 0x5555_5555_5555_5555;
+
+/// The maximum carry of a sum reduced by a `Sha256Word` gate,
+/// as at most seven words are added together in the compression function
+pub const MAX_CARRY: u64 = 6;
+
+/// The cell `(row offset, column)` holding the nybble `i` of a word decomposed in 4-bit values
+pub const fn nybble_cell(i: usize) -> (usize, usize) {
+    (i / 4, 7 + 2 * (i % 4))
+}
+
+/// The cell `(row offset, column)` holding the spread form of the nybble `i` of a word
+pub const fn spread_cell(i: usize) -> (usize, usize) {
+    (i / 4, 8 + 2 * (i % 4))
+}
+
+/// The cells `(row offset, column)` holding the bits of the nybbles split by a sigma gate
+pub const SIGMA_BIT_CELLS: [(usize, usize); 12] = [
+    (0, 2),
+    (0, 3),
+    (0, 4),
+    (0, 5),
+    (0, 6),
+    (1, 0),
+    (1, 1),
+    (1, 2),
+    (1, 3),
+    (1, 4),
+    (1, 5),
+    (1, 6),
+];
+
+// Returns the expression of a cell given as `(row offset, column)`
+fn cell<F: PrimeField, T: ExprOps<F>>(env: &ArgumentEnv<F, T>, (row, col): (usize, usize)) -> T {
+    if row == 0 {
+        env.witness_curr(col)
+    } else {
+        env.witness_next(col)
+    }
+}
+
+// Returns the combination of the values in `cells` with the powers of `base`
+fn combine<F: PrimeField, T: ExprOps<F>>(
+    env: &ArgumentEnv<F, T>,
+    cells: impl Iterator<Item = (usize, usize)>,
+    base: u64,
+) -> T {
+    let mut power = 1u64;
+    let mut sum = T::zero();
+    for c in cells {
+        sum += cell(env, c) * T::from(power);
+        power = power.wrapping_mul(base);
+    }
+    sum
+}
+
+//~ ##### `Sha256Word` - reduction of a sum of words
+//~
+//~ The `Sha256Word` gate checks that the sum `u` (copied from a generic gate)
+//~ is equal to $r + 2^{32} \cdot q$ with `r` a 32-bit word and $q \in [0, 6]$ a carry,
+//~ and computes `S`, the spread form of `r`.
+//~ It is also used without any sum to decompose the input words of the compression function.
+//~
+//~ | col | `Sha256Word` | `Zero`   |
+//~ | --- | ------------ | -------- |
+//~ |   0 | `r` (copy)   |          |
+//~ |   1 | `S` (copy)   |          |
+//~ |   2 | `u` (copy)   |          |
+//~ |   3 | `q`          |          |
+//~ | 7-14| $n_0 \ldots n_3$ | $n_4 \ldots n_7$ |
+//~
+//~ The constraints are
+//~
+//~ * $r = \sum_{i} 16^i \cdot n_i$
+//~ * $S = \sum_{i} 256^i \cdot s_i$
+//~ * $u = r + 2^{32} \cdot q$
+//~ * $\prod_{k=0}^{6} (q - k) = 0$
+
+/// The `Sha256Word` gate
+#[derive(Default)]
+pub struct Sha256Word<F>(PhantomData<F>);
+
+impl<F> Argument<F> for Sha256Word<F>
+where
+    F: PrimeField,
+{
+    const ARGUMENT_TYPE: ArgumentType = ArgumentType::Gate(GateType::Sha256Word);
+    const CONSTRAINTS: u32 = 4;
+
+    fn constraint_checks<T: ExprOps<F>>(env: &ArgumentEnv<F, T>) -> Vec<T> {
+        let word = env.witness_curr(0);
+        let spread = env.witness_curr(1);
+        let sum = env.witness_curr(2);
+        let carry = env.witness_curr(3);
+
+        let mut carry_range = T::one();
+        for k in 0..=MAX_CARRY {
+            carry_range *= carry.clone() - T::from(k);
+        }
+
+        vec![
+            combine(env, (0..NYBBLES).map(nybble_cell), 16) - word.clone(),
+            combine(env, (0..NYBBLES).map(spread_cell), 256) - spread,
+            word + carry * T::from(1u64 << 32) - sum,
+            carry_range,
+        ]
+    }
+}
+
+/// A shift of a 32-bit word used by the sigma functions
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Shift {
+    /// Right rotation
+    Rotr(u32),
+    /// Right shift
+    Shr(u32),
+}
+
+impl Shift {
+    /// Applies the shift to `x`
+    pub fn apply(self, x: u32) -> u32 {
+        match self {
+            Shift::Rotr(r) => x.rotate_right(r),
+            Shift::Shr(r) => x >> r,
+        }
+    }
+
+    /// The power of 4 multiplying the bit at position `pos` of a word
+    /// in the spread form of the shifted word
+    pub fn spread_weight(self, pos: u32) -> u64 {
+        match self {
+            Shift::Rotr(r) => 1 << (2 * ((pos + 32 - r) % 32)),
+            Shift::Shr(r) if pos >= r => 1 << (2 * (pos - r)),
+            Shift::Shr(_) => 0,
+        }
+    }
+}
+
+/// The sigma functions of SHA-256
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sigma {
+    /// $\sigma_0$, used by the message schedule
+    Small0,
+    /// $\sigma_1$, used by the message schedule
+    Small1,
+    /// $\Sigma_0$, used by the compression rounds
+    Big0,
+    /// $\Sigma_1$, used by the compression rounds
+    Big1,
+}
+
+impl Sigma {
+    /// The shifts whose XOR is the sigma function
+    pub const fn shifts(self) -> [Shift; 3] {
+        match self {
+            Sigma::Small0 => [Shift::Rotr(7), Shift::Rotr(18), Shift::Shr(3)],
+            Sigma::Small1 => [Shift::Rotr(17), Shift::Rotr(19), Shift::Shr(10)],
+            Sigma::Big0 => [Shift::Rotr(2), Shift::Rotr(13), Shift::Rotr(22)],
+            Sigma::Big1 => [Shift::Rotr(6), Shift::Rotr(11), Shift::Rotr(25)],
+        }
+    }
+
+    /// The nybbles that are not moved as a whole by one of the shifts,
+    /// and are thus decomposed into bits
+    pub const fn split_nybbles(self) -> &'static [usize] {
+        match self {
+            Sigma::Small0 => &[0, 1, 4],
+            Sigma::Small1 => &[2, 4],
+            Sigma::Big0 => &[0, 3, 5],
+            Sigma::Big1 => &[1, 2, 6],
+        }
+    }
+
+    /// The gate computing the sigma function
+    pub const fn gate_type(self) -> GateType {
+        match self {
+            Sigma::Small0 => GateType::Sha256SmallSigma0,
+            Sigma::Small1 => GateType::Sha256SmallSigma1,
+            Sigma::Big0 => GateType::Sha256BigSigma0,
+            Sigma::Big1 => GateType::Sha256BigSigma1,
+        }
+    }
+
+    /// The number of constraints of the gate computing the sigma function
+    pub const fn constraints(self) -> u32 {
+        // one constraint for the word, and for each split nybble:
+        // one constraint for its bits, and four boolean constraints
+        2 + 5 * self.split_nybbles().len() as u32
+    }
+
+    /// Computes the sigma function
+    pub fn compute(self, x: u32) -> u32 {
+        self.shifts()
+            .iter()
+            .fold(0, |acc, shift| acc ^ shift.apply(x))
+    }
+
+    /// The coefficient of the bit at position `pos` of a word in the spread sum computed by the gate
+    pub fn spread_weight(self, pos: u32) -> u64 {
+        self.shifts()
+            .iter()
+            .map(|shift| shift.spread_weight(pos))
+            .sum()
+    }
+}
+
+//~ ##### Sigma gates
+//~
+//~ The gates `Sha256SmallSigma0`, `Sha256SmallSigma1`, `Sha256BigSigma0` and `Sha256BigSigma1`
+//~ compute the sums `S` of the spread forms of the three shifts of a word `x` whose XOR is
+//~ the corresponding sigma function:
+//~
+//~ | gate                | shifts                       | split nybbles |
+//~ | ------------------- | ---------------------------- | ------------- |
+//~ | `Sha256SmallSigma0` | `ROTR 7`, `ROTR 18`, `SHR 3`   | 0, 1, 4       |
+//~ | `Sha256SmallSigma1` | `ROTR 17`, `ROTR 19`, `SHR 10` | 2, 4          |
+//~ | `Sha256BigSigma0`   | `ROTR 2`, `ROTR 13`, `ROTR 22` | 0, 3, 5       |
+//~ | `Sha256BigSigma1`   | `ROTR 6`, `ROTR 11`, `ROTR 25` | 1, 2, 6       |
+//~
+//~ A nybble that is moved as a whole by all the shifts contributes to `S` with its spread form
+//~ multiplied by a constant.
+//~ The other nybbles are split into bits $b_0, \ldots, b_{11}$ (four bits per split nybble, in order),
+//~ which contribute to `S` individually.
+//~ The value of the sigma function is then the even part of `S`, extracted with a `Sha256Spread` gate.
+//~
+//~ | col | sigma gate   | `Zero`   |
+//~ | --- | ------------ | -------- |
+//~ |   0 | `x` (copy)   | $b_5$    |
+//~ |   1 | `S` (copy)   | $b_6$    |
+//~ |   2 | $b_0$        | $b_7$    |
+//~ |   3 | $b_1$        | $b_8$    |
+//~ |   4 | $b_2$        | $b_9$    |
+//~ |   5 | $b_3$        | $b_{10}$ |
+//~ |   6 | $b_4$        | $b_{11}$ |
+//~ | 7-14| $n_0 \ldots n_3$ | $n_4 \ldots n_7$ |
+//~
+//~ The constraints are
+//~
+//~ * $x = \sum_{i} 16^i \cdot n_i$
+//~ * each split nybble is equal to the combination of its bits
+//~ * the bits are boolean
+//~ * `S` is equal to the weighted sum of the spread nybbles that are not split and of the bits
+
+// The constraints of a sigma gate
+fn sigma_constraint_checks<F: PrimeField, T: ExprOps<F>>(
+    sigma: Sigma,
+    env: &ArgumentEnv<F, T>,
+) -> Vec<T> {
+    let split = sigma.split_nybbles();
+    let bits: Vec<T> = SIGMA_BIT_CELLS
+        .iter()
+        .take(4 * split.len())
+        .map(|c| cell(env, *c))
+        .collect();
+
+    // the word is the combination of its nybbles
+    let mut constraints =
+        vec![combine(env, (0..NYBBLES).map(nybble_cell), 16) - env.witness_curr(0)];
+
+    // the split nybbles are the combination of their bits, which are boolean
+    for (j, i) in split.iter().enumerate() {
+        let mut nybble = T::zero();
+        for (l, bit) in bits[4 * j..4 * j + 4].iter().enumerate() {
+            nybble += bit.clone() * T::from(1u64 << l);
+        }
+        constraints.push(nybble - cell(env, nybble_cell(*i)));
+    }
+    constraints.extend(bits.iter().map(boolean));
+
+    // the spread sum of the shifted words
+    let mut spread_sum = T::zero();
+    for i in 0..NYBBLES {
+        let pos = 4 * i as u32;
+        match split.iter().position(|j| *j == i) {
+            Some(j) => {
+                for (l, bit) in bits[4 * j..4 * j + 4].iter().enumerate() {
+                    spread_sum += bit.clone() * T::from(sigma.spread_weight(pos + l as u32));
+                }
+            }
+            None => {
+                spread_sum += cell(env, spread_cell(i)) * T::from(sigma.spread_weight(pos));
+            }
+        }
+    }
+    constraints.push(spread_sum - env.witness_curr(1));
+
+    constraints
+}
+
+/// The `Sha256SmallSigma0` gate
+#[derive(Default)]
+pub struct Sha256SmallSigma0<F>(PhantomData<F>);
+
+impl<F> Argument<F> for Sha256SmallSigma0<F>
+where
+    F: PrimeField,
+{
+    const ARGUMENT_TYPE: ArgumentType = ArgumentType::Gate(GateType::Sha256SmallSigma0);
+    const CONSTRAINTS: u32 = Sigma::Small0.constraints();
+
+    fn constraint_checks<T: ExprOps<F>>(env: &ArgumentEnv<F, T>) -> Vec<T> {
+        sigma_constraint_checks(Sigma::Small0, env)
+    }
+}
+
+/// The `Sha256SmallSigma1` gate
+#[derive(Default)]
+pub struct Sha256SmallSigma1<F>(PhantomData<F>);
+
+impl<F> Argument<F> for Sha256SmallSigma1<F>
+where
+    F: PrimeField,
+{
+    const ARGUMENT_TYPE: ArgumentType = ArgumentType::Gate(GateType::Sha256SmallSigma1);
+    const CONSTRAINTS: u32 = Sigma::Small1.constraints();
+
+    fn constraint_checks<T: ExprOps<F>>(env: &ArgumentEnv<F, T>) -> Vec<T> {
+        sigma_constraint_checks(Sigma::Small1, env)
+    }
+}
+
+/// The `Sha256BigSigma0` gate
+#[derive(Default)]
+pub struct Sha256BigSigma0<F>(PhantomData<F>);
+
+impl<F> Argument<F> for Sha256BigSigma0<F>
+where
+    F: PrimeField,
+{
+    const ARGUMENT_TYPE: ArgumentType = ArgumentType::Gate(GateType::Sha256BigSigma0);
+    const CONSTRAINTS: u32 = Sigma::Big0.constraints();
+
+    fn constraint_checks<T: ExprOps<F>>(env: &ArgumentEnv<F, T>) -> Vec<T> {
+        sigma_constraint_checks(Sigma::Big0, env)
+    }
+}
+
+/// The `Sha256BigSigma1` gate
+#[derive(Default)]
+pub struct Sha256BigSigma1<F>(PhantomData<F>);
+
+impl<F> Argument<F> for Sha256BigSigma1<F>
+where
+    F: PrimeField,
+{
+    const ARGUMENT_TYPE: ArgumentType = ArgumentType::Gate(GateType::Sha256BigSigma1);
+    const CONSTRAINTS: u32 = Sigma::Big1.constraints();
+
+    fn constraint_checks<T: ExprOps<F>>(env: &ArgumentEnv<F, T>) -> Vec<T> {
+        sigma_constraint_checks(Sigma::Big1, env)
+    }
+}
+
+//~ ##### `Sha256Ch` - choice function
+//~
+//~ The choice function is computed as
+//~ $ch(e, f, g) = (e \wedge f) + (\neg e \wedge g)$,
+//~ where the two terms are the odd parts of the spread sums
+//~ $P = spread(e) + spread(f)$ and $Q = spread(\neg e) + spread(g)$,
+//~ with $spread(\neg e) = spread(2^{32} - 1) - spread(e)$.
+//~
+//~ | col | `Sha256Ch`          |
+//~ | --- | ------------------- |
+//~ |   0 | `spread(e)` (copy)  |
+//~ |   1 | `spread(f)` (copy)  |
+//~ |   2 | `spread(g)` (copy)  |
+//~ |   3 | `P` (copy)          |
+//~ |   4 | `Q` (copy)          |
+
+/// The `Sha256Ch` gate
+#[derive(Default)]
+pub struct Sha256Ch<F>(PhantomData<F>);
+
+impl<F> Argument<F> for Sha256Ch<F>
+where
+    F: PrimeField,
+{
+    const ARGUMENT_TYPE: ArgumentType = ArgumentType::Gate(GateType::Sha256Ch);
+    const CONSTRAINTS: u32 = 2;
+
+    fn constraint_checks<T: ExprOps<F>>(env: &ArgumentEnv<F, T>) -> Vec<T> {
+        let e = env.witness_curr(0);
+        let f = env.witness_curr(1);
+        let g = env.witness_curr(2);
+        let p = env.witness_curr(3);
+        let q = env.witness_curr(4);
+
+        vec![e.clone() + f - p, T::from(SPREAD_ONES) - e + g - q]
+    }
+}
+
+//~ ##### `Sha256Maj` - majority function
+//~
+//~ The majority function $maj(a, b, c)$ is the odd part of the spread sum
+//~ $M = spread(a) + spread(b) + spread(c)$.
+//~
+//~ | col | `Sha256Maj`         |
+//~ | --- | ------------------- |
+//~ |   0 | `spread(a)` (copy)  |
+//~ |   1 | `spread(b)` (copy)  |
+//~ |   2 | `spread(c)` (copy)  |
+//~ |   3 | `M` (copy)          |
+
+/// The `Sha256Maj` gate
+#[derive(Default)]
+pub struct Sha256Maj<F>(PhantomData<F>);
+
+impl<F> Argument<F> for Sha256Maj<F>
+where
+    F: PrimeField,
+{
+    const ARGUMENT_TYPE: ArgumentType = ArgumentType::Gate(GateType::Sha256Maj);
+    const CONSTRAINTS: u32 = 1;
+
+    fn constraint_checks<T: ExprOps<F>>(env: &ArgumentEnv<F, T>) -> Vec<T> {
+        vec![env.witness_curr(0) + env.witness_curr(1) + env.witness_curr(2) - env.witness_curr(3)]
+    }
+}
+
+//~ ##### `Sha256Spread` - decomposition of a spread sum
+//~
+//~ A 64-bit spread sum `S` is decomposed as $S = spread(E) + 2 \cdot spread(O)$
+//~ where `E` (resp. `O`) is the 32-bit word made of the even (resp. odd) bits of `S`.
+//~ As a gate can only perform eight lookups, the decomposition is done 16 bits of `E` and `O` at a time,
+//~ by two `Sha256Spread` gates each followed by a `Zero` gate.
+//~ Each of them decomposes a 32-bit part $S_h$ of `S` into the 16-bit halves $E_h$ and $O_h$,
+//~ whose nybbles $e_0, \ldots, e_3$ and $o_0, \ldots, o_3$ are looked up in the spread table:
+//~
+//~ | row  | 7     | 8            | 9     | 10           | 11    | 12           | 13    | 14           |
+//~ | ---- | ----- | ------------ | ----- | ------------ | ----- | ------------ | ----- | ------------ |
+//~ | Curr | $e_0$ | $spread(e_0)$ | $o_0$ | $spread(o_0)$ | $e_1$ | $spread(e_1)$ | $o_1$ | $spread(o_1)$ |
+//~ | Next | $e_2$ | $spread(e_2)$ | $o_2$ | $spread(o_2)$ | $e_3$ | $spread(e_3)$ | $o_3$ | $spread(o_3)$ |
+//~
+//~ The first gate also combines the low halves it decomposes with the high halves,
+//~ copied from the second gate to its next row:
+//~
+//~ | col | `Sha256Spread` | `Zero`        | `Sha256Spread` | `Zero` |
+//~ | --- | -------------- | ------------- | -------------- | ------ |
+//~ |   0 | $S_l$          | $S_h$ (copy)  | $S_h$ (copy)   | 0      |
+//~ |   1 | $E_l$          | $E_h$ (copy)  | $E_h$ (copy)   | 0      |
+//~ |   2 | $O_l$          | $O_h$ (copy)  | $O_h$ (copy)   | 0      |
+//~ |   3 | `S` (copy)     |               | $S_h$          |        |
+//~ |   4 | `E` (copy)     |               | $E_h$          |        |
+//~ |   5 | `O` (copy)     |               | $O_h$          |        |
+//~
+//~ The constraints are
+//~
+//~ * $S_l = \sum_{k} 256^k \cdot (spread(e_k) + 2 \cdot spread(o_k))$
+//~ * $E_l = \sum_{k} 16^k \cdot e_k$
+//~ * $O_l = \sum_{k} 16^k \cdot o_k$
+//~ * $S = S_l + 2^{32} \cdot S_h$
+//~ * $E = E_l + 2^{16} \cdot E_h$
+//~ * $O = O_l + 2^{16} \cdot O_h$
+
+/// The cells `(row offset, column)` holding the even nybble `k` of a `Sha256Spread` gate
+/// and its spread form
+pub const fn spread_gate_even_cells(k: usize) -> ((usize, usize), (usize, usize)) {
+    let col = 7 + 4 * (k % 2);
+    ((k / 2, col), (k / 2, col + 1))
+}
+
+/// The cells `(row offset, column)` holding the odd nybble `k` of a `Sha256Spread` gate
+/// and its spread form
+pub const fn spread_gate_odd_cells(k: usize) -> ((usize, usize), (usize, usize)) {
+    let col = 9 + 4 * (k % 2);
+    ((k / 2, col), (k / 2, col + 1))
+}
+
+/// The `Sha256Spread` gate
+#[derive(Default)]
+pub struct Sha256Spread<F>(PhantomData<F>);
+
+impl<F> Argument<F> for Sha256Spread<F>
+where
+    F: PrimeField,
+{
+    const ARGUMENT_TYPE: ArgumentType = ArgumentType::Gate(GateType::Sha256Spread);
+    const CONSTRAINTS: u32 = 6;
+
+    fn constraint_checks<T: ExprOps<F>>(env: &ArgumentEnv<F, T>) -> Vec<T> {
+        let spread_part = env.witness_curr(0);
+        let even_part = env.witness_curr(1);
+        let odd_part = env.witness_curr(2);
+
+        let mut spread_sum = T::zero();
+        for k in 0..4 {
+            let (_, even_spread) = spread_gate_even_cells(k);
+            let (_, odd_spread) = spread_gate_odd_cells(k);
+            spread_sum += (cell(env, even_spread) + cell(env, odd_spread) * T::from(2u64))
+                * T::from(1u64 << (8 * k));
+        }
+
+        vec![
+            spread_sum - spread_part.clone(),
+            combine(env, (0..4).map(|k| spread_gate_even_cells(k).0), 16) - even_part.clone(),
+            combine(env, (0..4).map(|k| spread_gate_odd_cells(k).0), 16) - odd_part.clone(),
+            spread_part + env.witness_next(0) * T::from(1u64 << 32) - env.witness_curr(3),
+            even_part + env.witness_next(1) * T::from(1u64 << 16) - env.witness_curr(4),
+            odd_part + env.witness_next(2) * T::from(1u64 << 16) - env.witness_curr(5),
+        ]
+    }
+}
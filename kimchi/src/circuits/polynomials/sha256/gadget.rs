@@ -0,0 +1,516 @@
+//! This module obtains the gates of a SHA-256 compression circuit.
+
+use ark_ff::{FftField, PrimeField};
+use std::array;
+
+use crate::{
+    alphas::Alphas,
+    circuits::{
+        argument::Argument,
+        constraints::ConstraintSystem,
+        expr::E,
+        gate::{CircuitGate, CircuitGateError, CircuitGateResult, Connect, CurrOrNext, GateType},
+        lookup::{
+            self,
+            lookups::LookupPattern,
+            tables::{sha256::spread, GateLookupTable, LookupTable},
+        },
+        polynomial::COLUMNS,
+        polynomials::generic::GENERIC_COEFFS,
+        wires::Wire,
+    },
+    curve::KimchiCurve,
+};
+
+use super::circuitgates::{
+    nybble_cell, spread_cell, spread_gate_even_cells, spread_gate_odd_cells, Sha256BigSigma0,
+    Sha256BigSigma1, Sha256Ch, Sha256Maj, Sha256SmallSigma0, Sha256SmallSigma1, Sha256Spread,
+    Sha256Word, Sigma, NYBBLES, SIGMA_BIT_CELLS, SPREAD_ONES,
+};
+
+/// Number of gates used by the SHA-256 gadget
+pub const GATE_COUNT: usize = 8;
+
+/// The round constants of SHA-256
+pub const ROUND_CONSTANTS: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// The number of state words of SHA-256
+pub const STATE_WORDS: usize = 8;
+
+/// The number of words of a SHA-256 block
+pub const BLOCK_WORDS: usize = 16;
+
+/// The row (relative to the start of the gadget) of the `Sha256Word` gate holding,
+/// in column 0, the input state word `i` of the compression function
+pub const fn state_input_row(i: usize) -> usize {
+    2 * i
+}
+
+/// The row (relative to the start of the gadget) of the `Sha256Word` gate holding,
+/// in column 0, the word `i` of the block compressed by the compression function
+pub const fn block_input_row(i: usize) -> usize {
+    2 * (STATE_WORDS + i)
+}
+
+/// The row (relative to the end of the gadget) of the `Sha256Word` gate holding,
+/// in column 0, the output state word `i` of the compression function
+pub const fn state_output_row_from_end(i: usize) -> usize {
+    2 * (STATE_WORDS - i)
+}
+
+impl<F: PrimeField> CircuitGate<F> {
+    /// Create the gates of the SHA-256 compression function
+    ///     Inputs the starting row
+    ///     Outputs tuple (`next_row`, `circuit_gates`) where
+    ///       `next_row`      - next row after this gadget
+    ///       `circuit_gates` - vector of circuit gates comprising this gadget
+    ///
+    /// The gadget starts with the `Sha256Word` gates decomposing its inputs
+    /// (see [`state_input_row`] and [`block_input_row`]), and ends with
+    /// the `Sha256Word` gates of the output state (see [`state_output_row_from_end`]).
+    /// The input and output words are in column 0 of these gates, and can be copied from or to.
+    pub fn create_sha256_compression(start_row: usize) -> (usize, Vec<Self>) {
+        let mut layout = Layout::new(start_row);
+        layout.compression([0; STATE_WORDS], [0; BLOCK_WORDS]);
+        (start_row + layout.gates.len(), layout.gates)
+    }
+
+    /// Verify the witness against a SHA-256 circuit gate
+    ///
+    /// The following verification checks are performed
+    ///   * Constraint checks for the circuit gate
+    ///   * Copy constraints of the cells of the gate
+    ///   * Lookups of the gate in the SHA-256 spread table
+    ///
+    /// # Errors
+    ///
+    /// Will give error if `self.typ` is not a SHA-256 gate type,
+    /// or if one of the checks fails.
+    pub fn verify_sha256<G: KimchiCurve<ScalarField = F>>(
+        &self,
+        row: usize,
+        witness: &[Vec<F>; COLUMNS],
+        cs: &ConstraintSystem<F>,
+    ) -> CircuitGateResult<()> {
+        if !circuit_gates().contains(&self.typ) {
+            return Err(CircuitGateError::InvalidCircuitGateType(self.typ));
+        }
+
+        // Constraints and copy constraints
+        self.verify_witness::<G>(row, witness, cs, &[])?;
+
+        // Lookups
+        let table = lookup_table::<F>();
+        for curr_or_next in [CurrOrNext::Curr, CurrOrNext::Next] {
+            let pattern = match LookupPattern::from_gate(self.typ, curr_or_next) {
+                Some(pattern) => pattern,
+                None => continue,
+            };
+            let lookup_row = row + curr_or_next.shift();
+            for lookup in pattern.lookups::<F>() {
+                let entry: Vec<F> = lookup
+                    .entry
+                    .iter()
+                    .map(|single| {
+                        single.evaluate(|pos| witness[pos.column][lookup_row + pos.row.shift()])
+                    })
+                    .collect();
+                let found = (0..table.len())
+                    .any(|i| table.data.iter().zip(&entry).all(|(col, v)| col[i] == *v));
+                if !found {
+                    return Err(CircuitGateError::InvalidLookup(self.typ, lookup_row));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A cell of the layout (with its row relative to the start of the layout) and its value
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Var {
+    pub(crate) row: usize,
+    pub(crate) col: usize,
+    pub(crate) value: u64,
+}
+
+/// A 32-bit word decomposed by a `Sha256Word` gate, and its spread form
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Word {
+    pub(crate) word: Var,
+    pub(crate) spread: Var,
+}
+
+/// Lays out the gates and the witness of the SHA-256 gadget
+/// (and of the other gadgets built from the 32-bit word gates, such as Keccak).
+///
+/// The gates do not depend on the values assigned to the cells,
+/// so the layout computed with any inputs gives the gates of the gadget.
+pub(crate) struct Layout<F: PrimeField> {
+    start_row: usize,
+    pub(crate) gates: Vec<CircuitGate<F>>,
+    pub(crate) witness: [Vec<F>; COLUMNS],
+    // a generic gate whose second half is unused
+    free_generic: Option<usize>,
+}
+
+impl<F: PrimeField> Layout<F> {
+    pub(crate) fn new(start_row: usize) -> Self {
+        Layout {
+            start_row,
+            gates: vec![],
+            witness: array::from_fn(|_| vec![]),
+            free_generic: None,
+        }
+    }
+
+    // Appends a row to the layout, and returns its index
+    fn row(&mut self, typ: GateType, coeffs: Vec<F>) -> usize {
+        let row = self.gates.len();
+        self.gates.push(CircuitGate {
+            typ,
+            wires: Wire::new(self.start_row + row),
+            coeffs,
+        });
+        for col in &mut self.witness {
+            col.push(F::zero());
+        }
+        row
+    }
+
+    // Appends a gate followed by a zero gate, and returns the index of the gate row
+    fn double_row(&mut self, typ: GateType) -> usize {
+        let row = self.row(typ, vec![]);
+        self.row(GateType::Zero, vec![]);
+        row
+    }
+
+    fn set(&mut self, row: usize, col: usize, value: u64) -> Var {
+        self.witness[col][row] = F::from(value);
+        Var { row, col, value }
+    }
+
+    /// Copies `var` to the cell `(row, col)`
+    pub(crate) fn copy(&mut self, var: Var, row: usize, col: usize) -> Var {
+        self.gates.connect_cell_pair((var.row, var.col), (row, col));
+        self.set(row, col, var.value)
+    }
+
+    // Sets the nybbles of `x` and their spread forms, used by the lookups of a gate at `row`
+    fn nybbles(&mut self, row: usize, x: u32) {
+        for i in 0..NYBBLES {
+            let nybble = (x >> (4 * i)) & 0xf;
+            let (offset, col) = nybble_cell(i);
+            self.set(row + offset, col, nybble as u64);
+            let (offset, col) = spread_cell(i);
+            self.set(row + offset, col, spread(nybble));
+        }
+    }
+
+    /// A `Sha256Word` gate decomposing `value`
+    pub(crate) fn word_gate(&mut self, value: u64) -> (usize, Word) {
+        let row = self.double_row(GateType::Sha256Word);
+        let reduced = value as u32;
+        let word = self.set(row, 0, reduced as u64);
+        let spread = self.set(row, 1, spread(reduced));
+        self.set(row, 2, value);
+        self.set(row, 3, value >> 32);
+        self.nybbles(row, reduced);
+        (row, Word { word, spread })
+    }
+
+    /// Decomposes an input word
+    pub(crate) fn input(&mut self, value: u32) -> Word {
+        self.word_gate(value as u64).1
+    }
+
+    /// Reduces a sum of words modulo `2^32`
+    pub(crate) fn reduce(&mut self, sum: Var) -> Word {
+        let (row, word) = self.word_gate(sum.value);
+        self.copy(sum, row, 2);
+        word
+    }
+
+    /// Ends the generic gate whose second half is unused, if any,
+    /// so that the next generic gate starts on a new row
+    pub(crate) fn flush_generic(&mut self) {
+        self.free_generic = None;
+    }
+
+    /// Constrains `l * left + r * right + c = output` with half a generic gate,
+    /// and returns the output cell, holding `value`
+    pub(crate) fn linear(
+        &mut self,
+        (l, left): (F, Var),
+        (r, right): (F, Var),
+        c: F,
+        value: u64,
+    ) -> Var {
+        let (row, offset) = match self.free_generic.take() {
+            Some(row) => (row, 3),
+            None => {
+                let row = self.row(GateType::Generic, vec![F::zero(); 2 * GENERIC_COEFFS]);
+                self.free_generic = Some(row);
+                (row, 0)
+            }
+        };
+
+        let coeffs = &mut self.gates[row].coeffs;
+        let coeff_offset = offset / 3 * GENERIC_COEFFS;
+        coeffs[coeff_offset] = l;
+        coeffs[coeff_offset + 1] = r;
+        coeffs[coeff_offset + 2] = -F::one();
+        coeffs[coeff_offset + 4] = c;
+
+        self.copy(left, row, offset);
+        self.copy(right, row, offset + 1);
+        self.set(row, offset + 2, value)
+    }
+
+    /// Adds the words `terms` and the constant `constant` with generic gates,
+    /// and returns the sum (which is not reduced)
+    pub(crate) fn add(&mut self, terms: &[Var], constant: u64) -> Var {
+        let mut sum = terms[0];
+        for (i, term) in terms.iter().enumerate().skip(1) {
+            // the constant is added with the last term
+            let constant = if i == terms.len() - 1 { constant } else { 0 };
+            let value = sum.value + term.value + constant;
+            sum = self.linear((F::one(), sum), (F::one(), *term), F::from(constant), value);
+        }
+        sum
+    }
+
+    /// Decomposes the spread sum `sum` into its even and odd parts
+    pub(crate) fn decompose(&mut self, sum: Var) -> (Var, Var) {
+        let (cell, even, odd) = self.spread_decomposition(sum.value);
+        self.copy(sum, cell.row, cell.col);
+        (even, odd)
+    }
+
+    /// Decomposes the 64-bit value `value` into its even and odd parts,
+    /// and returns the cell holding `value` with the parts
+    pub(crate) fn spread_decomposition(&mut self, value: u64) -> (Var, Var, Var) {
+        let even = unspread(value);
+        let odd = unspread(value >> 1);
+
+        let low = self.double_row(GateType::Sha256Spread);
+        let high = self.double_row(GateType::Sha256Spread);
+        self.spread_part(low, even as u16, odd as u16);
+        let parts = self.spread_part(high, (even >> 16) as u16, (odd >> 16) as u16);
+
+        // the high parts are copied to the row after the low gate,
+        // and combined with zeros by the high gate
+        for part in parts {
+            self.copy(part, low + 1, part.col);
+            self.set(high, part.col + 3, part.value);
+        }
+
+        let cell = self.set(low, 3, value);
+        let even = self.set(low, 4, even as u64);
+        let odd = self.set(low, 5, odd as u64);
+        (cell, even, odd)
+    }
+
+    // Sets the cells of a `Sha256Spread` gate decomposing `spread(even) + 2 spread(odd)`,
+    // and returns its parts
+    fn spread_part(&mut self, row: usize, even: u16, odd: u16) -> [Var; 3] {
+        let parts = [
+            self.set(row, 0, spread(even as u32) + 2 * spread(odd as u32)),
+            self.set(row, 1, even as u64),
+            self.set(row, 2, odd as u64),
+        ];
+        for k in 0..4 {
+            let e = ((even as u32) >> (4 * k)) & 0xf;
+            let o = ((odd as u32) >> (4 * k)) & 0xf;
+            let ((r, c), (sr, sc)) = spread_gate_even_cells(k);
+            self.set(row + r, c, e as u64);
+            self.set(row + sr, sc, spread(e));
+            let ((r, c), (sr, sc)) = spread_gate_odd_cells(k);
+            self.set(row + r, c, o as u64);
+            self.set(row + sr, sc, spread(o));
+        }
+        parts
+    }
+
+    /// Computes a sigma function of the word `x`
+    pub(crate) fn sigma(&mut self, sigma: Sigma, x: Var) -> Var {
+        let row = self.double_row(sigma.gate_type());
+        let value = x.value as u32;
+        self.copy(x, row, 0);
+        let sum = sigma
+            .shifts()
+            .iter()
+            .map(|shift| spread(shift.apply(value)))
+            .sum();
+        let sum = self.set(row, 1, sum);
+        self.nybbles(row, value);
+        for (j, i) in sigma.split_nybbles().iter().enumerate() {
+            for l in 0..4 {
+                let (offset, col) = SIGMA_BIT_CELLS[4 * j + l];
+                self.set(row + offset, col, (value as u64 >> (4 * i + l)) & 1);
+            }
+        }
+        self.decompose(sum).0
+    }
+
+    /// Computes the choice function, returned as two words whose sum is the result
+    pub(crate) fn ch(&mut self, e: &Word, f: &Word, g: &Word) -> (Var, Var) {
+        let row = self.row(GateType::Sha256Ch, vec![]);
+        self.copy(e.spread, row, 0);
+        self.copy(f.spread, row, 1);
+        self.copy(g.spread, row, 2);
+        let p = self.set(row, 3, e.spread.value + f.spread.value);
+        let q = self.set(row, 4, SPREAD_ONES - e.spread.value + g.spread.value);
+        (self.decompose(p).1, self.decompose(q).1)
+    }
+
+    /// Computes the majority function
+    pub(crate) fn maj(&mut self, a: &Word, b: &Word, c: &Word) -> Var {
+        let row = self.row(GateType::Sha256Maj, vec![]);
+        self.copy(a.spread, row, 0);
+        self.copy(b.spread, row, 1);
+        self.copy(c.spread, row, 2);
+        let m = self.set(row, 3, a.spread.value + b.spread.value + c.spread.value);
+        self.decompose(m).1
+    }
+
+    /// Lays out the compression of `block` from `state`,
+    /// and returns the output state
+    pub(crate) fn compression(
+        &mut self,
+        state: [u32; STATE_WORDS],
+        block: [u32; BLOCK_WORDS],
+    ) -> [u32; STATE_WORDS] {
+        let state: Vec<Word> = state.iter().map(|x| self.input(*x)).collect();
+        let mut schedule: Vec<Word> = block.iter().map(|x| self.input(*x)).collect();
+
+        // message schedule
+        for t in BLOCK_WORDS..ROUND_CONSTANTS.len() {
+            let s0 = self.sigma(Sigma::Small0, schedule[t - 15].word);
+            let s1 = self.sigma(Sigma::Small1, schedule[t - 2].word);
+            let sum = self.add(&[s1, schedule[t - 7].word, s0, schedule[t - 16].word], 0);
+            let word = self.reduce(sum);
+            schedule.push(word);
+        }
+
+        // compression rounds
+        let mut vars = state.clone();
+        for (t, k) in ROUND_CONSTANTS.iter().enumerate() {
+            let [a, b, c, d, e, f, g, h] = [
+                vars[0], vars[1], vars[2], vars[3], vars[4], vars[5], vars[6], vars[7],
+            ];
+            let s1 = self.sigma(Sigma::Big1, e.word);
+            let (ch0, ch1) = self.ch(&e, &f, &g);
+            let t1 = self.add(&[h.word, s1, ch0, ch1, schedule[t].word], *k as u64);
+            let s0 = self.sigma(Sigma::Big0, a.word);
+            let maj = self.maj(&a, &b, &c);
+            let e_sum = self.add(&[d.word, t1], 0);
+            let a_sum = self.add(&[t1, s0, maj], 0);
+            let new_e = self.reduce(e_sum);
+            let new_a = self.reduce(a_sum);
+            vars = vec![new_a, a, b, c, new_e, e, f, g];
+        }
+
+        // output state, with the `Sha256Word` gates at the end of the gadget
+        let sums: Vec<Var> = state
+            .iter()
+            .zip(&vars)
+            .map(|(input, var)| self.add(&[input.word, var.word], 0))
+            .collect();
+        let output: Vec<Word> = sums.into_iter().map(|sum| self.reduce(sum)).collect();
+        array::from_fn(|i| output[i].word.value as u32)
+    }
+}
+
+// Returns the word made of the even bits of `x`
+fn unspread(x: u64) -> u32 {
+    (0..32).fold(0u32, |acc, i| acc | ((((x >> (2 * i)) & 1) as u32) << i))
+}
+
+/// Get the index of the selector polynomial of a SHA-256 gate type
+///
+/// # Panics
+///
+/// Will panic if `typ` is not a SHA-256 gate type.
+pub fn circuit_gate_selector_index(typ: GateType) -> usize {
+    circuit_gates()
+        .iter()
+        .position(|gate| *gate == typ)
+        .expect("invalid gate type")
+}
+
+/// Get array of SHA-256 circuit gate types
+pub fn circuit_gates() -> [GateType; GATE_COUNT] {
+    [
+        GateType::Sha256Word,
+        GateType::Sha256SmallSigma0,
+        GateType::Sha256SmallSigma1,
+        GateType::Sha256BigSigma0,
+        GateType::Sha256BigSigma1,
+        GateType::Sha256Ch,
+        GateType::Sha256Maj,
+        GateType::Sha256Spread,
+    ]
+}
+
+/// Number of constraints for a given SHA-256 circuit gate type
+///
+/// # Panics
+///
+/// Will panic if `typ` is not a SHA-256 gate type.
+pub fn circuit_gate_constraint_count<F: PrimeField>(typ: GateType) -> u32 {
+    match typ {
+        GateType::Sha256Word => Sha256Word::<F>::CONSTRAINTS,
+        GateType::Sha256SmallSigma0 => Sha256SmallSigma0::<F>::CONSTRAINTS,
+        GateType::Sha256SmallSigma1 => Sha256SmallSigma1::<F>::CONSTRAINTS,
+        GateType::Sha256BigSigma0 => Sha256BigSigma0::<F>::CONSTRAINTS,
+        GateType::Sha256BigSigma1 => Sha256BigSigma1::<F>::CONSTRAINTS,
+        GateType::Sha256Ch => Sha256Ch::<F>::CONSTRAINTS,
+        GateType::Sha256Maj => Sha256Maj::<F>::CONSTRAINTS,
+        GateType::Sha256Spread => Sha256Spread::<F>::CONSTRAINTS,
+        _ => panic!("invalid gate type"),
+    }
+}
+
+/// Get combined constraints for a given SHA-256 circuit gate type
+///
+/// # Panics
+///
+/// Will panic if `typ` is not a SHA-256 gate type.
+pub fn circuit_gate_constraints<F: PrimeField>(typ: GateType, alphas: &Alphas<F>) -> E<F> {
+    match typ {
+        GateType::Sha256Word => Sha256Word::combined_constraints(alphas),
+        GateType::Sha256SmallSigma0 => Sha256SmallSigma0::combined_constraints(alphas),
+        GateType::Sha256SmallSigma1 => Sha256SmallSigma1::combined_constraints(alphas),
+        GateType::Sha256BigSigma0 => Sha256BigSigma0::combined_constraints(alphas),
+        GateType::Sha256BigSigma1 => Sha256BigSigma1::combined_constraints(alphas),
+        GateType::Sha256Ch => Sha256Ch::combined_constraints(alphas),
+        GateType::Sha256Maj => Sha256Maj::combined_constraints(alphas),
+        GateType::Sha256Spread => Sha256Spread::combined_constraints(alphas),
+        _ => panic!("invalid gate type"),
+    }
+}
+
+/// Get the combined constraints for all SHA-256 circuit gate types
+pub fn combined_constraints<F: PrimeField>(alphas: &Alphas<F>) -> E<F> {
+    circuit_gates()
+        .iter()
+        .map(|typ| circuit_gate_constraints(*typ, alphas))
+        .reduce(|acc, constraints| acc + constraints)
+        .unwrap()
+}
+
+/// Get the SHA-256 spread lookup table
+pub fn lookup_table<F: FftField>() -> LookupTable<F> {
+    lookup::tables::get_table::<F>(GateLookupTable::Sha256Spread)
+}
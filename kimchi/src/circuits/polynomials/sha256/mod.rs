@@ -0,0 +1,6 @@
+//! SHA-256 gadget module
+
+pub mod circuitgates;
+pub mod gadget;
+#[cfg(feature = "prover")]
+pub mod witness;
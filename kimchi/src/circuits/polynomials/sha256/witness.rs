@@ -0,0 +1,125 @@
+//! SHA-256 witness computation
+
+use ark_ff::PrimeField;
+
+use crate::circuits::{
+    gate::CircuitGate, polynomial::COLUMNS, witness_generator::WitnessGenerator,
+};
+
+use super::{
+    circuitgates::Sigma,
+    gadget::{
+        block_input_row, state_input_row, state_output_row_from_end, Layout, BLOCK_WORDS,
+        ROUND_CONSTANTS, STATE_WORDS,
+    },
+};
+
+/// The initial state of SHA-256
+pub const INITIAL_STATE: [u32; STATE_WORDS] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// Pads `message` and splits it into the blocks compressed by SHA-256
+pub fn pad_message(message: &[u8]) -> Vec<[u32; BLOCK_WORDS]> {
+    let mut bytes = message.to_vec();
+    bytes.push(0x80);
+    while bytes.len() % 64 != 56 {
+        bytes.push(0);
+    }
+    bytes.extend(((message.len() as u64) * 8).to_be_bytes());
+
+    bytes
+        .chunks(64)
+        .map(|block| {
+            let mut words = [0; BLOCK_WORDS];
+            for (word, chunk) in words.iter_mut().zip(block.chunks(4)) {
+                *word = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+            }
+            words
+        })
+        .collect()
+}
+
+/// Computes the SHA-256 compression of `block` from `state`
+pub fn compress(state: [u32; STATE_WORDS], block: [u32; BLOCK_WORDS]) -> [u32; STATE_WORDS] {
+    let mut schedule = block.to_vec();
+    for t in BLOCK_WORDS..ROUND_CONSTANTS.len() {
+        schedule.push(
+            Sigma::Small1
+                .compute(schedule[t - 2])
+                .wrapping_add(schedule[t - 7])
+                .wrapping_add(Sigma::Small0.compute(schedule[t - 15]))
+                .wrapping_add(schedule[t - 16]),
+        );
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+    for (k, w) in ROUND_CONSTANTS.iter().zip(schedule) {
+        let ch = (e & f) ^ (!e & g);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let t1 = h
+            .wrapping_add(Sigma::Big1.compute(e))
+            .wrapping_add(ch)
+            .wrapping_add(*k)
+            .wrapping_add(w);
+        let t2 = Sigma::Big0.compute(a).wrapping_add(maj);
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(t1);
+        d = c;
+        c = b;
+        b = a;
+        a = t1.wrapping_add(t2);
+    }
+
+    let output = [a, b, c, d, e, f, g, h];
+    let mut state = state;
+    for (x, y) in state.iter_mut().zip(output) {
+        *x = x.wrapping_add(y);
+    }
+    state
+}
+
+/// Create the witness of the SHA-256 compression of `block` from `state`
+/// (see [`super::gadget`] for the layout of its inputs and output)
+pub fn create_compression_witness<F: PrimeField>(
+    state: [u32; STATE_WORDS],
+    block: [u32; BLOCK_WORDS],
+) -> [Vec<F>; COLUMNS] {
+    let mut layout = Layout::new(0);
+    layout.compression(state, block);
+    layout.witness
+}
+
+/// The SHA-256 compression function, as a [WitnessGenerator] whose input is the state and the block.
+/// Its input cells are the words of the state followed by those of the block,
+/// and its output cells the words of the output state (see [CircuitGate::create_sha256_compression]).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Sha256CompressionGadget;
+
+impl<F: PrimeField> WitnessGenerator<F> for Sha256CompressionGadget {
+    type Input = ([u32; STATE_WORDS], [u32; BLOCK_WORDS]);
+
+    fn gates(&self, start_row: usize) -> Vec<CircuitGate<F>> {
+        CircuitGate::create_sha256_compression(start_row).1
+    }
+
+    fn input_cells(&self) -> Vec<(usize, usize)> {
+        (0..STATE_WORDS)
+            .map(|i| (state_input_row(i), 0))
+            .chain((0..BLOCK_WORDS).map(|i| (block_input_row(i), 0)))
+            .collect()
+    }
+
+    fn output_cells(&self) -> Vec<(usize, usize)> {
+        let rows = WitnessGenerator::<F>::rows(self);
+        (0..STATE_WORDS)
+            .map(|i| (rows - state_output_row_from_end(i), 0))
+            .collect()
+    }
+
+    fn witness(&self, (state, block): &Self::Input) -> [Vec<F>; COLUMNS] {
+        create_compression_witness(*state, *block)
+    }
+}
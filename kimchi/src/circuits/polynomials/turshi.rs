@@ -245,6 +245,7 @@ impl<F: PrimeField> CircuitGate<F> {
             endo_coefficient: cs.endo,
             mds: &G::sponge_params().mds,
             foreign_field_modulus: None,
+            zk_rows: cs.zk_rows,
         };
 
         let pt = F::rand(rng);
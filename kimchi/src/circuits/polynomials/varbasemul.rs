@@ -13,12 +13,15 @@
 use crate::circuits::{
     argument::{Argument, ArgumentEnv, ArgumentType},
     expr::{constraints::ExprOps, Cache, Column, Variable},
-    gate::{CircuitGate, CurrOrNext, GateType},
-    wires::{GateWires, COLUMNS},
+    gate::{CircuitGate, Connect, CurrOrNext, GateType},
+    wires::{GateWires, Wire, COLUMNS, PERMUTS},
 };
+#[cfg(feature = "prover")]
+use crate::circuits::witness_generator::WitnessGenerator;
 use ark_ff::{FftField, PrimeField};
+use std::array;
 use std::marker::PhantomData;
-use CurrOrNext::{Curr, Next};
+use CurrOrNext::{Curr, Next, Prev};
 
 //~ We implement custom Plonk constraints for short Weierstrass curve variable base scalar multiplication.
 //~
@@ -126,6 +129,15 @@ use CurrOrNext::{Curr, Next};
 //~ * Binary decomposition:
 //~ `0 = n' - (b4 + 2 * (b3 + 2 * (b2 + 2 * (b1 + 2 * (b0 + 2*n)))))`
 //~
+//~ Since the bits `b0..b4` live in the first `PERMUTS` columns of the `ZERO` row,
+//~ they can be copy-constrained to cells holding a decomposition of the scalar
+//~ computed elsewhere in the circuit (for example by another `VBSM` sequence over
+//~ the same scalar, or by a bit decomposition gadget). This allows a single
+//~ decomposition to be reused across several scalar multiplications.
+//~
+
+/// Number of scalar bits consumed by a single `VarBaseMul` gate
+pub const BITS_PER_CHUNK: usize = 5;
 
 impl<F: PrimeField> CircuitGate<F> {
     pub fn create_vbmul(wires: &[GateWires; 2]) -> Vec<Self> {
@@ -143,6 +155,27 @@ impl<F: PrimeField> CircuitGate<F> {
         ]
     }
 
+    /// Create the gates of a variable base scalar multiplication by a scalar of `num_bits` bits.
+    ///     Inputs the starting row and the number of bits of the scalar
+    ///     Outputs tuple (`next_row`, `circuit_gates`) where
+    ///       `next_row`      - next row after this gate
+    ///       `circuit_gates` - vector of circuit gates comprising this gate
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `num_bits` is not a multiple of [`BITS_PER_CHUNK`].
+    pub fn create_vbmul_scalar(start_row: usize, num_bits: usize) -> (usize, Vec<Self>) {
+        assert_eq!(num_bits % BITS_PER_CHUNK, 0);
+        let circuit_gates: Vec<_> = (0..num_bits / BITS_PER_CHUNK)
+            .flat_map(|chunk| {
+                let row = start_row + 2 * chunk;
+                Self::create_vbmul(&[Wire::new(row), Wire::new(row + 1)])
+            })
+            .collect();
+
+        (start_row + circuit_gates.len(), circuit_gates)
+    }
+
     /// Verify the `GateType::VarBaseMul`(TODO)
     ///
     /// # Errors
@@ -303,6 +336,7 @@ where
         match self.row {
             Curr => env.witness_curr(column_to_index(self.col)),
             Next => env.witness_next(column_to_index(self.col)),
+            Prev => env.witness_prev(column_to_index(self.col)),
         }
     }
 }
@@ -353,6 +387,55 @@ const fn v(row: CurrOrNext, col: usize) -> Variable {
     }
 }
 
+/// Returns the cells `(row, col)` holding the scalar bits of a variable base
+/// scalar multiplication starting at `start_row`, most significant bit first
+/// (i.e. in the order expected by [`witness`]).
+pub fn bit_cells(start_row: usize, num_bits: usize) -> Vec<(usize, usize)> {
+    let layout = Layout::create();
+    (0..num_bits / BITS_PER_CHUNK)
+        .flat_map(|chunk| {
+            let row = start_row + 2 * chunk;
+            let cells: [(usize, usize); BITS_PER_CHUNK] = array::from_fn(|i| {
+                let bit = layout.bits[i];
+                match bit.col {
+                    Column::Witness(col) => (row + bit.row.shift(), col),
+                    _ => panic!("scalar bits are always stored in witness columns"),
+                }
+            });
+            cells
+        })
+        .collect()
+}
+
+/// Copy-constrain the scalar bits of the variable base scalar multiplication starting
+/// at `start_row` to the given cells `bits` (most significant bit first).
+///
+/// The cells in `bits` can hold a decomposition of the scalar produced anywhere else
+/// in the circuit, for instance the bits of another scalar multiplication by the same
+/// scalar (see [`bit_cells`]), so that the decomposition is only checked once.
+///
+/// Note: the bit cells of the scalar multiplication must not be connected to anything yet.
+///
+/// # Panics
+///
+/// Will panic if the length of `bits` is not a multiple of [`BITS_PER_CHUNK`],
+/// or if one of the cells is not in the first `PERMUTS` columns.
+pub fn connect_bits<F: PrimeField>(
+    gates: &mut Vec<CircuitGate<F>>,
+    start_row: usize,
+    bits: &[(usize, usize)],
+) {
+    assert_eq!(bits.len() % BITS_PER_CHUNK, 0);
+    for (&external, own) in bits.iter().zip(bit_cells(start_row, bits.len())) {
+        assert!(
+            external.1 < PERMUTS,
+            "a scalar bit can only be copied from the first {} columns",
+            PERMUTS
+        );
+        gates.connect_cell_pair(external, own);
+    }
+}
+
 pub struct VarbaseMulResult<F> {
     pub acc: (F, F),
     pub n: F,
@@ -372,7 +455,7 @@ pub fn witness<F: FftField + std::fmt::Display>(
 ) -> VarbaseMulResult<F> {
     let layout = Layout::create();
     let bits: Vec<_> = bits.iter().map(|b| F::from(u64::from(*b))).collect();
-    let bits_per_chunk = 5;
+    let bits_per_chunk = BITS_PER_CHUNK;
     assert_eq!(bits_per_chunk * (bits.len() / bits_per_chunk), bits.len());
 
     let mut acc = acc0;
@@ -402,6 +485,81 @@ pub fn witness<F: FftField + std::fmt::Display>(
     VarbaseMulResult { acc, n: n_acc }
 }
 
+/// The variable base scalar multiplication by a scalar of `num_bits` bits, as a [WitnessGenerator]
+/// whose input is the base, the bits of the scalar (most significant bit first)
+/// and the initial accumulator (see [`witness`]).
+/// Its input cells are the base, the bits (see [`bit_cells`]) and the initial accumulator,
+/// and its output cells the final accumulator and the scalar `n` recomposed from the bits.
+///
+/// The gates of the chunks are wired together: each chunk uses the base of the first one,
+/// and starts from the accumulator and the partial scalar of the previous one.
+/// The partial scalar of the first chunk is zero in the witness,
+/// but it is not constrained by the gadget.
+#[cfg(feature = "prover")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VarBaseMulGadget {
+    /// The number of bits of the scalar, a multiple of [`BITS_PER_CHUNK`]
+    pub num_bits: usize,
+}
+
+#[cfg(feature = "prover")]
+impl<F: PrimeField> WitnessGenerator<F> for VarBaseMulGadget {
+    type Input = ((F, F), Vec<bool>, (F, F));
+
+    fn gates(&self, start_row: usize) -> Vec<CircuitGate<F>> {
+        let (_, mut gates) = CircuitGate::create_vbmul_scalar(start_row, self.num_bits);
+        let layout = Layout::create();
+        let cell = |row: usize, var: Variable| match var.col {
+            Column::Witness(col) => (row + var.row.shift(), col),
+            _ => panic!("the variables of the gate are witness columns"),
+        };
+        for row in (2..gates.len()).step_by(2) {
+            let prev = row - 2;
+            let copies = [
+                (cell(0, layout.base.x), cell(row, layout.base.x)),
+                (cell(0, layout.base.y), cell(row, layout.base.y)),
+                (cell(prev, layout.accs[5].x), cell(row, layout.accs[0].x)),
+                (cell(prev, layout.accs[5].y), cell(row, layout.accs[0].y)),
+                (cell(prev, layout.n_next), cell(row, layout.n_prev)),
+            ];
+            for (cell_pre, cell_new) in copies {
+                gates.connect_cell_pair(cell_pre, cell_new);
+            }
+        }
+        gates
+    }
+
+    fn rows(&self) -> usize {
+        2 * (self.num_bits / BITS_PER_CHUNK)
+    }
+
+    fn input_cells(&self) -> Vec<(usize, usize)> {
+        [(0, 0), (0, 1)]
+            .into_iter()
+            .chain(bit_cells(0, self.num_bits))
+            .chain([(0, 2), (0, 3)])
+            .collect()
+    }
+
+    fn output_cells(&self) -> Vec<(usize, usize)> {
+        let rows = WitnessGenerator::<F>::rows(self);
+        vec![(rows - 1, 0), (rows - 1, 1), (rows - 2, 5)]
+    }
+
+    fn witness(&self, (base, bits, acc0): &((F, F), Vec<bool>, (F, F))) -> [Vec<F>; COLUMNS] {
+        assert_eq!(
+            bits.len(),
+            self.num_bits,
+            "the scalar is not of {} bits",
+            self.num_bits
+        );
+        let rows = WitnessGenerator::<F>::rows(self);
+        let mut w = array::from_fn(|_| vec![F::zero(); rows]);
+        witness(&mut w, 0, *base, bits, *acc0);
+        w
+    }
+}
+
 /// Implementation of the `VarbaseMul` gate
 pub struct VarbaseMul<F>(PhantomData<F>);
 
@@ -0,0 +1,120 @@
+//! This module implements a Poseidon sponge in circuits written with a [CircuitBuilder],
+//! as [CircuitSponge].
+//!
+//! The sponge absorbs and squeezes variables of the circuit, and chains the Poseidon gadgets
+//! ([PoseidonGadget]) permuting its state, so that a vector of any length can be hashed
+//! without wiring the permutations by hand.
+//! With a rate of 2 and no domain separator, it computes the same values as the sponge
+//! [ArithmeticSponge](oracle::poseidon::ArithmeticSponge) used outside of circuits.
+
+use crate::circuits::{
+    builder::{CircuitBuilder, Var},
+    polynomials::poseidon::{PoseidonGadget, SPONGE_WIDTH},
+};
+use ark_ff::{Field, PrimeField, SquareRootField};
+use oracle::poseidon::{ArithmeticSpongeParams, SpongeState};
+
+/// The largest rate of a [CircuitSponge], which keeps one element of its state as capacity
+pub const MAX_SPONGE_RATE: usize = SPONGE_WIDTH - 1;
+
+/// A Poseidon sponge over the variables of a circuit (see the [module](self) documentation)
+pub struct CircuitSponge<F: Field + 'static> {
+    params: &'static ArithmeticSpongeParams<F>,
+    /// The number of elements of the state absorbed or squeezed between two permutations
+    rate: usize,
+    state: [Var; SPONGE_WIDTH],
+    sponge_state: SpongeState,
+}
+
+impl<F: PrimeField + SquareRootField> CircuitSponge<F> {
+    /// Creates a sponge with the parameters `params`, absorbing and squeezing `rate` elements
+    /// between two permutations, from the zero state.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `rate` is not between 1 and [MAX_SPONGE_RATE].
+    pub fn new(
+        builder: &mut CircuitBuilder<F>,
+        params: &'static ArithmeticSpongeParams<F>,
+        rate: usize,
+    ) -> Self {
+        Self::with_domain_separator(builder, params, rate, F::zero())
+    }
+
+    /// Same as [CircuitSponge::new], with the last element of the state (in its capacity)
+    /// initialized to `domain`, so that the sponges of different domains give unrelated outputs.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `rate` is not between 1 and [MAX_SPONGE_RATE].
+    pub fn with_domain_separator(
+        builder: &mut CircuitBuilder<F>,
+        params: &'static ArithmeticSpongeParams<F>,
+        rate: usize,
+        domain: F,
+    ) -> Self {
+        assert!(
+            (1..=MAX_SPONGE_RATE).contains(&rate),
+            "the rate of the sponge must be between 1 and {MAX_SPONGE_RATE}"
+        );
+        let zero = builder.constant(F::zero());
+        let mut state = [zero; SPONGE_WIDTH];
+        state[SPONGE_WIDTH - 1] = builder.constant(domain);
+        CircuitSponge {
+            params,
+            rate,
+            state,
+            sponge_state: SpongeState::Absorbed(0),
+        }
+    }
+
+    /// Lays out a Poseidon gadget permuting the state
+    fn permute(&mut self, builder: &mut CircuitBuilder<F>) {
+        let state = self.state;
+        let output = builder.gadget(
+            PoseidonGadget {
+                params: self.params,
+            },
+            &state,
+            move |v| state.map(|var| v.get(var)),
+        );
+        self.state = output
+            .try_into()
+            .expect("the Poseidon gadget outputs its state");
+    }
+
+    /// Absorbs the variables `xs`, permuting the state each time `rate` elements were absorbed
+    pub fn absorb(&mut self, builder: &mut CircuitBuilder<F>, xs: &[Var]) {
+        for x in xs {
+            let pos = match self.sponge_state {
+                SpongeState::Absorbed(n) if n < self.rate => n,
+                SpongeState::Absorbed(_) => {
+                    self.permute(builder);
+                    0
+                }
+                SpongeState::Squeezed(_) => 0,
+            };
+            self.state[pos] = builder.add(self.state[pos], *x);
+            self.sponge_state = SpongeState::Absorbed(pos + 1);
+        }
+    }
+
+    /// Squeezes a variable, permuting the state first if anything was absorbed since the last
+    /// permutation or if `rate` elements were squeezed since
+    pub fn squeeze(&mut self, builder: &mut CircuitBuilder<F>) -> Var {
+        let pos = match self.sponge_state {
+            SpongeState::Squeezed(n) if n < self.rate => n,
+            SpongeState::Squeezed(_) | SpongeState::Absorbed(_) => {
+                self.permute(builder);
+                0
+            }
+        };
+        self.sponge_state = SpongeState::Squeezed(pos + 1);
+        self.state[pos]
+    }
+
+    /// Squeezes `n` variables, see [CircuitSponge::squeeze]
+    pub fn squeeze_many(&mut self, builder: &mut CircuitBuilder<F>, n: usize) -> Vec<Var> {
+        (0..n).map(|_| self.squeeze(builder)).collect()
+    }
+}
@@ -0,0 +1,117 @@
+//! This module implements a comparison of two witnesses of the same circuit,
+//! which helps to find where two implementations of a witness generator diverge
+//! (for example when porting one from OCaml to Rust, or from native code to wasm).
+//!
+//! The differing cells are grouped by the gate of their row, in the order of the rows,
+//! so that the first group points at the first gate whose witness diverges.
+
+use crate::circuits::{
+    gate::{CircuitGate, GateType},
+    wires::COLUMNS,
+};
+use ark_ff::PrimeField;
+use std::fmt;
+
+/// A cell on which two witnesses differ
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CellDiff<F> {
+    /// The column of the cell
+    pub col: usize,
+    /// The value in the first witness, if its column is long enough
+    pub left: Option<F>,
+    /// The value in the second witness, if its column is long enough
+    pub right: Option<F>,
+}
+
+/// The differing cells of a row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowDiff<F> {
+    /// The row
+    pub row: usize,
+    /// The type of the gate of the row, if the row is not past the gates
+    pub typ: Option<GateType>,
+    /// The differing cells of the row, by column
+    pub cells: Vec<CellDiff<F>>,
+}
+
+/// The differences between two witnesses of a circuit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WitnessDiff<F> {
+    /// The rows with differing cells, in order
+    pub rows: Vec<RowDiff<F>>,
+}
+
+impl<F: PrimeField> WitnessDiff<F> {
+    /// Compares the witnesses `left` and `right` of the circuit made of `gates`.
+    /// A cell present in a single witness counts as a difference.
+    pub fn new(
+        gates: &[CircuitGate<F>],
+        left: &[Vec<F>; COLUMNS],
+        right: &[Vec<F>; COLUMNS],
+    ) -> Self {
+        let rows = left.iter().chain(right).map(Vec::len).max().unwrap_or(0);
+
+        let rows = (0..rows)
+            .filter_map(|row| {
+                let cells: Vec<_> = (0..COLUMNS)
+                    .filter_map(|col| {
+                        let (left, right) = (left[col].get(row), right[col].get(row));
+                        (left != right).then_some(CellDiff {
+                            col,
+                            left: left.copied(),
+                            right: right.copied(),
+                        })
+                    })
+                    .collect();
+                (!cells.is_empty()).then_some(RowDiff {
+                    row,
+                    typ: gates.get(row).map(|gate| gate.typ),
+                    cells,
+                })
+            })
+            .collect();
+
+        WitnessDiff { rows }
+    }
+
+    /// Whether the witnesses are identical
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// The first row on which the witnesses diverge, if any
+    pub fn first_divergence(&self) -> Option<&RowDiff<F>> {
+        self.rows.first()
+    }
+
+    /// The number of differing cells
+    pub fn cells(&self) -> usize {
+        self.rows.iter().map(|row| row.cells.len()).sum()
+    }
+}
+
+impl<F: PrimeField> fmt::Display for WitnessDiff<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = |value: &Option<F>| value.map_or_else(|| "-".to_string(), |v| v.to_string());
+
+        if self.is_empty() {
+            return writeln!(f, "the witnesses are identical");
+        }
+        writeln!(
+            f,
+            "the witnesses differ on {} cells in {} rows",
+            self.cells(),
+            self.rows.len()
+        )?;
+        for RowDiff { row, typ, cells } in &self.rows {
+            match typ {
+                Some(typ) => writeln!(f, "row {row} ({typ:?}):")?,
+                None => writeln!(f, "row {row} (past the gates):")?,
+            }
+            for CellDiff { col, left, right } in cells {
+                writeln!(f, "  column {col}: {} != {}", value(left), value(right))?;
+            }
+        }
+        Ok(())
+    }
+}
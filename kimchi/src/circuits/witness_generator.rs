@@ -0,0 +1,212 @@
+//! This module implements the [WitnessGenerator] trait, which describes the gadgets
+//! made of built-in gates uniformly: the gates they lay out, the cells of their inputs and outputs,
+//! and how their witness is generated.
+//!
+//! Each built-in gadget implements it next to its witness computation:
+//!
+//! - [MuxGadget](super::polynomials::select::witness::MuxGadget), for conditional selects and multiplexers,
+//! - [RangeCheckGadget](super::polynomials::range_check::witness::RangeCheckGadget) and
+//!   [MultiRangeCheckGadget](super::polynomials::range_check::witness::MultiRangeCheckGadget),
+//! - [ForeignFieldAddGadget](super::polynomials::foreign_field_add::witness::ForeignFieldAddGadget),
+//! - [PoseidonGadget](super::polynomials::poseidon::PoseidonGadget),
+//! - [Keccak256Gadget](super::polynomials::keccak::witness::Keccak256Gadget),
+//! - [Sha256CompressionGadget](super::polynomials::sha256::witness::Sha256CompressionGadget),
+//! - [GenericGadget](super::polynomials::generic::GenericGadget),
+//! - [CompleteAddGadget](super::polynomials::complete_add::CompleteAddGadget),
+//! - [VarBaseMulGadget](super::polynomials::varbasemul::VarBaseMulGadget),
+//! - [EndoMulGadget](super::polynomials::endosclmul::EndoMulGadget) and
+//!   [EndoMulScalarGadget](super::polynomials::endomul_scalar::EndoMulScalarGadget),
+//! - [ChaCha20Gadget](super::polynomials::chacha::ChaCha20Gadget),
+//! - [BitwiseGadget](super::polynomials::bitwise::gadget::BitwiseGadget).
+//!
+//! A gadget can be laid out in a circuit with
+//! [CircuitBuilder::gadget](super::builder::CircuitBuilder::gadget),
+//! or directly with its gates and witness.
+//! In the latter case, the witness of the whole circuit is assembled from the witness of its
+//! gadgets with [WitnessGenerator::append_witness], which checks that they match the gates
+//! of the circuit, or with the [WitnessBuilder] on top of it.
+
+use crate::{
+    circuits::{gate::CircuitGate, wires::COLUMNS},
+    error::WitnessBuilderError,
+};
+use ark_ff::PrimeField;
+use std::array;
+
+/// A gadget of built-in gates, with the layout of its inputs and outputs and its witness generation
+pub trait WitnessGenerator<F: PrimeField> {
+    /// The values from which the witness of the gadget is generated
+    type Input;
+
+    /// The gates of the gadget, laid out from the row `start_row`,
+    /// with the wiring between the cells of the gadget
+    fn gates(&self, start_row: usize) -> Vec<CircuitGate<F>>;
+
+    /// The number of rows of the gadget
+    fn rows(&self) -> usize {
+        self.gates(0).len()
+    }
+
+    /// The cells `(row, column)`, with the rows relative to the start of the gadget,
+    /// holding the inputs of the gadget, in order.
+    /// To constrain the inputs, these cells must be wired to the cells they are copied from.
+    fn input_cells(&self) -> Vec<(usize, usize)>;
+
+    /// The cells `(row, column)`, with the rows relative to the start of the gadget,
+    /// holding the outputs of the gadget, in order
+    fn output_cells(&self) -> Vec<(usize, usize)>;
+
+    /// Generates the witness of the rows of the gadget from `input`,
+    /// with [WitnessGenerator::rows] rows
+    fn witness(&self, input: &Self::Input) -> [Vec<F>; COLUMNS];
+
+    /// Appends the witness of the gadget generated from `input` to the `witness` of the circuit
+    /// made of `circuit_gates`, after the rows it already covers, and returns the row
+    /// at which the gadget starts.
+    ///
+    /// The gadget is checked to lay out the same gates (with the same coefficients)
+    /// as the circuit at its rows. The wiring is not checked, as the gadgets are wired
+    /// together by the circuit.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the gates of the gadget are not those of the circuit at its rows,
+    /// or if its witness does not have as many rows as its gates.
+    fn append_witness(
+        &self,
+        circuit_gates: &[CircuitGate<F>],
+        witness: &mut [Vec<F>; COLUMNS],
+        input: &Self::Input,
+    ) -> Result<usize, WitnessBuilderError> {
+        let row = witness[0].len();
+        let gates = self.gates(row);
+        check_gates(circuit_gates, row, &gates)?;
+
+        let gadget_witness = self.witness(input);
+        if let Some(column) = gadget_witness
+            .iter()
+            .find(|column| column.len() != gates.len())
+        {
+            return Err(WitnessBuilderError::WitnessRows(column.len(), gates.len()));
+        }
+        for (column, values) in witness.iter_mut().zip(gadget_witness) {
+            column.extend(values);
+        }
+        Ok(row)
+    }
+}
+
+/// Checks that the circuit made of `circuit_gates` has `rows` more rows from `row`
+fn check_rows<F: PrimeField>(
+    circuit_gates: &[CircuitGate<F>],
+    row: usize,
+    rows: usize,
+) -> Result<(), WitnessBuilderError> {
+    if row + rows > circuit_gates.len() {
+        return Err(WitnessBuilderError::TooManyRows {
+            row,
+            rows,
+            gates: circuit_gates.len(),
+        });
+    }
+    Ok(())
+}
+
+/// Checks that `gates`, laid out from `row`, are the gates of the circuit at these rows
+fn check_gates<F: PrimeField>(
+    circuit_gates: &[CircuitGate<F>],
+    row: usize,
+    gates: &[CircuitGate<F>],
+) -> Result<(), WitnessBuilderError> {
+    check_rows(circuit_gates, row, gates.len())?;
+    for (i, (expected, actual)) in gates.iter().zip(&circuit_gates[row..]).enumerate() {
+        if expected.typ != actual.typ {
+            return Err(WitnessBuilderError::GateType {
+                row: row + i,
+                expected: expected.typ,
+                actual: actual.typ,
+            });
+        }
+        if expected.coeffs != actual.coeffs {
+            return Err(WitnessBuilderError::Coefficients(actual.typ, row + i));
+        }
+    }
+    Ok(())
+}
+
+/// Assembles the witness of a circuit made of gadgets laid out one after the other,
+/// from the witness of each gadget.
+///
+/// The gates of the circuit are given upfront, and each gadget is appended to the witness
+/// with [WitnessGenerator::append_witness]. Rows of gates laid out without a gadget
+/// can be appended one by one.
+pub struct WitnessBuilder<'a, F: PrimeField> {
+    gates: &'a [CircuitGate<F>],
+    witness: [Vec<F>; COLUMNS],
+}
+
+impl<'a, F: PrimeField> WitnessBuilder<'a, F> {
+    /// Starts the witness of the circuit made of `gates`, from its first row
+    pub fn new(gates: &'a [CircuitGate<F>]) -> Self {
+        WitnessBuilder {
+            gates,
+            witness: array::from_fn(|_| Vec::with_capacity(gates.len())),
+        }
+    }
+
+    /// The row at which the next gadget is laid out
+    pub fn next_row(&self) -> usize {
+        self.witness[0].len()
+    }
+
+    /// The value of the cell `(row, col)` of a gadget already appended,
+    /// for example to compute the input of the next gadget from the output of a previous one
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the row is not covered by the witness yet.
+    pub fn cell(&self, row: usize, col: usize) -> F {
+        self.witness[col][row]
+    }
+
+    /// Appends the witness of `gadget` generated from `input`, returning the row at which it starts
+    ///
+    /// # Errors
+    ///
+    /// See [WitnessGenerator::append_witness].
+    pub fn gadget<G: WitnessGenerator<F>>(
+        &mut self,
+        gadget: &G,
+        input: &G::Input,
+    ) -> Result<usize, WitnessBuilderError> {
+        gadget.append_witness(self.gates, &mut self.witness, input)
+    }
+
+    /// Appends a row of `values`, for a gate of the circuit laid out without a gadget,
+    /// returning the row
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the circuit has no more rows.
+    pub fn row(&mut self, values: [F; COLUMNS]) -> Result<usize, WitnessBuilderError> {
+        let row = self.next_row();
+        check_rows(self.gates, row, 1)?;
+        for (column, value) in self.witness.iter_mut().zip(values) {
+            column.push(value);
+        }
+        Ok(row)
+    }
+
+    /// Returns the witness, which must cover all the rows of the circuit
+    ///
+    /// # Errors
+    ///
+    /// Will give error if some rows of the circuit have no witness.
+    pub fn build(self) -> Result<[Vec<F>; COLUMNS], WitnessBuilderError> {
+        let rows = self.next_row();
+        if rows != self.gates.len() {
+            return Err(WitnessBuilderError::Incomplete(rows, self.gates.len()));
+        }
+        Ok(self.witness)
+    }
+}
@@ -0,0 +1,203 @@
+//! This module implements a proof that committed columns are identical,
+//! without having to reveal them or to commit to them again.
+//!
+//! Two polynomials of degree less than the size of the SRS that agree on a random point are
+//! equal with overwhelming probability. So, given pairs of committed polynomials:
+//!
+//! 1. all the commitments are absorbed in the sponge, and an evaluation point $\zeta$ is squeezed,
+//! 2. the prover gives, for each pair, the evaluations (of the chunks) of its polynomials at $\zeta$,
+//!    which are absorbed in the sponge, and from which the polynomial scaling factor is squeezed,
+//! 3. the evaluations of each pair are opened against both of its commitments,
+//!    all in a single batched opening proof.
+//!
+//! The sponge is given by the caller, so that the proof can be bound to a larger transcript,
+//! for example to the proofs sharing a table whose commitments are compared.
+
+use crate::{curve::KimchiCurve, error::ColumnEqualityError};
+use ark_ff::{One, PrimeField};
+use ark_poly::univariate::DensePolynomial;
+use commitment_dlog::{
+    commitment::{BatchEvaluationProof, BlindedCommitment, Evaluation, PolyComm},
+    evaluation_proof::OpeningProof,
+    srs::SRS,
+};
+use o1_utils::ExtendedDensePolynomial as _;
+use oracle::{sponge::ScalarChallenge, FqSponge};
+use rand::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+
+/// A committed column, together with the randomness of its commitment
+pub struct CommittedColumn<'a, G: KimchiCurve> {
+    /// The polynomial of the column
+    pub poly: &'a DensePolynomial<G::ScalarField>,
+    /// The commitment to the polynomial, and its blinders
+    pub commitment: &'a BlindedCommitment<G>,
+}
+
+/// A proof that pairs of committed columns are identical
+#[serde_as]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound = "G: ark_serialize::CanonicalDeserialize + ark_serialize::CanonicalSerialize")]
+pub struct ColumnEqualityProof<G: KimchiCurve> {
+    /// For each pair, the evaluations of the chunks of both columns at the evaluation point
+    #[serde_as(as = "Vec<Vec<o1_utils::serialization::SerdeAs>>")]
+    pub evals: Vec<Vec<G::ScalarField>>,
+    /// The opening proof of the evaluations against all the commitments
+    pub opening: OpeningProof<G>,
+}
+
+/// The challenges of the proof
+struct Challenges<F> {
+    zeta: F,
+    polyscale: F,
+}
+
+impl<F: PrimeField> Challenges<F> {
+    /// Absorbs the commitments and the evaluations in `sponge` to derive the challenges
+    fn derive<G, EFqSponge>(
+        sponge: &mut EFqSponge,
+        commitments: &[(&PolyComm<G>, &PolyComm<G>)],
+        evals: impl FnOnce(F) -> Vec<Vec<F>>,
+    ) -> (Self, Vec<Vec<F>>)
+    where
+        G: KimchiCurve<ScalarField = F>,
+        EFqSponge: FqSponge<G::BaseField, G, F>,
+    {
+        let (_, endo_r) = G::endos();
+
+        for (left, right) in commitments {
+            sponge.absorb_g(&left.unshifted);
+            sponge.absorb_g(&right.unshifted);
+        }
+        let zeta = ScalarChallenge(sponge.challenge()).to_field(endo_r);
+
+        let evals = evals(zeta);
+        for chunks in &evals {
+            sponge.absorb_fr(chunks);
+        }
+        let polyscale = ScalarChallenge(sponge.challenge()).to_field(endo_r);
+
+        (Challenges { zeta, polyscale }, evals)
+    }
+}
+
+impl<G: KimchiCurve> ColumnEqualityProof<G>
+where
+    G::BaseField: PrimeField,
+{
+    /// Proves that the columns of each of the pairs `columns` are identical,
+    /// continuing the transcript of `sponge`.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the columns of a pair are different.
+    pub fn create<EFqSponge, RNG>(
+        srs: &SRS<G>,
+        group_map: &G::Map,
+        mut sponge: EFqSponge,
+        columns: &[(CommittedColumn<G>, CommittedColumn<G>)],
+        rng: &mut RNG,
+    ) -> Result<Self, ColumnEqualityError>
+    where
+        EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+        RNG: RngCore + CryptoRng,
+    {
+        if let Some(pair) = columns
+            .iter()
+            .position(|(left, right)| left.poly != right.poly)
+        {
+            return Err(ColumnEqualityError::DifferentColumns(pair));
+        }
+
+        let commitments: Vec<_> = columns
+            .iter()
+            .map(|(left, right)| (&left.commitment.commitment, &right.commitment.commitment))
+            .collect();
+        let (challenges, evals) = Challenges::derive(&mut sponge, &commitments, |zeta| {
+            columns
+                .iter()
+                .map(|(left, _)| {
+                    left.poly
+                        .to_chunked_polynomial(srs.g.len())
+                        .evaluate_chunks(zeta)
+                })
+                .collect()
+        });
+
+        let polys: Vec<_> = columns
+            .iter()
+            .flat_map(|(left, right)| [left, right])
+            .map(|column| (column.poly, None, column.commitment.blinders.clone()))
+            .collect();
+        let opening = srs.open(
+            group_map,
+            &polys,
+            &[challenges.zeta],
+            challenges.polyscale,
+            G::ScalarField::one(),
+            sponge,
+            rng,
+        );
+
+        Ok(ColumnEqualityProof { evals, opening })
+    }
+
+    /// Verifies that the columns committed to in each of the pairs `commitments` are identical,
+    /// continuing the transcript of `sponge`.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the proof is malformed, or if its opening proof does not verify.
+    pub fn verify<EFqSponge, RNG>(
+        &self,
+        srs: &SRS<G>,
+        group_map: &G::Map,
+        mut sponge: EFqSponge,
+        commitments: &[(&PolyComm<G>, &PolyComm<G>)],
+        rng: &mut RNG,
+    ) -> Result<(), ColumnEqualityError>
+    where
+        EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+        RNG: RngCore + CryptoRng,
+    {
+        if self.evals.len() != commitments.len() {
+            return Err(ColumnEqualityError::MalformedProof);
+        }
+        for ((left, right), chunks) in commitments.iter().zip(&self.evals) {
+            if left.unshifted.len() != chunks.len() || right.unshifted.len() != chunks.len() {
+                return Err(ColumnEqualityError::MalformedProof);
+            }
+        }
+
+        let (challenges, evals) =
+            Challenges::derive(&mut sponge, commitments, |_| self.evals.clone());
+
+        // the same evaluations are opened against both commitments of a pair
+        let evaluations = commitments
+            .iter()
+            .zip(evals)
+            .flat_map(|((left, right), chunks)| {
+                [left, right].map(|commitment| Evaluation {
+                    commitment: (*commitment).clone(),
+                    evaluations: vec![chunks.clone()],
+                    degree_bound: None,
+                })
+            })
+            .collect();
+        let mut batch = [BatchEvaluationProof {
+            sponge,
+            evaluations,
+            evaluation_points: vec![challenges.zeta],
+            polyscale: challenges.polyscale,
+            evalscale: G::ScalarField::one(),
+            opening: &self.opening,
+        }];
+
+        if srs.verify(group_map, &mut batch, rng) {
+            Ok(())
+        } else {
+            Err(ColumnEqualityError::OpenProof)
+        }
+    }
+}
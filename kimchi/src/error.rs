@@ -1,5 +1,6 @@
 //! This module implements the [`ProverError`] type.
 
+use crate::circuits::{custom_gate::CUSTOM_GATE_SLOTS, gate::GateType};
 use commitment_dlog::error::CommitmentError;
 use thiserror::Error;
 
@@ -13,6 +14,15 @@ pub enum ProverError {
     #[error("the witness columns are not all the same size")]
     WitnessCsInconsistent,
 
+    #[error("the witness has no column {0}")]
+    InvalidWitnessColumn(usize),
+
+    #[error("the witness column {0} was handed over more than once")]
+    DuplicateWitnessColumn(usize),
+
+    #[error("the witness column {0} was not handed over")]
+    MissingWitnessColumn(usize),
+
     #[error("the proof could not be constructed: {0}")]
     Prover(&'static str),
 
@@ -30,6 +40,9 @@ pub enum ProverError {
 
     #[error("wrong number of custom blinders given: {0}")]
     WrongBlinders(CommitmentError),
+
+    #[error("the public output is of an unexpected size (expected {0})")]
+    IncorrectPublicOutputLength(usize),
 }
 
 /// Errors that can arise when verifying a proof
@@ -56,6 +69,12 @@ pub enum VerifyError {
     #[error("lookup used in circuit, but proof has inconsistent number of lookup evaluations and commitments")]
     ProofInconsistentLookup,
 
+    #[error("lookup not used in circuit, but proof has lookup commitments or evaluations")]
+    UnexpectedLookup,
+
+    #[error("the proof has evaluations on the previous row if and only if the circuit reads it")]
+    IncorrectPrevRowEvaluations,
+
     #[error("cannot batch proofs using different SRSes")]
     DifferentSRS,
 
@@ -64,6 +83,96 @@ pub enum VerifyError {
 
     #[error("runtime tables are used, but missing from the proof")]
     IncorrectRuntimeProof,
+
+    #[error("the compressed proof could not be decoded")]
+    MalformedCompressedProof,
+
+    #[error("the verifier index has more public outputs ({0}) than public values")]
+    IncorrectPublicOutputLength(usize),
+}
+
+/// Errors that can arise when proving or verifying the equality of committed columns
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnEqualityError {
+    #[error("the columns of pair {0} are different")]
+    DifferentColumns(usize),
+
+    #[error("the number of evaluations does not match the commitments")]
+    MalformedProof,
+
+    #[error("the opening proof failed to verify")]
+    OpenProof,
+}
+
+/// Errors that can arise when building a circuit with a [crate::circuits::builder::CircuitBuilder]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum CircuitBuilderError {
+    #[error("the name {0} is already used")]
+    DuplicateName(String),
+
+    #[error("the input {0} is not given")]
+    MissingInput(String),
+
+    #[error("the circuit has no input {0}")]
+    UnknownInput(String),
+
+    #[error("the variable {0} is asserted equal to {1}, but their values differ")]
+    NotEqual(String, String),
+
+    #[error("the variable {0} is used in several cells, including one in the unwired column {1}")]
+    UnwiredCell(String, usize),
+
+    #[error("the index {0} is not in the lookup table selected by {1}")]
+    MissingEntry(String, String),
+}
+
+/// Errors that can arise when assembling a witness with a
+/// [WitnessBuilder](crate::circuits::witness_generator::WitnessBuilder)
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum WitnessBuilderError {
+    #[error("the {rows} rows from row {row} do not fit in the {gates} gates of the circuit")]
+    TooManyRows {
+        row: usize,
+        rows: usize,
+        gates: usize,
+    },
+
+    #[error(
+        "the gate of row {row} is a {actual:?} gate, but a {expected:?} gate is laid out there"
+    )]
+    GateType {
+        row: usize,
+        expected: GateType,
+        actual: GateType,
+    },
+
+    #[error("the {0:?} gate of row {1} does not have the coefficients of the gate laid out there")]
+    Coefficients(GateType, usize),
+
+    #[error("the witness of the gadget has {0} rows, but the gadget has {1}")]
+    WitnessRows(usize, usize),
+
+    #[error("the witness covers {0} of the {1} rows of the circuit")]
+    Incomplete(usize, usize),
+}
+
+/// Errors that can arise when encoding or decoding a proof with its metadata
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ProofMetadataError {
+    #[error("the encoding is truncated")]
+    Truncated,
+
+    #[error("the encoding has unexpected trailing bytes")]
+    TrailingBytes,
+
+    #[error("the version {0} of the encoding is not supported")]
+    UnsupportedVersion(u8),
+
+    #[error("the hash of the encoding does not match its content")]
+    Hash,
+
+    #[error("the proof or its metadata could not be encoded or decoded: {0}")]
+    Encoding(String),
 }
 
 /// Errors that can arise when preparing the setup
@@ -74,6 +183,63 @@ pub enum SetupError {
 
     #[error("the domain could not be constructed: {0}")]
     DomainCreation(&'static str),
+
+    #[error("unsupported number of zero-knowledge rows: {0}")]
+    ZkRows(u64),
+
+    #[error("the circuit reads the previous row, which requires at least {min} zero-knowledge rows, but {zk_rows} were set up")]
+    PrevRowZkRows { zk_rows: u64, min: u64 },
+
+    #[error("{output} public outputs were set up, but there are only {public} public values")]
+    PublicOutput { output: usize, public: usize },
+
+    #[error("invalid custom gate: {0}")]
+    CustomGate(#[from] CustomGateError),
+
+    #[error("the boolean column {0} is not a witness column")]
+    BooleanColumn(usize),
+}
+
+/// Errors that can arise when registering custom gates
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum CustomGateError {
+    #[error("{0} custom gates were registered, but at most {max} are supported", max = CUSTOM_GATE_SLOTS)]
+    TooManyGates(usize),
+
+    #[error("the custom gate {name} has {count} constraints, but at most {max} are supported")]
+    TooManyConstraints { name: String, count: u32, max: u32 },
+
+    #[error("the custom gate {name} declares {declared} constraints, but has {actual}")]
+    ConstraintCount {
+        name: String,
+        declared: u32,
+        actual: usize,
+    },
+
+    #[error("the custom gate {0} has the unsupported degree {1}")]
+    UnsupportedDegree(String, u64),
+
+    #[error("the custom gate {name} declares degree {declared}, but its constraints have degree {actual}")]
+    Degree {
+        name: String,
+        declared: u64,
+        actual: u64,
+    },
+
+    #[error("the constraints of the custom gate {0} are not linear in the columns that are not evaluated, like the coefficients")]
+    Linearization(String),
+
+    #[error("the custom gate {0} uses the unsupported number of rows {1}")]
+    UnsupportedRows(String, usize),
+
+    #[error("the gate type {0:?} of row {1} is not bound to a registered custom gate")]
+    Unregistered(GateType, usize),
+
+    #[error("the custom gate {0} of row {1} uses the next row, which is not a zero gate")]
+    NextRow(String, usize),
+
+    #[error("the custom gate {0} of row {1} reads the previous row, but there is none")]
+    PrevRow(String, usize),
 }
 
 /// Errors that can arise when creating a verifier index
@@ -81,4 +247,7 @@ pub enum SetupError {
 pub enum VerifierIndexError {
     #[error("srs has already been set")]
     SRSHasBeenSet,
+
+    #[error("unsupported version of the serialized verifier index: {0} (supported: {min} to {max})", min = crate::verifier_index::MIN_VERIFIER_INDEX_VERSION, max = crate::verifier_index::VERIFIER_INDEX_VERSION)]
+    UnsupportedVersion(u32),
 }
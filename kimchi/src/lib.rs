@@ -11,19 +11,29 @@ pub use o1_utils;
 pub use oracle;
 
 pub mod alphas;
+#[cfg(feature = "prover")]
 pub mod bench;
 pub mod circuits;
+pub mod column_equality;
 pub mod curve;
 pub mod error;
 pub mod linearization;
 pub mod oracles;
+pub mod parallel;
 pub mod plonk_sponge;
+#[cfg(feature = "prover")]
+pub mod profiling;
 pub mod proof;
+pub mod proof_compression;
+pub mod proof_metadata;
+#[cfg(feature = "prover")]
 pub mod prover;
+#[cfg(feature = "prover")]
 pub mod prover_index;
+#[cfg(feature = "prover")]
 pub mod snarky;
 pub mod verifier;
 pub mod verifier_index;
 
-#[cfg(test)]
+#[cfg(all(test, feature = "prover"))]
 mod tests;
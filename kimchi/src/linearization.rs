@@ -2,8 +2,10 @@
 
 use crate::alphas::Alphas;
 use crate::circuits::argument::{Argument, ArgumentType};
+use crate::circuits::custom_gate::CustomGates;
 use crate::circuits::lookup;
 use crate::circuits::lookup::constraints::LookupConfiguration;
+use crate::circuits::polynomials::boolean_columns;
 use crate::circuits::polynomials::chacha::{ChaCha0, ChaCha1, ChaCha2, ChaChaFinal};
 use crate::circuits::polynomials::complete_add::CompleteAdd;
 use crate::circuits::polynomials::endomul_scalar::EndomulScalar;
@@ -12,6 +14,7 @@ use crate::circuits::polynomials::foreign_field_add::circuitgates::ForeignFieldA
 use crate::circuits::polynomials::permutation;
 use crate::circuits::polynomials::poseidon::Poseidon;
 use crate::circuits::polynomials::range_check;
+use crate::circuits::polynomials::sha256;
 use crate::circuits::polynomials::varbasemul::VarbaseMul;
 use crate::circuits::{
     expr::{Column, ConstantExpr, Expr, Linearization, PolishToken},
@@ -25,11 +28,16 @@ use ark_ff::{FftField, PrimeField, SquareRootField};
 /// # Panics
 ///
 /// Will panic if `generic_gate` is not associate with `alpha^0`.
+#[allow(clippy::too_many_arguments)]
 pub fn constraints_expr<F: PrimeField + SquareRootField>(
     chacha: bool,
     range_check: bool,
     lookup_constraint_system: Option<&LookupConfiguration<F>>,
     foreign_field_add: bool,
+    sha256: bool,
+    custom_gates: &CustomGates<F>,
+    boolean_columns: &[usize],
+    zk_rows: u64,
 ) -> (Expr<ConstantExpr<F>>, Alphas<F>) {
     // register powers of alpha so that we don't reuse them across mutually inclusive constraints
     let mut powers_of_alpha = Alphas::<F>::default();
@@ -62,12 +70,20 @@ pub fn constraints_expr<F: PrimeField + SquareRootField>(
         expr += ForeignFieldAdd::combined_constraints(&powers_of_alpha);
     }
 
+    if sha256 {
+        expr += sha256::gadget::combined_constraints(&powers_of_alpha);
+    }
+
+    for gate_type in custom_gates.gate_types() {
+        expr += custom_gates.circuit_gate_constraints(gate_type, &powers_of_alpha);
+    }
+
     // permutation
     powers_of_alpha.register(ArgumentType::Permutation, permutation::CONSTRAINTS);
 
     // lookup
     if let Some(lcs) = lookup_constraint_system.as_ref() {
-        let constraints = lookup::constraints::constraints(lcs);
+        let constraints = lookup::constraints::constraints(lcs, zk_rows);
 
         // note: the number of constraints depends on the lookup configuration,
         // specifically the presence of runtime tables.
@@ -82,6 +98,14 @@ pub fn constraints_expr<F: PrimeField + SquareRootField>(
         expr += combined;
     }
 
+    // boolean columns
+    if !boolean_columns.is_empty() {
+        let constraints_len = u32::try_from(boolean_columns.len())
+            .expect("we always expect a relatively low amount of constraints");
+        powers_of_alpha.register(ArgumentType::BooleanColumns, constraints_len);
+        expr += boolean_columns::combined_constraints(boolean_columns, &powers_of_alpha);
+    }
+
     // the generic gate must be associated with alpha^0
     // to make the later addition with the public input work
     if cfg!(debug_assertions) {
@@ -96,7 +120,7 @@ pub fn constraints_expr<F: PrimeField + SquareRootField>(
 
 /// Adds the polynomials that are evaluated as part of the proof
 /// for the linearization to work.
-pub fn linearization_columns<F: FftField + SquareRootField>(
+pub fn linearization_columns<F: FftField>(
     lookup_constraint_system: Option<&LookupConfiguration<F>>,
 ) -> std::collections::HashSet<Column> {
     let mut h = std::collections::HashSet::new();
@@ -138,11 +162,16 @@ pub fn linearization_columns<F: FftField + SquareRootField>(
 /// # Panics
 ///
 /// Will panic if the `linearization` process fails.
+#[allow(clippy::too_many_arguments)]
 pub fn expr_linearization<F: PrimeField + SquareRootField>(
     chacha: bool,
     range_check: bool,
     lookup_constraint_system: Option<&LookupConfiguration<F>>,
     foreign_field_addition: bool,
+    sha256: bool,
+    custom_gates: &CustomGates<F>,
+    boolean_columns: &[usize],
+    zk_rows: u64,
 ) -> (Linearization<Vec<PolishToken<F>>>, Alphas<F>) {
     let evaluated_cols = linearization_columns::<F>(lookup_constraint_system);
 
@@ -151,6 +180,10 @@ pub fn expr_linearization<F: PrimeField + SquareRootField>(
         range_check,
         lookup_constraint_system,
         foreign_field_addition,
+        sha256,
+        custom_gates,
+        boolean_columns,
+        zk_rows,
     );
 
     let linearization = expr
@@ -20,10 +20,10 @@ where
     pub oracles: RandomOracles<G::ScalarField>,
     /// the computed powers of alpha
     pub all_alphas: Alphas<G::ScalarField>,
-    /// public polynomial evaluations
-    pub public_evals: [Vec<G::ScalarField>; 2],
-    /// zeta^n and (zeta * omega)^n
-    pub powers_of_eval_points_for_chunks: [G::ScalarField; 2],
+    /// public polynomial evaluations, at each evaluation point
+    pub public_evals: Vec<Vec<G::ScalarField>>,
+    /// zeta^n and (zeta * omega)^n, followed by (zeta / omega)^n if the circuit reads the previous row
+    pub powers_of_eval_points_for_chunks: Vec<G::ScalarField>,
     /// recursion data
     #[allow(clippy::type_complexity)]
     pub polys: Vec<(PolyComm<G>, Vec<Vec<G::ScalarField>>)>,
@@ -0,0 +1,204 @@
+//! This module abstracts the multi-threading of the prover and of the verifier,
+//! so that they can run where threads are not available, like in the browser.
+//!
+//! By default, the work is spread over the global thread pool of rayon.
+//! Two features change that:
+//!
+//! - `sequential`: the parallel loops of this crate run on the current thread,
+//!   and the witness columns given incrementally to the prover are committed to
+//!   once the witness is generated, rather than on a separate thread.
+//! - `wasm_threads`: under `wasm32`, the thread pool of rayon runs on Web Workers
+//!   with `wasm-bindgen-rayon`, and [init_thread_pool] must be awaited from JavaScript
+//!   before proving.
+//!
+//! Note that the dependencies of this crate (the arkworks crates, the polynomial commitments)
+//! use rayon directly, so a `wasm32` target without threads needs the `parallel` features
+//! of these crates disabled as well.
+//!
+//! The loops of this crate import the parallel iterators from [prelude],
+//! which under `sequential` gives the same methods over the standard iterators.
+
+#[cfg(not(feature = "sequential"))]
+pub use rayon::prelude;
+
+#[cfg(all(target_arch = "wasm32", feature = "wasm_threads"))]
+pub use wasm_bindgen_rayon::init_thread_pool;
+
+/// The number of threads the work is spread over
+#[cfg(not(feature = "sequential"))]
+pub fn current_num_threads() -> usize {
+    rayon::current_num_threads()
+}
+
+/// The number of threads the work is spread over
+#[cfg(feature = "sequential")]
+pub fn current_num_threads() -> usize {
+    1
+}
+
+/// The parallel iterators of rayon used by this crate, as standard iterators
+#[cfg(feature = "sequential")]
+pub mod prelude {
+    /// Same as `rayon::iter::IntoParallelIterator`
+    pub trait IntoParallelIterator {
+        type Iter: Iterator<Item = Self::Item>;
+        type Item;
+
+        fn into_par_iter(self) -> Self::Iter;
+    }
+
+    impl<I: IntoIterator> IntoParallelIterator for I {
+        type Iter = I::IntoIter;
+        type Item = I::Item;
+
+        fn into_par_iter(self) -> Self::Iter {
+            self.into_iter()
+        }
+    }
+
+    /// Same as `rayon::iter::IntoParallelRefIterator`
+    pub trait IntoParallelRefIterator<'data> {
+        type Iter: Iterator<Item = Self::Item>;
+        type Item: 'data;
+
+        fn par_iter(&'data self) -> Self::Iter;
+    }
+
+    impl<'data, I: 'data + ?Sized> IntoParallelRefIterator<'data> for I
+    where
+        &'data I: IntoIterator,
+    {
+        type Iter = <&'data I as IntoIterator>::IntoIter;
+        type Item = <&'data I as IntoIterator>::Item;
+
+        fn par_iter(&'data self) -> Self::Iter {
+            self.into_iter()
+        }
+    }
+
+    /// Same as `rayon::iter::IntoParallelRefMutIterator`
+    pub trait IntoParallelRefMutIterator<'data> {
+        type Iter: Iterator<Item = Self::Item>;
+        type Item: 'data;
+
+        fn par_iter_mut(&'data mut self) -> Self::Iter;
+    }
+
+    impl<'data, I: 'data + ?Sized> IntoParallelRefMutIterator<'data> for I
+    where
+        &'data mut I: IntoIterator,
+    {
+        type Iter = <&'data mut I as IntoIterator>::IntoIter;
+        type Item = <&'data mut I as IntoIterator>::Item;
+
+        fn par_iter_mut(&'data mut self) -> Self::Iter {
+            self.into_iter()
+        }
+    }
+
+    /// Same as `rayon::slice::ParallelSliceMut`
+    pub trait ParallelSliceMut<T> {
+        fn par_chunks_mut(&mut self, chunk_size: usize) -> std::slice::ChunksMut<'_, T>;
+    }
+
+    impl<T> ParallelSliceMut<T> for [T] {
+        fn par_chunks_mut(&mut self, chunk_size: usize) -> std::slice::ChunksMut<'_, T> {
+            self.chunks_mut(chunk_size)
+        }
+    }
+
+    /// The methods of `rayon::iter::ParallelIterator` that standard iterators do not have
+    pub trait ParallelIterator: Iterator + Sized {
+        /// Same as `rayon::iter::ParallelIterator::map_init`,
+        /// with a single value initialized for the whole iteration
+        fn map_init<T, R, INIT, F>(self, init: INIT, map_op: F) -> MapInit<Self, T, F>
+        where
+            INIT: FnOnce() -> T,
+            F: FnMut(&mut T, Self::Item) -> R,
+        {
+            MapInit {
+                iter: self,
+                state: init(),
+                map_op,
+            }
+        }
+
+        /// Same as `rayon::iter::ParallelIterator::find_map_first`
+        fn find_map_first<R, F>(mut self, find_op: F) -> Option<R>
+        where
+            F: FnMut(Self::Item) -> Option<R>,
+        {
+            self.find_map(find_op)
+        }
+    }
+
+    impl<I: Iterator> ParallelIterator for I {}
+
+    /// The iterator returned by [ParallelIterator::map_init]
+    pub struct MapInit<I, T, F> {
+        iter: I,
+        state: T,
+        map_op: F,
+    }
+
+    impl<I, T, R, F> Iterator for MapInit<I, T, F>
+    where
+        I: Iterator,
+        F: FnMut(&mut T, I::Item) -> R,
+    {
+        type Item = R;
+
+        fn next(&mut self) -> Option<R> {
+            let item = self.iter.next()?;
+            Some((self.map_op)(&mut self.state, item))
+        }
+    }
+}
+
+/// The domain size under which [Parallelism::for_domain] processes the columns concurrently
+pub const COLUMNS_PARTITIONING_MAX_DOMAIN: usize = 1 << 12;
+
+/// How the work of a phase of the prover is spread over the threads
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Partitioning {
+    /// The columns (or constraints) are processed in turn, each with its rows split between the threads.
+    /// This suits the tall circuits, whose columns keep all the threads busy.
+    Rows,
+    /// The columns (or constraints) are processed concurrently.
+    /// This suits the short circuits, whose columns are too short to be split efficiently,
+    /// at the cost of holding the results of all the columns at once.
+    Columns,
+}
+
+/// The partitioning of the work of each phase of the prover
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Parallelism {
+    /// The commitments to the witness columns
+    pub witness_commitments: Partitioning,
+    /// The evaluation of the constraints of the gates and of the lookup argument
+    pub constraints: Partitioning,
+    /// The evaluation of the permutation argument
+    pub permutation: Partitioning,
+}
+
+impl Parallelism {
+    /// The same partitioning for all the phases
+    pub fn uniform(partitioning: Partitioning) -> Self {
+        Parallelism {
+            witness_commitments: partitioning,
+            constraints: partitioning,
+            permutation: partitioning,
+        }
+    }
+
+    /// Picks the partitioning from the shape of the circuit, given by the size of its domain:
+    /// the columns are processed concurrently when they are shorter than
+    /// [COLUMNS_PARTITIONING_MAX_DOMAIN] and there are several threads, and split by rows otherwise.
+    pub fn for_domain(domain_size: usize) -> Self {
+        if domain_size < COLUMNS_PARTITIONING_MAX_DOMAIN && current_num_threads() > 1 {
+            Self::uniform(Partitioning::Columns)
+        } else {
+            Self::uniform(Partitioning::Rows)
+        }
+    }
+}
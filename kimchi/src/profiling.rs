@@ -0,0 +1,85 @@
+//! This module instruments the prover, so that integrators can see where the proving time goes.
+//!
+//! With the `prover-tracing` feature, the prover emits a span with the [tracing](https://docs.rs/tracing)
+//! crate for each of its phases ([ProverPhase]), at the `INFO` level and with the target `kimchi::prover`.
+//! The spans of a proof follow each other, as children of the span current when the prover is called,
+//! and any `tracing` subscriber can record them (for example to print their durations,
+//! or to export them as a flame graph).
+//!
+//! Without the feature, `tracing` is not a dependency and the prover is not instrumented.
+
+/// A phase of the prover, which has a span of its [name](ProverPhase::name)
+/// with the `prover-tracing` feature
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ProverPhase {
+    /// The witness is padded, committed to, and interpolated, along with the public input
+    WitnessCommitment,
+    /// The sorted lookup columns are computed and committed to (only if the circuit uses lookups)
+    Lookup,
+    /// The lookup and permutation aggregation polynomials are computed and committed to
+    Permutation,
+    /// The quotient polynomial is computed and committed to
+    Quotient,
+    /// The polynomials are evaluated at `zeta` and `zeta * omega`, and the linearization is computed
+    Evaluation,
+    /// The evaluations are absorbed and the batched opening proof is computed
+    Opening,
+}
+
+impl ProverPhase {
+    /// The name of the span of the phase
+    pub const fn name(self) -> &'static str {
+        match self {
+            ProverPhase::WitnessCommitment => "witness_commitment",
+            ProverPhase::Lookup => "lookup",
+            ProverPhase::Permutation => "permutation",
+            ProverPhase::Quotient => "quotient",
+            ProverPhase::Evaluation => "evaluation",
+            ProverPhase::Opening => "opening",
+        }
+    }
+
+    // the names of the spans must be literals, as they are part of the static metadata of the spans
+    #[cfg(feature = "prover-tracing")]
+    fn span(self) -> tracing::Span {
+        use tracing::info_span;
+        match self {
+            ProverPhase::WitnessCommitment => {
+                info_span!(target: "kimchi::prover", "witness_commitment")
+            }
+            ProverPhase::Lookup => info_span!(target: "kimchi::prover", "lookup"),
+            ProverPhase::Permutation => info_span!(target: "kimchi::prover", "permutation"),
+            ProverPhase::Quotient => info_span!(target: "kimchi::prover", "quotient"),
+            ProverPhase::Evaluation => info_span!(target: "kimchi::prover", "evaluation"),
+            ProverPhase::Opening => info_span!(target: "kimchi::prover", "opening"),
+        }
+    }
+}
+
+/// The span of the current phase of a proof, which is exited when the next phase starts
+/// or when it is dropped (and does nothing without the `prover-tracing` feature)
+#[derive(Default)]
+pub(crate) struct PhaseSpan {
+    #[cfg(feature = "prover-tracing")]
+    entered: Option<tracing::span::EnteredSpan>,
+}
+
+impl PhaseSpan {
+    /// Enters the span of the first phase `phase`
+    pub(crate) fn new(phase: ProverPhase) -> Self {
+        let mut span = PhaseSpan::default();
+        span.next(phase);
+        span
+    }
+
+    /// Exits the span of the current phase, and enters the span of the phase `phase`
+    #[cfg_attr(not(feature = "prover-tracing"), allow(unused_variables))]
+    pub(crate) fn next(&mut self, phase: ProverPhase) {
+        #[cfg(feature = "prover-tracing")]
+        {
+            // the current span is exited first, so that the phases are not nested
+            self.entered = None;
+            self.entered = Some(phase.span().entered());
+        }
+    }
+}
@@ -13,6 +13,8 @@ use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use std::array;
 
+pub mod inspect;
+
 //~ spec:startcode
 /// Evaluations of lookup polynomials
 #[serde_as]
@@ -97,6 +99,21 @@ pub struct ProverCommitments<G: AffineCurve> {
     pub lookup: Option<LookupCommitments<G>>,
 }
 
+/// The evaluations at `zeta / omega` of the polynomials of the proof,
+/// for the circuits whose gates read the previous row.
+/// They are opened by the same batched opening proof as the evaluations at `zeta` and `zeta * omega`.
+#[serde_as]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound = "G: ark_serialize::CanonicalDeserialize + ark_serialize::CanonicalSerialize")]
+pub struct PrevRowEvaluations<G: AffineCurve> {
+    /// The (chunked) evaluations of the polynomials at `zeta / omega`
+    pub evals: ProofEvaluations<Vec<G::ScalarField>>,
+
+    /// The evaluation of the ft polynomial at `zeta / omega`
+    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
+    pub ft_eval: G::ScalarField,
+}
+
 /// The proof that the prover creates from a [ProverIndex](super::prover_index::ProverIndex) and a `witness`.
 #[serde_as]
 #[derive(Clone, Serialize, Deserialize)]
@@ -122,6 +139,11 @@ pub struct ProverProof<G: AffineCurve> {
 
     /// The challenges underlying the optional polynomials folded into the proof
     pub prev_challenges: Vec<RecursionChallenge<G>>,
+
+    /// The evaluations at `zeta / omega`,
+    /// if the gates of the circuit read the previous row
+    #[serde(default)]
+    pub prev_row: Option<PrevRowEvaluations<G>>,
 }
 
 /// A struct to store the challenges inside a `ProverProof`
@@ -141,6 +163,26 @@ where
 
 //~ spec:endcode
 
+impl<G: AffineCurve> ProverProof<G> {
+    /// The chunked evaluations of the proof at `zeta` and `zeta * omega`,
+    /// followed by the ones at `zeta / omega` if present
+    pub fn chunked_evals(&self) -> impl Iterator<Item = &ProofEvaluations<Vec<G::ScalarField>>> {
+        self.evals
+            .iter()
+            .chain(self.prev_row.as_ref().map(|prev_row| &prev_row.evals))
+    }
+
+    /// The evaluations of the ft polynomial at all the evaluation points,
+    /// given its evaluation `ft_eval0` at `zeta` (which is computed by the verifier)
+    pub fn ft_evals(&self, ft_eval0: G::ScalarField) -> Vec<Vec<G::ScalarField>> {
+        let mut ft_evals = vec![vec![ft_eval0], vec![self.ft_eval1]];
+        if let Some(prev_row) = &self.prev_row {
+            ft_evals.push(vec![prev_row.ft_eval]);
+        }
+        ft_evals
+    }
+}
+
 impl<F> ProofEvaluations<F> {
     /// Transpose the `ProofEvaluations`.
     ///
@@ -202,9 +244,11 @@ impl<G: AffineCurve> RecursionChallenge<G> {
         let b_len = 1 << chals.len();
         let mut b: Option<Vec<G::ScalarField>> = None;
 
-        (0..2)
-            .map(|i| {
-                let full = b_poly(chals, evaluation_points[i]);
+        evaluation_points
+            .iter()
+            .zip(powers_of_eval_points_for_chunks)
+            .map(|(&pt, &pt_to_srs_len)| {
+                let full = b_poly(chals, pt);
                 if max_poly_size == b_len {
                     return vec![full];
                 }
@@ -222,11 +266,11 @@ impl<G: AffineCurve> RecursionChallenge<G> {
                         };
 
                         let ret = betaacc * b_j;
-                        betaacc *= &evaluation_points[i];
+                        betaacc *= &pt;
                         ret
                     })
                     .fold(G::ScalarField::zero(), |x, y| x + y);
-                vec![full - (diff * powers_of_eval_points_for_chunks[i]), diff]
+                vec![full - (diff * pt_to_srs_len), diff]
             })
             .collect()
     }
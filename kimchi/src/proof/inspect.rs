@@ -0,0 +1,455 @@
+//! This module implements an offline inspection of a [ProverProof] against a [VerifierIndex],
+//! for support and debugging workflows.
+//!
+//! The inspection summarizes the structure of the proof (its commitments and their chunks,
+//! and the table of its evaluations), checks this structure against the verifier index,
+//! and recomputes the challenges of the Fiat-Shamir transcript.
+//! It does not verify the proof: the opening proof is not checked, and no SRS is needed,
+//! except to recompute the challenges of a circuit with public inputs.
+//! In that case, the challenges are only recomputed if the SRS of the index is already set,
+//! and the commitment to the public inputs is recomputed, with a multi-scalar multiplication
+//! over the Lagrange basis of the SRS.
+
+use crate::{
+    circuits::{
+        scalars::RandomOracles,
+        wires::{COLUMNS, PERMUTS},
+    },
+    curve::KimchiCurve,
+    oracles::OraclesResult,
+    plonk_sponge::FrSponge,
+    proof::{ProofEvaluations, ProverProof},
+    verifier::public_commitment,
+    verifier_index::VerifierIndex,
+};
+use ark_ec::AffineCurve;
+use ark_ff::{Field, PrimeField};
+use commitment_dlog::commitment::PolyComm;
+use o1_utils::math;
+use oracle::FqSponge;
+use std::fmt;
+
+/// The number of chunks of a commitment of the proof
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitmentSummary {
+    /// The name of the committed polynomial
+    pub name: String,
+    /// The number of chunks of the commitment
+    pub chunks: usize,
+}
+
+/// The evaluations of a polynomial of the proof, by chunk
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvaluationSummary<F> {
+    /// The name of the evaluated polynomial
+    pub name: String,
+    /// The evaluations of the chunks at $\zeta$
+    pub zeta: Vec<F>,
+    /// The evaluations of the chunks at $\zeta\omega$
+    pub zeta_omega: Vec<F>,
+    /// The evaluations of the chunks at $\zeta / \omega$,
+    /// if the proof opens the polynomials on the previous row
+    pub zeta_prev: Option<Vec<F>>,
+}
+
+/// The challenges of the transcript of a proof, as recomputed by the verifier
+#[derive(Debug, Clone)]
+pub struct Challenges<F: Field> {
+    /// The challenges produced in the protocol
+    pub oracles: RandomOracles<F>,
+    /// The evaluation of $ft$ at $\zeta$, recomputed from the evaluations of the proof
+    pub ft_eval0: F,
+    /// The combined inner product of the evaluations
+    pub combined_inner_product: F,
+}
+
+/// A summary of a proof, see [crate::proof::inspect]
+#[derive(Debug, Clone)]
+pub struct ProofSummary<F: Field> {
+    /// The size of the domain of the circuit
+    pub domain_size: usize,
+    /// The size of the chunks of the polynomials, which is the size of the SRS
+    pub max_poly_size: usize,
+    /// The number of chunks of the polynomials over the domain
+    pub num_chunks: usize,
+    /// The number of public inputs of the proof
+    pub public: usize,
+    /// The number of previous recursion challenges of the proof
+    pub prev_challenges: usize,
+    /// The commitments of the proof
+    pub commitments: Vec<CommitmentSummary>,
+    /// The evaluations of the proof
+    pub evaluations: Vec<EvaluationSummary<F>>,
+    /// The evaluation of $ft$ at $\zeta\omega$
+    pub ft_eval1: F,
+    /// The evaluation of $ft$ at $\zeta / \omega$,
+    /// if the proof opens the polynomials on the previous row
+    pub ft_eval_prev: Option<F>,
+    /// The number of rounds of the opening proof
+    pub opening_rounds: usize,
+    /// The recomputed challenges, if they could be recomputed
+    pub challenges: Option<Challenges<F>>,
+    /// The ways in which the proof does not match the verifier index.
+    /// A proof with issues would not verify, but a proof without issues may still not verify.
+    pub issues: Vec<String>,
+}
+
+impl<F: Field> ProofSummary<F> {
+    /// Inspects `proof` against `index`.
+    pub fn new<G, EFqSponge, EFrSponge>(index: &VerifierIndex<G>, proof: &ProverProof<G>) -> Self
+    where
+        G: KimchiCurve<ScalarField = F>,
+        G::BaseField: PrimeField,
+        EFqSponge: Clone + FqSponge<G::BaseField, G, F>,
+        EFrSponge: FrSponge<F>,
+    {
+        let num_chunks = index.num_chunks();
+        let mut issues = vec![];
+
+        let commitments = commitments(proof);
+        let prev_evals = proof.prev_row.as_ref().map(|prev_row| &prev_row.evals);
+        let evaluations = evaluations(&proof.evals, prev_evals);
+
+        if proof.public.len() != index.public {
+            issues.push(format!(
+                "the proof has {} public inputs, but the index expects {}",
+                proof.public.len(),
+                index.public
+            ));
+        }
+        if proof.prev_challenges.len() != index.prev_challenges {
+            issues.push(format!(
+                "the proof has {} previous challenges, but the index expects {}",
+                proof.prev_challenges.len(),
+                index.prev_challenges
+            ));
+        }
+        if index.lookup_index.is_some() != proof.commitments.lookup.is_some() {
+            issues.push(format!(
+                "the proof {} lookup commitments, but the index {} lookups",
+                if proof.commitments.lookup.is_some() {
+                    "has"
+                } else {
+                    "has no"
+                },
+                if index.lookup_index.is_some() {
+                    "uses"
+                } else {
+                    "does not use"
+                },
+            ));
+        }
+        // the previous row is only opened when the constraints of the circuit read it
+        let reads_prev_row = index.linearization.reads_prev_row();
+        if proof.prev_row.is_some() != reads_prev_row {
+            issues.push(format!(
+                "the proof {} evaluations on the previous row, but the circuit {} it",
+                if proof.prev_row.is_some() {
+                    "has"
+                } else {
+                    "has no"
+                },
+                if reads_prev_row {
+                    "reads"
+                } else {
+                    "does not read"
+                },
+            ));
+        }
+        if let Some(prev_evals) = prev_evals {
+            if names(prev_evals) != names(&proof.evals[0]) {
+                issues.push(
+                    "the evaluations on the previous row do not have the shape of the evaluations at zeta"
+                        .to_string(),
+                );
+            }
+        }
+        for CommitmentSummary { name, chunks } in &commitments {
+            // the quotient is padded to its maximum number of chunks, but not the other polynomials
+            if name == "t" && *chunks != PERMUTS * num_chunks {
+                issues.push(format!(
+                    "the commitment to t has {chunks} chunks, but the domain requires {}",
+                    PERMUTS * num_chunks
+                ));
+            } else if *chunks > num_chunks && name != "t" {
+                issues.push(format!(
+                    "the commitment to {name} has {chunks} chunks, more than the domain"
+                ));
+            }
+        }
+        for EvaluationSummary {
+            name,
+            zeta,
+            zeta_omega,
+            zeta_prev,
+        } in &evaluations
+        {
+            if zeta.len() != zeta_omega.len() {
+                issues.push(format!(
+                    "{name} has {} chunks evaluated at zeta, but {} at zeta omega",
+                    zeta.len(),
+                    zeta_omega.len()
+                ));
+            }
+            if let Some(zeta_prev) = zeta_prev.as_ref().filter(|e| e.len() != zeta.len()) {
+                issues.push(format!(
+                    "{name} has {} chunks evaluated at zeta, but {} at zeta / omega",
+                    zeta.len(),
+                    zeta_prev.len()
+                ));
+            }
+            if let Some(c) = commitments.iter().find(|c| &c.name == name) {
+                if zeta.len() != c.chunks {
+                    issues.push(format!(
+                        "{name} has {} evaluated chunks, but its commitment has {}",
+                        zeta.len(),
+                        c.chunks
+                    ));
+                }
+            }
+        }
+        let expected_rounds = math::ceil_log2(index.max_poly_size);
+        if proof.proof.lr.len() != expected_rounds {
+            issues.push(format!(
+                "the opening proof has {} rounds, but the SRS requires {expected_rounds}",
+                proof.proof.lr.len()
+            ));
+        }
+
+        // the commitment to the public input needs the SRS, unless there is no public input
+        let public_comm = if index.public == 0 {
+            Some(PolyComm {
+                unshifted: vec![G::zero()],
+                shifted: None,
+            })
+        } else if index.srs.get().is_some() && proof.public.len() == index.public {
+            Some(public_commitment(index, &proof.public))
+        } else {
+            None
+        };
+        let challenges = public_comm.and_then(|public_comm| {
+            match proof.oracles::<EFqSponge, EFrSponge>(index, &public_comm) {
+                Ok(OraclesResult {
+                    oracles,
+                    ft_eval0,
+                    combined_inner_product,
+                    ..
+                }) => Some(Challenges {
+                    oracles,
+                    ft_eval0,
+                    combined_inner_product,
+                }),
+                Err(e) => {
+                    issues.push(format!("the challenges cannot be recomputed: {e}"));
+                    None
+                }
+            }
+        });
+
+        ProofSummary {
+            domain_size: index.domain.size as usize,
+            max_poly_size: index.max_poly_size,
+            num_chunks,
+            public: proof.public.len(),
+            prev_challenges: proof.prev_challenges.len(),
+            commitments,
+            evaluations,
+            ft_eval1: proof.ft_eval1,
+            ft_eval_prev: proof.prev_row.as_ref().map(|prev_row| prev_row.ft_eval),
+            opening_rounds: proof.proof.lr.len(),
+            challenges,
+            issues,
+        }
+    }
+
+    /// Decodes a proof serialized with MessagePack, and inspects it against `index`.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the proof cannot be decoded.
+    pub fn from_bytes<G, EFqSponge, EFrSponge>(
+        index: &VerifierIndex<G>,
+        bytes: &[u8],
+    ) -> Result<Self, rmp_serde::decode::Error>
+    where
+        G: KimchiCurve<ScalarField = F>,
+        G::BaseField: PrimeField,
+        EFqSponge: Clone + FqSponge<G::BaseField, G, F>,
+        EFrSponge: FrSponge<F>,
+    {
+        let proof: ProverProof<G> = rmp_serde::from_slice(bytes)?;
+        Ok(Self::new::<G, EFqSponge, EFrSponge>(index, &proof))
+    }
+
+    /// Whether the structure of the proof matches the verifier index
+    pub fn is_well_formed(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// The names and numbers of chunks of the commitments of `proof`
+fn commitments<G: AffineCurve>(proof: &ProverProof<G>) -> Vec<CommitmentSummary> {
+    let summary = |name: String, comm: &PolyComm<G>| CommitmentSummary {
+        name,
+        chunks: comm.unshifted.len(),
+    };
+
+    let commitments = &proof.commitments;
+    let mut res: Vec<_> = (0..COLUMNS)
+        .map(|i| summary(format!("w_{i}"), &commitments.w_comm[i]))
+        .collect();
+    res.push(summary("z".to_string(), &commitments.z_comm));
+    res.push(summary("t".to_string(), &commitments.t_comm));
+    if let Some(lookup) = &commitments.lookup {
+        for (i, sorted) in lookup.sorted.iter().enumerate() {
+            res.push(summary(format!("lookup_sorted_{i}"), sorted));
+        }
+        res.push(summary("lookup_aggreg".to_string(), &lookup.aggreg));
+        if let Some(runtime) = &lookup.runtime {
+            res.push(summary("lookup_runtime".to_string(), runtime));
+        }
+    }
+    res
+}
+
+/// The names of the polynomials evaluated in `evals`, with their evaluations
+fn named_evaluations<F>(evals: &ProofEvaluations<Vec<F>>) -> Vec<(String, &Vec<F>)> {
+    let mut res: Vec<_> = (0..COLUMNS)
+        .map(|i| (format!("w_{i}"), &evals.w[i]))
+        .collect();
+    res.push(("z".to_string(), &evals.z));
+    res.extend((0..PERMUTS - 1).map(|i| (format!("s_{i}"), &evals.s[i])));
+    res.push(("generic_selector".to_string(), &evals.generic_selector));
+    res.push(("poseidon_selector".to_string(), &evals.poseidon_selector));
+    if let Some(lookup) = &evals.lookup {
+        for (i, sorted) in lookup.sorted.iter().enumerate() {
+            res.push((format!("lookup_sorted_{i}"), sorted));
+        }
+        res.push(("lookup_aggreg".to_string(), &lookup.aggreg));
+        res.push(("lookup_table".to_string(), &lookup.table));
+        if let Some(runtime) = &lookup.runtime {
+            res.push(("lookup_runtime".to_string(), runtime));
+        }
+    }
+    res
+}
+
+/// The names of the polynomials evaluated in `evals`
+fn names<F>(evals: &ProofEvaluations<Vec<F>>) -> Vec<String> {
+    named_evaluations(evals)
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect()
+}
+
+/// The table of the evaluations of `evals` at $\zeta$ and $\zeta\omega$,
+/// and of `prev` at $\zeta / \omega$, of the polynomials evaluated at both $\zeta$ and $\zeta\omega$
+fn evaluations<F: Field>(
+    evals: &[ProofEvaluations<Vec<F>>; 2],
+    prev: Option<&ProofEvaluations<Vec<F>>>,
+) -> Vec<EvaluationSummary<F>> {
+    let [e0, e1] = evals;
+    let zeta_omega = named_evaluations(e1);
+    let prev = prev.map(named_evaluations);
+    let find = |evals: &[(String, &Vec<F>)], name: &str| {
+        evals
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, e)| (*e).clone())
+    };
+
+    named_evaluations(e0)
+        .into_iter()
+        .filter_map(|(name, zeta)| {
+            Some(EvaluationSummary {
+                zeta: zeta.clone(),
+                zeta_omega: find(&zeta_omega, &name)?,
+                zeta_prev: prev.as_ref().and_then(|prev| find(prev, &name)),
+                name,
+            })
+        })
+        .collect()
+}
+
+impl<F: Field> fmt::Display for ProofSummary<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let values = |values: &[F]| {
+            values
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        writeln!(
+            f,
+            "domain of size {}, chunks of size {} ({} per polynomial)",
+            self.domain_size, self.max_poly_size, self.num_chunks
+        )?;
+        writeln!(
+            f,
+            "{} public inputs, {} previous challenges, {} opening rounds",
+            self.public, self.prev_challenges, self.opening_rounds
+        )?;
+
+        writeln!(f, "commitments:")?;
+        for CommitmentSummary { name, chunks } in &self.commitments {
+            writeln!(f, "  {name}: {chunks} chunks")?;
+        }
+
+        if self.ft_eval_prev.is_some() {
+            writeln!(f, "evaluations (zeta | zeta omega | zeta / omega):")?;
+        } else {
+            writeln!(f, "evaluations (zeta | zeta omega):")?;
+        }
+        for EvaluationSummary {
+            name,
+            zeta,
+            zeta_omega,
+            zeta_prev,
+        } in &self.evaluations
+        {
+            write!(f, "  {name}: [{}] | [{}]", values(zeta), values(zeta_omega))?;
+            match zeta_prev {
+                Some(zeta_prev) => writeln!(f, " | [{}]", values(zeta_prev))?,
+                None => writeln!(f)?,
+            }
+        }
+        match self.ft_eval_prev {
+            Some(ft_eval_prev) => writeln!(f, "  ft: | [{}] | [{ft_eval_prev}]", self.ft_eval1)?,
+            None => writeln!(f, "  ft: | [{}]", self.ft_eval1)?,
+        }
+
+        match &self.challenges {
+            Some(Challenges {
+                oracles,
+                ft_eval0,
+                combined_inner_product,
+            }) => {
+                writeln!(f, "challenges:")?;
+                writeln!(f, "  beta: {}", oracles.beta)?;
+                writeln!(f, "  gamma: {}", oracles.gamma)?;
+                if let Some((_, joint_combiner)) = &oracles.joint_combiner {
+                    writeln!(f, "  joint combiner: {joint_combiner}")?;
+                }
+                writeln!(f, "  alpha: {}", oracles.alpha)?;
+                writeln!(f, "  zeta: {}", oracles.zeta)?;
+                writeln!(f, "  v: {}", oracles.v)?;
+                writeln!(f, "  u: {}", oracles.u)?;
+                writeln!(f, "  ft(zeta): {ft_eval0}")?;
+                writeln!(f, "  combined inner product: {combined_inner_product}")?;
+            }
+            None => writeln!(f, "challenges: not recomputed")?,
+        }
+
+        if self.issues.is_empty() {
+            writeln!(f, "the proof is well-formed")
+        } else {
+            writeln!(f, "issues:")?;
+            for issue in &self.issues {
+                writeln!(f, "  {issue}")?;
+            }
+            Ok(())
+        }
+    }
+}
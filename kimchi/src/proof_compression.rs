@@ -0,0 +1,913 @@
+//! This module implements a compact binary encoding of a [ProverProof],
+//! of its [ProverCommitments], and of a [VerifierIndex].
+//!
+//! The encoding relies on two observations:
+//!
+//! - curve points are written in compressed form,
+//!   that is their x-coordinate together with a flag encoding the parity of their y-coordinate,
+//! - the evaluation vectors share a common prefix: the evaluations at the two evaluation points
+//!   have the same shape, which is written once for both of them, and all their vectors usually
+//!   have the same number of chunks, which is then also written once for all of them,
+//!   the evaluations being written back to back, without any length prefix
+//!   (the evaluations on the previous row have the same shape, which is not written again).
+//!
+//! Compared to the serde encoding of a proof, this removes the framing of every single
+//! field element and curve point, at the cost of a small amount of CPU time spent
+//! decompressing the points.
+//!
+//! The encoding does not depend on the platform:
+//!
+//! - the first byte is the version of the encoding
+//!   ([COMPRESSED_PROOF_VERSION] or [COMPRESSED_VERIFIER_INDEX_VERSION]),
+//!   so that the encodings written by a release can be read by the following ones,
+//! - field elements and curve points are written in the canonical encoding of arkworks,
+//!   that is in little-endian order,
+//! - integers and lengths are written as LEB128 variable-length integers,
+//!   whatever the size of `usize`,
+//! - big integers are written as their length in bytes followed by their little-endian bytes.
+
+use crate::{
+    circuits::{
+        expr::{Column, Linearization, PolishToken, Variable},
+        lookup::{index::LookupSelectors, lookups::LookupsUsed},
+        wires::{COLUMNS, PERMUTS},
+    },
+    curve::KimchiCurve,
+    proof::{
+        LookupCommitments, LookupEvaluations, PrevRowEvaluations, ProofEvaluations,
+        ProverCommitments, ProverProof, RecursionChallenge,
+    },
+    verifier_index::{LookupVerifierIndex, VerifierIndex},
+};
+use ark_ec::AffineCurve;
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain as D};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+use commitment_dlog::{
+    commitment::{CommitmentCurve, PolyComm},
+    evaluation_proof::OpeningProof,
+    srs::SRS,
+};
+use num_bigint::BigUint;
+use once_cell::sync::OnceCell;
+use std::{array, sync::Arc};
+
+/// The version of the compressed encoding, written as the first byte of a compressed proof
+/// (or of compressed commitments).
+/// Version 2 added the evaluations of all the polynomials at `zeta / omega`,
+/// for the gates reading the previous row.
+pub const COMPRESSED_PROOF_VERSION: u8 = 2;
+
+/// The version of the compressed encoding of a [VerifierIndex], written as its first byte.
+/// Version 2 added the number of public outputs, version 3 the boolean columns,
+/// version 4 the zero-knowledge mode, and version 5 made the commitments
+/// to the selectors of the elliptic curve gates optional.
+pub const COMPRESSED_VERIFIER_INDEX_VERSION: u8 = 5;
+
+type Result<T> = std::result::Result<T, SerializationError>;
+
+impl<G: AffineCurve> ProverProof<G> {
+    /// Encodes the proof in the compact format described in [crate::proof_compression].
+    ///
+    /// # Errors
+    ///
+    /// Will give error if a field element or a curve point cannot be serialized,
+    /// or if the evaluations at the evaluation points do not have the same shape.
+    pub fn compress(&self) -> Result<Vec<u8>> {
+        let mut writer = Writer::default();
+        writer.byte(COMPRESSED_PROOF_VERSION);
+
+        writer.commitments(&self.commitments)?;
+        writer.opening(&self.proof)?;
+        writer.evaluations(&self.evals)?;
+        writer.item(&self.ft_eval1)?;
+        writer.items(&self.public)?;
+
+        writer.length(self.prev_challenges.len());
+        for RecursionChallenge { chals, comm } in &self.prev_challenges {
+            writer.items(chals)?;
+            writer.poly_comm(comm)?;
+        }
+
+        writer.option(self.prev_row.as_ref(), |writer, prev_row| {
+            // the evaluations at `zeta / omega` have the shape of the evaluations at `zeta`
+            if evaluations_shape(&prev_row.evals) != evaluations_shape(&self.evals[0]) {
+                return Err(SerializationError::InvalidData);
+            }
+            writer.vectors(&evaluation_vectors(&prev_row.evals))?;
+            writer.item(&prev_row.ft_eval)
+        })?;
+
+        Ok(writer.0)
+    }
+
+    /// Decodes a proof encoded with [ProverProof::compress].
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the bytes are not a valid compressed proof,
+    /// for example if a curve point is not on the curve.
+    pub fn decompress(bytes: &[u8]) -> Result<Self> {
+        let mut reader = Reader::new(bytes, COMPRESSED_PROOF_VERSION)?;
+
+        let commitments = reader.commitments()?;
+        let proof = reader.opening()?;
+        let evals = reader.evaluations()?;
+        let ft_eval1 = reader.item()?;
+        let public = reader.items()?;
+
+        let prev_challenges = (0..reader.length()?)
+            .map(|_| {
+                let chals = reader.items()?;
+                let comm = reader.poly_comm()?;
+                Ok(RecursionChallenge { chals, comm })
+            })
+            .collect::<Result<_>>()?;
+        let prev_row = reader.option(|reader| {
+            let len = reader.vectors_length()?;
+            Ok(PrevRowEvaluations {
+                evals: reader.evaluations_at(evaluations_shape(&evals[0]), len)?,
+                ft_eval: reader.item()?,
+            })
+        })?;
+        reader.finish()?;
+
+        Ok(ProverProof {
+            commitments,
+            proof,
+            evals,
+            ft_eval1,
+            public,
+            prev_challenges,
+            prev_row,
+        })
+    }
+}
+
+impl<G: AffineCurve> ProverCommitments<G> {
+    /// Encodes the commitments of a proof in the compact format described in [crate::proof_compression].
+    ///
+    /// # Errors
+    ///
+    /// Will give error if a curve point cannot be serialized.
+    pub fn compress(&self) -> Result<Vec<u8>> {
+        let mut writer = Writer::default();
+        writer.byte(COMPRESSED_PROOF_VERSION);
+        writer.commitments(self)?;
+        Ok(writer.0)
+    }
+
+    /// Decodes commitments encoded with [ProverCommitments::compress].
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the bytes are not valid compressed commitments.
+    pub fn decompress(bytes: &[u8]) -> Result<Self> {
+        let mut reader = Reader::new(bytes, COMPRESSED_PROOF_VERSION)?;
+        let commitments = reader.commitments()?;
+        reader.finish()?;
+        Ok(commitments)
+    }
+}
+
+impl<G: KimchiCurve> VerifierIndex<G> {
+    /// Encodes the index in the compact format described in [crate::proof_compression].
+    /// Like with serde, the SRS, the endoscalar coefficient, the linearization
+    /// and the powers of alpha are not part of the encoding.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if a field element or a curve point cannot be serialized.
+    pub fn compress(&self) -> Result<Vec<u8>> {
+        // We fully expand this to make the compiler check that we aren't missing any field
+        let VerifierIndex {
+            domain,
+            max_poly_size,
+            max_quot_size,
+            srs: _,
+            public,
+            public_output,
+            prev_challenges,
+            zk_rows,
+            sigma_comm,
+            coefficients_comm,
+            generic_comm,
+            psm_comm,
+            complete_add_comm,
+            mul_comm,
+            emul_comm,
+            endomul_scalar_comm,
+            chacha_comm,
+            range_check_comm,
+            foreign_field_modulus,
+            foreign_field_add_comm,
+            shift,
+            zkpm: _,
+            w: _,
+            endo: _,
+            lookup_index,
+            linearization: _,
+            powers_of_alpha: _,
+            sha256_comm,
+            custom_gates_comm,
+            boolean_columns,
+            zk,
+        } = self;
+
+        let mut writer = Writer::default();
+        writer.byte(COMPRESSED_VERIFIER_INDEX_VERSION);
+
+        writer.integer(domain.size);
+        writer.length(*max_poly_size);
+        writer.length(*max_quot_size);
+        writer.length(*public);
+        writer.length(*public_output);
+        writer.length(*prev_challenges);
+        writer.integer(*zk_rows);
+        writer.byte(u8::from(*zk));
+
+        for comm in sigma_comm.iter().chain(coefficients_comm) {
+            writer.poly_comm(comm)?;
+        }
+        writer.poly_comm(generic_comm)?;
+        writer.poly_comm(psm_comm)?;
+        for comm in [complete_add_comm, mul_comm, emul_comm, endomul_scalar_comm] {
+            writer.option(comm.as_ref(), Writer::poly_comm)?;
+        }
+
+        writer.option(chacha_comm.as_ref(), |w, comms| w.poly_comms(comms))?;
+        writer.option(range_check_comm.as_ref(), |w, comms| w.poly_comms(comms))?;
+        writer.option(foreign_field_modulus.as_ref(), |w, modulus| {
+            w.big_uint(modulus);
+            Ok(())
+        })?;
+        writer.option(foreign_field_add_comm.as_ref(), Writer::poly_comm)?;
+        shift.iter().try_for_each(|shift| writer.item(shift))?;
+        writer.option(lookup_index.as_ref(), Writer::lookup_index)?;
+        writer.option(sha256_comm.as_ref(), |w, comms| w.poly_comms(comms))?;
+
+        writer.length(custom_gates_comm.len());
+        writer.poly_comms(custom_gates_comm)?;
+
+        writer.length(boolean_columns.len());
+        for col in boolean_columns {
+            writer.length(*col);
+        }
+
+        Ok(writer.0)
+    }
+
+    /// Encodes the linearization of the index, which is not part of [VerifierIndex::compress],
+    /// with the same conventions: each token of the constant term,
+    /// then each column of the index terms followed by the tokens of its coefficient.
+    /// There is no reader, the encoding is only hashed by [VerifierIndex::circuit_digest].
+    pub(crate) fn compress_linearization(&self) -> Result<Vec<u8>> {
+        let Linearization {
+            constant_term,
+            index_terms,
+        } = &self.linearization;
+
+        let mut writer = Writer::default();
+        writer.polish(constant_term)?;
+        writer.length(index_terms.len());
+        for (col, tokens) in index_terms {
+            writer.column(col);
+            writer.polish(tokens)?;
+        }
+        Ok(writer.0)
+    }
+
+    /// Decodes an index encoded with [VerifierIndex::compress],
+    /// given a pointer to an SRS and the endoscalar coefficient.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the bytes are not a valid compressed index,
+    /// for example if a curve point is not on the curve.
+    pub fn decompress(
+        bytes: &[u8],
+        srs: Option<Arc<SRS<G>>>,
+        endo: G::ScalarField,
+    ) -> Result<Self> {
+        let mut reader = Reader::new(bytes, COMPRESSED_VERIFIER_INDEX_VERSION)?;
+
+        let size = reader.integer()?;
+        let domain = usize::try_from(size)
+            .ok()
+            .and_then(D::new)
+            .filter(|domain| domain.size == size)
+            .ok_or(SerializationError::InvalidData)?;
+        let max_poly_size = reader.length()?;
+        let max_quot_size = reader.length()?;
+        let public = reader.length()?;
+        let public_output = reader.length()?;
+        let prev_challenges = reader.length()?;
+        let zk_rows = reader.integer()?;
+        let zk = match reader.byte()? {
+            0 => false,
+            1 => true,
+            _ => return Err(SerializationError::InvalidData),
+        };
+
+        let sigma_comm = reader.array(Reader::poly_comm)?;
+        let coefficients_comm = reader.array(Reader::poly_comm)?;
+        let generic_comm = reader.poly_comm()?;
+        let psm_comm = reader.poly_comm()?;
+        let complete_add_comm = reader.option(Reader::poly_comm)?;
+        let mul_comm = reader.option(Reader::poly_comm)?;
+        let emul_comm = reader.option(Reader::poly_comm)?;
+        let endomul_scalar_comm = reader.option(Reader::poly_comm)?;
+
+        let chacha_comm = reader.option(|r| r.array(Reader::poly_comm))?;
+        let range_check_comm = reader.option(|r| r.array(Reader::poly_comm))?;
+        let foreign_field_modulus = reader.option(Reader::big_uint)?;
+        let foreign_field_add_comm = reader.option(Reader::poly_comm)?;
+        let shift = reader.array(Reader::item)?;
+        let lookup_index = reader.option(Reader::lookup_index)?;
+        let sha256_comm = reader.option(|r| r.array(Reader::poly_comm))?;
+
+        let custom_gates_comm = (0..reader.length()?)
+            .map(|_| reader.poly_comm())
+            .collect::<Result<_>>()?;
+
+        let boolean_columns = (0..reader.length()?)
+            .map(|_| reader.length())
+            .collect::<Result<_>>()?;
+        reader.finish()?;
+
+        let srs = srs.map_or_else(OnceCell::new, OnceCell::from);
+
+        Ok(VerifierIndex {
+            domain,
+            max_poly_size,
+            max_quot_size,
+            srs,
+            public,
+            public_output,
+            prev_challenges,
+            zk_rows,
+            sigma_comm,
+            coefficients_comm,
+            generic_comm,
+            psm_comm,
+            complete_add_comm,
+            mul_comm,
+            emul_comm,
+            endomul_scalar_comm,
+            chacha_comm,
+            range_check_comm,
+            foreign_field_modulus,
+            foreign_field_add_comm,
+            shift,
+            zkpm: OnceCell::new(),
+            w: OnceCell::new(),
+            endo,
+            lookup_index,
+            linearization: Default::default(),
+            powers_of_alpha: Default::default(),
+            sha256_comm,
+            custom_gates_comm,
+            boolean_columns,
+            zk,
+        })
+    }
+}
+
+/// Returns the shape of `evals`: if present, the number of sorted lookup polynomials
+/// and whether there is a runtime table.
+fn evaluations_shape<F>(evals: &ProofEvaluations<Vec<F>>) -> Option<(usize, bool)> {
+    evals
+        .lookup
+        .as_ref()
+        .map(|lookup| (lookup.sorted.len(), lookup.runtime.is_some()))
+}
+
+/// Returns the evaluation vectors of `evals`, in the order in which they are encoded.
+fn evaluation_vectors<F>(evals: &ProofEvaluations<Vec<F>>) -> Vec<&Vec<F>> {
+    let mut vectors: Vec<_> = evals.w.iter().collect();
+    vectors.push(&evals.z);
+    vectors.extend(evals.s.iter());
+    vectors.push(&evals.generic_selector);
+    vectors.push(&evals.poseidon_selector);
+    if let Some(lookup) = &evals.lookup {
+        vectors.extend(lookup.sorted.iter());
+        vectors.push(&lookup.aggreg);
+        vectors.push(&lookup.table);
+        if let Some(runtime) = &lookup.runtime {
+            vectors.push(runtime);
+        }
+    }
+    vectors
+}
+
+#[derive(Default)]
+struct Writer(Vec<u8>);
+
+impl Writer {
+    fn byte(&mut self, byte: u8) {
+        self.0.push(byte);
+    }
+
+    /// Writes an integer as a LEB128 variable-length integer.
+    fn integer(&mut self, mut n: u64) {
+        while n >= 0x80 {
+            self.byte((n as u8 & 0x7f) | 0x80);
+            n >>= 7;
+        }
+        self.byte(n as u8);
+    }
+
+    fn length(&mut self, len: usize) {
+        self.integer(len as u64);
+    }
+
+    fn big_uint(&mut self, n: &BigUint) {
+        let bytes = n.to_bytes_le();
+        self.length(bytes.len());
+        self.0.extend(bytes);
+    }
+
+    /// Writes a flag telling if `value` is present, followed by the value if it is.
+    fn option<T>(
+        &mut self,
+        value: Option<&T>,
+        write: impl FnOnce(&mut Self, &T) -> Result<()>,
+    ) -> Result<()> {
+        match value {
+            None => {
+                self.byte(0);
+                Ok(())
+            }
+            Some(value) => {
+                self.byte(1);
+                write(self, value)
+            }
+        }
+    }
+
+    /// Writes a field element or a (compressed) curve point.
+    fn item(&mut self, item: &impl CanonicalSerialize) -> Result<()> {
+        item.serialize(&mut self.0)
+    }
+
+    fn items<T: CanonicalSerialize>(&mut self, items: &[T]) -> Result<()> {
+        self.length(items.len());
+        items.iter().try_for_each(|item| self.item(item))
+    }
+
+    fn poly_comm<G: AffineCurve>(&mut self, comm: &PolyComm<G>) -> Result<()> {
+        self.items(&comm.unshifted)?;
+        match &comm.shifted {
+            None => self.byte(0),
+            Some(shifted) => {
+                self.byte(1);
+                self.item(shifted)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes commitments whose number is known by the reader.
+    fn poly_comms<G: AffineCurve>(&mut self, comms: &[PolyComm<G>]) -> Result<()> {
+        comms.iter().try_for_each(|comm| self.poly_comm(comm))
+    }
+
+    fn lookup_index<G: CommitmentCurve>(&mut self, index: &LookupVerifierIndex<G>) -> Result<()> {
+        self.byte(match index.lookup_used {
+            LookupsUsed::Single => 0,
+            LookupsUsed::Joint => 1,
+        });
+        self.length(index.lookup_table.len());
+        self.poly_comms(&index.lookup_table)?;
+
+        let LookupSelectors {
+            chacha,
+            chacha_final,
+            lookup_gate,
+            range_check_gate,
+            sha256,
+        } = &index.lookup_selectors;
+        for selector in [chacha, chacha_final, lookup_gate, range_check_gate, sha256] {
+            self.option(selector.as_ref(), Self::poly_comm)?;
+        }
+
+        self.option(index.table_ids.as_ref(), Self::poly_comm)?;
+        self.integer(index.max_joint_size.into());
+        self.option(index.runtime_tables_selector.as_ref(), Self::poly_comm)
+    }
+
+    fn commitments<G: AffineCurve>(&mut self, comms: &ProverCommitments<G>) -> Result<()> {
+        for comm in comms.w_comm.iter().chain([&comms.z_comm, &comms.t_comm]) {
+            self.poly_comm(comm)?;
+        }
+
+        match &comms.lookup {
+            None => self.byte(0),
+            Some(lookup) => {
+                self.byte(if lookup.runtime.is_some() { 2 } else { 1 });
+                self.length(lookup.sorted.len());
+                for comm in lookup.sorted.iter().chain([&lookup.aggreg]) {
+                    self.poly_comm(comm)?;
+                }
+                if let Some(runtime) = &lookup.runtime {
+                    self.poly_comm(runtime)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn opening<G: AffineCurve>(&mut self, proof: &OpeningProof<G>) -> Result<()> {
+        self.length(proof.lr.len());
+        for (l, r) in &proof.lr {
+            self.item(l)?;
+            self.item(r)?;
+        }
+        self.item(&proof.delta)?;
+        self.item(&proof.z1)?;
+        self.item(&proof.z2)?;
+        self.item(&proof.sg)
+    }
+
+    /// Writes vectors which usually have the same length: the length is then written once
+    /// (as `len + 1`), otherwise 0 is written and each vector is prefixed by its own length.
+    fn vectors<T: CanonicalSerialize>(&mut self, vectors: &[&Vec<T>]) -> Result<()> {
+        let len = vectors.first().map_or(0, |v| v.len());
+        if vectors.iter().all(|v| v.len() == len) {
+            self.length(len + 1);
+            vectors
+                .iter()
+                .flat_map(|v| v.iter())
+                .try_for_each(|item| self.item(item))
+        } else {
+            self.length(0);
+            vectors.iter().try_for_each(|v| self.items(v))
+        }
+    }
+
+    fn column(&mut self, col: &Column) {
+        match col {
+            Column::Witness(i) => {
+                self.byte(0);
+                self.length(*i);
+            }
+            Column::Z => self.byte(1),
+            Column::LookupSorted(i) => {
+                self.byte(2);
+                self.length(*i);
+            }
+            Column::LookupAggreg => self.byte(3),
+            Column::LookupTable => self.byte(4),
+            Column::LookupKindIndex(pattern) => {
+                self.byte(5);
+                self.byte(*pattern as u8);
+            }
+            Column::LookupRuntimeSelector => self.byte(6),
+            Column::LookupRuntimeTable => self.byte(7),
+            Column::Index(typ) => {
+                self.byte(8);
+                self.byte(*typ as u8);
+            }
+            Column::Coefficient(i) => {
+                self.byte(9);
+                self.length(*i);
+            }
+        }
+    }
+
+    fn variable(&mut self, var: &Variable) {
+        self.column(&var.col);
+        self.byte(var.row as u8);
+    }
+
+    /// Writes an expression in reverse Polish notation, prefixed by its number of tokens.
+    fn polish<F: CanonicalSerialize>(&mut self, tokens: &[PolishToken<F>]) -> Result<()> {
+        self.length(tokens.len());
+        for token in tokens {
+            match token {
+                PolishToken::Alpha => self.byte(0),
+                PolishToken::Beta => self.byte(1),
+                PolishToken::Gamma => self.byte(2),
+                PolishToken::JointCombiner => self.byte(3),
+                PolishToken::EndoCoefficient => self.byte(4),
+                PolishToken::Mds { row, col } => {
+                    self.byte(5);
+                    self.length(*row);
+                    self.length(*col);
+                }
+                PolishToken::ForeignFieldModulus(i) => {
+                    self.byte(6);
+                    self.length(*i);
+                }
+                PolishToken::Literal(x) => {
+                    self.byte(7);
+                    self.item(x)?;
+                }
+                PolishToken::Cell(var) => {
+                    self.byte(8);
+                    self.variable(var);
+                }
+                PolishToken::Dup => self.byte(9),
+                PolishToken::Pow(d) => {
+                    self.byte(10);
+                    self.integer(*d);
+                }
+                PolishToken::Add => self.byte(11),
+                PolishToken::Mul => self.byte(12),
+                PolishToken::Sub => self.byte(13),
+                PolishToken::VanishesOnZeroKnowledgeAndPreviousRows => self.byte(14),
+                PolishToken::UnnormalizedLagrangeBasis(i) => {
+                    self.byte(15);
+                    self.byte(u8::from(*i < 0));
+                    self.integer(i.unsigned_abs().into());
+                }
+                PolishToken::Store => self.byte(16),
+                PolishToken::Load(i) => {
+                    self.byte(17);
+                    self.length(*i);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the evaluations at the two evaluation points, which must have the same shape.
+    fn evaluations<F: CanonicalSerialize>(
+        &mut self,
+        evals: &[ProofEvaluations<Vec<F>>; 2],
+    ) -> Result<()> {
+        // the shape of the evaluations, shared by the two points
+        let [zeta, zeta_omega] = evals;
+        if evaluations_shape(zeta) != evaluations_shape(zeta_omega) {
+            return Err(SerializationError::InvalidData);
+        }
+        match evaluations_shape(zeta) {
+            None => self.byte(0),
+            Some((sorted, runtime)) => {
+                self.byte(if runtime { 2 } else { 1 });
+                self.length(sorted);
+            }
+        }
+
+        // the number of chunks is shared by all the vectors of the two points if possible
+        let vectors: Vec<_> = evaluation_vectors(zeta)
+            .into_iter()
+            .chain(evaluation_vectors(zeta_omega))
+            .collect();
+        self.vectors(&vectors)
+    }
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    /// Starts reading `bytes`, which must be an encoding of version `version`.
+    fn new(bytes: &'a [u8], version: u8) -> Result<Self> {
+        let mut reader = Reader(bytes);
+        if reader.byte()? != version {
+            return Err(SerializationError::InvalidData);
+        }
+        Ok(reader)
+    }
+
+    /// Checks that all the bytes were read, as trailing bytes are not part of the encoding.
+    fn finish(self) -> Result<()> {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            Err(SerializationError::InvalidData)
+        }
+    }
+
+    fn byte(&mut self) -> Result<u8> {
+        let (byte, rest) = self
+            .0
+            .split_first()
+            .ok_or(SerializationError::InvalidData)?;
+        self.0 = rest;
+        Ok(*byte)
+    }
+
+    fn integer(&mut self) -> Result<u64> {
+        let mut n = 0u64;
+        for shift in (0..u64::BITS).step_by(7) {
+            let byte = self.byte()?;
+            n |= ((byte & 0x7f) as u64)
+                .checked_shl(shift)
+                .ok_or(SerializationError::InvalidData)?;
+            if byte & 0x80 == 0 {
+                return Ok(n);
+            }
+        }
+        Err(SerializationError::InvalidData)
+    }
+
+    fn length(&mut self) -> Result<usize> {
+        usize::try_from(self.integer()?).map_err(|_| SerializationError::InvalidData)
+    }
+
+    fn big_uint(&mut self) -> Result<BigUint> {
+        let len = self.length()?;
+        if len > self.0.len() {
+            return Err(SerializationError::InvalidData);
+        }
+        let (bytes, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(BigUint::from_bytes_le(bytes))
+    }
+
+    fn option<T>(&mut self, read: impl FnOnce(&mut Self) -> Result<T>) -> Result<Option<T>> {
+        match self.byte()? {
+            0 => Ok(None),
+            1 => read(self).map(Some),
+            _ => Err(SerializationError::InvalidData),
+        }
+    }
+
+    /// Reads `N` values with `read`.
+    fn array<T, const N: usize>(
+        &mut self,
+        mut read: impl FnMut(&mut Self) -> Result<T>,
+    ) -> Result<[T; N]> {
+        (0..N)
+            .map(|_| read(self))
+            .collect::<Result<Vec<_>>>()?
+            .try_into()
+            .map_err(|_| SerializationError::InvalidData)
+    }
+
+    fn item<T: CanonicalDeserialize>(&mut self) -> Result<T> {
+        T::deserialize(&mut self.0)
+    }
+
+    fn items<T: CanonicalDeserialize>(&mut self) -> Result<Vec<T>> {
+        // the vector is not preallocated, as the length is not trusted
+        (0..self.length()?).map(|_| self.item()).collect()
+    }
+
+    fn poly_comm<G: AffineCurve>(&mut self) -> Result<PolyComm<G>> {
+        let unshifted = self.items()?;
+        let shifted = match self.byte()? {
+            0 => None,
+            1 => Some(self.item()?),
+            _ => return Err(SerializationError::InvalidData),
+        };
+        Ok(PolyComm { unshifted, shifted })
+    }
+
+    fn lookup_index<G: CommitmentCurve>(&mut self) -> Result<LookupVerifierIndex<G>> {
+        let lookup_used = match self.byte()? {
+            0 => LookupsUsed::Single,
+            1 => LookupsUsed::Joint,
+            _ => return Err(SerializationError::InvalidData),
+        };
+        let lookup_table = (0..self.length()?)
+            .map(|_| self.poly_comm())
+            .collect::<Result<_>>()?;
+        let lookup_selectors = LookupSelectors {
+            chacha: self.option(Self::poly_comm)?,
+            chacha_final: self.option(Self::poly_comm)?,
+            lookup_gate: self.option(Self::poly_comm)?,
+            range_check_gate: self.option(Self::poly_comm)?,
+            sha256: self.option(Self::poly_comm)?,
+        };
+        let table_ids = self.option(Self::poly_comm)?;
+        let max_joint_size =
+            u32::try_from(self.integer()?).map_err(|_| SerializationError::InvalidData)?;
+        let runtime_tables_selector = self.option(Self::poly_comm)?;
+
+        Ok(LookupVerifierIndex {
+            lookup_used,
+            lookup_table,
+            lookup_selectors,
+            table_ids,
+            max_joint_size,
+            runtime_tables_selector,
+        })
+    }
+
+    fn commitments<G: AffineCurve>(&mut self) -> Result<ProverCommitments<G>> {
+        let w_comm = self.array(Self::poly_comm)?;
+        let z_comm = self.poly_comm()?;
+        let t_comm = self.poly_comm()?;
+
+        let lookup = match self.byte()? {
+            0 => None,
+            flag @ (1 | 2) => {
+                let sorted = (0..self.length()?)
+                    .map(|_| self.poly_comm())
+                    .collect::<Result<_>>()?;
+                let aggreg = self.poly_comm()?;
+                let runtime = if flag == 2 {
+                    Some(self.poly_comm()?)
+                } else {
+                    None
+                };
+                Some(LookupCommitments {
+                    sorted,
+                    aggreg,
+                    runtime,
+                })
+            }
+            _ => return Err(SerializationError::InvalidData),
+        };
+
+        Ok(ProverCommitments {
+            w_comm,
+            z_comm,
+            t_comm,
+            lookup,
+        })
+    }
+
+    fn opening<G: AffineCurve>(&mut self) -> Result<OpeningProof<G>> {
+        let lr = (0..self.length()?)
+            .map(|_| Ok((self.item()?, self.item()?)))
+            .collect::<Result<_>>()?;
+        Ok(OpeningProof {
+            lr,
+            delta: self.item()?,
+            z1: self.item()?,
+            z2: self.item()?,
+            sg: self.item()?,
+        })
+    }
+
+    /// Reads the length shared by vectors written with [Writer::vectors],
+    /// or `None` if each vector is prefixed by its own length.
+    fn vectors_length(&mut self) -> Result<Option<usize>> {
+        Ok(self.length()?.checked_sub(1))
+    }
+
+    /// Reads one of the vectors written with [Writer::vectors], given their shared length.
+    fn vector<T: CanonicalDeserialize>(&mut self, len: Option<usize>) -> Result<Vec<T>> {
+        match len {
+            None => self.items(),
+            Some(len) => (0..len).map(|_| self.item()).collect(),
+        }
+    }
+
+    fn evaluations<F: CanonicalDeserialize>(&mut self) -> Result<[ProofEvaluations<Vec<F>>; 2]> {
+        let shape = match self.byte()? {
+            0 => None,
+            flag @ (1 | 2) => Some((self.length()?, flag == 2)),
+            _ => return Err(SerializationError::InvalidData),
+        };
+        let len = self.vectors_length()?;
+        Ok([
+            self.evaluations_at(shape, len)?,
+            self.evaluations_at(shape, len)?,
+        ])
+    }
+
+    /// Reads the evaluations at one of the evaluation points, given their shape
+    /// and the length of their vectors.
+    fn evaluations_at<F: CanonicalDeserialize>(
+        &mut self,
+        shape: Option<(usize, bool)>,
+        len: Option<usize>,
+    ) -> Result<ProofEvaluations<Vec<F>>> {
+        let mut vector = || self.vector(len);
+
+        // the order must match [evaluation_vectors]
+        let w: [_; COLUMNS] = array::from_fn(|_| vector());
+        let w = w
+            .into_iter()
+            .collect::<Result<Vec<_>>>()?
+            .try_into()
+            .map_err(|_| SerializationError::InvalidData)?;
+        let z = vector()?;
+        let s: [_; PERMUTS - 1] = array::from_fn(|_| vector());
+        let s = s
+            .into_iter()
+            .collect::<Result<Vec<_>>>()?
+            .try_into()
+            .map_err(|_| SerializationError::InvalidData)?;
+        let generic_selector = vector()?;
+        let poseidon_selector = vector()?;
+
+        let lookup = match shape {
+            None => None,
+            Some((sorted_len, has_runtime)) => {
+                let sorted = (0..sorted_len).map(|_| vector()).collect::<Result<_>>()?;
+                let aggreg = vector()?;
+                let table = vector()?;
+                let runtime = if has_runtime { Some(vector()?) } else { None };
+                Some(LookupEvaluations {
+                    sorted,
+                    aggreg,
+                    table,
+                    runtime,
+                })
+            }
+        };
+
+        Ok(ProofEvaluations {
+            w,
+            z,
+            s,
+            lookup,
+            generic_selector,
+            poseidon_selector,
+        })
+    }
+}
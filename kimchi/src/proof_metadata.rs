@@ -0,0 +1,211 @@
+//! This module implements an encoding of a [ProverProof] together with a section of metadata,
+//! which carries information useful to debug a fleet of provers:
+//! the version of the prover, the digest of the circuit, and the time spent proving.
+//!
+//! The metadata is not part of the proof: it is not absorbed in the transcript,
+//! and the verification of the proof ignores it.
+//! Instead, the encoding ends with a hash of everything before it,
+//! so that the metadata cannot be altered or stripped without being noticed.
+//! The hash is not a signature though, so it only protects against accidental corruption
+//! and against tampering with a proof whose hash is known from elsewhere.
+//!
+//! The encoding is:
+//!
+//! - the version [PROOF_METADATA_VERSION] of the encoding, as one byte,
+//! - the length of the proof, as 4 big-endian bytes, followed by the proof in the
+//!   [compressed encoding](crate::proof_compression),
+//! - the length of the metadata, as 4 big-endian bytes, followed by the metadata serialized
+//!   with MessagePack (no metadata is encoded with a length of 0),
+//! - the Blake2b-256 hash of all the bytes above.
+//!
+//! The metadata can be read with [read_metadata] without decoding the proof.
+
+use crate::{
+    curve::KimchiCurve, error::ProofMetadataError, proof::ProverProof,
+    verifier_index::VerifierIndex,
+};
+use ark_ec::AffineCurve;
+use blake2::{digest::consts::U32, Blake2b, Digest};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// The version of the encoding, written as its first byte
+pub const PROOF_METADATA_VERSION: u8 = 1;
+
+/// The version of the prover, as recorded in the metadata
+pub const PROVER_VERSION: &str = concat!(env!("CARGO_PKG_NAME"), " ", env!("CARGO_PKG_VERSION"));
+
+/// The size in bytes of the hashes of the encoding and of the circuit digests
+pub const HASH_SIZE: usize = 32;
+
+type Blake2b256 = Blake2b<U32>;
+
+/// Information on how a proof was created
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofMetadata {
+    /// The version of the prover (see [PROVER_VERSION])
+    pub prover_version: String,
+    /// The digest of the circuit, see [ProofMetadata::circuit_digest]
+    pub circuit_digest: [u8; HASH_SIZE],
+    /// The time spent creating the proof, in milliseconds
+    pub proving_time_ms: u64,
+    /// Free-form labels, for example the host of the prover
+    pub labels: Vec<(String, String)>,
+}
+
+impl ProofMetadata {
+    /// Creates the metadata of a proof for the circuit of `index`, created in `proving_time`.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the index cannot be encoded.
+    pub fn new<G: KimchiCurve>(
+        index: &VerifierIndex<G>,
+        proving_time: Duration,
+    ) -> Result<Self, ProofMetadataError> {
+        Ok(ProofMetadata {
+            prover_version: PROVER_VERSION.to_string(),
+            circuit_digest: Self::circuit_digest(index)?,
+            proving_time_ms: u64::try_from(proving_time.as_millis()).unwrap_or(u64::MAX),
+            labels: vec![],
+        })
+    }
+
+    /// Adds the label `key` with `value`.
+    pub fn label(mut self, key: &str, value: &str) -> Self {
+        self.labels.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// The digest of the circuit of `index`: the Blake2b-256 hash of its
+    /// [compressed encoding](crate::proof_compression).
+    /// Unlike [VerifierIndex::digest], it does not depend on a sponge.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the index cannot be encoded.
+    pub fn circuit_digest<G: KimchiCurve>(
+        index: &VerifierIndex<G>,
+    ) -> Result<[u8; HASH_SIZE], ProofMetadataError> {
+        let encoding = index
+            .compress()
+            .map_err(|e| ProofMetadataError::Encoding(e.to_string()))?;
+        Ok(Blake2b256::digest(&encoding).into())
+    }
+
+    /// Whether the proof was created for the circuit of `index`.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the index cannot be encoded.
+    pub fn is_for<G: KimchiCurve>(
+        &self,
+        index: &VerifierIndex<G>,
+    ) -> Result<bool, ProofMetadataError> {
+        Ok(self.circuit_digest == Self::circuit_digest(index)?)
+    }
+}
+
+impl<G: AffineCurve> ProverProof<G> {
+    /// Encodes the proof together with `metadata`, in the format described in [crate::proof_metadata].
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the proof or the metadata cannot be encoded.
+    pub fn to_bytes_with_metadata(
+        &self,
+        metadata: Option<&ProofMetadata>,
+    ) -> Result<Vec<u8>, ProofMetadataError> {
+        let proof = self
+            .compress()
+            .map_err(|e| ProofMetadataError::Encoding(e.to_string()))?;
+        let metadata = metadata
+            .map(rmp_serde::to_vec)
+            .transpose()
+            .map_err(|e| ProofMetadataError::Encoding(e.to_string()))?
+            .unwrap_or_default();
+
+        let mut bytes = vec![PROOF_METADATA_VERSION];
+        for section in [proof, metadata] {
+            let len = u32::try_from(section.len())
+                .map_err(|_| ProofMetadataError::Encoding("section too large".to_string()))?;
+            bytes.extend(len.to_be_bytes());
+            bytes.extend(section);
+        }
+        let hash = Blake2b256::digest(&bytes);
+        bytes.extend(hash);
+        Ok(bytes)
+    }
+
+    /// Decodes a proof and its metadata encoded with [ProverProof::to_bytes_with_metadata].
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the encoding is malformed or altered.
+    pub fn from_bytes_with_metadata(
+        bytes: &[u8],
+    ) -> Result<(Self, Option<ProofMetadata>), ProofMetadataError> {
+        let (proof, metadata) = sections(bytes)?;
+        let proof = ProverProof::decompress(proof)
+            .map_err(|e| ProofMetadataError::Encoding(e.to_string()))?;
+        Ok((proof, decode_metadata(metadata)?))
+    }
+}
+
+/// Reads the metadata of a proof encoded with [ProverProof::to_bytes_with_metadata],
+/// without decoding nor verifying the proof.
+///
+/// # Errors
+///
+/// Will give error if the encoding is malformed or altered.
+pub fn read_metadata(bytes: &[u8]) -> Result<Option<ProofMetadata>, ProofMetadataError> {
+    let (_, metadata) = sections(bytes)?;
+    decode_metadata(metadata)
+}
+
+/// Checks the version and the hash of the encoding, and splits it into the proof and the metadata.
+fn sections(bytes: &[u8]) -> Result<(&[u8], &[u8]), ProofMetadataError> {
+    if bytes.len() < HASH_SIZE {
+        return Err(ProofMetadataError::Truncated);
+    }
+    let (content, hash) = bytes.split_at(bytes.len() - HASH_SIZE);
+    if Blake2b256::digest(content).as_slice() != hash {
+        return Err(ProofMetadataError::Hash);
+    }
+
+    let (version, mut rest) = content.split_first().ok_or(ProofMetadataError::Truncated)?;
+    if *version != PROOF_METADATA_VERSION {
+        return Err(ProofMetadataError::UnsupportedVersion(*version));
+    }
+
+    let mut section = || {
+        if rest.len() < 4 {
+            return Err(ProofMetadataError::Truncated);
+        }
+        let (len, tail) = rest.split_at(4);
+        let len = u32::from_be_bytes([len[0], len[1], len[2], len[3]]) as usize;
+        if tail.len() < len {
+            return Err(ProofMetadataError::Truncated);
+        }
+        let (section, tail) = tail.split_at(len);
+        rest = tail;
+        Ok(section)
+    };
+    let proof = section()?;
+    let metadata = section()?;
+
+    if !rest.is_empty() {
+        return Err(ProofMetadataError::TrailingBytes);
+    }
+    Ok((proof, metadata))
+}
+
+/// Decodes the metadata section, which is empty if there is no metadata
+fn decode_metadata(bytes: &[u8]) -> Result<Option<ProofMetadata>, ProofMetadataError> {
+    if bytes.is_empty() {
+        return Ok(None);
+    }
+    rmp_serde::from_slice(bytes)
+        .map(Some)
+        .map_err(|e| ProofMetadataError::Encoding(e.to_string()))
+}
@@ -3,30 +3,36 @@
 use crate::{
     circuits::{
         argument::{Argument, ArgumentType},
-        expr::{l0_1, Constants, Environment, LookupEnvironment},
+        expr::{l0_1, Constants, Environment, LookupEnvironment, E},
         gate::GateType,
         lookup::{
-            self, lookups::LookupsUsed, runtime_tables::RuntimeTable, tables::combine_table_entry,
+            self,
+            lookups::LookupsUsed,
+            runtime_tables::{order_as_configured, RuntimeTable},
+            tables::combine_table_entry,
         },
+        polynomial::WitnessOverDomains,
         polynomials::{
+            boolean_columns,
             chacha::{ChaCha0, ChaCha1, ChaCha2, ChaChaFinal},
             complete_add::CompleteAdd,
             endomul_scalar::EndomulScalar,
             endosclmul::EndosclMul,
             foreign_field_add, generic, permutation,
-            permutation::ZK_ROWS,
             poseidon::Poseidon,
-            range_check,
+            range_check, sha256,
             varbasemul::VarbaseMul,
         },
         wires::{COLUMNS, PERMUTS},
     },
     curve::KimchiCurve,
     error::ProverError,
+    parallel::{prelude::*, Partitioning},
     plonk_sponge::FrSponge,
+    profiling::{PhaseSpan, ProverPhase},
     proof::{
-        LookupCommitments, LookupEvaluations, ProofEvaluations, ProverCommitments, ProverProof,
-        RecursionChallenge,
+        LookupCommitments, LookupEvaluations, PrevRowEvaluations, ProofEvaluations,
+        ProverCommitments, ProverProof, RecursionChallenge,
     },
     prover_index::ProverIndex,
 };
@@ -42,9 +48,10 @@ use commitment_dlog::commitment::{
 use itertools::Itertools;
 use o1_utils::ExtendedDensePolynomial as _;
 use oracle::{sponge::ScalarChallenge, FqSponge};
-use rayon::prelude::*;
+use rand::{CryptoRng, RngCore};
 use std::array;
 use std::collections::HashMap;
+use std::sync::mpsc;
 
 /// The result of a proof creation or verification.
 type Result<T> = std::result::Result<T, ProverError>;
@@ -102,6 +109,7 @@ where
     /// The evaluations of the aggregation polynomial for the proof
     eval_zeta: Option<LookupEvaluations<Vec<F>>>,
     eval_zeta_omega: Option<LookupEvaluations<Vec<F>>>,
+    eval_zeta_prev: Option<LookupEvaluations<Vec<F>>>,
 
     /// Runtime table
     runtime_table: Option<DensePolynomial<F>>,
@@ -110,6 +118,58 @@ where
     runtime_second_col_d8: Option<Evaluations<F, D<F>>>,
 }
 
+/// A constraint folded into the quotient polynomial by the prover
+struct QuotientConstraint<F> {
+    /// The name reported when the witness does not satisfy the constraint
+    label: String,
+    /// The expression of the constraint, with its repeated sub-expressions cached
+    expr: E<F>,
+    /// The power of alpha the evaluations are scaled by, if not already in the expression
+    alpha_pow: Option<F>,
+    /// Whether the expression has so many terms that it is always evaluated in chunks
+    chunked: bool,
+}
+
+impl<F: Field> QuotientConstraint<F> {
+    fn new(label: impl Into<String>, expr: E<F>) -> Self {
+        QuotientConstraint {
+            label: label.into(),
+            expr: expr.share_subexpressions(),
+            alpha_pow: None,
+            chunked: false,
+        }
+    }
+
+    fn chunked(label: impl Into<String>, expr: E<F>) -> Self {
+        QuotientConstraint {
+            chunked: true,
+            ..Self::new(label, expr)
+        }
+    }
+
+    fn scaled(self, alpha_pow: F) -> Self {
+        QuotientConstraint {
+            alpha_pow: Some(alpha_pow),
+            ..self
+        }
+    }
+}
+
+/// Hands over the columns of a witness to the prover as soon as they are generated,
+/// see [ProverProof::create_with_incremental_witness]
+pub struct WitnessCommitter<F> {
+    sender: mpsc::Sender<(usize, Vec<F>)>,
+}
+
+impl<F> WitnessCommitter<F> {
+    /// Hands over the column `col` of the witness, of the length of the circuit,
+    /// to be committed to while the next columns are generated
+    pub fn commit(&self, col: usize, column: Vec<F>) {
+        // the committer only stops receiving columns on an error, which the prover returns
+        let _ = self.sender.send((col, column));
+    }
+}
+
 impl<G: KimchiCurve> ProverProof<G>
 where
     G::BaseField: PrimeField,
@@ -138,6 +198,36 @@ where
         )
     }
 
+    /// Same as [ProverProof::create], but all the randomness of the proof (the blinders
+    /// of the commitments and the zero-knowledge rows) is drawn from `rng`.
+    /// With a seeded `rng`, the proof is reproducible byte-for-byte,
+    /// which is useful for test fixtures and to compare against other implementations.
+    /// Such a proof is only zero-knowledge if the seed is kept secret.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if `create_recursive_with_rng` process fails.
+    pub fn create_with_rng<
+        EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+        EFrSponge: FrSponge<G::ScalarField>,
+    >(
+        groupmap: &G::Map,
+        witness: [Vec<G::ScalarField>; COLUMNS],
+        runtime_tables: &[RuntimeTable<G::ScalarField>],
+        index: &ProverIndex<G>,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> Result<Self> {
+        Self::create_recursive_with_rng::<EFqSponge, EFrSponge>(
+            groupmap,
+            witness,
+            runtime_tables,
+            index,
+            Vec::new(),
+            None,
+            rng,
+        )
+    }
+
     /// This function constructs prover's recursive zk-proof from the witness & the `ProverIndex` against SRS instance
     ///
     /// # Errors
@@ -152,23 +242,45 @@ where
         EFrSponge: FrSponge<G::ScalarField>,
     >(
         group_map: &G::Map,
-        mut witness: [Vec<G::ScalarField>; COLUMNS],
+        witness: [Vec<G::ScalarField>; COLUMNS],
         runtime_tables: &[RuntimeTable<G::ScalarField>],
         index: &ProverIndex<G>,
         prev_challenges: Vec<RecursionChallenge<G>>,
         blinders: Option<[Option<PolyComm<G::ScalarField>>; COLUMNS]>,
     ) -> Result<Self> {
-        // make sure that the SRS is not smaller than the domain size
-        let d1_size = index.cs.domain.d1.size();
-        if index.srs.max_degree() < d1_size {
-            return Err(ProverError::SRSTooSmall);
-        }
-
-        let (_, endo_r) = G::endos();
-
-        // TODO: rng should be passed as arg
-        let rng = &mut rand::rngs::OsRng;
+        Self::create_recursive_with_rng::<EFqSponge, EFrSponge>(
+            group_map,
+            witness,
+            runtime_tables,
+            index,
+            prev_challenges,
+            blinders,
+            &mut rand::rngs::OsRng,
+        )
+    }
 
+    /// Same as [ProverProof::create_recursive], but all the randomness of the proof
+    /// is drawn from `rng` (see [ProverProof::create_with_rng]).
+    ///
+    /// # Errors
+    ///
+    /// Will give error if inputs(like `lookup_context.joint_lookup_table_d8`) are None.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `lookup_context.joint_lookup_table_d8` is None.
+    pub fn create_recursive_with_rng<
+        EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+        EFrSponge: FrSponge<G::ScalarField>,
+    >(
+        group_map: &G::Map,
+        mut witness: [Vec<G::ScalarField>; COLUMNS],
+        runtime_tables: &[RuntimeTable<G::ScalarField>],
+        index: &ProverIndex<G>,
+        prev_challenges: Vec<RecursionChallenge<G>>,
+        blinders: Option<[Option<PolyComm<G::ScalarField>>; COLUMNS]>,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> Result<Self> {
         // double-check the witness
         if cfg!(debug_assertions) {
             let public = witness[0][0..index.cs.public].to_vec();
@@ -178,36 +290,300 @@ where
                 .expect("incorrect witness");
         }
 
+        let phase = PhaseSpan::new(ProverPhase::WitnessCommitment);
+
         //~ 1. Ensure we have room in the witness for the zero-knowledge rows.
         //~    We currently expect the witness not to be of the same length as the domain,
         //~    but instead be of the length of the (smaller) circuit.
-        //~    If we cannot add `ZK_ROWS` rows to the columns of the witness before reaching
+        //~    If we cannot add `zk_rows` rows to the columns of the witness before reaching
         //~    the size of the domain, abort.
         let length_witness = witness[0].len();
-        let length_padding = d1_size
+        let length_padding = Self::witness_padding(index, length_witness)?;
+
+        //~ 1. Pad the witness columns with Zero gates to make them the same length as the domain.
+        //~    Then, randomize the last `zk_rows` of each columns (unless zero-knowledge is disabled).
+        for w in &mut witness {
+            if w.len() != length_witness {
+                return Err(ProverError::WitnessCsInconsistent);
+            }
+            Self::pad_witness_column(index, w, length_padding, rng);
+        }
+
+        //~ 1. Commit to the witness columns by creating `COLUMNS` hidding commitments.
+        //~
+        //~    Note: since the witness is in evaluation form,
+        //~    we can use the `commit_evaluation` optimization.
+        //~    The columns can be committed to concurrently, as they are then blinded in order.
+        let w_comm_non_hiding: Vec<_> = match index.parallelism().witness_commitments {
+            Partitioning::Rows => witness
+                .iter()
+                .map(|w| Self::commit_witness_column_non_hiding(index, w))
+                .collect(),
+            Partitioning::Columns => witness
+                .par_iter()
+                .map(|w| Self::commit_witness_column_non_hiding(index, w))
+                .collect(),
+        };
+        let mut w_comm = vec![];
+        for (col, comm) in w_comm_non_hiding.into_iter().enumerate() {
+            let blinder = blinders.as_ref().and_then(|b| b[col].as_ref());
+            w_comm.push(Self::blind_witness_column(index, comm, blinder, rng)?);
+        }
+
+        let w_comm: [BlindedCommitment<G>; COLUMNS] = w_comm
+            .try_into()
+            .expect("previous loop is of the correct length");
+
+        Self::create_from_committed_witness::<EFqSponge, EFrSponge>(
+            group_map,
+            witness,
+            w_comm,
+            runtime_tables,
+            index,
+            prev_challenges,
+            rng,
+            phase,
+        )
+    }
+
+    /// Same as [ProverProof::create_recursive_with_rng] without blinders, but the witness is handed over
+    /// column by column by `generate`, through a [WitnessCommitter].
+    /// Each column is padded and committed on another thread as soon as it is handed over,
+    /// so that the commitments to the witness, which are multi-scalar multiplications
+    /// of the size of the domain, are computed while the next columns are generated.
+    ///
+    /// Each of the `COLUMNS` columns must be handed over exactly once, in any order,
+    /// with the length of the circuit.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if a column is not handed over, is handed over twice,
+    /// or is not of the same length as the others, or if `create_recursive_with_rng` process fails.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `generate` panics.
+    pub fn create_with_incremental_witness<
+        EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+        EFrSponge: FrSponge<G::ScalarField>,
+    >(
+        group_map: &G::Map,
+        generate: impl FnOnce(&WitnessCommitter<G::ScalarField>),
+        runtime_tables: &[RuntimeTable<G::ScalarField>],
+        index: &ProverIndex<G>,
+        prev_challenges: Vec<RecursionChallenge<G>>,
+        rng: &mut (impl RngCore + CryptoRng + Send),
+    ) -> Result<Self> {
+        let phase = PhaseSpan::new(ProverPhase::WitnessCommitment);
+        let committer_rng = &mut *rng;
+        #[cfg(not(feature = "sequential"))]
+        let (witness, length_witness, w_comm) = std::thread::scope(|scope| {
+            let (sender, receiver) = mpsc::channel();
+            let committer =
+                scope.spawn(move || Self::commit_witness_columns(index, &receiver, committer_rng));
+
+            // the sender is dropped once the witness is generated, which ends the commitments
+            generate(&WitnessCommitter { sender });
+
+            committer.join().expect("the witness committer panicked")
+        })?;
+        // without threads, the columns are committed to once they are all generated
+        #[cfg(feature = "sequential")]
+        let (witness, length_witness, w_comm) = {
+            let (sender, receiver) = mpsc::channel();
+            generate(&WitnessCommitter { sender });
+            Self::commit_witness_columns(index, &receiver, committer_rng)?
+        };
+
+        // double-check the witness, without its padding
+        if cfg!(debug_assertions) {
+            let unpadded: [Vec<G::ScalarField>; COLUMNS] =
+                array::from_fn(|i| witness[i][..length_witness].to_vec());
+            let public = witness[0][0..index.cs.public].to_vec();
+            index
+                .cs
+                .verify::<G>(&unpadded, &public)
+                .expect("incorrect witness");
+        }
+
+        Self::create_from_committed_witness::<EFqSponge, EFrSponge>(
+            group_map,
+            witness,
+            w_comm,
+            runtime_tables,
+            index,
+            prev_challenges,
+            rng,
+            phase,
+        )
+    }
+
+    /// Returns the number of rows to add to a witness of `length_witness` rows
+    /// to reach the size of the domain
+    fn witness_padding(index: &ProverIndex<G>, length_witness: usize) -> Result<usize> {
+        let length_padding = index
+            .cs
+            .domain
+            .d1
+            .size()
             .checked_sub(length_witness)
             .ok_or(ProverError::NoRoomForZkInWitness)?;
 
-        if length_padding < ZK_ROWS as usize {
+        if length_padding < index.cs.zk_rows as usize {
             return Err(ProverError::NoRoomForZkInWitness);
         }
 
-        //~ 1. Pad the witness columns with Zero gates to make them the same length as the domain.
-        //~    Then, randomize the last `ZK_ROWS` of each columns.
-        for w in &mut witness {
-            if w.len() != length_witness {
+        Ok(length_padding)
+    }
+
+    /// Pads a column of the witness with `length_padding` zeros,
+    /// and randomizes its last [crate::circuits::constraints::ConstraintSystem::random_rows] rows
+    fn pad_witness_column(
+        index: &ProverIndex<G>,
+        column: &mut Vec<G::ScalarField>,
+        length_padding: usize,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) {
+        // padding
+        column.extend(std::iter::repeat(G::ScalarField::zero()).take(length_padding));
+
+        // zk-rows
+        for row in column
+            .iter_mut()
+            .rev()
+            .take(index.cs.random_rows() as usize)
+        {
+            *row = <G::ScalarField as UniformRand>::rand(rng);
+        }
+    }
+
+    /// Commits to a padded column of the witness, hidden with `blinder` if given, or with a random blinder
+    fn commit_witness_column(
+        index: &ProverIndex<G>,
+        column: &[G::ScalarField],
+        blinder: Option<&PolyComm<G::ScalarField>>,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> Result<BlindedCommitment<G>> {
+        let witness_com = Self::commit_witness_column_non_hiding(index, column);
+        Self::blind_witness_column(index, witness_com, blinder, rng)
+    }
+
+    /// Commits to a padded column of the witness, without hiding it
+    fn commit_witness_column_non_hiding(
+        index: &ProverIndex<G>,
+        column: &[G::ScalarField],
+    ) -> PolyComm<G> {
+        // witness coeff -> witness eval
+        let witness_eval = Evaluations::<G::ScalarField, D<G::ScalarField>>::from_vec_and_domain(
+            column.to_vec(),
+            index.cs.domain.d1,
+        );
+        index
+            .srs
+            .commit_evaluations_non_hiding(index.cs.domain.d1, &witness_eval, None)
+    }
+
+    /// Hides the commitment to a column of the witness with `blinder` if given, or with a random blinder
+    fn blind_witness_column(
+        index: &ProverIndex<G>,
+        witness_com: PolyComm<G>,
+        blinder: Option<&PolyComm<G::ScalarField>>,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> Result<BlindedCommitment<G>> {
+        match blinder {
+            // no blinders: blind the witness
+            None => Ok(Self::mask(index, witness_com, rng)),
+            // blinders: blind the witness with them
+            Some(blinder) => index
+                .srs
+                .mask_custom(witness_com, blinder)
+                .map_err(ProverError::WrongBlinders),
+        }
+    }
+
+    /// Hides `comm` with random blinders, or with zero blinders
+    /// when zero-knowledge is disabled (see [crate::circuits::constraints::ConstraintSystem::zk])
+    fn mask(
+        index: &ProverIndex<G>,
+        comm: PolyComm<G>,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> BlindedCommitment<G> {
+        if index.cs.zk {
+            index.srs.mask(comm, rng)
+        } else {
+            BlindedCommitment {
+                blinders: comm.map(|_| G::ScalarField::zero()),
+                commitment: comm,
+            }
+        }
+    }
+
+    /// Pads and commits to the columns of the witness received from `receiver`, as they arrive.
+    /// Returns the padded witness, its length before padding, and its commitments.
+    #[allow(clippy::type_complexity)]
+    fn commit_witness_columns(
+        index: &ProverIndex<G>,
+        receiver: &mpsc::Receiver<(usize, Vec<G::ScalarField>)>,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> Result<(
+        [Vec<G::ScalarField>; COLUMNS],
+        usize,
+        [BlindedCommitment<G>; COLUMNS],
+    )> {
+        let mut witness: [Option<Vec<G::ScalarField>>; COLUMNS] = array::from_fn(|_| None);
+        let mut w_comm: [Option<BlindedCommitment<G>>; COLUMNS] = array::from_fn(|_| None);
+        let mut length_witness = None;
+
+        for (col, mut column) in receiver {
+            if col >= COLUMNS {
+                return Err(ProverError::InvalidWitnessColumn(col));
+            }
+            if witness[col].is_some() {
+                return Err(ProverError::DuplicateWitnessColumn(col));
+            }
+            if column.len() != *length_witness.get_or_insert(column.len()) {
                 return Err(ProverError::WitnessCsInconsistent);
             }
 
-            // padding
-            w.extend(std::iter::repeat(G::ScalarField::zero()).take(length_padding));
+            let length_padding = Self::witness_padding(index, column.len())?;
+            Self::pad_witness_column(index, &mut column, length_padding, rng);
+            w_comm[col] = Some(Self::commit_witness_column(index, &column, None, rng)?);
+            witness[col] = Some(column);
+        }
 
-            // zk-rows
-            for row in w.iter_mut().rev().take(ZK_ROWS as usize) {
-                *row = <G::ScalarField as UniformRand>::rand(rng);
-            }
+        if let Some(col) = witness.iter().position(Option::is_none) {
+            return Err(ProverError::MissingWitnessColumn(col));
         }
 
+        Ok((
+            witness.map(|w| w.expect("all the columns were checked to be present")),
+            length_witness.expect("all the columns were checked to be present"),
+            w_comm.map(|c| c.expect("all the columns were checked to be present")),
+        ))
+    }
+
+    /// Creates the proof of a padded witness, whose columns are committed to by `w_comm`,
+    /// continuing from the phase `phase`
+    #[allow(clippy::too_many_arguments)]
+    fn create_from_committed_witness<
+        EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+        EFrSponge: FrSponge<G::ScalarField>,
+    >(
+        group_map: &G::Map,
+        witness: [Vec<G::ScalarField>; COLUMNS],
+        w_comm: [BlindedCommitment<G>; COLUMNS],
+        runtime_tables: &[RuntimeTable<G::ScalarField>],
+        index: &ProverIndex<G>,
+        prev_challenges: Vec<RecursionChallenge<G>>,
+        rng: &mut (impl RngCore + CryptoRng),
+        mut phase: PhaseSpan,
+    ) -> Result<Self> {
+        // the SRS can be smaller than the domain, in which case the commitments are chunked
+        let d1_size = index.cs.domain.d1.size();
+        let num_chunks = index.num_chunks();
+
+        let (_, endo_r) = G::endos();
+
         //~ 1. Setup the Fq-Sponge.
         let mut fq_sponge = EFqSponge::new(G::OtherCurve::sponge_params());
 
@@ -240,46 +616,6 @@ where
         //~    This is why we need to absorb the commitment to the public polynomial at this point.
         fq_sponge.absorb_g(&public_comm.unshifted);
 
-        //~ 1. Commit to the witness columns by creating `COLUMNS` hidding commitments.
-        //~
-        //~    Note: since the witness is in evaluation form,
-        //~    we can use the `commit_evaluation` optimization.
-        let mut w_comm = vec![];
-        for col in 0..COLUMNS {
-            // witness coeff -> witness eval
-            let witness_eval =
-                Evaluations::<G::ScalarField, D<G::ScalarField>>::from_vec_and_domain(
-                    witness[col].clone(),
-                    index.cs.domain.d1,
-                );
-
-            let com = match blinders.as_ref().and_then(|b| b[col].as_ref()) {
-                // no blinders: blind the witness
-                None => index
-                    .srs
-                    .commit_evaluations(index.cs.domain.d1, &witness_eval, None, rng),
-                // blinders: blind the witness with them
-                Some(blinder) => {
-                    // TODO: make this a function rather no? mask_with_custom()
-                    let witness_com = index.srs.commit_evaluations_non_hiding(
-                        index.cs.domain.d1,
-                        &witness_eval,
-                        None,
-                    );
-                    index
-                        .srs
-                        .mask_custom(witness_com, blinder)
-                        .map_err(ProverError::WrongBlinders)?
-                }
-            };
-
-            w_comm.push(com);
-        }
-
-        let w_comm: [BlindedCommitment<G>; COLUMNS] = w_comm
-            .try_into()
-            .expect("previous loop is of the correct length");
-
         //~ 1. Absorb the witness commitments with the Fq-Sponge.
         w_comm
             .iter()
@@ -299,21 +635,14 @@ where
 
         //~ 1. If using lookup:
         if let Some(lcs) = &index.cs.lookup_constraint_system {
+            phase.next(ProverPhase::Lookup);
+
             //~~ - if using runtime table:
             if let Some(cfg_runtime_tables) = &lcs.runtime_tables {
-                //~~~ - check that all the provided runtime tables have length and IDs that match the runtime table configuration of the index
-                //~~~   we expect the given runtime tables to be sorted as configured, this makes it easier afterwards
-                let expected_runtime: Vec<_> = cfg_runtime_tables
-                    .iter()
-                    .map(|rt| (rt.id, rt.len))
-                    .collect();
-                let runtime: Vec<_> = runtime_tables
-                    .iter()
-                    .map(|rt| (rt.id, rt.data.len()))
-                    .collect();
-                if expected_runtime != runtime {
-                    return Err(ProverError::RuntimeTablesInconsistent);
-                }
+                //~~~ - check that all the provided runtime tables have length and IDs that match the runtime table configuration of the index,
+                //~~~   and order them as configured
+                let runtime_tables = order_as_configured(cfg_runtime_tables, runtime_tables)
+                    .ok_or(ProverError::RuntimeTablesInconsistent)?;
 
                 //~~~ - calculate the contribution to the second column of the lookup table
                 //~~~   (the runtime vector)
@@ -330,7 +659,7 @@ where
                     }
 
                     // zero-knowledge
-                    for e in evals.iter_mut().rev().take(ZK_ROWS as usize) {
+                    for e in evals.iter_mut().rev().take(index.cs.random_rows() as usize) {
                         *e = <G::ScalarField as UniformRand>::rand(rng);
                     }
 
@@ -346,7 +675,13 @@ where
 
                 // commit the runtime polynomial
                 // (and save it to the proof)
-                let runtime_table_comm = index.srs.commit(&runtime_table_contribution, None, rng);
+                let runtime_table_comm = Self::mask(
+                    index,
+                    index
+                        .srs
+                        .commit_non_hiding(&runtime_table_contribution, None),
+                    rng,
+                );
 
                 // absorb the commitment
                 fq_sponge.absorb_g(&runtime_table_comm.commitment.unshifted);
@@ -459,6 +794,7 @@ where
                 dummy_lookup_value,
                 &joint_lookup_table_d8,
                 index.cs.domain.d1,
+                index.cs.zk_rows,
                 &index.cs.gates,
                 &witness,
                 joint_combiner,
@@ -466,20 +802,28 @@ where
                 &lcs.configuration.lookup_info,
             )?;
 
-            //~~ - Randomize the last `EVALS` rows in each of the sorted polynomials
-            //~~   in order to add zero-knowledge to the protocol.
+            //~~ - Randomize the last `zk_rows` rows in each of the sorted polynomials
+            //~~   in order to add zero-knowledge to the protocol (unless zero-knowledge is disabled).
             let sorted: Vec<_> = sorted
                 .into_iter()
-                .map(|chunk| lookup::constraints::zk_patch(chunk, index.cs.domain.d1, rng))
+                .map(|chunk| {
+                    lookup::constraints::zk_patch(
+                        chunk,
+                        index.cs.domain.d1,
+                        index.cs.random_rows(),
+                        rng,
+                    )
+                })
                 .collect();
 
             //~~ - Commit each of the sorted polynomials.
             let sorted_comms: Vec<_> = sorted
                 .iter()
                 .map(|v| {
-                    index
+                    let comm = index
                         .srs
-                        .commit_evaluations(index.cs.domain.d1, v, None, rng)
+                        .commit_evaluations_non_hiding(index.cs.domain.d1, v, None);
+                    Self::mask(index, comm, rng)
                 })
                 .collect();
 
@@ -505,6 +849,8 @@ where
             lookup_context.joint_lookup_table = Some(joint_lookup_table);
         }
 
+        phase.next(ProverPhase::Permutation);
+
         //~ 1. Sample $\beta$ with the Fq-Sponge.
         let beta = fq_sponge.challenge();
 
@@ -520,6 +866,8 @@ where
                 lookup_context.dummy_lookup_value.unwrap(),
                 joint_lookup_table_d8,
                 index.cs.domain.d1,
+                index.cs.zk_rows,
+                index.cs.random_rows(),
                 &index.cs.gates,
                 &witness,
                 &lookup_context.joint_combiner.unwrap(),
@@ -532,9 +880,13 @@ where
             )?;
 
             //~~ - Commit to the aggregation polynomial.
-            let aggreg_comm = index
-                .srs
-                .commit_evaluations(index.cs.domain.d1, &aggreg, None, rng);
+            let aggreg_comm = Self::mask(
+                index,
+                index
+                    .srs
+                    .commit_evaluations_non_hiding(index.cs.domain.d1, &aggreg, None),
+                rng,
+            );
 
             //~~ - Absorb the commitment to the aggregation polynomial with the Fq-Sponge.
             fq_sponge.absorb_g(&aggreg_comm.commitment.unshifted);
@@ -554,11 +906,13 @@ where
         let z_poly = index.cs.perm_aggreg(&witness, &beta, &gamma, rng)?;
 
         //~ 1. Commit (hidding) to the permutation aggregation polynomial $z$.
-        let z_comm = index.srs.commit(&z_poly, None, rng);
+        let z_comm = Self::mask(index, index.srs.commit_non_hiding(&z_poly, None), rng);
 
         //~ 1. Absorb the permutation aggregation polynomial $z$ with the Fq-Sponge.
         fq_sponge.absorb_g(&z_comm.commitment.unshifted);
 
+        phase.next(ProverPhase::Quotient);
+
         //~ 1. Sample $\alpha'$ with the Fq-Sponge.
         let alpha_chal = ScalarChallenge(fq_sponge.challenge());
 
@@ -592,8 +946,24 @@ where
             None
         };
 
-        let lagrange = index.cs.evaluate(&witness_poly, &z_poly);
-        let env = {
+        let WitnessOverDomains {
+            d4: witness_d4,
+            d8: witness_d8,
+        } = index.cs.evaluate(&witness_poly, &z_poly);
+
+        // generic, the only constraint computed from the evaluations of the witness over d4
+        let alphas =
+            all_alphas.get_alphas(ArgumentType::Gate(GateType::Generic), generic::CONSTRAINTS);
+        let generic4 = index.cs.gnrc_quot(alphas, &witness_d4.this.w);
+        drop(witness_d4);
+
+        // once folded into the quotient, the evaluations of the witness over d8 are freed,
+        // and the environment is only kept for the index columns used by the linearization
+        let freed_witness: [Evaluations<G::ScalarField, D<G::ScalarField>>; COLUMNS] =
+            array::from_fn(|_| Evaluations::from_vec_and_domain(vec![], index.cs.domain.d8));
+        let freed_z = Evaluations::from_vec_and_domain(vec![], index.cs.domain.d8);
+
+        let (index_evals, constants) = {
             let mut index_evals = HashMap::new();
             use GateType::*;
             index_evals.insert(Poseidon, &index.cs.ps8);
@@ -628,174 +998,251 @@ where
                 );
             }
 
-            let mds = &G::sponge_params().mds;
-            Environment {
-                constants: Constants {
-                    alpha,
-                    beta,
-                    gamma,
-                    joint_combiner: lookup_context.joint_combiner,
-                    endo_coefficient: index.cs.endo,
-                    mds,
-                    foreign_field_modulus: index.cs.foreign_field_modulus.clone(),
-                },
-                witness: &lagrange.d8.this.w,
-                coefficient: &index.cs.coefficients8,
-                vanishes_on_last_4_rows: &index.cs.precomputations().vanishes_on_last_4_rows,
-                z: &lagrange.d8.this.z,
-                l0_1: l0_1(index.cs.domain.d1),
-                domain: index.cs.domain,
-                index: index_evals,
-                lookup: lookup_env,
+            if let Some(polys) = &index.cs.sha256_selector_polys {
+                index_evals.extend(
+                    sha256::gadget::circuit_gates()
+                        .iter()
+                        .enumerate()
+                        .map(|(i, gate_type)| (*gate_type, &polys[i].eval8)),
+                );
             }
-        };
 
-        let quotient_poly = {
-            // generic
-            let alphas =
-                all_alphas.get_alphas(ArgumentType::Gate(GateType::Generic), generic::CONSTRAINTS);
-            let mut t4 = index.cs.gnrc_quot(alphas, &lagrange.d4.this.w);
+            index_evals.extend(
+                index
+                    .cs
+                    .custom_gates
+                    .gate_types()
+                    .zip(&index.cs.custom_selector_polys)
+                    .map(|(gate_type, poly)| (gate_type, &poly.eval8)),
+            );
 
-            if cfg!(debug_assertions) {
-                let p4 = public_poly.evaluate_over_domain_by_ref(index.cs.domain.d4);
-                let gen_minus_pub = &t4 + &p4;
+            let mds = &G::sponge_params().mds;
+            let constants = Constants {
+                alpha,
+                beta,
+                gamma,
+                joint_combiner: lookup_context.joint_combiner,
+                endo_coefficient: index.cs.endo,
+                mds,
+                foreign_field_modulus: index.cs.foreign_field_modulus.clone(),
+                zk_rows: index.cs.zk_rows,
+            };
+            (index_evals, constants)
+        };
 
-                check_constraint!(index, gen_minus_pub);
-            }
+        let env = Environment {
+            constants: constants.clone(),
+            witness: &witness_d8.this.w,
+            coefficient: &index.cs.coefficients8,
+            vanishes_on_zero_knowledge_and_previous_rows: &index
+                .cs
+                .precomputations()
+                .vanishes_on_zero_knowledge_and_previous_rows,
+            z: &witness_d8.this.z,
+            l0_1: l0_1(index.cs.domain.d1),
+            domain: index.cs.domain,
+            index: index_evals.clone(),
+            lookup: lookup_env.clone(),
+        };
+
+        // with a memory budget, all the constraints are evaluated in chunks sized to fit it,
+        // and each evaluation is freed as soon as it is folded into the quotient
+        let chunk_size = index.evaluation_chunk_size();
+        let parallelism = index.parallelism();
 
+        // the constraints of the gates and of the lookup argument
+        let mut constraints = vec![
             // complete addition
-            {
-                let add_constraint = CompleteAdd::combined_constraints(&all_alphas);
-                let add4 = add_constraint.evaluations(&env);
-                t4 += &add4;
+            QuotientConstraint::new("add4", CompleteAdd::combined_constraints(&all_alphas)),
+            // scalar multiplication
+            QuotientConstraint::chunked("mul8", VarbaseMul::combined_constraints(&all_alphas)),
+            // endoscaling
+            QuotientConstraint::chunked("emul8", EndosclMul::combined_constraints(&all_alphas)),
+            // endoscaling scalar computation
+            QuotientConstraint::chunked(
+                "emulscalar8",
+                EndomulScalar::combined_constraints(&all_alphas),
+            ),
+            // poseidon
+            QuotientConstraint::chunked("pos8", Poseidon::combined_constraints(&all_alphas)),
+        ];
+
+        // chacha
+        if index.cs.chacha8.as_ref().is_some() {
+            constraints.extend([
+                QuotientConstraint::new("chacha0", ChaCha0::combined_constraints(&all_alphas)),
+                QuotientConstraint::new("chacha1", ChaCha1::combined_constraints(&all_alphas)),
+                QuotientConstraint::new("chacha2", ChaCha2::combined_constraints(&all_alphas)),
+                QuotientConstraint::new(
+                    "chacha_final",
+                    ChaChaFinal::combined_constraints(&all_alphas),
+                ),
+            ]);
+        }
 
-                check_constraint!(index, add4);
+        // range check gates
+        if index.cs.range_check_selector_polys.is_some() {
+            for gate_type in range_check::gadget::circuit_gates() {
+                constraints.push(QuotientConstraint::new(
+                    "range_check_constraint",
+                    range_check::gadget::circuit_gate_constraints(gate_type, &all_alphas),
+                ));
             }
+        }
 
-            // permutation
-            let (mut t8, bnd) = {
-                let alphas =
-                    all_alphas.get_alphas(ArgumentType::Permutation, permutation::CONSTRAINTS);
-                let (perm, bnd) = index
-                    .cs
-                    .perm_quot(&lagrange, beta, gamma, &z_poly, alphas)?;
+        // foreign field addition
+        if index.cs.foreign_field_add_selector_poly.is_some() {
+            constraints.push(QuotientConstraint::new(
+                "ffadd",
+                foreign_field_add::gadget::combined_constraints(&all_alphas),
+            ));
+        }
 
-                check_constraint!(index, perm);
+        // SHA-256 gates
+        if index.cs.sha256_selector_polys.is_some() {
+            constraints.push(QuotientConstraint::new(
+                "sha256_constraint",
+                sha256::gadget::combined_constraints(&all_alphas),
+            ));
+        }
 
-                (perm, bnd)
-            };
+        // custom gates, whose degree determines the domain of their constraints
+        for gate_type in index.cs.custom_gates.gate_types() {
+            constraints.push(QuotientConstraint::chunked(
+                "custom_constraint",
+                index
+                    .cs
+                    .custom_gates
+                    .circuit_gate_constraints(gate_type, &all_alphas),
+            ));
+        }
 
-            // scalar multiplication
-            {
-                let mul8 = VarbaseMul::combined_constraints(&all_alphas).evaluations(&env);
-                t8 += &mul8;
+        // lookup
+        if let Some(lcs) = index.cs.lookup_constraint_system.as_ref() {
+            let lookup_constraints =
+                lookup::constraints::constraints(&lcs.configuration, index.cs.zk_rows);
+            let constraints_len = u32::try_from(lookup_constraints.len())
+                .expect("not expecting a large amount of constraints");
+            let lookup_alphas = all_alphas.get_alphas(ArgumentType::Lookup, constraints_len);
 
-                check_constraint!(index, mul8);
+            for (ii, (constraint, alpha_pow)) in lookup_constraints
+                .into_iter()
+                .zip_eq(lookup_alphas)
+                .enumerate()
+            {
+                constraints.push(
+                    QuotientConstraint::new(format!("lookup constraint #{ii}"), constraint)
+                        .scaled(alpha_pow),
+                );
             }
+        }
 
-            // endoscaling
-            {
-                let emul8 = EndosclMul::combined_constraints(&all_alphas).evaluations(&env);
-                t8 += &emul8;
+        // boolean columns
+        if !index.cs.boolean_columns.is_empty() {
+            constraints.push(QuotientConstraint::new(
+                "boolean_constraint",
+                boolean_columns::combined_constraints(&index.cs.boolean_columns, &all_alphas),
+            ));
+        }
 
-                check_constraint!(index, emul8);
+        // the constraints with many terms are evaluated in chunks,
+        // and all of them are with a memory budget
+        let evaluate = |constraint: &QuotientConstraint<G::ScalarField>| {
+            let mut eval = if constraint.chunked || index.memory_budget.is_some() {
+                constraint.expr.evaluations_chunked(&env, chunk_size).0
+            } else {
+                constraint.expr.evaluations(&env)
+            };
+            if let Some(alpha_pow) = constraint.alpha_pow {
+                eval.evals.par_iter_mut().for_each(|x| *x *= alpha_pow);
             }
 
-            // endoscaling scalar computation
-            {
-                let emulscalar8 =
-                    EndomulScalar::combined_constraints(&all_alphas).evaluations(&env);
-                t8 += &emulscalar8;
+            check_constraint!(index, &constraint.label, eval);
 
-                check_constraint!(index, emulscalar8);
-            }
+            eval
+        };
 
-            // poseidon
-            {
-                let pos8 = Poseidon::combined_constraints(&all_alphas).evaluations(&env);
-                t8 += &pos8;
+        let (t4, t8, bnd) = {
+            // generic
+            let mut t4 = generic4;
 
-                check_constraint!(index, pos8);
-            }
+            if cfg!(debug_assertions) {
+                let p4 = public_poly.evaluate_over_domain_by_ref(index.cs.domain.d4);
+                let gen_minus_pub = &t4 + &p4;
 
-            // chacha
-            {
-                if index.cs.chacha8.as_ref().is_some() {
-                    let chacha0 = ChaCha0::combined_constraints(&all_alphas).evaluations(&env);
-                    t4 += &chacha0;
+                check_constraint!(index, gen_minus_pub);
+            }
 
-                    let chacha1 = ChaCha1::combined_constraints(&all_alphas).evaluations(&env);
-                    t4 += &chacha1;
+            // permutation
+            let (mut t8, bnd) = {
+                let alphas =
+                    all_alphas.get_alphas(ArgumentType::Permutation, permutation::CONSTRAINTS);
+                let (perm, bnd) = index.cs.perm_quot(
+                    &witness_d8,
+                    beta,
+                    gamma,
+                    &z_poly,
+                    alphas,
+                    parallelism.permutation,
+                )?;
 
-                    let chacha2 = ChaCha2::combined_constraints(&all_alphas).evaluations(&env);
-                    t4 += &chacha2;
+                check_constraint!(index, perm);
 
-                    let chacha_final =
-                        ChaChaFinal::combined_constraints(&all_alphas).evaluations(&env);
-                    t4 += &chacha_final;
+                (perm, bnd)
+            };
 
-                    check_constraint!(index, chacha0);
-                    check_constraint!(index, chacha1);
-                    check_constraint!(index, chacha2);
-                    check_constraint!(index, chacha_final);
+            // as the constraints are computed with the expression framework,
+            // each of them can result in Evaluations of different domains
+            let mut add_to_quotient = |eval: Evaluations<G::ScalarField, D<G::ScalarField>>| {
+                if eval.domain().size == t4.domain().size {
+                    t4 += &eval;
+                } else if eval.domain().size == t8.domain().size {
+                    t8 += &eval;
+                } else {
+                    panic!("Bad evaluation")
                 }
-            }
+            };
 
-            // range check gates
-            if index.cs.range_check_selector_polys.is_some() {
-                for gate_type in range_check::gadget::circuit_gates() {
-                    let range_check_constraint =
-                        range_check::gadget::circuit_gate_constraints(gate_type, &all_alphas)
-                            .evaluations(&env);
-                    assert_eq!(range_check_constraint.domain().size, t8.domain().size);
-                    t8 += &range_check_constraint;
-                    check_constraint!(index, range_check_constraint);
+            match parallelism.constraints {
+                // each evaluation is folded into the quotient before the next one is computed
+                Partitioning::Rows => {
+                    for constraint in &constraints {
+                        add_to_quotient(evaluate(constraint));
+                    }
                 }
-            }
-
-            // foreign field addition
-            {
-                if index.cs.foreign_field_add_selector_poly.is_some() {
-                    let ffadd = foreign_field_add::gadget::combined_constraints(&all_alphas)
-                        .evaluations(&env);
-                    assert_eq!(ffadd.domain().size, t4.domain().size);
-                    t4 += &ffadd;
-                    check_constraint!(index, ffadd);
+                // all the evaluations are computed concurrently, then folded into the quotient
+                Partitioning::Columns => {
+                    let evals: Vec<_> = constraints.par_iter().map(evaluate).collect();
+                    for eval in evals {
+                        add_to_quotient(eval);
+                    }
                 }
             }
 
-            // lookup
-            {
-                if let Some(lcs) = index.cs.lookup_constraint_system.as_ref() {
-                    let constraints = lookup::constraints::constraints(&lcs.configuration);
-                    let constraints_len = u32::try_from(constraints.len())
-                        .expect("not expecting a large amount of constraints");
-                    let lookup_alphas =
-                        all_alphas.get_alphas(ArgumentType::Lookup, constraints_len);
-
-                    // as lookup constraints are computed with the expression framework,
-                    // each of them can result in Evaluations of different domains
-                    for (ii, (constraint, alpha_pow)) in
-                        constraints.into_iter().zip_eq(lookup_alphas).enumerate()
-                    {
-                        let mut eval = constraint.evaluations(&env);
-                        eval.evals.par_iter_mut().for_each(|x| *x *= alpha_pow);
-
-                        if eval.domain().size == t4.domain().size {
-                            t4 += &eval;
-                        } else if eval.domain().size == t8.domain().size {
-                            t8 += &eval;
-                        } else {
-                            panic!("Bad evaluation")
-                        }
+            (t4, t8, bnd)
+        };
 
-                        check_constraint!(index, format!("lookup constraint #{ii}"), eval);
-                    }
-                }
-            }
+        drop(env);
+        drop(witness_d8);
+        let env = Environment {
+            constants,
+            witness: &freed_witness,
+            coefficient: &index.cs.coefficients8,
+            vanishes_on_zero_knowledge_and_previous_rows: &index
+                .cs
+                .precomputations()
+                .vanishes_on_zero_knowledge_and_previous_rows,
+            z: &freed_z,
+            l0_1: l0_1(index.cs.domain.d1),
+            domain: index.cs.domain,
+            index: index_evals,
+            lookup: lookup_env,
+        };
 
+        let quotient_poly = {
             // public polynomial
-            let mut f = t4.interpolate() + t8.interpolate();
+            let mut f = t4.interpolate();
+            f += &t8.interpolate();
             f += &public_poly;
 
             // divide contributions with vanishing polynomial
@@ -815,15 +1262,23 @@ where
         //~ 1. commit (hiding) to the quotient polynomial $t$
         //~    TODO: specify the dummies
         let t_comm = {
-            let mut t_comm = index.srs.commit(&quotient_poly, None, rng);
+            let mut t_comm = Self::mask(
+                index,
+                index.srs.commit_non_hiding(&quotient_poly, None),
+                rng,
+            );
 
-            let expected_t_size = PERMUTS;
+            let expected_t_size = PERMUTS * num_chunks;
             let dummies = expected_t_size - t_comm.commitment.unshifted.len();
             // Add `dummies` many hiding commitments to the 0 polynomial, since if the
             // number of commitments in `t_comm` is less than the max size, it means that
             // the higher degree coefficients of `t` are 0.
             for _ in 0..dummies {
-                let w = <G::ScalarField as UniformRand>::rand(rng);
+                let w = if index.cs.zk {
+                    <G::ScalarField as UniformRand>::rand(rng)
+                } else {
+                    G::ScalarField::zero()
+                };
                 t_comm
                     .commitment
                     .unshifted
@@ -836,6 +1291,8 @@ where
         //~ 1. Absorb the the commitment of the quotient polynomial with the Fq-Sponge.
         fq_sponge.absorb_g(&t_comm.commitment.unshifted);
 
+        phase.next(ProverPhase::Evaluation);
+
         //~ 1. Sample $\zeta'$ with the Fq-Sponge.
         let zeta_chal = ScalarChallenge(fq_sponge.challenge());
 
@@ -845,7 +1302,13 @@ where
         let omega = index.cs.domain.d1.group_gen;
         let zeta_omega = zeta * omega;
 
-        //~ 1. If lookup is used, evaluate the following polynomials at $\zeta$ and $\zeta \omega$:
+        // the gates of the circuit that read the previous row
+        // require the evaluations at $\zeta / \omega$ as well
+        let reads_prev_row = index.linearization.reads_prev_row();
+        let zeta_prev = zeta * index.cs.domain.d1.group_gen_inv;
+
+        //~ 1. If lookup is used, evaluate the following polynomials at $\zeta$ and $\zeta \omega$
+        //~    (and at $\zeta / \omega$ if the gates of the circuit read the previous row):
         if index.cs.lookup_constraint_system.is_some() {
             //~~ - the aggregation polynomial
             let aggreg = lookup_context
@@ -888,6 +1351,9 @@ where
 
             lookup_context.eval_zeta = Some(lookup_evals(zeta));
             lookup_context.eval_zeta_omega = Some(lookup_evals(zeta_omega));
+            if reads_prev_row {
+                lookup_context.eval_zeta_prev = Some(lookup_evals(zeta_prev));
+            }
         }
 
         //~ 1. Chunk evaluate the following polynomials at both $\zeta$ and $\zeta \omega$:
@@ -908,71 +1374,48 @@ where
         //~    $$(f_0(x), f_1(x), f_2(x), \ldots)$$
         //~
         //~    TODO: do we want to specify more on that? It seems unecessary except for the t polynomial (or if for some reason someone sets that to a low value)
-        let chunked_evals = {
-            let chunked_evals_zeta = ProofEvaluations::<Vec<G::ScalarField>> {
-                s: array::from_fn(|i| {
-                    index.cs.sigmam[0..PERMUTS - 1][i]
-                        .to_chunked_polynomial(index.max_poly_size)
-                        .evaluate_chunks(zeta)
-                }),
-                w: array::from_fn(|i| {
-                    witness_poly[i]
-                        .to_chunked_polynomial(index.max_poly_size)
-                        .evaluate_chunks(zeta)
-                }),
-
-                z: z_poly
-                    .to_chunked_polynomial(index.max_poly_size)
-                    .evaluate_chunks(zeta),
+        let chunked_evals_at =
+            |pt: G::ScalarField, lookup: Option<LookupEvaluations<Vec<G::ScalarField>>>| {
+                ProofEvaluations::<Vec<G::ScalarField>> {
+                    s: array::from_fn(|i| {
+                        index.cs.sigmam[0..PERMUTS - 1][i]
+                            .to_chunked_polynomial(index.max_poly_size)
+                            .evaluate_chunks(pt)
+                    }),
+                    w: array::from_fn(|i| {
+                        witness_poly[i]
+                            .to_chunked_polynomial(index.max_poly_size)
+                            .evaluate_chunks(pt)
+                    }),
 
-                lookup: lookup_context.eval_zeta.take(),
+                    z: z_poly
+                        .to_chunked_polynomial(index.max_poly_size)
+                        .evaluate_chunks(pt),
 
-                generic_selector: index
-                    .cs
-                    .genericm
-                    .to_chunked_polynomial(index.max_poly_size)
-                    .evaluate_chunks(zeta),
+                    lookup,
 
-                poseidon_selector: index
-                    .cs
-                    .psm
-                    .to_chunked_polynomial(index.max_poly_size)
-                    .evaluate_chunks(zeta),
-            };
-            let chunked_evals_zeta_omega = ProofEvaluations::<Vec<G::ScalarField>> {
-                s: array::from_fn(|i| {
-                    index.cs.sigmam[0..PERMUTS - 1][i]
+                    generic_selector: index
+                        .cs
+                        .genericm
                         .to_chunked_polynomial(index.max_poly_size)
-                        .evaluate_chunks(zeta_omega)
-                }),
+                        .evaluate_chunks(pt),
 
-                w: array::from_fn(|i| {
-                    witness_poly[i]
+                    poseidon_selector: index
+                        .cs
+                        .psm
                         .to_chunked_polynomial(index.max_poly_size)
-                        .evaluate_chunks(zeta_omega)
-                }),
-
-                z: z_poly
-                    .to_chunked_polynomial(index.max_poly_size)
-                    .evaluate_chunks(zeta_omega),
-
-                lookup: lookup_context.eval_zeta_omega.take(),
-
-                generic_selector: index
-                    .cs
-                    .genericm
-                    .to_chunked_polynomial(index.max_poly_size)
-                    .evaluate_chunks(zeta_omega),
-
-                poseidon_selector: index
-                    .cs
-                    .psm
-                    .to_chunked_polynomial(index.max_poly_size)
-                    .evaluate_chunks(zeta_omega),
+                        .evaluate_chunks(pt),
+                }
             };
+        let chunked_evals = [
+            chunked_evals_at(zeta, lookup_context.eval_zeta.take()),
+            chunked_evals_at(zeta_omega, lookup_context.eval_zeta_omega.take()),
+        ];
 
-            [chunked_evals_zeta, chunked_evals_zeta_omega]
-        };
+        //~ 1. If the gates of the circuit read the previous row,
+        //~    chunk evaluate the same polynomials at $\zeta / \omega$ as well.
+        let chunked_prev_row_evals = reads_prev_row
+            .then(|| chunked_evals_at(zeta_prev, lookup_context.eval_zeta_prev.take()));
 
         let zeta_to_srs_len = zeta.pow(&[index.max_poly_size as u64]);
         let zeta_omega_to_srs_len = zeta_omega.pow(&[index.max_poly_size as u64]);
@@ -981,10 +1424,15 @@ where
         //~ 1. Evaluate the same polynomials without chunking them
         //~    (so that each polynomial should correspond to a single value this time).
         let evals = {
-            let power_of_eval_points_for_chunks = [zeta_to_srs_len, zeta_omega_to_srs_len];
-            &chunked_evals
+            let power_of_eval_points_for_chunks = [
+                zeta_to_srs_len,
+                zeta_omega_to_srs_len,
+                zeta_prev.pow(&[index.max_poly_size as u64]),
+            ];
+            chunked_evals
                 .iter()
-                .zip(power_of_eval_points_for_chunks.iter()) // (zeta , zeta_omega)
+                .chain(&chunked_prev_row_evals)
+                .zip(power_of_eval_points_for_chunks.iter()) // (zeta , zeta_omega [, zeta_prev])
                 .map(|(es, &e1)| ProofEvaluations::<G::ScalarField> {
                     s: array::from_fn(|i| DensePolynomial::eval_polynomial(&es.s[i], e1)),
                     w: array::from_fn(|i| DensePolynomial::eval_polynomial(&es.w[i], e1)),
@@ -1007,6 +1455,7 @@ where
                 })
                 .collect::<Vec<_>>()
         };
+        let evals = &evals;
 
         //~ 1. Compute the ft polynomial.
         //~    This is to implement [Maller's optimization](https://o1-labs.github.io/mina-book/crypto/plonk/maller_15.html).
@@ -1067,6 +1516,12 @@ where
         //~ 1. Evaluate the ft polynomial at $\zeta\omega$ only.
         let ft_eval1 = ft.evaluate(&zeta_omega);
 
+        //~ 1. If the gates of the circuit read the previous row,
+        //~    evaluate the ft polynomial at $\zeta / \omega$ as well.
+        let ft_eval_prev = reads_prev_row.then(|| ft.evaluate(&zeta_prev));
+
+        phase.next(ProverPhase::Opening);
+
         //~ 1. Setup the Fr-Sponge
         let fq_sponge_before_evaluations = fq_sponge.clone();
         let mut fr_sponge = EFrSponge::new(G::sponge_params());
@@ -1097,14 +1552,18 @@ where
             })
             .collect::<Vec<_>>();
 
-        //~ 1. Evaluate the negated public polynomial (if present) at $\zeta$ and $\zeta\omega$.
-        let public_evals = if public_poly.is_zero() {
-            [vec![G::ScalarField::zero()], vec![G::ScalarField::zero()]]
-        } else {
-            [
-                vec![public_poly.evaluate(&zeta)],
-                vec![public_poly.evaluate(&zeta_omega)],
-            ]
+        //~ 1. Evaluate the negated public polynomial (if present) at $\zeta$ and $\zeta\omega$
+        //~    (and at $\zeta / \omega$ if the gates of the circuit read the previous row).
+        let public_evals = {
+            let public_poly = public_poly.to_chunked_polynomial(index.max_poly_size);
+            let mut public_evals = vec![
+                public_poly.evaluate_chunks(zeta),
+                public_poly.evaluate_chunks(zeta_omega),
+            ];
+            if reads_prev_row {
+                public_evals.push(public_poly.evaluate_chunks(zeta_prev));
+            }
+            public_evals
         };
 
         //~ 1. Absorb the unique evaluation of ft: $ft(\zeta\omega)$.
@@ -1121,6 +1580,15 @@ where
         fr_sponge.absorb_multiple(&public_evals[1]);
         fr_sponge.absorb_evaluations([&chunked_evals[0], &chunked_evals[1]]);
 
+        //~ 1. If the gates of the circuit read the previous row,
+        //~    absorb the evaluation $ft(\zeta / \omega)$,
+        //~    then all the polynomial evaluations in $\zeta / \omega$, in the same order.
+        if let (Some(ft_eval_prev), Some(prev_evals)) = (&ft_eval_prev, &chunked_prev_row_evals) {
+            fr_sponge.absorb(ft_eval_prev);
+            fr_sponge.absorb_multiple(&public_evals[2]);
+            fr_sponge.absorb_evaluations([prev_evals]);
+        }
+
         //~ 1. Sample $v'$ with the Fr-Sponge
         let v_chal = fr_sponge.challenge();
 
@@ -1151,6 +1619,14 @@ where
             shifted: None,
         };
 
+        // the number of chunks of the commitment to a polynomial (at least one, even for zero)
+        let chunks = |p: &DensePolynomial<G::ScalarField>| {
+            std::cmp::max(
+                (p.coeffs.len() + index.max_poly_size - 1) / index.max_poly_size,
+                1,
+            )
+        };
+
         //~ 1. Then, include:
         //~~ - the negated public polynomial
         //~~ - the ft polynomial
@@ -1160,11 +1636,19 @@ where
         //~~ - the 15 registers/witness columns
         //~~ - the 6 sigmas
         //~~ - optionally, the runtime table
-        polynomials.extend(vec![(&public_poly, None, non_hiding(1))]);
+        polynomials.extend(vec![(&public_poly, None, non_hiding(chunks(&public_poly)))]);
         polynomials.extend(vec![(&ft, None, blinding_ft)]);
         polynomials.extend(vec![(&z_poly, None, z_comm.blinders)]);
-        polynomials.extend(vec![(&index.cs.genericm, None, fixed_hiding(1))]);
-        polynomials.extend(vec![(&index.cs.psm, None, fixed_hiding(1))]);
+        polynomials.extend(vec![(
+            &index.cs.genericm,
+            None,
+            fixed_hiding(chunks(&index.cs.genericm)),
+        )]);
+        polynomials.extend(vec![(
+            &index.cs.psm,
+            None,
+            fixed_hiding(chunks(&index.cs.psm)),
+        )]);
         polynomials.extend(
             witness_poly
                 .iter()
@@ -1175,7 +1659,7 @@ where
         polynomials.extend(
             index.cs.sigmam[0..PERMUTS - 1]
                 .iter()
-                .map(|w| (w, None, non_hiding(1)))
+                .map(|w| (w, None, non_hiding(chunks(w))))
                 .collect::<Vec<_>>(),
         );
 
@@ -1195,22 +1679,25 @@ where
             polynomials.push((aggreg_poly, None, aggreg_comm.blinders.clone()));
 
             //~~ - add the combined table polynomial
+            let joint_lookup_table = lookup_context.joint_lookup_table.as_ref().unwrap();
+
             let table_blinding = if lcs.runtime_selector.is_some() {
                 let runtime_comm = lookup_context.runtime_table_comm.as_ref().unwrap();
                 let joint_combiner = lookup_context.joint_combiner.as_ref().unwrap();
 
-                let blinding = runtime_comm.blinders.unshifted[0];
-
-                PolyComm {
-                    unshifted: vec![*joint_combiner * blinding],
-                    shifted: None,
+                let mut blinding = non_hiding(chunks(joint_lookup_table));
+                for (b, runtime_b) in blinding
+                    .unshifted
+                    .iter_mut()
+                    .zip(&runtime_comm.blinders.unshifted)
+                {
+                    *b = *joint_combiner * runtime_b;
                 }
+                blinding
             } else {
-                non_hiding(1)
+                non_hiding(chunks(joint_lookup_table))
             };
 
-            let joint_lookup_table = lookup_context.joint_lookup_table.as_ref().unwrap();
-
             polynomials.push((joint_lookup_table, None, table_blinding));
 
             //~~ - if present, add the runtime table polynomial
@@ -1222,17 +1709,26 @@ where
             }
         }
 
-        //~ 1. Create an aggregated evaluation proof for all of these polynomials at $\zeta$ and $\zeta\omega$ using $u$ and $v$.
+        //~ 1. Create an aggregated evaluation proof for all of these polynomials at $\zeta$ and $\zeta\omega$
+        //~    (and $\zeta / \omega$ if the gates of the circuit read the previous row) using $u$ and $v$.
+        let mut evaluation_points = vec![zeta, zeta_omega];
+        if reads_prev_row {
+            evaluation_points.push(zeta_prev);
+        }
         let proof = index.srs.open(
             group_map,
             &polynomials,
-            &[zeta, zeta_omega],
+            &evaluation_points,
             v,
             u,
             fq_sponge_before_evaluations,
             rng,
         );
 
+        let prev_row = chunked_prev_row_evals
+            .zip(ft_eval_prev)
+            .map(|(evals, ft_eval)| PrevRowEvaluations { evals, ft_eval });
+
         let lookup = lookup_context
             .aggreg_comm
             .zip(lookup_context.sorted_comms)
@@ -1254,6 +1750,7 @@ where
             ft_eval1,
             public,
             prev_challenges,
+            prev_row,
         })
     }
 }
@@ -1500,6 +1997,7 @@ pub mod caml {
                     .into_iter()
                     .map(Into::into)
                     .collect(),
+                prev_row: None,
             }
         }
     }
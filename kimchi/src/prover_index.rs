@@ -4,11 +4,13 @@ use crate::{
     alphas::Alphas,
     circuits::{
         constraints::ConstraintSystem,
-        expr::{Linearization, PolishToken},
-        wires::PERMUTS,
+        expr::{Linearization, PolishToken, DEFAULT_EVALUATION_CHUNK_SIZE},
+        wires::{COLUMNS, PERMUTS},
     },
     curve::KimchiCurve,
+    error::ProverError,
     linearization::expr_linearization,
+    parallel::{self, Parallelism, Partitioning},
     verifier_index::VerifierIndex,
 };
 use ark_poly::EvaluationDomain;
@@ -18,6 +20,10 @@ use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_with::serde_as;
 use std::sync::Arc;
 
+/// An estimate of the number of field elements held per row by the evaluation of a constraint,
+/// for its intermediate results and its cached sub-expressions
+const EVALUATION_ELEMENTS_PER_ROW: usize = 64;
+
 /// The index used by the prover
 #[serde_as]
 #[derive(Serialize, Deserialize, Debug)]
@@ -52,27 +58,32 @@ pub struct ProverIndex<G: KimchiCurve> {
     /// The verifier index digest corresponding to this prover index
     #[serde_as(as = "Option<o1_utils::serialization::SerdeAs>")]
     pub verifier_index_digest: Option<G::BaseField>,
+
+    /// The peak memory, in bytes, targeted by the evaluation of the constraints when proving.
+    /// If set, all the constraints are evaluated in chunks (see [ProverIndex::evaluation_chunk_size]),
+    /// at the cost of some speed.
+    /// The evaluations of the witness columns over the domain d8 are not accounted for,
+    /// as they are needed in any case.
+    #[serde(skip)]
+    pub memory_budget: Option<usize>,
+
+    /// The partitioning of the work of the prover between the threads.
+    /// If not set, it is picked from the size of the domain (see [Parallelism::for_domain]).
+    #[serde(skip)]
+    pub parallelism: Option<Parallelism>,
 }
 //~spec:endcode
 
 impl<G: KimchiCurve> ProverIndex<G> {
-    /// this function compiles the index from constraints
-    ///
-    /// # Panics
-    ///
-    /// Will panic if `polynomial segment size` is bigger than `circuit`.
+    /// this function compiles the index from constraints.
+    /// The SRS can be smaller than the domain of the circuit,
+    /// in which case the commitments are split into chunks of the size of the SRS.
     pub fn create(
         mut cs: ConstraintSystem<G::ScalarField>,
         endo_q: G::ScalarField,
         srs: Arc<SRS<G>>,
     ) -> Self {
         let max_poly_size = srs.g.len();
-        if cs.public > 0 {
-            assert!(
-                max_poly_size >= cs.domain.d1.size(),
-                "polynomial segment size has to be not smaller that that of the circuit!"
-            );
-        }
         cs.endo = endo_q;
 
         // pre-compute the linearization
@@ -83,6 +94,10 @@ impl<G: KimchiCurve> ProverIndex<G> {
                 .as_ref()
                 .map(|lcs| &lcs.configuration),
             cs.foreign_field_add_selector_poly.is_some(),
+            cs.sha256_selector_polys.is_some(),
+            &cs.custom_gates,
+            &cs.boolean_columns,
+            cs.zk_rows,
         );
 
         // set `max_quot_size` to the degree of the quotient polynomial,
@@ -100,7 +115,73 @@ impl<G: KimchiCurve> ProverIndex<G> {
             max_quot_size,
             verifier_index: None,
             verifier_index_digest: None,
+            memory_budget: None,
+            parallelism: None,
+        }
+    }
+
+    /// The number of chunks of the commitments to polynomials over the domain,
+    /// which is more than one when the domain is larger than the SRS.
+    ///
+    /// Note that a proof reveals the evaluations of every chunk of the witness polynomials,
+    /// so chunked proofs need more zero-knowledge rows (see [ConstraintSystem::zk_rows])
+    /// to stay zero-knowledge.
+    pub fn num_chunks(&self) -> usize {
+        (self.cs.domain.d1.size() + self.max_poly_size - 1) / self.max_poly_size
+    }
+
+    /// The partitioning of the work of the prover between the threads:
+    /// [ProverIndex::parallelism] if set, otherwise the one suited to the size of the domain.
+    /// With a [ProverIndex::memory_budget], the constraints are always evaluated in turn.
+    pub fn parallelism(&self) -> Parallelism {
+        let mut parallelism = self
+            .parallelism
+            .unwrap_or_else(|| Parallelism::for_domain(self.cs.domain.d1.size()));
+        if self.memory_budget.is_some() {
+            parallelism.constraints = Partitioning::Rows;
+        }
+        parallelism
+    }
+
+    /// The number of rows evaluated together when evaluating the constraints.
+    ///
+    /// Without [ProverIndex::memory_budget], this is [DEFAULT_EVALUATION_CHUNK_SIZE].
+    /// Otherwise, the chunks of all the threads fit in the budget, down to a single row.
+    pub fn evaluation_chunk_size(&self) -> usize {
+        match self.memory_budget {
+            None => DEFAULT_EVALUATION_CHUNK_SIZE,
+            Some(budget) => {
+                let row_size = EVALUATION_ELEMENTS_PER_ROW
+                    * std::mem::size_of::<G::ScalarField>()
+                    * parallel::current_num_threads();
+                std::cmp::max(budget / row_size, 1)
+            }
+        }
+    }
+
+    /// Sets the public outputs `public_output` of the circuit in the public rows of `witness`
+    /// designated by [ConstraintSystem::public_output], before creating a proof.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the number of outputs does not match the constraint system.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the witness is shorter than the public values.
+    pub fn set_public_output(
+        &self,
+        witness: &mut [Vec<G::ScalarField>; COLUMNS],
+        public_output: &[G::ScalarField],
+    ) -> Result<(), ProverError> {
+        if public_output.len() != self.cs.public_output {
+            return Err(ProverError::IncorrectPublicOutputLength(
+                self.cs.public_output,
+            ));
         }
+        let start = self.cs.public - self.cs.public_output;
+        witness[0][start..self.cs.public].copy_from_slice(public_output);
+        Ok(())
     }
 
     /// Retrieve or compute the digest for the corresponding verifier index.
@@ -146,11 +227,131 @@ pub mod testing {
     use crate::circuits::{
         gate::CircuitGate,
         lookup::{runtime_tables::RuntimeTableCfg, tables::LookupTable},
+        polynomials::permutation::ZK_ROWS,
     };
     use commitment_dlog::srs::endos;
     use mina_curves::pasta::{Fp, Pallas, Vesta};
     use num_bigint::BigUint;
 
+    /// A builder of prover indexes for the tests, over [Vesta].
+    /// Only the gates are required, the other parameters default to:
+    ///
+    /// - no public input and no previous challenges,
+    /// - no fixed nor runtime lookup tables,
+    /// - no foreign field modulus,
+    /// - [ZK_ROWS] zero-knowledge rows, with zero-knowledge enabled,
+    /// - an SRS of the size of the domain (so that the commitments are not chunked).
+    pub struct TestIndexBuilder {
+        gates: Vec<CircuitGate<Fp>>,
+        public: usize,
+        prev_challenges: usize,
+        lookup_tables: Vec<LookupTable<Fp>>,
+        runtime_tables: Option<Vec<RuntimeTableCfg<Fp>>>,
+        foreign_modulus: Option<BigUint>,
+        zk_rows: u64,
+        zk: bool,
+        srs_size: Option<usize>,
+    }
+
+    impl TestIndexBuilder {
+        /// Starts the index of the circuit made of `gates`
+        pub fn new(gates: Vec<CircuitGate<Fp>>) -> Self {
+            TestIndexBuilder {
+                gates,
+                public: 0,
+                prev_challenges: 0,
+                lookup_tables: vec![],
+                runtime_tables: None,
+                foreign_modulus: None,
+                zk_rows: ZK_ROWS,
+                zk: true,
+                srs_size: None,
+            }
+        }
+
+        /// Sets the number of public inputs
+        #[must_use]
+        pub fn public(mut self, public: usize) -> Self {
+            self.public = public;
+            self
+        }
+
+        /// Sets the number of previous challenges of the recursive proofs
+        #[must_use]
+        pub fn prev_challenges(mut self, prev_challenges: usize) -> Self {
+            self.prev_challenges = prev_challenges;
+            self
+        }
+
+        /// Sets the fixed lookup tables
+        #[must_use]
+        pub fn lookup_tables(mut self, lookup_tables: Vec<LookupTable<Fp>>) -> Self {
+            self.lookup_tables = lookup_tables;
+            self
+        }
+
+        /// Sets the configuration of the runtime tables
+        #[must_use]
+        pub fn runtime_tables(mut self, runtime_tables: Option<Vec<RuntimeTableCfg<Fp>>>) -> Self {
+            self.runtime_tables = runtime_tables;
+            self
+        }
+
+        /// Sets the foreign field modulus
+        #[must_use]
+        pub fn foreign_modulus(mut self, foreign_modulus: Option<BigUint>) -> Self {
+            self.foreign_modulus = foreign_modulus;
+            self
+        }
+
+        /// Sets the number of rows reserved for zero-knowledge at the end of the domain
+        #[must_use]
+        pub fn zk_rows(mut self, zk_rows: u64) -> Self {
+            self.zk_rows = zk_rows;
+            self
+        }
+
+        /// Sets whether the proofs are zero-knowledge
+        #[must_use]
+        pub fn zk(mut self, zk: bool) -> Self {
+            self.zk = zk;
+            self
+        }
+
+        /// Sets the size of the SRS, which can be smaller than the domain,
+        /// in which case the commitments are chunked
+        #[must_use]
+        pub fn srs_size(mut self, srs_size: usize) -> Self {
+            self.srs_size = Some(srs_size);
+            self
+        }
+
+        /// Creates the constraint system, the SRS and the index.
+        ///
+        /// # Panics
+        ///
+        /// Will panic if the constraint system cannot be built from the parameters.
+        pub fn build(self) -> ProverIndex<Vesta> {
+            let cs = ConstraintSystem::<Fp>::create(self.gates)
+                .lookup(self.lookup_tables)
+                .runtime(self.runtime_tables)
+                .public(self.public)
+                .prev_challenges(self.prev_challenges)
+                .foreign_field_modulus(&self.foreign_modulus)
+                .zk_rows(self.zk_rows)
+                .zk(self.zk)
+                .build()
+                .unwrap();
+
+            let mut srs = SRS::<Vesta>::create(self.srs_size.unwrap_or(cs.domain.d1.size()));
+            srs.add_lagrange_basis(cs.domain.d1);
+            let srs = Arc::new(srs);
+
+            let (endo_q, _endo_r) = endos::<Pallas>();
+            ProverIndex::<Vesta>::create(cs, endo_q, srs)
+        }
+    }
+
     /// Create new index for lookups.
     ///
     /// # Panics
@@ -163,25 +364,19 @@ pub mod testing {
         lookup_tables: Vec<LookupTable<Fp>>,
         runtime_tables: Option<Vec<RuntimeTableCfg<Fp>>>,
         foreign_modulus: Option<BigUint>,
+        zk_rows: Option<u64>,
     ) -> ProverIndex<Vesta> {
-        // not sure if theres a smarter way instead of the double unwrap, but should be fine in the test
-        let cs = ConstraintSystem::<Fp>::create(gates)
-            .lookup(lookup_tables)
-            .runtime(runtime_tables)
+        TestIndexBuilder::new(gates)
             .public(public)
             .prev_challenges(prev_challenges)
-            .foreign_field_modulus(&foreign_modulus)
+            .lookup_tables(lookup_tables)
+            .runtime_tables(runtime_tables)
+            .foreign_modulus(foreign_modulus)
+            .zk_rows(zk_rows.unwrap_or(ZK_ROWS))
             .build()
-            .unwrap();
-        let mut srs = SRS::<Vesta>::create(cs.domain.d1.size());
-        srs.add_lagrange_basis(cs.domain.d1);
-        let srs = Arc::new(srs);
-
-        let (endo_q, _endo_r) = endos::<Pallas>();
-        ProverIndex::<Vesta>::create(cs, endo_q, srs)
     }
 
     pub fn new_index_for_test(gates: Vec<CircuitGate<Fp>>, public: usize) -> ProverIndex<Vesta> {
-        new_index_for_test_with_lookups(gates, public, 0, vec![], None, None)
+        TestIndexBuilder::new(gates).public(public).build()
     }
 }
@@ -0,0 +1,102 @@
+use crate::circuits::{
+    constraints::ConstraintSystem,
+    polynomial::COLUMNS,
+    polynomials::bitwise::gadget::{Bitwise, RotatableWord},
+};
+
+use ark_ff::One;
+use mina_curves::pasta::{Fp, Vesta};
+
+// The value of an output, its expected value, and its cell
+type Output = (u64, u64, (usize, usize));
+
+// Lays out operations on 16-bit, 32-bit and 64-bit words of the inputs,
+// and returns the gadget with the values of the outputs and their cells
+fn operations(a: u64, b: u64, k: u32) -> (Bitwise<Fp>, Vec<Output>) {
+    let mut gadget = Bitwise::new(0);
+    let mut outputs = vec![];
+
+    let x = gadget.input16(a as u16);
+    let a16 = a as u16;
+    for (word, expected) in [
+        (gadget.rotl16(x, k), a16.rotate_left(k)),
+        (gadget.rotr16(x, k), a16.rotate_right(k)),
+        (gadget.rotl(x, k), a16.rotate_left(k)),
+    ] {
+        outputs.push((word.value() as u64, expected as u64, word.cell()));
+    }
+
+    let (x, y) = (gadget.input32(a as u32), gadget.input32(b as u32));
+    let (a32, b32) = (a as u32, b as u32);
+    let k32 = k % 32;
+    for (word, expected) in [
+        (gadget.xor32(x, y), a32 ^ b32),
+        (gadget.and32(x, y), a32 & b32),
+        (gadget.or32(x, y), a32 | b32),
+        (gadget.not32(x), !a32),
+        (gadget.shl32(x, k32), a32 << k32),
+        (gadget.shr32(x, k32), a32 >> k32),
+        (gadget.rotl32(x, k), a32.rotate_left(k)),
+        (gadget.rotr32(x, k), a32.rotate_right(k)),
+        (gadget.rotr(x, k), a32.rotate_right(k)),
+    ] {
+        outputs.push((word.value() as u64, expected as u64, word.cell()));
+    }
+
+    let (x, y) = (gadget.input64(a), gadget.input64(b));
+    for (word, expected) in [
+        (gadget.xor64(x, y), a ^ b),
+        (gadget.and64(x, y), a & b),
+        (gadget.or64(x, y), a | b),
+        (gadget.not64(x), !a),
+        (gadget.shl64(x, k), a << k),
+        (gadget.shr64(x, k), a >> k),
+        (gadget.rotl64(x, k), a.rotate_left(k)),
+        (gadget.rotr64(x, k), a.rotate_right(k)),
+    ] {
+        outputs.push((word.value(), expected, word.high.cell()));
+    }
+
+    // the rotation generic over the word size
+    let rotated = gadget.rotl(x, k);
+    outputs.push((
+        RotatableWord::<Fp>::to_u64(&rotated),
+        a.rotate_left(k),
+        rotated.high.cell(),
+    ));
+
+    (gadget, outputs)
+}
+
+#[test]
+fn test_bitwise_witness() {
+    for (a, b) in [
+        (0, 0),
+        (u64::MAX, 0x0123_4567_89ab_cdef),
+        (0xdead_beef_cafe_f00d, 0x8000_0000_0000_0001),
+    ] {
+        for k in [0, 1, 7, 31, 32, 33, 63] {
+            let (_, outputs) = operations(a, b, k);
+            for (value, expected, _) in outputs {
+                assert_eq!(value, expected, "a = {a:#x}, b = {b:#x}, k = {k}");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_bitwise_circuit() {
+    for k in [0, 5, 32, 45] {
+        let (gates, _) = operations(0, 0, k).0.finish();
+        let cs = ConstraintSystem::create(gates).build().unwrap();
+
+        let (gadget, outputs) = operations(0xdead_beef_cafe_f00d, 0x0123_4567_89ab_cdef, k);
+        let (_, mut witness): (_, [Vec<Fp>; COLUMNS]) = gadget.finish();
+        assert_eq!(cs.verify::<Vesta>(&witness, &[]), Ok(()));
+
+        // a wrong output is rejected
+        let (_, _, (row, col)) = outputs[outputs.len() - 1];
+        witness[col][row] += Fp::one();
+        assert!(cs.verify::<Vesta>(&witness, &[]).is_err());
+    }
+}
@@ -0,0 +1,89 @@
+use crate::circuits::{
+    constraints::{ConstraintSystem, GateError},
+    polynomials::generic::testing::{create_circuit, fill_in_witness},
+    wires::{Wire, COLUMNS},
+};
+use crate::{error::SetupError, proof::ProverProof, prover_index::ProverIndex, verifier::verify};
+use ark_ff::Zero;
+use ark_poly::EvaluationDomain;
+use commitment_dlog::{commitment::CommitmentCurve, srs::endos, srs::SRS};
+use groupmap::GroupMap;
+use mina_curves::pasta::{Fp, Pallas, Vesta, VestaParameters};
+use oracle::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use std::{array, sync::Arc};
+
+type BaseSponge = DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>;
+type ScalarSponge = DefaultFrSponge<Fp, PlonkSpongeConstantsKimchi>;
+
+// A generic circuit whose columns 7 and 8 hold bits, which are not constrained by its gates
+fn circuit_with_bits() -> (ConstraintSystem<Fp>, [Vec<Fp>; COLUMNS]) {
+    let gates = create_circuit(0, 0);
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &[]);
+    witness[7] = (0..gates.len())
+        .map(|row| Fp::from((row % 3 == 0) as u64))
+        .collect();
+    witness[8] = (0..gates.len())
+        .map(|row| Fp::from((row % 2) as u64))
+        .collect();
+
+    let cs = ConstraintSystem::create(gates)
+        .boolean_columns(vec![8, 7, 8])
+        .build()
+        .unwrap();
+    (cs, witness)
+}
+
+fn prove_and_verify(cs: ConstraintSystem<Fp>, witness: [Vec<Fp>; COLUMNS]) {
+    let mut srs = SRS::<Vesta>::create(cs.domain.d1.size());
+    srs.add_lagrange_basis(cs.domain.d1);
+    let (endo_q, _endo_r) = endos::<Pallas>();
+    let index = ProverIndex::<Vesta>::create(cs, endo_q, Arc::new(srs));
+
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+    let proof =
+        ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness, &[], &index).unwrap();
+
+    let verifier_index = index.verifier_index();
+    assert_eq!(verifier_index.boolean_columns, vec![7, 8]);
+    verify::<Vesta, BaseSponge, ScalarSponge>(&group_map, &verifier_index, &proof).unwrap();
+}
+
+#[test]
+fn test_boolean_columns() {
+    let (cs, witness) = circuit_with_bits();
+    assert_eq!(cs.boolean_columns, vec![7, 8]);
+    assert_eq!(cs.verify::<Vesta>(&witness, &[]), Ok(()));
+    prove_and_verify(cs, witness);
+}
+
+#[test]
+fn test_boolean_columns_verify() {
+    let (cs, mut witness) = circuit_with_bits();
+    witness[7][2] = Fp::from(2u64);
+    assert_eq!(
+        cs.verify::<Vesta>(&witness, &[]),
+        Err(GateError::NotBoolean(Wire { row: 2, col: 7 }))
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_boolean_columns_rejects_non_boolean_cells() {
+    let (cs, mut witness) = circuit_with_bits();
+    witness[8][3] = Fp::from(2u64);
+    prove_and_verify(cs, witness);
+}
+
+#[test]
+fn test_boolean_columns_setup() {
+    assert!(matches!(
+        ConstraintSystem::create(create_circuit::<Fp>(0, 0))
+            .boolean_columns(vec![3, COLUMNS])
+            .build(),
+        Err(SetupError::BooleanColumn(COLUMNS))
+    ));
+}
@@ -0,0 +1,223 @@
+use super::framework::TestFramework;
+use crate::{
+    circuits::{
+        builder::{CircuitBuilder, Var},
+        gate::GateType,
+        lookup::tables::{LookupTable, TableId},
+        wires::COLUMNS,
+    },
+    error::CircuitBuilderError,
+};
+use ark_ff::{Field, One};
+use mina_curves::pasta::Fp;
+
+// A gadget computing x^3
+fn cube(builder: &mut CircuitBuilder<Fp>, x: Var) -> Var {
+    let square = builder.mul(x, x);
+    builder.mul(square, x)
+}
+
+// x^3 + x + 5 = y, with x private and y public, and the sum of some booleans as output
+fn create_circuit() -> CircuitBuilder<Fp> {
+    let mut builder = CircuitBuilder::new();
+    let y = builder.public_input("y").unwrap();
+    let x = builder.private_input("x").unwrap();
+
+    let x3 = cube(&mut builder, x);
+    let sum = builder.add(x3, x);
+    let five = builder.constant(Fp::from(5u8));
+    let res = builder.add(sum, five);
+    builder.assert_eq(res, y);
+
+    let bits: Vec<_> = (0..4)
+        .map(|i| {
+            let bit = builder.private_input(&format!("bit{i}")).unwrap();
+            builder.assert_boolean(bit);
+            bit
+        })
+        .collect();
+    let count = bits[1..]
+        .iter()
+        .fold(bits[0], |acc, bit| builder.add(acc, *bit));
+    builder.name(count, "count").unwrap();
+    builder.public_output(count);
+
+    // the inverse of x, as a hint checked by a multiplication
+    let x_inv = builder.witness(move |v| v.get(x).inverse().unwrap());
+    let one = builder.mul(x, x_inv);
+    let expected_one = builder.constant(Fp::one());
+    builder.assert_eq(one, expected_one);
+
+    builder
+}
+
+#[test]
+fn test_circuit_builder() {
+    let builder = create_circuit();
+    let x = Fp::from(3u8);
+    let bits = [1u8, 0, 1, 1].map(Fp::from);
+    let mut inputs = vec![("y", Fp::from(35u8)), ("x", x)];
+    let names = ["bit0", "bit1", "bit2", "bit3"];
+    inputs.extend(names.into_iter().zip(bits));
+
+    let witness = builder.generate_witness(&inputs).unwrap();
+    let public = witness[0][..builder.public()].to_vec();
+    assert_eq!(public, vec![Fp::from(35u8), Fp::from(3u8)]);
+
+    let cs = builder.constraint_system().unwrap().build().unwrap();
+    assert_eq!(cs.public, 2);
+    assert_eq!(cs.public_output, 1);
+
+    TestFramework::default()
+        .gates(builder.gates().unwrap())
+        .witness(witness)
+        .public_inputs(public)
+        .setup()
+        .prove_and_verify();
+}
+
+#[test]
+fn test_circuit_builder_errors() {
+    let mut builder = create_circuit();
+    assert_eq!(
+        builder.private_input("x"),
+        Err(CircuitBuilderError::DuplicateName("x".to_string()))
+    );
+
+    let mut inputs = vec![("y", Fp::from(35u8)), ("x", Fp::from(3u8))];
+    assert_eq!(
+        builder.generate_witness(&inputs).err(),
+        Some(CircuitBuilderError::MissingInput("bit0".to_string()))
+    );
+    inputs.push(("z", Fp::one()));
+    assert_eq!(
+        builder.generate_witness(&inputs).err(),
+        Some(CircuitBuilderError::UnknownInput("z".to_string()))
+    );
+
+    // a wrong public input breaks the equality with the result
+    let inputs: Vec<_> = ["bit0", "bit1", "bit2", "bit3"]
+        .into_iter()
+        .map(|name| (name, Fp::one()))
+        .chain([("y", Fp::from(36u8)), ("x", Fp::from(3u8))])
+        .collect();
+    assert!(matches!(
+        builder.generate_witness(&inputs),
+        Err(CircuitBuilderError::NotEqual(_, _))
+    ));
+}
+
+const SQUARES: TableId = TableId::new(-1);
+const CUBES: TableId = TableId::new(-2);
+
+// The table of ID `id` mapping each x of 0..16 to x^exp
+fn power_table(id: TableId, exp: u64) -> LookupTable<Fp> {
+    let indexes: Vec<_> = (0..16u64).map(Fp::from).collect();
+    let values = indexes.iter().map(|x| x.pow([exp])).collect();
+    LookupTable {
+        id,
+        data: vec![indexes, values],
+    }
+}
+
+// An instruction `op` selecting the table of squares or cubes, applied to x0 to x3,
+// and the squares of x0 and x1 looked up in a fixed table, as public outputs
+fn create_lookup_circuit() -> CircuitBuilder<Fp> {
+    let mut builder = CircuitBuilder::new();
+    builder.lookup_table(power_table(SQUARES, 2));
+    builder.lookup_table(power_table(CUBES, 3));
+
+    let op = builder.private_input("op").unwrap();
+    let xs: Vec<_> = (0..4)
+        .map(|i| builder.private_input(&format!("x{i}")).unwrap())
+        .collect();
+    let results: Vec<_> = xs.iter().map(|x| builder.lookup(op, *x)).collect();
+    let squares = builder.constant(SQUARES.to_field());
+    let fixed: Vec<_> = xs[..2]
+        .iter()
+        .map(|x| builder.lookup(squares, *x))
+        .collect();
+    for output in results.into_iter().chain(fixed) {
+        builder.public_output(output);
+    }
+    builder
+}
+
+fn lookup_inputs(op: TableId, xs: [u64; 4]) -> Vec<(String, Fp)> {
+    let mut inputs = vec![("op".to_string(), op.to_field())];
+    inputs.extend((0..4).map(|i| (format!("x{i}"), Fp::from(xs[i]))));
+    inputs
+}
+
+fn lookup_witness(builder: &CircuitBuilder<Fp>, op: TableId) -> [Vec<Fp>; COLUMNS] {
+    let inputs = lookup_inputs(op, [2, 3, 5, 15]);
+    let inputs: Vec<_> = inputs.iter().map(|(name, x)| (name.as_str(), *x)).collect();
+    builder.generate_witness(&inputs).unwrap()
+}
+
+fn prove_lookups(builder: &CircuitBuilder<Fp>, witness: [Vec<Fp>; COLUMNS]) {
+    let public = witness[0][..builder.public()].to_vec();
+    TestFramework::default()
+        .gates(builder.gates().unwrap())
+        .witness(witness)
+        .public_inputs(public)
+        .lookup_tables(builder.lookup_tables().to_vec())
+        .setup()
+        .prove_and_verify();
+}
+
+#[test]
+fn test_circuit_builder_lookup() {
+    let builder = create_lookup_circuit();
+    let gates = builder.gates().unwrap();
+    let lookup_rows = gates
+        .iter()
+        .filter(|gate| gate.typ == GateType::Lookup)
+        .count();
+    assert_eq!(lookup_rows, 3);
+
+    for (op, exp) in [(SQUARES, 2), (CUBES, 3)] {
+        let witness = lookup_witness(&builder, op);
+        let results = [2u64, 3, 5, 15].map(|x| Fp::from(x).pow([exp]));
+        let expected: Vec<_> = results
+            .into_iter()
+            .chain([Fp::from(4u8), Fp::from(9u8)])
+            .collect();
+        assert_eq!(witness[0][..builder.public()], expected);
+        prove_lookups(&builder, witness);
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_circuit_builder_lookup_rejects_wrong_table() {
+    // the squares, looked up as if the instruction selected the cubes
+    // (in the two rows of the lookups selected by the instruction, laid out first)
+    let builder = create_lookup_circuit();
+    let mut witness = lookup_witness(&builder, SQUARES);
+    let gates = builder.gates().unwrap();
+    let rows = gates
+        .iter()
+        .enumerate()
+        .filter(|(_, gate)| gate.typ == GateType::Lookup)
+        .map(|(row, _)| row)
+        .take(2);
+    for row in rows {
+        witness[0][row] = CUBES.to_field();
+    }
+    prove_lookups(&builder, witness);
+}
+
+#[test]
+fn test_circuit_builder_lookup_missing_entry() {
+    let builder = create_lookup_circuit();
+    let inputs = lookup_inputs(CUBES, [1, 2, 16, 3]);
+    let inputs: Vec<_> = inputs.iter().map(|(name, x)| (name.as_str(), *x)).collect();
+    assert_eq!(
+        builder.generate_witness(&inputs).err(),
+        Some(CircuitBuilderError::MissingEntry(
+            "x2".to_string(),
+            "op".to_string()
+        ))
+    );
+}
@@ -1,11 +1,16 @@
 use super::framework::TestFramework;
 use crate::{
     circuits::{
+        constraints::ConstraintSystem,
         gate::{CircuitGate, GateType},
-        lookup::tables::{LookupTable, XOR_TABLE_ID},
+        lookup::{
+            index::LookupError,
+            tables::{LookupTable, TableId, XOR_TABLE_ID},
+        },
         polynomials::chacha,
         wires::{Wire, COLUMNS},
     },
+    error::SetupError,
     proof::ProverProof,
     prover_index::testing::new_index_for_test,
     verifier::verify,
@@ -100,7 +105,16 @@ fn chacha_prover() {
     }
 }
 
-fn chacha_setup_bad_lookup(table_id: i32) {
+// A circuit with one 'real' ChaCha0 gate and one 'fake' one, whose XOR lookups are in a table
+// with ID `table_id`, and its witness.
+#[allow(clippy::type_complexity)]
+fn chacha_bad_lookup_circuit(
+    table_id: TableId,
+) -> (
+    Vec<CircuitGate<Fp>>,
+    [Vec<Fp>; COLUMNS],
+    Vec<LookupTable<Fp>>,
+) {
     // circuit gates: one 'real' ChaCha0 and one 'fake' one.
     let gates = vec![
         GateType::ChaCha0,
@@ -199,16 +213,10 @@ fn chacha_setup_bad_lookup(table_id: i32) {
     // .. and one fake witness.
     push_rows(true);
 
-    let lookup_tables = vec![
-        LookupTable {
-            id: 0,
-            data: vec![vec![Fp::from(0); 10]],
-        },
-        LookupTable {
-            id: table_id,
-            data: fakes,
-        },
-    ];
+    let lookup_tables = vec![LookupTable {
+        id: table_id,
+        data: fakes,
+    }];
 
     let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
     for r in rows.into_iter() {
@@ -217,6 +225,11 @@ fn chacha_setup_bad_lookup(table_id: i32) {
         }
     }
 
+    (gates, witness, lookup_tables)
+}
+
+fn chacha_setup_bad_lookup(table_id: TableId) {
+    let (gates, witness, lookup_tables) = chacha_bad_lookup_circuit(table_id);
     TestFramework::default()
         .gates(gates)
         .witness(witness)
@@ -230,12 +243,17 @@ fn chacha_setup_bad_lookup(table_id: i32) {
 #[test]
 #[should_panic]
 fn chacha_prover_fake_lookup_in_different_table_fails() {
-    chacha_setup_bad_lookup(XOR_TABLE_ID + 1)
+    chacha_setup_bad_lookup(TableId::new(-1))
 }
 
-// Test lookup domain collisions: if the same table ID is used, we should be able to inject and use
-// a value when it wasn't previously in the table.
+// Test lookup domain collisions: if the same table ID was used, we would be able to inject and use
+// a value when it wasn't previously in the table, so the ID of the XOR table must be rejected.
 #[test]
-fn chacha_prover_fake_lookup_in_same_table() {
-    chacha_setup_bad_lookup(XOR_TABLE_ID)
+fn chacha_fake_lookup_in_same_table_is_rejected() {
+    let (gates, _, lookup_tables) = chacha_bad_lookup_circuit(XOR_TABLE_ID);
+    let result = ConstraintSystem::create(gates)
+        .lookup(lookup_tables)
+        .build();
+    let expected = LookupError::ReservedTableId(XOR_TABLE_ID).to_string();
+    assert!(matches!(result, Err(SetupError::ConstraintSystem(e)) if e == expected));
 }
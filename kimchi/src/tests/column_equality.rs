@@ -0,0 +1,133 @@
+use crate::{
+    column_equality::{ColumnEqualityProof, CommittedColumn},
+    error::ColumnEqualityError,
+};
+use ark_ff::UniformRand;
+use ark_poly::{univariate::DensePolynomial, UVPolynomial};
+use commitment_dlog::{
+    commitment::{BlindedCommitment, CommitmentCurve, PolyComm},
+    srs::SRS,
+};
+use groupmap::GroupMap;
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use oracle::{constants::PlonkSpongeConstantsKimchi, sponge::DefaultFqSponge, FqSponge};
+use rand::{rngs::StdRng, SeedableRng};
+
+type BaseSponge = DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>;
+
+const SRS_SIZE: usize = 32;
+
+fn new_sponge() -> BaseSponge {
+    BaseSponge::new(oracle::pasta::fq_kimchi::static_params())
+}
+
+// two polynomials, the second one being larger than the SRS, and two commitments to each of them
+fn setup(
+    srs: &SRS<Vesta>,
+    rng: &mut StdRng,
+) -> Vec<(DensePolynomial<Fp>, [BlindedCommitment<Vesta>; 2])> {
+    [SRS_SIZE, 3 * SRS_SIZE - 5]
+        .into_iter()
+        .map(|len| {
+            let poly =
+                DensePolynomial::from_coefficients_vec((0..len).map(|_| Fp::rand(rng)).collect());
+            let comms = [srs.commit(&poly, None, rng), srs.commit(&poly, None, rng)];
+            (poly, comms)
+        })
+        .collect()
+}
+
+fn columns<'a>(
+    setup: &'a [(DensePolynomial<Fp>, [BlindedCommitment<Vesta>; 2])],
+) -> Vec<(CommittedColumn<'a, Vesta>, CommittedColumn<'a, Vesta>)> {
+    setup
+        .iter()
+        .map(|(poly, [left, right])| {
+            (
+                CommittedColumn {
+                    poly,
+                    commitment: left,
+                },
+                CommittedColumn {
+                    poly,
+                    commitment: right,
+                },
+            )
+        })
+        .collect()
+}
+
+fn commitments(
+    setup: &[(DensePolynomial<Fp>, [BlindedCommitment<Vesta>; 2])],
+) -> Vec<(&PolyComm<Vesta>, &PolyComm<Vesta>)> {
+    setup
+        .iter()
+        .map(|(_, [left, right])| (&left.commitment, &right.commitment))
+        .collect()
+}
+
+#[test]
+fn test_column_equality() {
+    let rng = &mut StdRng::from_seed([0; 32]);
+    let srs = SRS::<Vesta>::create(SRS_SIZE);
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+    let setup = setup(&srs, rng);
+
+    // the commitments are hiding, so they differ even though the columns are equal
+    assert_ne!(
+        setup[0].1[0].commitment.unshifted,
+        setup[0].1[1].commitment.unshifted
+    );
+    assert_eq!(setup[1].1[0].commitment.unshifted.len(), 3);
+
+    let proof =
+        ColumnEqualityProof::create(&srs, &group_map, new_sponge(), &columns(&setup), rng).unwrap();
+    let commitments = commitments(&setup);
+    proof
+        .verify(&srs, &group_map, new_sponge(), &commitments, rng)
+        .unwrap();
+
+    // the proof is bound to the transcript of the sponge
+    let mut other_sponge = new_sponge();
+    other_sponge.absorb_fr(&[Fp::from(1u64)]);
+    assert_eq!(
+        proof.verify(&srs, &group_map, other_sponge, &commitments, rng),
+        Err(ColumnEqualityError::OpenProof)
+    );
+
+    // the proof does not hold for commitments to other columns
+    let other = DensePolynomial::from_coefficients_vec(
+        (0..setup[1].0.coeffs.len())
+            .map(|_| Fp::rand(rng))
+            .collect(),
+    );
+    let other = srs.commit(&other, None, rng);
+    let mut swapped = commitments.clone();
+    swapped[1].1 = &other.commitment;
+    assert_eq!(
+        proof.verify(&srs, &group_map, new_sponge(), &swapped, rng),
+        Err(ColumnEqualityError::OpenProof)
+    );
+
+    // nor for commitments with another number of chunks
+    swapped[1].1 = &setup[0].1[0].commitment;
+    assert_eq!(
+        proof.verify(&srs, &group_map, new_sponge(), &swapped, rng),
+        Err(ColumnEqualityError::MalformedProof)
+    );
+}
+
+#[test]
+fn test_column_equality_different_columns() {
+    let rng = &mut StdRng::from_seed([1; 32]);
+    let srs = SRS::<Vesta>::create(SRS_SIZE);
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+    let setup = setup(&srs, rng);
+
+    let mut columns = columns(&setup);
+    columns[1].1.poly = &setup[0].0;
+    assert!(matches!(
+        ColumnEqualityProof::create(&srs, &group_map, new_sponge(), &columns, rng),
+        Err(ColumnEqualityError::DifferentColumns(1))
+    ));
+}
@@ -0,0 +1,152 @@
+use super::framework::TestFramework;
+use crate::circuits::{
+    builder::CircuitBuilder,
+    compare::{UInt, UInt256},
+    constraints::ConstraintSystem,
+    wires::COLUMNS,
+};
+use ark_ff::{One, Zero};
+use mina_curves::pasta::{Fp, Vesta};
+use num_bigint::BigUint;
+
+// The names of the limbs of the private input `name`
+fn limb_names<const LIMBS: usize>(name: &str) -> [String; LIMBS] {
+    std::array::from_fn(|i| format!("{name}{i}"))
+}
+
+// Compares the private inputs a and b, with the results of <, >, <=, >= and == as public outputs
+fn create_circuit<const LIMBS: usize>() -> CircuitBuilder<Fp> {
+    let mut builder = CircuitBuilder::new();
+    let [a, b] = ["a", "b"].map(|name| {
+        let limbs = limb_names::<LIMBS>(name).map(|limb| builder.private_input(&limb).unwrap());
+        UInt::from_limbs(&mut builder, limbs)
+    });
+
+    let outputs = [
+        a.lt(&mut builder, &b),
+        a.gt(&mut builder, &b),
+        a.le(&mut builder, &b),
+        a.ge(&mut builder, &b),
+        a.equals(&mut builder, &b),
+    ];
+    for output in outputs {
+        builder.public_output(output);
+    }
+
+    builder
+}
+
+// The witness of the circuit comparing a and b, and its public outputs
+fn compare<const LIMBS: usize>(
+    builder: &CircuitBuilder<Fp>,
+    a: &BigUint,
+    b: &BigUint,
+) -> ([Vec<Fp>; COLUMNS], Vec<Fp>) {
+    let names: Vec<_> = limb_names::<LIMBS>("a")
+        .into_iter()
+        .chain(limb_names::<LIMBS>("b"))
+        .collect();
+    let values = UInt::<LIMBS>::to_limbs::<Fp>(a)
+        .into_iter()
+        .chain(UInt::<LIMBS>::to_limbs(b));
+    let inputs: Vec<_> = names.iter().map(String::as_str).zip(values).collect();
+
+    let witness = builder.generate_witness(&inputs).unwrap();
+    let public = witness[0][..builder.public()].to_vec();
+    (witness, public)
+}
+
+fn expected(a: &BigUint, b: &BigUint) -> Vec<Fp> {
+    [a < b, a > b, a <= b, a >= b, a == b]
+        .map(|result| if result { Fp::one() } else { Fp::zero() })
+        .to_vec()
+}
+
+fn check_comparisons<const LIMBS: usize>(pairs: &[(BigUint, BigUint)]) {
+    let builder = create_circuit::<LIMBS>();
+    let cs = builder.constraint_system().unwrap().build().unwrap();
+
+    for (a, b) in pairs {
+        let (witness, public) = compare::<LIMBS>(&builder, a, b);
+        assert_eq!(public, expected(a, b), "comparing {a} and {b}");
+        assert_eq!(cs.verify::<Vesta>(&witness, &public), Ok(()));
+    }
+
+    let (a, b) = &pairs[0];
+    let (witness, public) = compare::<LIMBS>(&builder, a, b);
+    TestFramework::default()
+        .gates(builder.gates().unwrap())
+        .witness(witness)
+        .public_inputs(public)
+        .setup()
+        .prove_and_verify();
+}
+
+// Pairs of integers of `bits` bits differing in their low or high limbs, or equal
+fn pairs(bits: u64) -> Vec<(BigUint, BigUint)> {
+    let one = BigUint::one();
+    let max = (&one << bits) - 1u32;
+    let high = &one << (bits - 1);
+    let low = BigUint::from(u64::MAX);
+    vec![
+        (low.clone(), high.clone()),
+        (high.clone(), low.clone()),
+        (high.clone(), high.clone()),
+        (&high + 1u32, high.clone()),
+        (high.clone(), &high + &low),
+        (max.clone(), max.clone()),
+        (max.clone(), &max - 1u32),
+        (BigUint::zero(), max),
+        (BigUint::zero(), BigUint::zero()),
+    ]
+}
+
+#[test]
+fn test_compare_uint128() {
+    check_comparisons::<2>(&pairs(128));
+}
+
+#[test]
+fn test_compare_uint256() {
+    check_comparisons::<4>(&pairs(256));
+}
+
+#[test]
+fn test_compare_uint256_witness_and_constant() {
+    // a balance given as a private 64-bit input, scaled to 256 bits, must cover a constant amount
+    let mut builder = CircuitBuilder::<Fp>::new();
+    let x = builder.private_input("x").unwrap();
+    let balance = UInt256::witness(&mut builder, move |v| {
+        let x: BigUint = v.get(x).into();
+        x << 192
+    });
+    let amount = UInt256::constant(&mut builder, &(BigUint::from(5u32) << 192));
+    let covered = balance.ge(&mut builder, &amount);
+    builder.public_output(covered);
+
+    let cs = builder.constraint_system().unwrap().build().unwrap();
+    for (x, covered) in [(4u64, false), (5, true), (u64::MAX, true)] {
+        let witness = builder.generate_witness(&[("x", Fp::from(x))]).unwrap();
+        let public = witness[0][..builder.public()].to_vec();
+        assert_eq!(public, vec![Fp::from(u64::from(covered))]);
+        assert_eq!(cs.verify::<Vesta>(&witness, &public), Ok(()));
+    }
+}
+
+#[test]
+fn test_compare_limb_out_of_range() {
+    // a limb of 2^64 is not a limb of 64 bits, even if the integer is the same
+    let builder = create_circuit::<2>();
+    let cs: ConstraintSystem<Fp> = builder.constraint_system().unwrap().build().unwrap();
+
+    let two_to_64 = Fp::from(u64::MAX) + Fp::one();
+    let inputs = [
+        ("a0", two_to_64),
+        ("a1", Fp::zero()),
+        ("b0", Fp::zero()),
+        ("b1", Fp::one()),
+    ];
+    let witness = builder.generate_witness(&inputs).unwrap();
+    let public = witness[0][..builder.public()].to_vec();
+    assert!(cs.verify::<Vesta>(&witness, &public).is_err());
+}
@@ -0,0 +1,543 @@
+use crate::{
+    circuits::{
+        argument::ArgumentEnv,
+        constraints::ConstraintSystem,
+        custom_gate::{CustomGate, CUSTOM_GATE_SLOTS},
+        expr::{constraints::ExprOps, E},
+        gate::{CircuitGate, CircuitGateError, GateType},
+        polynomials::permutation::min_zk_rows,
+        wires::{Wire, COLUMNS},
+    },
+    error::{CustomGateError, SetupError, VerifyError},
+    proof::{inspect::ProofSummary, ProverProof},
+    prover_index::ProverIndex,
+    verifier::verify,
+};
+use ark_ff::{PrimeField, Zero};
+use ark_poly::EvaluationDomain;
+use commitment_dlog::{
+    commitment::CommitmentCurve,
+    srs::{endos, SRS},
+};
+use groupmap::GroupMap;
+use mina_curves::pasta::{Fp, Pallas, Vesta, VestaParameters};
+use oracle::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use std::{array, sync::Arc};
+
+type BaseSponge = DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>;
+type ScalarSponge = DefaultFrSponge<Fp, PlonkSpongeConstantsKimchi>;
+
+// w1 = w0^3 + w2
+struct Cube;
+
+impl Cube {
+    fn checks<F: PrimeField, T: ExprOps<F>>(env: &ArgumentEnv<F, T>) -> Vec<T> {
+        vec![env.witness_curr(1) - env.witness_curr(0).pow(3) - env.witness_curr(2)]
+    }
+}
+
+impl<F: PrimeField> CustomGate<F> for Cube {
+    fn name(&self) -> &str {
+        "Cube"
+    }
+
+    fn constraint_count(&self) -> u32 {
+        1
+    }
+
+    fn degree(&self) -> u64 {
+        3
+    }
+
+    fn rows(&self) -> usize {
+        1
+    }
+
+    fn constraints(&self, env: &ArgumentEnv<F, E<F>>) -> Vec<E<F>> {
+        Self::checks(env)
+    }
+
+    fn constraint_checks(&self, env: &ArgumentEnv<F, F>) -> Vec<F> {
+        Self::checks(env)
+    }
+}
+
+// w1 = w0^3 - w2, which only differs from Cube by its constraint
+struct CubeSub;
+
+impl CubeSub {
+    fn checks<F: PrimeField, T: ExprOps<F>>(env: &ArgumentEnv<F, T>) -> Vec<T> {
+        vec![env.witness_curr(1) - env.witness_curr(0).pow(3) + env.witness_curr(2)]
+    }
+}
+
+impl<F: PrimeField> CustomGate<F> for CubeSub {
+    fn name(&self) -> &str {
+        "Cube"
+    }
+
+    fn constraint_count(&self) -> u32 {
+        1
+    }
+
+    fn degree(&self) -> u64 {
+        3
+    }
+
+    fn rows(&self) -> usize {
+        1
+    }
+
+    fn constraints(&self, env: &ArgumentEnv<F, E<F>>) -> Vec<E<F>> {
+        Self::checks(env)
+    }
+
+    fn constraint_checks(&self, env: &ArgumentEnv<F, F>) -> Vec<F> {
+        Self::checks(env)
+    }
+}
+
+// w0' = w0 * w1, and w2 is boolean
+struct MulNext;
+
+impl MulNext {
+    fn checks<F: PrimeField, T: ExprOps<F>>(env: &ArgumentEnv<F, T>) -> Vec<T> {
+        vec![
+            env.witness_next(0) - env.witness_curr(0) * env.witness_curr(1),
+            env.witness_curr(2).boolean(),
+        ]
+    }
+}
+
+impl<F: PrimeField> CustomGate<F> for MulNext {
+    fn name(&self) -> &str {
+        "MulNext"
+    }
+
+    fn constraint_count(&self) -> u32 {
+        2
+    }
+
+    fn degree(&self) -> u64 {
+        2
+    }
+
+    fn rows(&self) -> usize {
+        2
+    }
+
+    fn constraints(&self, env: &ArgumentEnv<F, E<F>>) -> Vec<E<F>> {
+        Self::checks(env)
+    }
+
+    fn constraint_checks(&self, env: &ArgumentEnv<F, F>) -> Vec<F> {
+        Self::checks(env)
+    }
+}
+
+// w0 = w0_prev + w1_prev and w1 = w0_prev (a Fibonacci step)
+struct FibonacciPrev;
+
+impl FibonacciPrev {
+    fn checks<F: PrimeField, T: ExprOps<F>>(env: &ArgumentEnv<F, T>) -> Vec<T> {
+        vec![
+            env.witness_curr(0) - env.witness_prev(0) - env.witness_prev(1),
+            env.witness_curr(1) - env.witness_prev(0),
+        ]
+    }
+}
+
+impl<F: PrimeField> CustomGate<F> for FibonacciPrev {
+    fn name(&self) -> &str {
+        "FibonacciPrev"
+    }
+
+    fn constraint_count(&self) -> u32 {
+        2
+    }
+
+    fn degree(&self) -> u64 {
+        1
+    }
+
+    fn rows(&self) -> usize {
+        1
+    }
+
+    fn constraints(&self, env: &ArgumentEnv<F, E<F>>) -> Vec<E<F>> {
+        Self::checks(env)
+    }
+
+    fn constraint_checks(&self, env: &ArgumentEnv<F, F>) -> Vec<F> {
+        Self::checks(env)
+    }
+}
+
+// a gate declaring the wrong degree
+struct WrongDegree;
+
+impl<F: PrimeField> CustomGate<F> for WrongDegree {
+    fn name(&self) -> &str {
+        "WrongDegree"
+    }
+
+    fn constraint_count(&self) -> u32 {
+        1
+    }
+
+    fn degree(&self) -> u64 {
+        2
+    }
+
+    fn rows(&self) -> usize {
+        1
+    }
+
+    fn constraints(&self, env: &ArgumentEnv<F, E<F>>) -> Vec<E<F>> {
+        Cube::checks(env)
+    }
+
+    fn constraint_checks(&self, env: &ArgumentEnv<F, F>) -> Vec<F> {
+        Cube::checks(env)
+    }
+}
+
+// a gate reading a coefficient: w1 = w0 + c0
+struct AddCoeff;
+
+impl AddCoeff {
+    fn checks<F: PrimeField, T: ExprOps<F>>(env: &ArgumentEnv<F, T>) -> Vec<T> {
+        vec![env.witness_curr(1) - env.witness_curr(0) - env.coeff(0)]
+    }
+}
+
+impl<F: PrimeField> CustomGate<F> for AddCoeff {
+    fn name(&self) -> &str {
+        "AddCoeff"
+    }
+
+    fn constraint_count(&self) -> u32 {
+        1
+    }
+
+    fn degree(&self) -> u64 {
+        1
+    }
+
+    fn rows(&self) -> usize {
+        1
+    }
+
+    fn constraints(&self, env: &ArgumentEnv<F, E<F>>) -> Vec<E<F>> {
+        Self::checks(env)
+    }
+
+    fn constraint_checks(&self, env: &ArgumentEnv<F, F>) -> Vec<F> {
+        Self::checks(env)
+    }
+}
+
+fn custom_gates() -> Vec<Arc<dyn CustomGate<Fp>>> {
+    vec![Arc::new(Cube), Arc::new(MulNext)]
+}
+
+// a circuit made of a few Cube gates, and of a MulNext gate followed by a zero gate
+fn create_circuit() -> Vec<CircuitGate<Fp>> {
+    let cube = GateType::custom(0).unwrap();
+    let mul_next = GateType::custom(1).unwrap();
+    let mut gates: Vec<_> = (0..4)
+        .map(|row| CircuitGate {
+            typ: cube,
+            wires: Wire::new(row),
+            coeffs: vec![],
+        })
+        .collect();
+    gates.push(CircuitGate {
+        typ: mul_next,
+        wires: Wire::new(4),
+        coeffs: vec![],
+    });
+    gates.push(CircuitGate::zero(Wire::new(5)));
+    gates
+}
+
+fn create_witness() -> [Vec<Fp>; COLUMNS] {
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); 6]);
+    for (row, offset) in (0..4u64).map(Fp::from).enumerate() {
+        let x = offset + Fp::from(2u64);
+        witness[0][row] = x;
+        witness[1][row] = x * x * x + offset;
+        witness[2][row] = offset;
+    }
+    witness[0][4] = Fp::from(6u64);
+    witness[1][4] = Fp::from(7u64);
+    witness[2][4] = Fp::from(1u64);
+    witness[0][5] = Fp::from(42u64);
+    witness
+}
+
+fn create_constraint_system() -> ConstraintSystem<Fp> {
+    ConstraintSystem::create(create_circuit())
+        .custom_gates(custom_gates())
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn test_custom_gate_valid_witness() {
+    let cs = create_constraint_system();
+    assert_eq!(cs.custom_selector_polys.len(), 2);
+    cs.verify::<Vesta>(&create_witness(), &[]).unwrap();
+}
+
+#[test]
+fn test_custom_gate_invalid_witness() {
+    let cs = create_constraint_system();
+
+    let mut witness = create_witness();
+    witness[1][2] += Fp::from(1u64);
+    assert_eq!(
+        cs.gates[2].verify_witness::<Vesta>(2, &witness, &cs, &[]),
+        Err(CircuitGateError::Constraint(GateType::Custom0, 0))
+    );
+
+    let mut witness = create_witness();
+    witness[0][5] += Fp::from(1u64);
+    assert_eq!(
+        cs.gates[4].verify_witness::<Vesta>(4, &witness, &cs, &[]),
+        Err(CircuitGateError::Constraint(GateType::Custom1, 0))
+    );
+}
+
+#[test]
+fn test_custom_gate_registration_errors() {
+    // the gate declares a degree that does not match its constraints
+    let result = ConstraintSystem::create(create_circuit())
+        .custom_gates(vec![Arc::new(WrongDegree), Arc::new(MulNext)])
+        .build();
+    assert!(matches!(
+        result,
+        Err(SetupError::CustomGate(CustomGateError::Degree {
+            declared: 2,
+            actual: 3,
+            ..
+        }))
+    ));
+
+    // the gate reads a coefficient, which is multiplied by its selector
+    let result = ConstraintSystem::create(create_circuit())
+        .custom_gates(vec![Arc::new(AddCoeff), Arc::new(MulNext)])
+        .build();
+    assert!(matches!(
+        result,
+        Err(SetupError::CustomGate(CustomGateError::Linearization(name))) if name == "AddCoeff"
+    ));
+
+    // the second custom gate type is used, but not registered
+    let result = ConstraintSystem::create(create_circuit())
+        .custom_gates(vec![Arc::new(Cube)])
+        .build();
+    assert!(matches!(
+        result,
+        Err(SetupError::CustomGate(CustomGateError::Unregistered(
+            GateType::Custom1,
+            4
+        )))
+    ));
+
+    // the gate using two rows is not followed by a zero gate
+    let mut gates = create_circuit();
+    gates[5].typ = GateType::Generic;
+    let result = ConstraintSystem::create(gates)
+        .custom_gates(custom_gates())
+        .build();
+    assert!(matches!(
+        result,
+        Err(SetupError::CustomGate(CustomGateError::NextRow(_, 4)))
+    ));
+
+    // too many custom gates
+    let gates = vec![Arc::new(Cube) as Arc<dyn CustomGate<Fp>>; CUSTOM_GATE_SLOTS + 1];
+    let result = ConstraintSystem::create(create_circuit())
+        .custom_gates(gates)
+        .build();
+    assert!(matches!(
+        result,
+        Err(SetupError::CustomGate(CustomGateError::TooManyGates(_)))
+    ));
+}
+
+#[test]
+fn test_custom_gate_prove_and_verify() {
+    let cs = create_constraint_system();
+    let mut srs = SRS::<Vesta>::create(cs.domain.d1.size());
+    srs.add_lagrange_basis(cs.domain.d1);
+    let (endo_q, _endo_r) = endos::<Pallas>();
+    let index = ProverIndex::<Vesta>::create(cs, endo_q, Arc::new(srs));
+    let verifier_index = index.verifier_index();
+    assert_eq!(verifier_index.custom_gates_comm.len(), 2);
+
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+    let proof =
+        ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, create_witness(), &[], &index)
+            .unwrap();
+    verify::<Vesta, BaseSponge, ScalarSponge>(&group_map, &verifier_index, &proof).unwrap();
+
+    // the gates only read the current and next rows
+    assert!(proof.prev_row.is_none());
+}
+
+#[test]
+fn test_custom_gate_circuit_digest() {
+    let verifier_index = |gates: Vec<Arc<dyn CustomGate<Fp>>>| {
+        let cs = ConstraintSystem::create(create_circuit())
+            .custom_gates(gates)
+            .build()
+            .unwrap();
+        let mut srs = SRS::<Vesta>::create(cs.domain.d1.size());
+        srs.add_lagrange_basis(cs.domain.d1);
+        let (endo_q, _endo_r) = endos::<Pallas>();
+        ProverIndex::<Vesta>::create(cs, endo_q, Arc::new(srs)).verifier_index()
+    };
+    let index = verifier_index(custom_gates());
+    let digest = index.circuit_digest().unwrap();
+
+    // the linearization, and so the digest, does not depend on the order of a hash map
+    assert_eq!(
+        verifier_index(custom_gates()).circuit_digest().unwrap(),
+        digest
+    );
+
+    // the constraints of the custom gates are covered, not only their selectors
+    let other = verifier_index(vec![Arc::new(CubeSub), Arc::new(MulNext)]);
+    assert_eq!(other.compress().unwrap(), index.compress().unwrap());
+    assert_ne!(other.circuit_digest().unwrap(), digest);
+}
+
+// a zero gate followed by Fibonacci steps, each reading the row before it
+fn create_fibonacci_circuit(steps: usize) -> (Vec<CircuitGate<Fp>>, [Vec<Fp>; COLUMNS]) {
+    let fibonacci = GateType::custom(0).unwrap();
+    let mut gates = vec![CircuitGate::zero(Wire::new(0))];
+    gates.extend((1..=steps).map(|row| CircuitGate {
+        typ: fibonacci,
+        wires: Wire::new(row),
+        coeffs: vec![],
+    }));
+
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); steps + 1]);
+    witness[0][0] = Fp::from(1u64);
+    witness[1][0] = Fp::from(1u64);
+    for row in 1..=steps {
+        witness[0][row] = witness[0][row - 1] + witness[1][row - 1];
+        witness[1][row] = witness[0][row - 1];
+    }
+    (gates, witness)
+}
+
+#[test]
+fn test_custom_gate_reading_prev_row() {
+    let (gates, witness) = create_fibonacci_circuit(8);
+    let cs = ConstraintSystem::create(gates)
+        .custom_gates(vec![Arc::new(FibonacciPrev)])
+        .zk_rows(min_zk_rows(3))
+        .build()
+        .unwrap();
+    cs.verify::<Vesta>(&witness, &[]).unwrap();
+
+    let mut invalid = witness.clone();
+    invalid[1][3] += Fp::from(1u64);
+    assert_eq!(
+        cs.gates[4].verify_witness::<Vesta>(4, &invalid, &cs, &[]),
+        Err(CircuitGateError::Constraint(GateType::Custom0, 0))
+    );
+
+    let mut srs = SRS::<Vesta>::create(cs.domain.d1.size());
+    srs.add_lagrange_basis(cs.domain.d1);
+    let (endo_q, _endo_r) = endos::<Pallas>();
+    let index = ProverIndex::<Vesta>::create(cs, endo_q, Arc::new(srs));
+    let verifier_index = index.verifier_index();
+    assert!(index.linearization.reads_prev_row());
+
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+    let proof =
+        ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness, &[], &index).unwrap();
+    verify::<Vesta, BaseSponge, ScalarSponge>(&group_map, &verifier_index, &proof).unwrap();
+
+    // the evaluations on the previous row are part of the compressed encoding
+    let decompressed = ProverProof::<Vesta>::decompress(&proof.compress().unwrap()).unwrap();
+    verify::<Vesta, BaseSponge, ScalarSponge>(&group_map, &verifier_index, &decompressed).unwrap();
+
+    // the inspection of the proof reports them
+    let summary = ProofSummary::new::<Vesta, BaseSponge, ScalarSponge>(&verifier_index, &proof);
+    assert!(summary.is_well_formed());
+    let prev_row = proof.prev_row.as_ref().unwrap();
+    assert_eq!(summary.ft_eval_prev, Some(prev_row.ft_eval));
+    assert_eq!(
+        summary.evaluations[0].zeta_prev,
+        Some(prev_row.evals.w[0].clone())
+    );
+    assert!(summary
+        .to_string()
+        .contains("\nevaluations (zeta | zeta omega | zeta / omega):\n"));
+
+    // the evaluations on the previous row are required
+    let mut stripped = proof.clone();
+    stripped.prev_row = None;
+    assert!(matches!(
+        verify::<Vesta, BaseSponge, ScalarSponge>(&group_map, &verifier_index, &stripped),
+        Err(VerifyError::IncorrectPrevRowEvaluations)
+    ));
+    let summary = ProofSummary::new::<Vesta, BaseSponge, ScalarSponge>(&verifier_index, &stripped);
+    assert_eq!(
+        summary.issues[0],
+        "the proof has no evaluations on the previous row, but the circuit reads it"
+    );
+
+    // and they are checked against the commitments by the batched opening proof
+    let mut tampered = proof.clone();
+    tampered.prev_row.as_mut().unwrap().evals.w[0][0] += Fp::from(1u64);
+    assert!(
+        verify::<Vesta, BaseSponge, ScalarSponge>(&group_map, &verifier_index, &tampered).is_err()
+    );
+    let mut tampered = proof;
+    tampered.prev_row.as_mut().unwrap().ft_eval += Fp::from(1u64);
+    assert!(
+        verify::<Vesta, BaseSponge, ScalarSponge>(&group_map, &verifier_index, &tampered).is_err()
+    );
+}
+
+#[test]
+fn test_custom_gate_reading_prev_row_errors() {
+    // the third evaluation point requires one more zero-knowledge row
+    let (gates, _) = create_fibonacci_circuit(8);
+    let result = ConstraintSystem::create(gates)
+        .custom_gates(vec![Arc::new(FibonacciPrev)])
+        .build();
+    assert!(matches!(
+        result,
+        Err(SetupError::PrevRowZkRows { zk_rows: 3, min: 4 })
+    ));
+
+    // but not without zero-knowledge
+    let (gates, _) = create_fibonacci_circuit(8);
+    let result = ConstraintSystem::create(gates)
+        .custom_gates(vec![Arc::new(FibonacciPrev)])
+        .zk(false)
+        .build();
+    assert!(result.is_ok());
+
+    // the first row has no previous row
+    let (mut gates, _) = create_fibonacci_circuit(8);
+    gates[0].typ = GateType::custom(0).unwrap();
+    let result = ConstraintSystem::create(gates)
+        .custom_gates(vec![Arc::new(FibonacciPrev)])
+        .zk_rows(min_zk_rows(3))
+        .build();
+    assert!(matches!(
+        result,
+        Err(SetupError::CustomGate(CustomGateError::PrevRow(_, 0)))
+    ));
+}
@@ -0,0 +1,121 @@
+use crate::circuits::{
+    constraints::ConstraintSystem,
+    description::CircuitDescription,
+    gate::{CircuitGate, GateType},
+    lookup::{
+        runtime_tables::{RuntimeTableCfg, RuntimeTableSpec},
+        tables::{LookupTable, TableId, TableKind},
+    },
+    polynomials::generic::testing::create_circuit,
+    wires::Wire,
+};
+use ark_ff::One;
+use ark_poly::EvaluationDomain;
+use mina_curves::pasta::Fp;
+
+#[test]
+fn test_description_json_roundtrip() {
+    let gates = create_circuit::<Fp>(0, 0);
+    let cs = ConstraintSystem::create(gates.clone()).build().unwrap();
+
+    let description = CircuitDescription::<Fp>::from_json(&cs.to_json()).unwrap();
+    assert_eq!(description, cs.description());
+    // the gates are padded with zero gates up to the size of the domain
+    assert_eq!(description.gates.len(), cs.domain.d1.size());
+    assert_eq!(description.gates[..gates.len()], gates);
+    assert!(description.gates[gates.len()..]
+        .iter()
+        .all(|gate| gate.typ == GateType::Zero));
+    assert_eq!(description.domain_size, cs.domain.d1.size());
+    assert_eq!(description.shift, cs.shift);
+    assert!(description.lookup.is_none());
+}
+
+#[test]
+fn test_description_lookup_tables() {
+    let fixed_id = TableId::new(-1);
+    let runtime_id = TableId::new(-2);
+    let data: Vec<Vec<Fp>> = vec![
+        [1u32, 2, 3].into_iter().map(Into::into).collect(),
+        [4u32, 5, 6].into_iter().map(Into::into).collect(),
+    ];
+    let spec = RuntimeTableSpec {
+        id: runtime_id,
+        len: 4,
+    };
+
+    let gates = (0..10)
+        .map(|row| CircuitGate {
+            typ: GateType::Lookup,
+            wires: Wire::new(row),
+            coeffs: vec![],
+        })
+        .collect();
+    let cs = ConstraintSystem::create(gates)
+        .lookup(vec![LookupTable {
+            id: fixed_id,
+            data: data.clone(),
+        }])
+        .runtime(Some(vec![RuntimeTableCfg::Indexed(spec.clone())]))
+        .build()
+        .unwrap();
+
+    let description = CircuitDescription::<Fp>::from_json(&cs.to_json()).unwrap();
+    assert_eq!(description, cs.description());
+
+    let lookup = description.lookup.unwrap();
+    assert_eq!(lookup.table_registry.get(fixed_id), Some(TableKind::Fixed));
+    assert_eq!(
+        lookup.table_registry.get(runtime_id),
+        Some(TableKind::Runtime)
+    );
+    assert_eq!(lookup.runtime_tables, Some(vec![spec]));
+
+    // the entries of the fixed table are the rows of the concatenated table with its ID
+    let table_ids = lookup.table_ids.unwrap();
+    let fixed_id: Fp = fixed_id.to_field();
+    let rows: Vec<_> = (0..description.domain_size)
+        .filter(|row| table_ids[*row] == fixed_id)
+        .collect();
+    for (col, values) in data.iter().enumerate() {
+        let entries: Vec<_> = rows.iter().map(|row| lookup.table[col][*row]).collect();
+        assert_eq!(&entries, values);
+    }
+}
+
+#[test]
+fn test_description_detects_changes() {
+    let gates = create_circuit::<Fp>(0, 0);
+    let cs = ConstraintSystem::create(gates.clone()).build().unwrap();
+
+    // a different coefficient
+    let mut other_gates = gates.clone();
+    other_gates[0].coeffs[0] += Fp::one();
+    let other = ConstraintSystem::create(other_gates).build().unwrap();
+    assert_ne!(cs.description(), other.description());
+
+    // a different wiring
+    let mut other_gates = gates.clone();
+    other_gates[0].wires[0] = Wire { row: 1, col: 0 };
+    other_gates[1].wires[0] = Wire { row: 0, col: 0 };
+    let other = ConstraintSystem::create(other_gates).build().unwrap();
+    assert_ne!(cs.description(), other.description());
+
+    // a different number of previous challenges
+    let other = ConstraintSystem::create(gates)
+        .prev_challenges(1)
+        .build()
+        .unwrap();
+    assert_ne!(cs.description(), other.description());
+}
+
+#[test]
+fn test_description_rejects_invalid_json() {
+    assert!(CircuitDescription::<Fp>::from_json("{}").is_err());
+
+    let cs = ConstraintSystem::create(create_circuit::<Fp>(0, 0))
+        .build()
+        .unwrap();
+    let json = cs.to_json().replace("\"public\": 0", "\"public\": -1");
+    assert!(CircuitDescription::<Fp>::from_json(&json).is_err());
+}
@@ -3,14 +3,11 @@ use crate::{
         constraints::ConstraintSystem,
         gate::{CircuitGate, CircuitGateError, GateType},
         polynomial::COLUMNS,
-        polynomials::foreign_field_add::{
-            self,
-            witness::{create_witness, FFOps},
-        },
+        polynomials::foreign_field_add::witness::{create_witness, FFOps},
         wires::Wire,
     },
     proof::ProverProof,
-    prover_index::{testing::new_index_for_test_with_lookups, ProverIndex},
+    prover_index::{testing::TestIndexBuilder, ProverIndex},
     verifier::verify,
 };
 use ark_ec::AffineCurve;
@@ -166,14 +163,9 @@ fn create_test_prover_index(num: usize, modulus: BigUint) -> ProverIndex<Vesta>
         next_row += 1;
     }
 
-    new_index_for_test_with_lookups(
-        gates,
-        0,
-        0,
-        vec![foreign_field_add::gadget::lookup_table()],
-        None,
-        Some(modulus),
-    )
+    TestIndexBuilder::new(gates)
+        .foreign_modulus(Some(modulus))
+        .build()
 }
 
 // returns the maximum value for a field of modulus size
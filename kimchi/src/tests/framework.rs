@@ -10,7 +10,7 @@ use crate::{
         wires::COLUMNS,
     },
     proof::{ProverProof, RecursionChallenge},
-    prover_index::{testing::new_index_for_test_with_lookups, ProverIndex},
+    prover_index::{testing::TestIndexBuilder, ProverIndex},
     verifier::verify,
     verifier_index::VerifierIndex,
 };
@@ -42,6 +42,9 @@ pub(crate) struct TestFramework {
     recursion: Vec<RecursionChallenge<Vesta>>,
     foreign_modulus: Option<BigUint>,
     num_prev_challenges: usize,
+    zk_rows: Option<u64>,
+    zk: Option<bool>,
+    srs_size: Option<usize>,
 
     prover_index: Option<ProverIndex<Vesta>>,
     verifier_index: Option<VerifierIndex<Vesta>>,
@@ -86,6 +89,24 @@ impl TestFramework {
         self
     }
 
+    #[must_use]
+    pub(crate) fn zk_rows(mut self, zk_rows: u64) -> Self {
+        self.zk_rows = Some(zk_rows);
+        self
+    }
+
+    #[must_use]
+    pub(crate) fn zk(mut self, zk: bool) -> Self {
+        self.zk = Some(zk);
+        self
+    }
+
+    #[must_use]
+    pub(crate) fn srs_size(mut self, srs_size: usize) -> Self {
+        self.srs_size = Some(srs_size);
+        self
+    }
+
     #[must_use]
     pub(crate) fn runtime_tables_setup(
         mut self,
@@ -104,14 +125,22 @@ impl TestFramework {
         let runtime_tables_setup = mem::replace(&mut self.runtime_tables_setup, None);
         let foreign_modulus_setup = mem::replace(&mut self.foreign_modulus, None);
 
-        let index = new_index_for_test_with_lookups(
-            self.gates.take().unwrap(),
-            self.public_inputs.len(),
-            self.num_prev_challenges,
-            lookup_tables,
-            runtime_tables_setup,
-            foreign_modulus_setup,
-        );
+        let mut builder = TestIndexBuilder::new(self.gates.take().unwrap())
+            .public(self.public_inputs.len())
+            .prev_challenges(self.num_prev_challenges)
+            .lookup_tables(lookup_tables)
+            .runtime_tables(runtime_tables_setup)
+            .foreign_modulus(foreign_modulus_setup);
+        if let Some(zk_rows) = self.zk_rows {
+            builder = builder.zk_rows(zk_rows);
+        }
+        if let Some(zk) = self.zk {
+            builder = builder.zk(zk);
+        }
+        if let Some(srs_size) = self.srs_size {
+            builder = builder.srs_size(srs_size);
+        }
+        let index = builder.build();
         println!(
             "- time to create prover index: {:?}s",
             start.elapsed().as_secs()
@@ -137,6 +166,12 @@ impl TestRunner {
         self
     }
 
+    #[must_use]
+    pub(crate) fn memory_budget(mut self, memory_budget: usize) -> Self {
+        self.0.prover_index.as_mut().unwrap().memory_budget = Some(memory_budget);
+        self
+    }
+
     pub(crate) fn prover_index(&self) -> &ProverIndex<Vesta> {
         self.0.prover_index.as_ref().unwrap()
     }
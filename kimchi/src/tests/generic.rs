@@ -1,9 +1,111 @@
 use super::framework::TestFramework;
-use crate::circuits::polynomials::generic::testing::{create_circuit, fill_in_witness};
-use crate::circuits::wires::COLUMNS;
+use crate::circuits::{
+    constraints::{ConstraintSystem, GateError},
+    gate::{CircuitGate, Connect, GateType},
+    polynomials::generic::{
+        testing::{create_circuit, fill_in_witness},
+        GenericGateSpec,
+    },
+    wires::{Wire, COLUMNS},
+};
+use crate::{
+    error::{ProverError, SetupError},
+    parallel::{Parallelism, Partitioning},
+    proof::ProverProof,
+    prover::WitnessCommitter,
+    prover_index::{testing::new_index_for_test, ProverIndex},
+    verifier::{aggregate_verify, verify, verify_with_public_output, BatchEntry},
+};
 use ark_ff::Zero;
-use mina_curves::pasta::Fp;
-use std::array;
+use ark_poly::EvaluationDomain;
+use commitment_dlog::{
+    commitment::{CommitmentCurve, PolyComm},
+    srs::endos,
+    srs::SRS,
+};
+use groupmap::GroupMap;
+use mina_curves::pasta::{Fp, Pallas, Vesta, VestaParameters};
+use oracle::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use rand::{rngs::StdRng, SeedableRng};
+use std::{array, sync::Arc};
+
+type BaseSponge = DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>;
+type ScalarSponge = DefaultFrSponge<Fp, PlonkSpongeConstantsKimchi>;
+
+#[test]
+fn test_generic_gate_failures() {
+    let gates = create_circuit(0, 0);
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &[]);
+    let cs = ConstraintSystem::create(gates).build().unwrap();
+    assert_eq!(cs.verify_verbose::<Vesta>(&witness, &[]), vec![]);
+
+    // break the output of the multiplication of row 1, and of the addition of row 3
+    witness[5][1] += Fp::from(1u8);
+    witness[2][3] += Fp::from(1u8);
+
+    // the first failure is pinpointed to the second equation of the generic gate
+    let failure = match cs.verify::<Vesta>(&witness, &[]) {
+        Err(GateError::Constraint(failure)) => failure,
+        res => panic!("unexpected result {res:?}"),
+    };
+    assert_eq!((failure.row, failure.typ), (1, GateType::Generic));
+    assert_eq!(failure.constraint, Some(1));
+    assert!(failure.values[0].is_zero());
+    assert_eq!(failure.values[1], -Fp::from(1u8));
+    assert_eq!(failure.witness[5], witness[5][1]);
+
+    // the verbose verification reports both failures
+    let failures: Vec<_> = cs
+        .verify_verbose::<Vesta>(&witness, &[])
+        .into_iter()
+        .map(|err| match err {
+            GateError::Constraint(failure) => (failure.row, failure.constraint),
+            err => panic!("unexpected error {err:?}"),
+        })
+        .collect();
+    assert_eq!(failures, vec![(1, Some(1)), (3, Some(0))]);
+}
+
+#[test]
+fn test_generic_gate_first_failure() {
+    // a circuit long enough for its rows to be checked by several threads
+    let gates: Vec<_> = (0..64).flat_map(|i| create_circuit(20 * i, 0)).collect();
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    for i in 0..64 {
+        fill_in_witness(20 * i, &mut witness, &[]);
+    }
+    let cs = ConstraintSystem::create(gates).build().unwrap();
+    assert_eq!(cs.verify::<Vesta>(&witness, &[]), Ok(()));
+
+    // break the output of the addition of every row of additions after the first ones
+    let rows: Vec<_> = (1..64).flat_map(|i| 20 * i..20 * i + 10).collect();
+    for row in &rows {
+        witness[2][*row] += Fp::from(1u8);
+    }
+
+    // the failure reported is always the one of the first broken row
+    for _ in 0..4 {
+        match cs.verify::<Vesta>(&witness, &[]) {
+            Err(GateError::Constraint(failure)) => assert_eq!(failure.row, rows[0]),
+            res => panic!("unexpected result {res:?}"),
+        }
+    }
+
+    // and the verbose verification reports all of them, in order
+    let failures: Vec<_> = cs
+        .verify_verbose::<Vesta>(&witness, &[])
+        .into_iter()
+        .map(|err| match err {
+            GateError::Constraint(failure) => failure.row,
+            err => panic!("unexpected error {err:?}"),
+        })
+        .collect();
+    assert_eq!(failures, rows);
+}
 
 #[test]
 fn test_generic_gate() {
@@ -57,6 +159,291 @@ fn test_generic_gate_pub_all_zeros() {
         .prove_and_verify();
 }
 
+#[test]
+fn test_generic_gate_small_srs() {
+    let public = vec![Fp::from(3u8); 5];
+    let gates = create_circuit(0, public.len());
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &public);
+
+    // the SRS is smaller than the domain, so the commitments are chunked
+    let runner = TestFramework::default()
+        .gates(gates)
+        .witness(witness)
+        .public_inputs(public)
+        .srs_size(16)
+        .setup();
+    assert!(runner.prover_index().num_chunks() > 1);
+    runner.prove_and_verify();
+}
+
+/// Proves and verifies the generic circuit with an SRS a quarter of the size of the domain
+fn prove_and_verify_chunked(public: Vec<Fp>) {
+    let gates = create_circuit(0, public.len());
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &public);
+
+    let cs = ConstraintSystem::create(gates)
+        .public(public.len())
+        .build()
+        .unwrap();
+    let mut srs = SRS::<Vesta>::create(cs.domain.d1.size() / 4);
+    srs.add_lagrange_basis(cs.domain.d1);
+    let (endo_q, _endo_r) = endos::<Pallas>();
+    let index = ProverIndex::<Vesta>::create(cs, endo_q, Arc::new(srs));
+    assert_eq!(index.num_chunks(), 4);
+
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+    let proof =
+        ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness, &[], &index).unwrap();
+    assert_eq!(proof.commitments.w_comm[0].unshifted.len(), 4);
+    assert_eq!(proof.evals[0].w[0].len(), 4);
+
+    let verifier_index = index.verifier_index();
+    verify::<Vesta, BaseSponge, ScalarSponge>(&group_map, &verifier_index, &proof).unwrap();
+}
+
+#[test]
+fn test_generic_gate_chunked() {
+    prove_and_verify_chunked(vec![]);
+    prove_and_verify_chunked(vec![Fp::from(3u8); 5]);
+    prove_and_verify_chunked(vec![Fp::from(0u8); 5]);
+}
+
+#[test]
+fn test_generic_gate_deterministic() {
+    let public = vec![Fp::from(3u8); 5];
+    let gates = create_circuit(0, public.len());
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &public);
+
+    let index = new_index_for_test(gates, public.len());
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+    let prove = |seed: u64| {
+        let proof = ProverProof::create_with_rng::<BaseSponge, ScalarSponge>(
+            &group_map,
+            witness.clone(),
+            &[],
+            &index,
+            &mut StdRng::seed_from_u64(seed),
+        )
+        .unwrap();
+        rmp_serde::to_vec(&proof).unwrap()
+    };
+
+    // the same seed gives the same proof, byte-for-byte
+    let proof = prove(0);
+    assert_eq!(proof, prove(0));
+    assert_ne!(proof, prove(1));
+
+    let verifier_index = index.verifier_index();
+    let proof: ProverProof<Vesta> = rmp_serde::from_slice(&proof).unwrap();
+    verify::<Vesta, BaseSponge, ScalarSponge>(&group_map, &verifier_index, &proof).unwrap();
+}
+
+#[test]
+fn test_generic_gate_partitioning() {
+    let public = vec![Fp::from(3u8); 5];
+    let gates = create_circuit(0, public.len());
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &public);
+
+    let mut index = new_index_for_test(gates, public.len());
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+    let mut prove = |partitioning: Partitioning| {
+        index.parallelism = Some(Parallelism::uniform(partitioning));
+        let proof = ProverProof::create_with_rng::<BaseSponge, ScalarSponge>(
+            &group_map,
+            witness.clone(),
+            &[],
+            &index,
+            &mut StdRng::seed_from_u64(0),
+        )
+        .unwrap();
+        rmp_serde::to_vec(&proof).unwrap()
+    };
+
+    // the partitioning of the work between the threads does not change the proof
+    assert_eq!(prove(Partitioning::Rows), prove(Partitioning::Columns));
+}
+
+#[test]
+fn test_generic_gate_absent_selectors() {
+    let gates = create_circuit(0, 0);
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &[]);
+    let index = new_index_for_test(gates, 0);
+
+    // the circuit has no elliptic curve gate, whose selectors are not committed to
+    let verifier_index = index.verifier_index();
+    assert!(verifier_index.complete_add_comm.is_none());
+    assert!(verifier_index.mul_comm.is_none());
+    assert!(verifier_index.emul_comm.is_none());
+    assert!(verifier_index.endomul_scalar_comm.is_none());
+
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+    let proof =
+        ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness, &[], &index).unwrap();
+    verify::<Vesta, BaseSponge, ScalarSponge>(&group_map, &verifier_index, &proof).unwrap();
+
+    // committing to the zero selectors gives the same digest, and verifies the same proofs,
+    // with a larger index
+    let zero = PolyComm {
+        unshifted: vec![Vesta::zero()],
+        shifted: None,
+    };
+    let mut committed = verifier_index.clone();
+    committed.complete_add_comm = Some(zero.clone());
+    committed.mul_comm = Some(zero.clone());
+    committed.emul_comm = Some(zero.clone());
+    committed.endomul_scalar_comm = Some(zero);
+    assert_eq!(
+        committed.digest::<BaseSponge>(),
+        verifier_index.digest::<BaseSponge>()
+    );
+    verify::<Vesta, BaseSponge, ScalarSponge>(&group_map, &committed, &proof).unwrap();
+    assert!(committed.compress().unwrap().len() > verifier_index.compress().unwrap().len());
+}
+
+#[test]
+fn test_generic_gate_aggregation() {
+    // two different circuits, with and without public inputs, sharing one SRS
+    let publics = [vec![], vec![Fp::from(3u8); 5]];
+    let css: Vec<_> = publics
+        .iter()
+        .map(|public| {
+            let gates = create_circuit(0, public.len());
+            let rows = gates.len();
+            let cs = ConstraintSystem::create(gates)
+                .public(public.len())
+                .build()
+                .unwrap();
+            (rows, cs)
+        })
+        .collect();
+    let srs_size = css.iter().map(|(_, cs)| cs.domain.d1.size()).max().unwrap();
+    let mut srs = SRS::<Vesta>::create(srs_size);
+    for (_, cs) in &css {
+        srs.add_lagrange_basis(cs.domain.d1);
+    }
+    let srs = Arc::new(srs);
+    let (endo_q, _endo_r) = endos::<Pallas>();
+
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+    let (verifier_indexes, proofs): (Vec<_>, Vec<_>) = css
+        .into_iter()
+        .zip(&publics)
+        .map(|((rows, cs), public)| {
+            let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); rows]);
+            fill_in_witness(0, &mut witness, public);
+            let index = ProverIndex::<Vesta>::create(cs, endo_q, srs.clone());
+            let proof =
+                ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness, &[], &index)
+                    .unwrap();
+            (index.verifier_index(), proof)
+        })
+        .unzip();
+
+    // the proofs of both circuits are verified together, one of them twice
+    let entries = [
+        BatchEntry::new(&verifier_indexes[0], &proofs[0]),
+        BatchEntry::new(&verifier_indexes[1], &proofs[1]),
+        BatchEntry::from((&verifier_indexes[0], &proofs[0])),
+    ];
+    aggregate_verify::<Vesta, BaseSponge, ScalarSponge>(&group_map, &entries).unwrap();
+
+    // a proof verified with the index of the other circuit fails the batch
+    let entries = [
+        BatchEntry::new(&verifier_indexes[0], &proofs[0]),
+        BatchEntry::new(&verifier_indexes[0], &proofs[1]),
+    ];
+    assert!(aggregate_verify::<Vesta, BaseSponge, ScalarSponge>(&group_map, &entries).is_err());
+
+    // as does an invalid proof
+    let mut invalid = proofs[1].clone();
+    invalid.ft_eval1 += Fp::from(1u8);
+    let entries = [
+        BatchEntry::new(&verifier_indexes[0], &proofs[0]),
+        BatchEntry::new(&verifier_indexes[1], &invalid),
+    ];
+    assert!(aggregate_verify::<Vesta, BaseSponge, ScalarSponge>(&group_map, &entries).is_err());
+}
+
+#[test]
+fn test_generic_gate_incremental_witness() {
+    let public = vec![Fp::from(3u8); 5];
+    let gates = create_circuit(0, public.len());
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &public);
+
+    let index = new_index_for_test(gates, public.len());
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+
+    // the columns can be handed over in any order
+    let proof = ProverProof::create_with_incremental_witness::<BaseSponge, ScalarSponge>(
+        &group_map,
+        |committer| {
+            for (col, column) in witness.clone().into_iter().enumerate().rev() {
+                committer.commit(col, column);
+            }
+        },
+        &[],
+        &index,
+        vec![],
+        &mut StdRng::seed_from_u64(0),
+    )
+    .unwrap();
+
+    let verifier_index = index.verifier_index();
+    verify::<Vesta, BaseSponge, ScalarSponge>(&group_map, &verifier_index, &proof).unwrap();
+}
+
+#[test]
+fn test_generic_gate_incremental_witness_errors() {
+    let gates = create_circuit(0, 0);
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &[]);
+
+    let index = new_index_for_test(gates, 0);
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+    let prove = |generate: &dyn Fn(&WitnessCommitter<Fp>)| {
+        ProverProof::create_with_incremental_witness::<BaseSponge, ScalarSponge>(
+            &group_map,
+            generate,
+            &[],
+            &index,
+            vec![],
+            &mut StdRng::seed_from_u64(0),
+        )
+        .err()
+    };
+
+    let res = prove(&|committer| {
+        for (col, column) in witness.iter().enumerate().skip(1) {
+            committer.commit(col, column.clone());
+        }
+    });
+    assert!(matches!(res, Some(ProverError::MissingWitnessColumn(0))));
+
+    let res = prove(&|committer| {
+        committer.commit(3, witness[3].clone());
+        committer.commit(3, witness[3].clone());
+    });
+    assert!(matches!(res, Some(ProverError::DuplicateWitnessColumn(3))));
+
+    let res = prove(&|committer| committer.commit(COLUMNS, witness[0].clone()));
+    assert!(matches!(
+        res,
+        Some(ProverError::InvalidWitnessColumn(COLUMNS))
+    ));
+
+    let res = prove(&|committer| {
+        committer.commit(0, witness[0].clone());
+        committer.commit(1, witness[1][1..].to_vec());
+    });
+    assert!(matches!(res, Some(ProverError::WitnessCsInconsistent)));
+}
+
 #[test]
 fn test_generic_gate_pub_empty() {
     let public = vec![];
@@ -74,3 +461,74 @@ fn test_generic_gate_pub_empty() {
         .setup()
         .prove_and_verify();
 }
+
+#[test]
+fn test_generic_gate_public_output() {
+    // two public inputs, and their sum as public output
+    let (a, b) = (Fp::from(3u8), Fp::from(4u8));
+    let mut gates: Vec<_> = (0..3)
+        .map(|row| CircuitGate::create_generic_gadget(Wire::new(row), GenericGateSpec::Pub, None))
+        .collect();
+    gates.push(CircuitGate::create_generic_gadget(
+        Wire::new(3),
+        GenericGateSpec::Add {
+            left_coeff: None,
+            right_coeff: None,
+            output_coeff: None,
+        },
+        None,
+    ));
+    gates.connect_cell_pair((0, 0), (3, 0));
+    gates.connect_cell_pair((1, 0), (3, 1));
+    gates.connect_cell_pair((2, 0), (3, 2));
+    gates.extend(create_circuit(gates.len(), 0));
+
+    let cs = ConstraintSystem::create(gates.clone())
+        .public(3)
+        .public_output(1)
+        .build()
+        .unwrap();
+    let mut srs = SRS::<Vesta>::create(cs.domain.d1.size());
+    srs.add_lagrange_basis(cs.domain.d1);
+    let (endo_q, _endo_r) = endos::<Pallas>();
+    let index = ProverIndex::<Vesta>::create(cs, endo_q, Arc::new(srs));
+
+    // the output is computed by the circuit, and set by the prover
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    for (col, value) in [a, b, a + b].into_iter().enumerate() {
+        witness[col][3] = value;
+    }
+    witness[0][0] = a;
+    witness[0][1] = b;
+    fill_in_witness(4, &mut witness, &[]);
+    assert!(matches!(
+        index.set_public_output(&mut witness, &[]),
+        Err(ProverError::IncorrectPublicOutputLength(1))
+    ));
+    index.set_public_output(&mut witness, &[a + b]).unwrap();
+
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+    let proof =
+        ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness, &[], &index).unwrap();
+
+    let verifier_index = index.verifier_index();
+    let output = verify_with_public_output::<Vesta, BaseSponge, ScalarSponge>(
+        &group_map,
+        &verifier_index,
+        &proof,
+    )
+    .unwrap();
+    assert_eq!(output, &[a + b]);
+
+    // there cannot be more outputs than public values
+    assert!(matches!(
+        ConstraintSystem::create(gates)
+            .public(1)
+            .public_output(2)
+            .build(),
+        Err(SetupError::PublicOutput {
+            output: 2,
+            public: 1
+        })
+    ));
+}
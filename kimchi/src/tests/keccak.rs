@@ -0,0 +1,81 @@
+use crate::circuits::{
+    constraints::ConstraintSystem,
+    gate::CircuitGate,
+    polynomial::COLUMNS,
+    polynomials::keccak::{
+        gadget::{digest_lane_cell_from_end, input_lane_row, interleave, INPUT_LANE_COL},
+        witness::{create_keccak256_witness, keccak256, pad_message},
+    },
+};
+
+use ark_ff::One;
+use mina_curves::pasta::{Fp, Vesta};
+use num_bigint::BigUint;
+use sha3::{Digest, Keccak256};
+
+// Reads the digest from the witness of the gadget
+fn digest(witness: &[Vec<Fp>; COLUMNS]) -> Vec<u8> {
+    let len = witness[0].len();
+    (0..4)
+        .flat_map(|i| {
+            let (row, col) = digest_lane_cell_from_end(i);
+            let lane: BigUint = witness[col][len - row].into();
+            u64::try_from(lane).unwrap().to_le_bytes()
+        })
+        .collect()
+}
+
+#[test]
+fn test_keccak256_reference() {
+    for message in [
+        &b""[..],
+        &b"abc"[..],
+        &[0x5a; 135][..],
+        &[0xa5; 136][..],
+        &[0x42; 300][..],
+    ] {
+        assert_eq!(
+            keccak256(message).to_vec(),
+            Keccak256::digest(message).to_vec()
+        );
+    }
+}
+
+#[test]
+fn test_keccak256_interleave() {
+    let lane = 0x0123_4567_89ab_cdef;
+    let [even, odd] = interleave(lane);
+    let recombined = (0..32).fold(0u64, |acc, i| {
+        acc | ((even as u64 >> i) & 1) << (2 * i) | ((odd as u64 >> i) & 1) << (2 * i + 1)
+    });
+    assert_eq!(recombined, lane);
+}
+
+#[test]
+fn test_keccak256_witness() {
+    for message in [&b""[..], &[0xa5; 136][..]] {
+        let witness = create_keccak256_witness::<Fp>(message);
+        assert_eq!(digest(&witness), Keccak256::digest(message).to_vec());
+
+        // the input lanes are at the expected cells
+        for (j, lane) in pad_message(message).iter().flatten().enumerate() {
+            assert_eq!(witness[INPUT_LANE_COL][input_lane_row(j)], Fp::from(*lane));
+        }
+    }
+}
+
+#[test]
+fn test_keccak256_circuit() {
+    let message = b"kimchi";
+    let (_, gates) = CircuitGate::<Fp>::create_keccak256(0, pad_message(message).len());
+    let cs = ConstraintSystem::create(gates).build().unwrap();
+
+    let mut witness = create_keccak256_witness::<Fp>(message);
+    cs.verify::<Vesta>(&witness, &[]).unwrap();
+
+    // a wrong digest is rejected
+    let (row, col) = digest_lane_cell_from_end(0);
+    let row = witness[0].len() - row;
+    witness[col][row] += Fp::one();
+    assert!(cs.verify::<Vesta>(&witness, &[]).is_err());
+}
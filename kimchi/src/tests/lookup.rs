@@ -1,18 +1,52 @@
 use super::framework::{print_witness, TestFramework};
-use crate::circuits::{
-    gate::{CircuitGate, GateType},
-    lookup::{
-        runtime_tables::{RuntimeTable, RuntimeTableCfg, RuntimeTableSpec},
-        tables::LookupTable,
+use crate::{
+    circuits::{
+        domains::EvaluationDomains,
+        gate::{CircuitGate, GateType},
+        lookup::{
+            index::{LookupConstraintSystem, LookupError},
+            runtime_tables::{
+                order_as_configured, RuntimeTable, RuntimeTableCfg, RuntimeTableSpec,
+            },
+            tables::{LookupTable, TableId, TableKind, XOR_TABLE_ID},
+        },
+        polynomial::COLUMNS,
+        polynomials::{
+            generic::testing::{create_circuit, fill_in_witness},
+            permutation::{MAX_ZK_ROWS, ZK_ROWS},
+        },
+        wires::Wire,
     },
-    polynomial::COLUMNS,
-    wires::Wire,
+    error::VerifyError,
+    proof::{LookupCommitments, LookupEvaluations, ProverProof},
+    prover_index::testing::new_index_for_test,
+    verifier::verify,
 };
 use ark_ff::Zero;
-use mina_curves::pasta::Fp;
+use ark_poly::EvaluationDomain;
+use commitment_dlog::commitment::CommitmentCurve;
+use groupmap::GroupMap;
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use oracle::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
 use std::array;
 
-fn setup_lookup_proof(use_values_from_table: bool, num_lookups: usize, table_sizes: Vec<usize>) {
+type BaseSponge = DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>;
+type ScalarSponge = DefaultFrSponge<Fp, PlonkSpongeConstantsKimchi>;
+
+// The ID of the `i`-th table of the tests
+fn table_id(i: usize) -> TableId {
+    TableId::new(-1 - i as i32)
+}
+
+fn setup_lookup_proof(
+    use_values_from_table: bool,
+    num_lookups: usize,
+    table_sizes: Vec<usize>,
+    memory_budget: Option<usize>,
+) {
     let lookup_table_values: Vec<Vec<_>> = table_sizes
         .iter()
         .map(|size| (0..*size).map(|_| rand::random()).collect())
@@ -25,7 +59,7 @@ fn setup_lookup_proof(use_values_from_table: bool, num_lookups: usize, table_siz
                 .map(Into::into)
                 .collect();
             LookupTable {
-                id: id as i32,
+                id: table_id(id),
                 data: vec![index_column, lookup_table_values.clone()],
             }
         })
@@ -49,10 +83,10 @@ fn setup_lookup_proof(use_values_from_table: bool, num_lookups: usize, table_siz
         let num_tables = table_sizes.len();
         let mut tables_used = std::collections::HashSet::new();
         for _ in 0..num_lookups {
-            let table_id = rand::random::<usize>() % num_tables;
-            tables_used.insert(table_id);
-            let lookup_table_values: &Vec<Fp> = &lookup_table_values[table_id];
-            lookup_table_ids.push((table_id as u64).into());
+            let table = rand::random::<usize>() % num_tables;
+            tables_used.insert(table);
+            let lookup_table_values: &Vec<Fp> = &lookup_table_values[table];
+            lookup_table_ids.push(table_id(table).to_field());
             for i in 0..3 {
                 let index = rand::random::<usize>() % lookup_table_values.len();
                 let value = if use_values_from_table {
@@ -90,48 +124,57 @@ fn setup_lookup_proof(use_values_from_table: bool, num_lookups: usize, table_siz
         ]
     };
 
-    TestFramework::default()
+    let runner = TestFramework::default()
         .gates(gates)
         .witness(witness)
         .lookup_tables(lookup_tables)
-        .setup()
-        .prove_and_verify();
+        .setup();
+    match memory_budget {
+        None => runner.prove_and_verify(),
+        Some(budget) => runner.memory_budget(budget).prove_and_verify(),
+    }
 }
 
 #[test]
 fn lookup_gate_proving_works() {
-    setup_lookup_proof(true, 500, vec![256])
+    setup_lookup_proof(true, 500, vec![256], None)
 }
 
 #[test]
 #[should_panic]
 fn lookup_gate_rejects_bad_lookups() {
-    setup_lookup_proof(false, 500, vec![256])
+    setup_lookup_proof(false, 500, vec![256], None)
 }
 
 #[test]
 fn lookup_gate_proving_works_multiple_tables() {
-    setup_lookup_proof(true, 500, vec![100, 50, 50, 2, 2])
+    setup_lookup_proof(true, 500, vec![100, 50, 50, 2, 2], None)
 }
 
 #[test]
 #[should_panic]
 fn lookup_gate_rejects_bad_lookups_multiple_tables() {
-    setup_lookup_proof(false, 500, vec![100, 50, 50, 2, 2])
+    setup_lookup_proof(false, 500, vec![100, 50, 50, 2, 2], None)
+}
+
+#[test]
+fn lookup_gate_proving_works_with_memory_budget() {
+    // a budget this small evaluates the constraints one row at a time
+    setup_lookup_proof(true, 500, vec![100, 50, 50, 2, 2], Some(1))
 }
 
-fn runtime_table(num: usize, indexed: bool) {
+fn runtime_table(num: usize, indexed: bool, zk_rows: u64) {
     // runtime
     let mut runtime_tables_setup = vec![];
-    for table_id in 0..num {
+    for i in 0..num {
         let cfg = if indexed {
             RuntimeTableCfg::Indexed(RuntimeTableSpec {
-                id: table_id as i32,
+                id: table_id(i),
                 len: 5,
             })
         } else {
             RuntimeTableCfg::Custom {
-                id: table_id as i32,
+                id: table_id(i),
                 first_column: [8u32, 9, 8, 7, 1].into_iter().map(Into::into).collect(),
             }
         };
@@ -166,7 +209,7 @@ fn runtime_table(num: usize, indexed: bool) {
 
         for row in 0..20 {
             // the first register is the table id
-            lookup_cols[0][row] = 0u32.into();
+            lookup_cols[0][row] = table_id(0).to_field();
 
             // create queries into our runtime lookup table
             let lookup_cols = &mut lookup_cols[1..];
@@ -181,6 +224,94 @@ fn runtime_table(num: usize, indexed: bool) {
     print_witness(&witness, 0, 20);
 
     // run test
+    let runner = TestFramework::default()
+        .gates(gates)
+        .witness(witness)
+        .runtime_tables_setup(runtime_tables_setup)
+        .zk_rows(zk_rows)
+        .setup();
+
+    // the domain is large enough for the runtime tables to stay clear of the zero-knowledge rows
+    let cs = &runner.prover_index().cs;
+    let lcs = cs.lookup_constraint_system.as_ref().unwrap();
+    let runtime_end = lcs.runtime_table_offset.unwrap() + 5 * num;
+    assert!(runtime_end <= cs.lookup_table_capacity());
+    assert!(cs.lookup_table_capacity() < cs.usable_rows());
+    assert_eq!(cs.usable_rows(), cs.domain.d1.size() - zk_rows as usize);
+
+    runner.runtime_tables(runtime_tables).prove_and_verify();
+}
+
+#[test]
+fn test_indexed_runtime_table() {
+    runtime_table(5, true, ZK_ROWS);
+}
+
+#[test]
+fn test_custom_runtime_table() {
+    runtime_table(5, false, ZK_ROWS);
+}
+
+#[test]
+fn test_runtime_table_zk_rows() {
+    // the tables (25 entries) followed by the reserved rows don't fit in the 32 rows
+    // required by the gates, so the domain grows to fit them
+    for indexed in [true, false] {
+        runtime_table(5, indexed, MAX_ZK_ROWS);
+    }
+}
+
+#[test]
+fn test_runtime_table_memory_snapshot() {
+    // two memories of 8 words, whose content is only known at proving time
+    let (ram, rom) = (table_id(0), table_id(1));
+    let runtime_tables_setup = vec![
+        RuntimeTableCfg::indexed(ram, 8),
+        RuntimeTableCfg::indexed(rom, 8),
+    ];
+    let memory = |seed: u64| -> Vec<Fp> { (0..8).map(|addr| Fp::from(seed * addr + 1)).collect() };
+
+    // the snapshots can be given in another order than configured
+    let runtime_tables = vec![
+        RuntimeTable::new(rom, memory(5)),
+        RuntimeTable::new(ram, memory(3)),
+    ];
+
+    // each lookup gate reads three words of one of the memories
+    let gates: Vec<_> = (0..8)
+        .map(|row| CircuitGate {
+            typ: GateType::Lookup,
+            wires: Wire::new(row),
+            coeffs: vec![],
+        })
+        .collect();
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    for row in 0..gates.len() {
+        let table = &runtime_tables[row % 2];
+        witness[0][row] = table.id.to_field();
+        for (i, addr) in [row, (row + 3) % 8, 7 - row].into_iter().enumerate() {
+            witness[1 + 2 * i][row] = Fp::from(addr as u64);
+            witness[2 + 2 * i][row] = table.data[addr];
+        }
+    }
+
+    // each configured table must be given once, with the configured length
+    let specs: Vec<RuntimeTableSpec> = runtime_tables_setup
+        .iter()
+        .cloned()
+        .map(Into::into)
+        .collect();
+    let ordered = order_as_configured(&specs, &runtime_tables).unwrap();
+    assert_eq!((ordered[0].id, ordered[1].id), (ram, rom));
+    assert!(order_as_configured(&specs, &runtime_tables[..1]).is_none());
+    let duplicated = vec![runtime_tables[0].clone(), runtime_tables[0].clone()];
+    assert!(order_as_configured(&specs, &duplicated).is_none());
+    let truncated = vec![
+        runtime_tables[0].clone(),
+        RuntimeTable::new(ram, memory(3)[..4].to_vec()),
+    ];
+    assert!(order_as_configured(&specs, &truncated).is_none());
+
     TestFramework::default()
         .gates(gates)
         .witness(witness)
@@ -191,13 +322,200 @@ fn runtime_table(num: usize, indexed: bool) {
 }
 
 #[test]
-fn test_indexed_runtime_table() {
-    runtime_table(5, true);
+fn test_runtime_table_too_long() {
+    let runtime_tables = vec![RuntimeTableCfg::<Fp>::Indexed(RuntimeTableSpec {
+        id: table_id(0),
+        len: 30,
+    })];
+    let gates: Vec<_> = (0..4)
+        .map(|row| CircuitGate {
+            typ: GateType::Lookup,
+            wires: Wire::new(row),
+            coeffs: vec![],
+        })
+        .collect();
+    let domain = EvaluationDomains::<Fp>::create(32).unwrap();
+
+    let res =
+        LookupConstraintSystem::create(&gates, vec![], Some(runtime_tables), &domain, ZK_ROWS);
+    assert!(matches!(
+        res,
+        Err(LookupError::LookupTableTooLong {
+            length: 30,
+            maximum_allowed: 27
+        })
+    ));
 }
 
 #[test]
-fn test_custom_runtime_table() {
-    runtime_table(5, false);
+fn test_table_id_errors() {
+    let gates: Vec<_> = (0..4)
+        .map(|row| CircuitGate {
+            typ: GateType::Lookup,
+            wires: Wire::new(row),
+            coeffs: vec![],
+        })
+        .collect();
+    let domain = EvaluationDomains::<Fp>::create(32).unwrap();
+    let fixed_table = |id| LookupTable {
+        id,
+        data: vec![vec![Fp::zero(); 4]],
+    };
+    let runtime_table = |id| RuntimeTableCfg::Indexed(RuntimeTableSpec { id, len: 4 });
+
+    // a runtime table cannot use the ID of a built-in table
+    let res = LookupConstraintSystem::create(
+        &gates,
+        vec![],
+        Some(vec![runtime_table(XOR_TABLE_ID)]),
+        &domain,
+        ZK_ROWS,
+    );
+    assert!(matches!(res, Err(LookupError::ReservedTableId(id)) if id == XOR_TABLE_ID));
+
+    // nor can a fixed table, even if the built-in table is not used
+    let res = LookupConstraintSystem::create(
+        &gates,
+        vec![fixed_table(TableId::new(7))],
+        None,
+        &domain,
+        ZK_ROWS,
+    );
+    assert!(matches!(res, Err(LookupError::ReservedTableId(id)) if id == TableId::new(7)));
+
+    // a fixed table and a runtime table cannot share an ID
+    let res = LookupConstraintSystem::create(
+        &gates,
+        vec![fixed_table(table_id(0))],
+        Some(vec![runtime_table(table_id(0))]),
+        &domain,
+        ZK_ROWS,
+    );
+    assert!(matches!(
+        res,
+        Err(LookupError::TableIdCollision {
+            registered: TableKind::Fixed,
+            kind: TableKind::Runtime,
+            ..
+        })
+    ));
+
+    // otherwise, the tables are registered on the lookup constraint system
+    let lcs = LookupConstraintSystem::create(
+        &gates,
+        vec![fixed_table(table_id(0))],
+        Some(vec![runtime_table(table_id(1))]),
+        &domain,
+        ZK_ROWS,
+    )
+    .unwrap()
+    .unwrap();
+    let registered: Vec<_> = lcs.table_registry.iter().collect();
+    assert_eq!(
+        registered,
+        vec![
+            (table_id(1), TableKind::Runtime),
+            (table_id(0), TableKind::Fixed)
+        ]
+    );
 }
 
-// TODO: add a test with a runtime table with ID 0 (it should panic)
+// A fixed table with 3 columns, looked up by lookup gates on its first 2 columns,
+// at `index` in each row, as if the table had the ID `table_id(0) + id_offset`
+fn wide_table_proof(index: u64, id_offset: Fp) {
+    let num_lookups = 4;
+    let table = LookupTable {
+        id: table_id(0),
+        data: vec![
+            (0u64..8).map(Into::into).collect(),
+            (10u64..18).map(Into::into).collect(),
+            // the last column only matches the lookups where it is zero
+            (0u64..8).map(|i| Fp::from(u64::from(i == 0))).collect(),
+        ],
+    };
+
+    let gates: Vec<_> = (0..num_lookups)
+        .map(|row| CircuitGate {
+            typ: GateType::Lookup,
+            wires: Wire::new(row),
+            coeffs: vec![],
+        })
+        .collect();
+
+    // the table is wider than the lookups of the lookup gate
+    let domain = EvaluationDomains::<Fp>::create(32).unwrap();
+    let lcs = LookupConstraintSystem::create(&gates, vec![table.clone()], None, &domain, ZK_ROWS)
+        .unwrap()
+        .unwrap();
+    assert_eq!(lcs.configuration.lookup_info.max_joint_size, 3);
+
+    let id: Fp = table_id(0).to_field();
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); num_lookups]);
+    witness[0] = vec![id + id_offset; num_lookups];
+    for col in [1, 3, 5] {
+        witness[col] = vec![index.into(); num_lookups];
+        witness[col + 1] = vec![(10 + index).into(); num_lookups];
+    }
+
+    TestFramework::default()
+        .gates(gates)
+        .witness(witness)
+        .lookup_tables(vec![table])
+        .setup()
+        .prove_and_verify();
+}
+
+#[test]
+fn test_lookup_table_wider_than_lookups() {
+    wide_table_proof(3, Fp::zero());
+}
+
+#[test]
+#[should_panic]
+fn test_lookup_table_wider_than_lookups_rejects_aliased_table_id() {
+    // the entry `(0, 10, 1)` of the table must not be confused
+    // with an entry `(0, 10)` of a table whose ID is one more
+    wide_table_proof(0, Fp::from(1u64));
+}
+
+#[test]
+fn test_verifier_rejects_lookups_without_lookup_index() {
+    let public = vec![Fp::from(3u8); 5];
+    let gates = create_circuit(0, public.len());
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &public);
+    let index = new_index_for_test(gates, public.len());
+    let verifier_index = index.verifier_index();
+    assert!(verifier_index.lookup_index.is_none());
+
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+    let proof =
+        ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness, &[], &index).unwrap();
+    assert!(proof.commitments.lookup.is_none());
+    verify::<Vesta, BaseSponge, ScalarSponge>(&group_map, &verifier_index, &proof).unwrap();
+
+    // lookup commitments would change the transcript, so they are rejected upfront
+    let mut with_commitments = proof.clone();
+    with_commitments.commitments.lookup = Some(LookupCommitments {
+        sorted: vec![proof.commitments.z_comm.clone()],
+        aggreg: proof.commitments.z_comm.clone(),
+        runtime: None,
+    });
+    assert!(matches!(
+        verify::<Vesta, BaseSponge, ScalarSponge>(&group_map, &verifier_index, &with_commitments),
+        Err(VerifyError::UnexpectedLookup)
+    ));
+
+    // and so are lookup evaluations
+    let mut with_evals = proof.clone();
+    with_evals.evals[1].lookup = Some(LookupEvaluations {
+        sorted: vec![proof.evals[1].z.clone()],
+        aggreg: proof.evals[1].z.clone(),
+        table: proof.evals[1].z.clone(),
+        runtime: None,
+    });
+    assert!(matches!(
+        verify::<Vesta, BaseSponge, ScalarSponge>(&group_map, &verifier_index, &with_evals),
+        Err(VerifyError::UnexpectedLookup)
+    ));
+}
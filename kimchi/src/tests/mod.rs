@@ -1,14 +1,31 @@
+mod bitwise;
+mod boolean_columns;
+mod builder;
 mod chacha;
+mod column_equality;
+mod compare;
+mod custom_gate;
+mod description;
 mod ec;
 mod endomul;
 mod endomul_scalar;
 mod foreign_field_add;
 mod framework;
 mod generic;
+mod keccak;
 mod lookup;
+mod plan;
 mod poseidon;
+#[cfg(feature = "prover-tracing")]
+mod profiling;
 mod range_check;
 mod recursion;
+mod select;
 mod serde;
+mod sha256;
+mod sponge;
 mod turshi;
 mod varbasemul;
+mod witness_diff;
+mod witness_generator;
+mod zk_rows;
@@ -0,0 +1,102 @@
+use crate::circuits::{
+    constraints::ConstraintSystem,
+    gate::{CircuitGate, GateType},
+    lookup::{
+        runtime_tables::{RuntimeTableCfg, RuntimeTableSpec},
+        tables::{LookupTable, TableId},
+    },
+    plan::{plan_circuit, LimitingFactor},
+    polynomials::{generic::testing::create_circuit, permutation::ZK_ROWS},
+    wires::Wire,
+};
+use ark_ff::Zero;
+use ark_poly::EvaluationDomain;
+use mina_curves::pasta::Fp;
+
+#[test]
+fn test_plan_generic_circuit() {
+    let gates = create_circuit::<Fp>(0, 0);
+    let plan = plan_circuit(&gates).unwrap();
+
+    assert_eq!(plan.gates, gates.len());
+    assert_eq!(plan.gate_rows, gates.len() + ZK_ROWS as usize);
+    assert_eq!(plan.lookup_table_entries, 0);
+    assert_eq!(plan.lookup_rows, 0);
+    assert_eq!(plan.limiting_factor, LimitingFactor::Gates);
+    assert_eq!(plan.free_rows(), plan.domain_size - plan.gate_rows);
+
+    let cs = ConstraintSystem::create(gates).build().unwrap();
+    assert_eq!(plan.domain_size, cs.domain.d1.size());
+}
+
+#[test]
+fn test_plan_zk_rows() {
+    // fill the domain up to the zero-knowledge rows
+    let mut gates = create_circuit::<Fp>(0, 0);
+    let free_rows = plan_circuit(&gates).unwrap().free_rows();
+    for _ in 0..free_rows {
+        gates.push(CircuitGate::zero(Wire::new(gates.len())));
+    }
+    let default_plan = plan_circuit(&gates).unwrap();
+    assert_eq!(default_plan.free_rows(), 0);
+
+    // a single additional zero-knowledge row doubles the size of the domain
+    let builder = ConstraintSystem::create(gates).zk_rows(ZK_ROWS + 1);
+    let plan = builder.plan().unwrap();
+
+    assert_eq!(plan.zk_rows, ZK_ROWS as usize + 1);
+    assert_eq!(plan.domain_size, 2 * default_plan.domain_size);
+    assert_eq!(plan.domain_size, builder.build().unwrap().domain.d1.size());
+}
+
+#[test]
+fn test_plan_range_check_tables() {
+    let (_, gates) = CircuitGate::<Fp>::create_multi_range_check(0);
+    let plan = plan_circuit(&gates).unwrap();
+
+    // the range check table has 2^12 entries, and dictates the size of the domain
+    assert_eq!(plan.lookup_table_entries, 1 << 12);
+    assert_eq!(plan.lookup_rows, (1 << 12) + ZK_ROWS as usize + 2);
+    assert_eq!(plan.limiting_factor, LimitingFactor::LookupTables);
+    assert_eq!(plan.domain_size, 1 << 13);
+
+    // the constraint system is created with a large enough domain
+    let cs = ConstraintSystem::create(gates).build().unwrap();
+    assert_eq!(cs.domain.d1.size(), plan.domain_size);
+}
+
+#[test]
+fn test_plan_lookup_and_runtime_tables() {
+    let gates: Vec<_> = (0..10)
+        .map(|row| CircuitGate {
+            typ: GateType::Lookup,
+            coeffs: vec![],
+            wires: Wire::new(row),
+        })
+        .collect();
+
+    let table = LookupTable {
+        id: TableId::new(-1),
+        data: vec![vec![Fp::zero(); 20], vec![Fp::zero(); 20]],
+    };
+    let runtime_tables = vec![
+        RuntimeTableCfg::Indexed(RuntimeTableSpec {
+            id: TableId::new(-2),
+            len: 30,
+        }),
+        RuntimeTableCfg::Custom {
+            id: TableId::new(-3),
+            first_column: vec![Fp::zero(); 40],
+        },
+    ];
+
+    let builder = ConstraintSystem::create(gates)
+        .lookup(vec![table])
+        .runtime(Some(runtime_tables));
+    let plan = builder.plan().unwrap();
+
+    assert_eq!(plan.lookup_table_entries, 20 + 30 + 40);
+    assert_eq!(plan.limiting_factor, LimitingFactor::LookupTables);
+    assert_eq!(plan.domain_size, 128);
+    assert_eq!(builder.build().unwrap().domain.d1.size(), plan.domain_size);
+}
@@ -0,0 +1,133 @@
+use super::framework::TestFramework;
+use crate::{
+    circuits::{
+        gate::{CircuitGate, GateType},
+        lookup::tables::{LookupTable, TableId},
+        polynomials::generic::testing::{create_circuit, fill_in_witness},
+        wires::{Wire, COLUMNS},
+    },
+    profiling::ProverPhase,
+};
+use ark_ff::Zero;
+use mina_curves::pasta::Fp;
+use std::{
+    array,
+    sync::{Arc, Mutex},
+};
+use tracing::{
+    span::{Attributes, Id, Record},
+    subscriber, Event, Metadata, Subscriber,
+};
+
+// A subscriber recording when the spans of the prover are entered and exited
+#[derive(Clone, Default)]
+struct PhaseRecorder {
+    names: Arc<Mutex<Vec<&'static str>>>,
+    log: Arc<Mutex<Vec<(bool, &'static str)>>>,
+}
+
+impl PhaseRecorder {
+    fn name(&self, id: &Id) -> &'static str {
+        self.names.lock().unwrap()[id.into_u64() as usize - 1]
+    }
+}
+
+impl Subscriber for PhaseRecorder {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.target() == "kimchi::prover"
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut names = self.names.lock().unwrap();
+        names.push(span.metadata().name());
+        Id::from_u64(names.len() as u64)
+    }
+
+    fn record(&self, _: &Id, _: &Record<'_>) {}
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, _: &Event<'_>) {}
+
+    fn enter(&self, id: &Id) {
+        let name = self.name(id);
+        self.log.lock().unwrap().push((true, name));
+    }
+
+    fn exit(&self, id: &Id) {
+        let name = self.name(id);
+        self.log.lock().unwrap().push((false, name));
+    }
+}
+
+// The phases are entered in order, each after the previous one is exited
+fn check_phases(prove: impl FnOnce(), phases: &[ProverPhase]) {
+    let recorder = PhaseRecorder::default();
+    subscriber::with_default(recorder.clone(), prove);
+
+    let expected: Vec<_> = phases
+        .iter()
+        .flat_map(|phase| [(true, phase.name()), (false, phase.name())])
+        .collect();
+    assert_eq!(*recorder.log.lock().unwrap(), expected);
+}
+
+#[test]
+fn test_prover_phases() {
+    let gates = create_circuit(0, 0);
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &[]);
+    let runner = TestFramework::default()
+        .gates(gates)
+        .witness(witness)
+        .setup();
+
+    check_phases(
+        || runner.prove_and_verify(),
+        &[
+            ProverPhase::WitnessCommitment,
+            ProverPhase::Permutation,
+            ProverPhase::Quotient,
+            ProverPhase::Evaluation,
+            ProverPhase::Opening,
+        ],
+    );
+}
+
+#[test]
+fn test_prover_phases_with_lookup() {
+    // a single lookup of (1, 2) in a table of ID -1
+    let id = TableId::new(-1);
+    let table = LookupTable {
+        id,
+        data: vec![vec![Fp::from(1u8)], vec![Fp::from(2u8)]],
+    };
+    let gates = vec![CircuitGate {
+        typ: GateType::Lookup,
+        wires: Wire::new(0),
+        coeffs: vec![],
+    }];
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero()]);
+    witness[0][0] = id.to_field();
+    for i in 0..3 {
+        witness[2 * i + 1][0] = Fp::from(1u8);
+        witness[2 * i + 2][0] = Fp::from(2u8);
+    }
+    let runner = TestFramework::default()
+        .gates(gates)
+        .witness(witness)
+        .lookup_tables(vec![table])
+        .setup();
+
+    check_phases(
+        || runner.prove_and_verify(),
+        &[
+            ProverPhase::WitnessCommitment,
+            ProverPhase::Lookup,
+            ProverPhase::Permutation,
+            ProverPhase::Quotient,
+            ProverPhase::Evaluation,
+            ProverPhase::Opening,
+        ],
+    );
+}
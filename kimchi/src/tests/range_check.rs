@@ -10,7 +10,7 @@ use crate::{
         wires::Wire,
     },
     proof::ProverProof,
-    prover_index::testing::new_index_for_test_with_lookups,
+    prover_index::testing::TestIndexBuilder,
 };
 
 use ark_ec::AffineCurve;
@@ -54,14 +54,7 @@ fn create_test_prover_index(public_size: usize) -> ProverIndex<Vesta> {
         next_row += 1;
     }
 
-    new_index_for_test_with_lookups(
-        gates,
-        public_size,
-        0,
-        vec![range_check::gadget::lookup_table()],
-        None,
-        None,
-    )
+    TestIndexBuilder::new(gates).public(public_size).build()
 }
 
 #[test]
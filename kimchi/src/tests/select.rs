@@ -0,0 +1,82 @@
+use crate::circuits::{
+    constraints::ConstraintSystem,
+    gate::CircuitGate,
+    polynomials::select::{
+        gadget::{
+            mux_cells, CONDITION_CELL, IF_FALSE_CELL, IF_TRUE_CELL, SELECT_OUTPUT_CELL, SELECT_ROWS,
+        },
+        witness::{create_mux_witness, create_select_witness},
+    },
+};
+
+use ark_ff::{One, Zero};
+use mina_curves::pasta::{Fp, Vesta};
+
+#[test]
+fn test_select_circuit() {
+    let (next_row, gates) = CircuitGate::<Fp>::create_select(0);
+    assert_eq!(next_row, SELECT_ROWS);
+    let cs = ConstraintSystem::create(gates).build().unwrap();
+
+    let (x, y) = (Fp::from(42u64), Fp::from(7u64));
+    for (condition, expected) in [(true, x), (false, y)] {
+        let mut witness = create_select_witness(condition, x, y);
+        let cell = |(row, col): (usize, usize)| witness[col][row];
+        assert_eq!(cell(CONDITION_CELL), Fp::from(condition as u64));
+        assert_eq!(cell(IF_TRUE_CELL), x);
+        assert_eq!(cell(IF_FALSE_CELL), y);
+        assert_eq!(cell(SELECT_OUTPUT_CELL), expected);
+        assert_eq!(cs.verify::<Vesta>(&witness, &[]), Ok(()));
+
+        // a wrong output is rejected
+        let (row, col) = SELECT_OUTPUT_CELL;
+        witness[col][row] += Fp::one();
+        assert!(cs.verify::<Vesta>(&witness, &[]).is_err());
+    }
+}
+
+#[test]
+fn test_select_non_boolean_condition() {
+    let (_, gates) = CircuitGate::<Fp>::create_select(0);
+    let cs = ConstraintSystem::create(gates).build().unwrap();
+
+    // a condition of 2 would select 2x - y
+    let (x, y) = (Fp::from(42u64), Fp::from(7u64));
+    let mut witness = create_select_witness(true, x, y);
+    let two = Fp::from(2u64);
+    for (row, col) in [(0, 0), (0, 1), (1, 0)] {
+        witness[col][row] = two;
+    }
+    witness[2][1] = two * (x - y);
+    witness[3][1] = two * (x - y);
+    witness[5][1] = two * (x - y) + y;
+    assert!(cs.verify::<Vesta>(&witness, &[]).is_err());
+}
+
+#[test]
+fn test_mux_circuit() {
+    for n in [2, 3, 5, 8] {
+        let values: Vec<Fp> = (0..n).map(|i| Fp::from(100 + i as u64)).collect();
+        let (next_row, gates) = CircuitGate::<Fp>::create_mux(0, n);
+        let cs = ConstraintSystem::create(gates).build().unwrap();
+        let cells = mux_cells(n);
+
+        for index in 0..n {
+            let mut witness = create_mux_witness(index, &values);
+            assert_eq!(witness[0].len(), next_row);
+
+            let cell = |(row, col): (usize, usize)| witness[col][row];
+            assert_eq!(cell(cells.index), Fp::from(index as u64));
+            for (value, value_cell) in values.iter().zip(&cells.values) {
+                assert_eq!(cell(*value_cell), *value);
+            }
+            assert_eq!(cell(cells.output), values[index]);
+            assert_eq!(cs.verify::<Vesta>(&witness, &[]), Ok(()));
+
+            // a wrong output is rejected
+            let (row, col) = cells.output;
+            witness[col][row] = Fp::zero();
+            assert!(cs.verify::<Vesta>(&witness, &[]).is_err());
+        }
+    }
+}
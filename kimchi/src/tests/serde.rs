@@ -1,17 +1,29 @@
 use crate::{
     bench::BenchmarkCtx,
     circuits::{
+        gate::{CircuitGate, GateType},
+        lookup::tables::{LookupTable, TableId},
         polynomials::generic::testing::{create_circuit, fill_in_witness},
-        wires::COLUMNS,
+        wires::{Wire, COLUMNS},
+    },
+    error::{ProofMetadataError, VerifierIndexError, VerifyError},
+    proof::{inspect::ProofSummary, ProofEvaluations, ProverCommitments, ProverProof},
+    proof_compression::COMPRESSED_VERIFIER_INDEX_VERSION,
+    proof_metadata::{read_metadata, ProofMetadata, PROVER_VERSION},
+    prover_index::testing::{new_index_for_test, new_index_for_test_with_lookups},
+    verifier::{public_commitment, verify, verify_compressed},
+    verifier_index::{
+        VerifierIndex, VerifierIndexV1, VERIFIER_INDEX_MAGIC, VERIFIER_INDEX_VERSION,
     },
-    proof::ProverProof,
-    prover_index::testing::new_index_for_test,
-    verifier::verify,
-    verifier_index::VerifierIndex,
 };
 use ark_ec::short_weierstrass_jacobian::GroupAffine;
 use ark_ff::Zero;
-use commitment_dlog::{commitment::CommitmentCurve, srs::SRS};
+use ark_serialize::CanonicalSerialize;
+use commitment_dlog::{
+    commitment::{CommitmentCurve, PolyComm},
+    evaluation_proof::OpeningProof,
+    srs::SRS,
+};
 use groupmap::GroupMap;
 use mina_curves::pasta::{Fp, Vesta, VestaParameters};
 use oracle::{
@@ -19,6 +31,7 @@ use oracle::{
     sponge::{DefaultFqSponge, DefaultFrSponge},
 };
 use std::array;
+use std::fs::{self, File};
 use std::time::Instant;
 
 type SpongeParams = PlonkSpongeConstantsKimchi;
@@ -48,6 +61,333 @@ mod tests {
         ctx.batch_verification(vec![de_pf.clone()]);
     }
 
+    #[test]
+    fn test_compressed_proof() {
+        let ctx = BenchmarkCtx::new(1 << 4);
+
+        let proof = ctx.create_proof();
+
+        // the compressed encoding is smaller than the serde one
+        let compressed = proof.compress().unwrap();
+        let ser_pf = rmp_serde::to_vec(&proof).unwrap();
+        println!(
+            "proof size: {} bytes (compressed), {} bytes (rmp)",
+            compressed.len(),
+            ser_pf.len()
+        );
+        assert!(compressed.len() < ser_pf.len());
+
+        // decompressing gives back the same proof
+        let de_pf = ProverProof::<Vesta>::decompress(&compressed).unwrap();
+        assert_eq!(rmp_serde::to_vec(&de_pf).unwrap(), ser_pf);
+
+        // verify the decompressed proof (must accept the proof)
+        ctx.batch_verification(vec![de_pf]);
+
+        // truncated or extended encodings are rejected
+        assert!(ProverProof::<Vesta>::decompress(&compressed[..compressed.len() - 1]).is_err());
+        let mut extended = compressed.clone();
+        extended.push(0);
+        assert!(ProverProof::<Vesta>::decompress(&extended).is_err());
+
+        // so are encodings of another version
+        let mut other_version = compressed;
+        other_version[0] += 1;
+        assert!(ProverProof::<Vesta>::decompress(&other_version).is_err());
+    }
+
+    /// The size of `proof` in the canonical encoding of arkworks, with uncompressed points
+    /// and the lengths of the vectors written as `u64`, which is what the compressed encoding
+    /// is compared with.
+    fn canonical_size(proof: &ProverProof<Vesta>) -> usize {
+        let comm_size = |comm: &PolyComm<Vesta>| {
+            comm.unshifted.uncompressed_size() + comm.shifted.uncompressed_size()
+        };
+        let opening_size = |opening: &OpeningProof<Vesta>| {
+            opening.lr.uncompressed_size()
+                + opening.delta.uncompressed_size()
+                + opening.z1.uncompressed_size()
+                + opening.z2.uncompressed_size()
+                + opening.sg.uncompressed_size()
+        };
+        let evals_size = |evals: &ProofEvaluations<Vec<Fp>>| {
+            let lookup_size = evals.lookup.as_ref().map_or(1, |lookup| {
+                1 + lookup.sorted.uncompressed_size()
+                    + lookup.aggreg.uncompressed_size()
+                    + lookup.table.uncompressed_size()
+                    + lookup.runtime.uncompressed_size()
+            });
+            evals
+                .w
+                .iter()
+                .chain(&evals.s)
+                .chain([&evals.z, &evals.generic_selector, &evals.poseidon_selector])
+                .map(CanonicalSerialize::uncompressed_size)
+                .sum::<usize>()
+                + lookup_size
+        };
+
+        let commitments = &proof.commitments;
+        let lookup_comms_size = commitments.lookup.as_ref().map_or(1, |lookup| {
+            1 + 8
+                + lookup.sorted.iter().map(comm_size).sum::<usize>()
+                + comm_size(&lookup.aggreg)
+                + 1
+                + lookup.runtime.as_ref().map_or(0, comm_size)
+        });
+        let commitments_size = commitments.w_comm.iter().map(comm_size).sum::<usize>()
+            + comm_size(&commitments.z_comm)
+            + comm_size(&commitments.t_comm)
+            + lookup_comms_size;
+        let prev_challenges_size = 8 + proof
+            .prev_challenges
+            .iter()
+            .map(|chal| chal.chals.uncompressed_size() + comm_size(&chal.comm))
+            .sum::<usize>();
+        let prev_row_size = proof.prev_row.as_ref().map_or(1, |prev_row| {
+            1 + evals_size(&prev_row.evals) + prev_row.ft_eval.uncompressed_size()
+        });
+
+        commitments_size
+            + opening_size(&proof.proof)
+            + proof.evals.iter().map(evals_size).sum::<usize>()
+            + proof.ft_eval1.uncompressed_size()
+            + proof.public.uncompressed_size()
+            + prev_challenges_size
+            + prev_row_size
+    }
+
+    #[test]
+    fn test_compressed_proof_fixture() {
+        // a compressed proof of the circuit of `BenchmarkCtx::new(1 << 4)`,
+        // whose index is deterministic, so that the proof still verifies
+        let compressed: &[u8] = include_bytes!("fixtures/proof_v2.bin");
+        let ctx = BenchmarkCtx::new(1 << 4);
+
+        let proof = ProverProof::<Vesta>::decompress(compressed).unwrap();
+        assert_eq!(proof.compress().unwrap(), compressed);
+        ctx.batch_verification(vec![proof.clone()]);
+
+        // the compressed encoding is about 35% smaller than the canonical encoding,
+        // and a fresh proof has the same size as the fixture
+        assert!(compressed.len() * 100 <= canonical_size(&proof) * 66);
+        assert_eq!(
+            ctx.create_proof().compress().unwrap().len(),
+            compressed.len()
+        );
+    }
+
+    #[test]
+    fn test_compressed_commitments() {
+        let ctx = BenchmarkCtx::new(1 << 4);
+        let proof = ctx.create_proof();
+
+        let compressed = proof.commitments.compress().unwrap();
+        let de_comms = ProverCommitments::<Vesta>::decompress(&compressed).unwrap();
+        assert_eq!(
+            rmp_serde::to_vec(&de_comms).unwrap(),
+            rmp_serde::to_vec(&proof.commitments).unwrap()
+        );
+
+        // the commitments are a prefix of the compressed proof
+        assert!(proof.compress().unwrap().starts_with(&compressed));
+
+        assert!(
+            ProverCommitments::<Vesta>::decompress(&compressed[..compressed.len() - 1]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_compressed_verifier_index() {
+        // an index without lookups, and an index with lookups
+        let public = [Fp::from(3u8); 5];
+        let generic = new_index_for_test(create_circuit(0, public.len()), public.len());
+        let lookup_gates = (0..4)
+            .map(|row| CircuitGate {
+                typ: GateType::Lookup,
+                wires: Wire::new(row),
+                coeffs: vec![],
+            })
+            .collect();
+        let table = LookupTable {
+            id: TableId::new(-1),
+            data: vec![(0..8u64).map(Into::into).collect()],
+        };
+        let lookup =
+            new_index_for_test_with_lookups(lookup_gates, 0, 0, vec![table], None, None, None);
+
+        for index in [generic, lookup] {
+            let verifier_index = index.verifier_index();
+            let compressed = verifier_index.compress().unwrap();
+            assert_eq!(compressed[0], COMPRESSED_VERIFIER_INDEX_VERSION);
+
+            // the compressed encoding is smaller than the serde one
+            let expected = rmp_serde::to_vec(&verifier_index).unwrap();
+            assert!(compressed.len() < expected.len());
+
+            // decompressing gives back the same index
+            let srs = verifier_index.srs().clone();
+            let de_index =
+                VerifierIndex::<Vesta>::decompress(&compressed, Some(srs), verifier_index.endo)
+                    .unwrap();
+            assert_eq!(rmp_serde::to_vec(&de_index).unwrap(), expected);
+            assert_eq!(
+                de_index.digest::<BaseSponge>(),
+                verifier_index.digest::<BaseSponge>()
+            );
+
+            // truncated or extended encodings are rejected, as are encodings of another version
+            let decompress =
+                |bytes: &[u8]| VerifierIndex::<Vesta>::decompress(bytes, None, Fp::zero());
+            assert!(decompress(&compressed[..compressed.len() - 1]).is_err());
+            let mut extended = compressed.clone();
+            extended.push(0);
+            assert!(decompress(&extended).is_err());
+            let mut other_version = compressed;
+            other_version[0] += 1;
+            assert!(decompress(&other_version).is_err());
+        }
+    }
+
+    #[test]
+    fn test_verifier_index_circuit_digest() {
+        let public = [Fp::from(3u8); 5];
+        let index = new_index_for_test(create_circuit(0, public.len()), public.len());
+        let verifier_index = index.verifier_index();
+        let digest = verifier_index.circuit_digest().unwrap();
+
+        // the digest is deterministic, and kept by serialization,
+        assert_eq!(verifier_index.circuit_digest().unwrap(), digest);
+        let compressed = verifier_index.compress().unwrap();
+        let mut de_index = VerifierIndex::<Vesta>::decompress(
+            &compressed,
+            Some(verifier_index.srs().clone()),
+            verifier_index.endo,
+        )
+        .unwrap();
+        // once its linearization, which is not serialized, is restored
+        assert!(de_index.circuit_digest().is_err());
+        de_index.linearization = verifier_index.linearization.clone();
+        assert_eq!(de_index.circuit_digest().unwrap(), digest);
+        let bytes = rmp_serde::to_vec(&verifier_index).unwrap();
+        let mut de_index: VerifierIndex<Vesta> = rmp_serde::from_slice(&bytes).unwrap();
+        de_index.linearization = verifier_index.linearization.clone();
+        assert_eq!(de_index.circuit_digest().unwrap(), digest);
+
+        // another number of public inputs, with the same gates, is another circuit,
+        // although its commitments (and Fiat-Shamir digest) are the same
+        let other = new_index_for_test(create_circuit(0, public.len()), public.len() - 1);
+        let other_index = other.verifier_index();
+        assert_eq!(
+            other_index.digest::<BaseSponge>(),
+            verifier_index.digest::<BaseSponge>()
+        );
+        assert_ne!(other_index.circuit_digest().unwrap(), digest);
+
+        // as are other gates
+        let other = new_index_for_test(create_circuit(0, public.len() + 1), public.len());
+        assert_ne!(other.verifier_index().circuit_digest().unwrap(), digest);
+    }
+
+    #[test]
+    fn test_verifier_index_versions() {
+        let public = [Fp::from(3u8); 5];
+        let gates = create_circuit(0, public.len());
+        let index = new_index_for_test(gates, public.len());
+        let verifier_index = index.verifier_index();
+        let expected = rmp_serde::to_vec(&verifier_index).unwrap();
+
+        let path = std::env::temp_dir().join(format!(
+            "kimchi_verifier_index_versions_{}",
+            std::process::id()
+        ));
+        let read = |offset| {
+            VerifierIndex::<Vesta>::from_file(None, &path, offset, Fp::zero())
+                .map(|index| rmp_serde::to_vec(&index).unwrap())
+        };
+
+        // the current format starts with a header
+        File::create(&path).unwrap();
+        verifier_index.to_file(&path, None).unwrap();
+        let bytes = fs::read(&path).unwrap();
+        assert_eq!(&bytes[..4], VERIFIER_INDEX_MAGIC);
+        assert_eq!(bytes[4..8], VERIFIER_INDEX_VERSION.to_be_bytes());
+        assert_eq!(bytes[8..], expected);
+        assert_eq!(read(None).unwrap(), expected);
+
+        // the previous format has no header and no number of zero-knowledge rows,
+        // which defaults to the only value supported at the time
+        let legacy = rmp_serde::to_vec(&VerifierIndexV1::from(&verifier_index)).unwrap();
+        fs::write(&path, &legacy).unwrap();
+        assert_eq!(read(None).unwrap(), expected);
+
+        // both formats can be mixed in a file
+        verifier_index.to_file(&path, None).unwrap();
+        assert_eq!(read(None).unwrap(), expected);
+        assert_eq!(read(Some(legacy.len() as u64)).unwrap(), expected);
+
+        // unknown versions are rejected
+        let mut unknown = VERIFIER_INDEX_MAGIC.to_vec();
+        unknown.extend((VERIFIER_INDEX_VERSION + 1).to_be_bytes());
+        unknown.extend(&expected);
+        fs::write(&path, &unknown).unwrap();
+        assert_eq!(
+            read(None).unwrap_err(),
+            VerifierIndexError::UnsupportedVersion(VERIFIER_INDEX_VERSION + 1).to_string()
+        );
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_verifier_index_fixtures() {
+        // the index of the same circuit, as written by each version of the format
+        let fixtures: [&[u8]; VERIFIER_INDEX_VERSION as usize] = [
+            include_bytes!("fixtures/verifier_index_v1.bin"),
+            include_bytes!("fixtures/verifier_index_v2.bin"),
+            include_bytes!("fixtures/verifier_index_v3.bin"),
+            include_bytes!("fixtures/verifier_index_v4.bin"),
+            include_bytes!("fixtures/verifier_index_v5.bin"),
+            include_bytes!("fixtures/verifier_index_v6.bin"),
+            include_bytes!("fixtures/verifier_index_v7.bin"),
+            include_bytes!("fixtures/verifier_index_v8.bin"),
+        ];
+
+        let public = 5;
+        let index = new_index_for_test(create_circuit(0, public), public);
+        let verifier_index = index.verifier_index();
+        let expected = rmp_serde::to_vec(&verifier_index).unwrap();
+
+        let path = std::env::temp_dir().join(format!(
+            "kimchi_verifier_index_fixtures_{}",
+            std::process::id()
+        ));
+
+        // every version still reads as the same index
+        for (version, fixture) in (1..).zip(fixtures) {
+            if version == 1 {
+                assert!(!fixture.starts_with(VERIFIER_INDEX_MAGIC));
+            } else {
+                assert_eq!(&fixture[..4], VERIFIER_INDEX_MAGIC);
+                assert_eq!(fixture[4..8], u32::to_be_bytes(version));
+            }
+            fs::write(&path, fixture).unwrap();
+            let read = VerifierIndex::<Vesta>::from_file(None, &path, None, Fp::zero()).unwrap();
+            assert_eq!(
+                rmp_serde::to_vec(&read).unwrap(),
+                expected,
+                "version {version}"
+            );
+        }
+
+        // and the current version is written byte for byte as its fixture
+        File::create(&path).unwrap();
+        verifier_index.to_file(&path, None).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), fixtures[fixtures.len() - 1]);
+
+        fs::remove_file(&path).unwrap();
+    }
+
     #[test]
     pub fn test_serialization() {
         let public = vec![Fp::from(3u8); 5];
@@ -87,5 +427,136 @@ mod tests {
         verify::<Vesta, BaseSponge, ScalarSponge>(&group_map, &verifier_index_deserialize, &proof)
             .unwrap();
         println!("- time to verify: {}ms", start.elapsed().as_millis());
+
+        // verify the compressed proof
+        let compressed = proof.compress().unwrap();
+        verify_compressed::<Vesta, BaseSponge, ScalarSponge>(
+            &group_map,
+            &verifier_index_deserialize,
+            &compressed,
+        )
+        .unwrap();
+        assert!(matches!(
+            verify_compressed::<Vesta, BaseSponge, ScalarSponge>(
+                &group_map,
+                &verifier_index_deserialize,
+                &compressed[1..],
+            ),
+            Err(VerifyError::MalformedCompressedProof)
+        ));
+    }
+
+    #[test]
+    fn test_proof_metadata() {
+        let public = vec![Fp::from(3u8); 5];
+        let gates = create_circuit(0, public.len());
+        let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+        fill_in_witness(0, &mut witness, &public);
+        let index = new_index_for_test(gates, public.len());
+        let verifier_index = index.verifier_index();
+
+        let group_map = <Vesta as CommitmentCurve>::Map::setup();
+        let start = Instant::now();
+        let proof =
+            ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness, &[], &index)
+                .unwrap();
+        let metadata = ProofMetadata::new(&verifier_index, start.elapsed())
+            .unwrap()
+            .label("host", "prover-1");
+        assert_eq!(metadata.prover_version, PROVER_VERSION);
+        assert!(metadata.is_for(&verifier_index).unwrap());
+
+        // the metadata is for this circuit only
+        let other_index = new_index_for_test(create_circuit(0, 2), 2).verifier_index();
+        assert!(!metadata.is_for(&other_index).unwrap());
+
+        // the metadata can be read without decoding the proof
+        let bytes = proof.to_bytes_with_metadata(Some(&metadata)).unwrap();
+        assert_eq!(read_metadata(&bytes).unwrap(), Some(metadata.clone()));
+
+        // the decoded proof still verifies
+        let (de_pf, de_metadata) = ProverProof::<Vesta>::from_bytes_with_metadata(&bytes).unwrap();
+        assert_eq!(de_metadata, Some(metadata));
+        assert_eq!(
+            rmp_serde::to_vec(&de_pf).unwrap(),
+            rmp_serde::to_vec(&proof).unwrap()
+        );
+        verify::<Vesta, BaseSponge, ScalarSponge>(&group_map, &verifier_index, &de_pf).unwrap();
+
+        // the metadata is optional
+        let bare = proof.to_bytes_with_metadata(None).unwrap();
+        assert!(bare.len() < bytes.len());
+        assert_eq!(read_metadata(&bare).unwrap(), None);
+
+        // altered or truncated encodings are rejected
+        let mut altered = bytes.clone();
+        altered[bytes.len() - 40] ^= 1;
+        assert_eq!(read_metadata(&altered), Err(ProofMetadataError::Hash));
+        assert!(ProverProof::<Vesta>::from_bytes_with_metadata(&altered).is_err());
+        assert_eq!(
+            read_metadata(&bytes[..bytes.len() - 1]),
+            Err(ProofMetadataError::Hash)
+        );
+        assert_eq!(read_metadata(&[]), Err(ProofMetadataError::Truncated));
+    }
+
+    #[test]
+    fn test_proof_inspection() {
+        let public = vec![Fp::from(3u8); 5];
+        let gates = create_circuit(0, public.len());
+        let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+        fill_in_witness(0, &mut witness, &public);
+        let index = new_index_for_test(gates, public.len());
+        let verifier_index = index.verifier_index();
+
+        let group_map = <Vesta as CommitmentCurve>::Map::setup();
+        let mut proof =
+            ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness, &[], &index)
+                .unwrap();
+        let bytes = rmp_serde::to_vec(&proof).unwrap();
+
+        let summary =
+            ProofSummary::from_bytes::<Vesta, BaseSponge, ScalarSponge>(&verifier_index, &bytes)
+                .unwrap();
+        assert!(summary.is_well_formed());
+        assert_eq!(summary.public, public.len());
+        assert_eq!(summary.commitments.len(), COLUMNS + 2);
+        assert_eq!(summary.evaluations[0].zeta, proof.evals[0].w[0]);
+
+        // the circuit does not read the previous row
+        assert!(summary.ft_eval_prev.is_none());
+        assert!(summary.evaluations.iter().all(|e| e.zeta_prev.is_none()));
+
+        // the display lists the evaluations and the challenges
+        let display = summary.to_string();
+        assert!(display.starts_with(&format!(
+            "domain of size {}, chunks of size {} (1 per polynomial)\n",
+            verifier_index.domain.size, verifier_index.max_poly_size
+        )));
+        assert!(display.contains("\nevaluations (zeta | zeta omega):\n"));
+        assert!(display.contains(&format!(
+            "\n  w_0: [{}] | [{}]\n",
+            proof.evals[0].w[0][0], proof.evals[1].w[0][0]
+        )));
+        assert!(display.contains(&format!("\n  ft: | [{}]\n", proof.ft_eval1)));
+        assert!(display.ends_with("the proof is well-formed\n"));
+
+        // the challenges are the ones of the verifier
+        let public_comm = public_commitment(&verifier_index, &proof.public);
+        let oracles = proof
+            .oracles::<BaseSponge, ScalarSponge>(&verifier_index, &public_comm)
+            .unwrap();
+        let challenges = summary.challenges.unwrap();
+        assert_eq!(challenges.oracles.zeta, oracles.oracles.zeta);
+        assert_eq!(challenges.ft_eval0, oracles.ft_eval0);
+        assert!(display.contains(&format!("\n  zeta: {}\n", oracles.oracles.zeta)));
+
+        // a malformed proof is reported
+        proof.commitments.t_comm.unshifted.pop();
+        proof.public.pop();
+        let summary = ProofSummary::new::<Vesta, BaseSponge, ScalarSponge>(&verifier_index, &proof);
+        assert!(!summary.is_well_formed());
+        assert!(summary.challenges.is_none());
+        assert_eq!(summary.issues.len(), 2);
     }
 }
@@ -0,0 +1,175 @@
+use crate::circuits::{
+    constraints::ConstraintSystem,
+    gate::{CircuitGate, CircuitGateError, GateType},
+    polynomial::COLUMNS,
+    polynomials::sha256::{
+        circuitgates::Sigma,
+        gadget::{self, state_output_row_from_end, BLOCK_WORDS, STATE_WORDS},
+        witness::{compress, create_compression_witness, pad_message, INITIAL_STATE},
+    },
+};
+
+use super::framework::TestFramework;
+use ark_ff::One;
+use mina_curves::pasta::{Fp, Vesta};
+use num_bigint::BigUint;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use sha2::{digest::generic_array::GenericArray, Digest};
+
+fn create_test_constraint_system() -> ConstraintSystem<Fp> {
+    let (_, gates) = CircuitGate::<Fp>::create_sha256_compression(0);
+    ConstraintSystem::create(gates).build().unwrap()
+}
+
+fn random_input(seed: u8) -> ([u32; STATE_WORDS], [u32; BLOCK_WORDS]) {
+    let rng = &mut StdRng::from_seed([seed; 32]);
+    (rng.gen(), rng.gen())
+}
+
+// Reads the output state from the witness of a compression
+fn output_state(witness: &[Vec<Fp>; COLUMNS]) -> [u32; STATE_WORDS] {
+    let len = witness[0].len();
+    std::array::from_fn(|i| value_to_u64(witness[0][len - state_output_row_from_end(i)]) as u32)
+}
+
+fn value_to_u64(value: Fp) -> u64 {
+    let bigint: BigUint = value.into();
+    u64::try_from(bigint).unwrap()
+}
+
+// The compression of the reference implementation
+fn reference_compress(state: [u32; STATE_WORDS], block: [u32; BLOCK_WORDS]) -> [u32; STATE_WORDS] {
+    let mut state = state;
+    let bytes: Vec<u8> = block.iter().flat_map(|word| word.to_be_bytes()).collect();
+    sha2::compress256(&mut state, &[*GenericArray::from_slice(&bytes)]);
+    state
+}
+
+#[test]
+fn test_sha256_sigma_split_nybbles() {
+    // the nybbles that are not split must be moved as a whole by all the shifts
+    for sigma in [Sigma::Small0, Sigma::Small1, Sigma::Big0, Sigma::Big1] {
+        for i in (0..8).filter(|i| !sigma.split_nybbles().contains(i)) {
+            for shift in sigma.shifts() {
+                let pos = 4 * i as u32;
+                for l in 0..4 {
+                    assert_eq!(
+                        shift.spread_weight(pos + l),
+                        shift.spread_weight(pos) << (2 * l)
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_sha256_compress() {
+    for seed in 0..4 {
+        let (state, block) = random_input(seed);
+        assert_eq!(compress(state, block), reference_compress(state, block));
+    }
+}
+
+#[test]
+fn test_sha256_compression_witness() {
+    for seed in 0..4 {
+        let (state, block) = random_input(seed);
+        let witness = create_compression_witness::<Fp>(state, block);
+        assert_eq!(output_state(&witness), reference_compress(state, block));
+    }
+}
+
+#[test]
+fn test_sha256_abc_digest() {
+    let message = b"abc";
+    let state = pad_message(message)
+        .into_iter()
+        .fold(INITIAL_STATE, compress);
+    let digest: Vec<u8> = state.iter().flat_map(|word| word.to_be_bytes()).collect();
+    assert_eq!(digest, sha2::Sha256::digest(message).to_vec());
+}
+
+#[test]
+fn test_sha256_valid_witness() {
+    let cs = create_test_constraint_system();
+    for seed in 0..2 {
+        let (state, block) = random_input(seed);
+        let witness = create_compression_witness::<Fp>(state, block);
+        assert_eq!(cs.verify::<Vesta>(&witness, &[]), Ok(()));
+    }
+}
+
+#[test]
+fn test_sha256_invalid_witness() {
+    let cs = create_test_constraint_system();
+    let (state, block) = random_input(0);
+    let mut witness = create_compression_witness::<Fp>(state, block);
+
+    // the carry of the last output word is out of range
+    let row = witness[0].len() - state_output_row_from_end(STATE_WORDS - 1);
+    witness[3][row] += Fp::from(7u64);
+    witness[2][row] += Fp::from(7u64 << 32);
+    assert!(cs.verify::<Vesta>(&witness, &[]).is_err());
+}
+
+#[test]
+fn test_sha256_invalid_lookup() {
+    let cs = create_test_constraint_system();
+    let (state, block) = random_input(1);
+    let mut witness = create_compression_witness::<Fp>(state, block);
+
+    // moving 16 from the second nybble of the first input word to its first nybble
+    // (and likewise for their spread forms) satisfies the constraints, but not the lookups
+    witness[7][0] += Fp::from(16u64);
+    witness[9][0] -= Fp::one();
+    witness[8][0] += Fp::from(256u64);
+    witness[10][0] -= Fp::one();
+
+    assert_eq!(
+        cs.gates[0].verify_sha256::<Vesta>(0, &witness, &cs),
+        Err(CircuitGateError::InvalidLookup(GateType::Sha256Word, 0))
+    );
+}
+
+#[test]
+fn test_sha256_spread_table() {
+    let table = gadget::lookup_table::<Fp>();
+    assert_eq!(table.len(), 16);
+    assert!(table.has_zero_entry());
+    for i in 0..16 {
+        let n = value_to_u64(table.data[0][i]);
+        let spread = value_to_u64(table.data[1][i]);
+        let expected = (0..4).fold(0, |acc, b| acc | ((n >> b) & 1) << (2 * b));
+        assert_eq!(spread, expected);
+    }
+}
+
+#[test]
+fn test_sha256_invalid_gate_type() {
+    let cs = create_test_constraint_system();
+    let (state, block) = random_input(0);
+    let witness = create_compression_witness::<Fp>(state, block);
+    let row = cs
+        .gates
+        .iter()
+        .position(|gate| !gadget::circuit_gates().contains(&gate.typ))
+        .unwrap();
+    assert_eq!(
+        cs.gates[row].verify_sha256::<Vesta>(row, &witness, &cs),
+        Err(CircuitGateError::InvalidCircuitGateType(cs.gates[row].typ))
+    );
+}
+
+#[test]
+fn test_sha256_prove_and_verify() {
+    let (_, gates) = CircuitGate::<Fp>::create_sha256_compression(0);
+    let (state, block) = random_input(2);
+    let witness = create_compression_witness(state, block);
+
+    TestFramework::default()
+        .gates(gates)
+        .witness(witness)
+        .setup()
+        .prove_and_verify();
+}
@@ -0,0 +1,93 @@
+use super::framework::TestFramework;
+use crate::{
+    circuits::{builder::CircuitBuilder, sponge::CircuitSponge},
+    curve::KimchiCurve,
+};
+use ark_ff::Zero;
+use mina_curves::pasta::{Fp, Vesta};
+use oracle::{
+    constants::PlonkSpongeConstantsKimchi,
+    permutation::poseidon_block_cipher,
+    poseidon::{ArithmeticSponge, Sponge},
+};
+
+// Hashes the public inputs a, b and c with a sponge of rate `rate`,
+// and squeezes three public outputs
+fn create_circuit(rate: usize, domain: Fp) -> CircuitBuilder<Fp> {
+    let mut builder = CircuitBuilder::new();
+    let inputs = ["a", "b", "c"].map(|name| builder.public_input(name).unwrap());
+
+    let params = Vesta::sponge_params();
+    let mut sponge = CircuitSponge::with_domain_separator(&mut builder, params, rate, domain);
+    sponge.absorb(&mut builder, &inputs);
+    for output in sponge.squeeze_many(&mut builder, 3) {
+        builder.public_output(output);
+    }
+
+    builder
+}
+
+fn prove_and_verify(builder: &CircuitBuilder<Fp>, inputs: [Fp; 3]) -> Vec<Fp> {
+    let inputs = [("a", inputs[0]), ("b", inputs[1]), ("c", inputs[2])];
+    let witness = builder.generate_witness(&inputs).unwrap();
+    let public = witness[0][..builder.public()].to_vec();
+
+    TestFramework::default()
+        .gates(builder.gates().unwrap())
+        .witness(witness)
+        .public_inputs(public.clone())
+        .setup()
+        .prove_and_verify();
+
+    // the outputs follow the inputs
+    public[3..].to_vec()
+}
+
+#[test]
+fn test_circuit_sponge() {
+    let inputs = [1u64, 2, 3].map(Fp::from);
+    let outputs = prove_and_verify(&create_circuit(2, Fp::zero()), inputs);
+
+    // same as the sponge outside of circuits
+    let mut sponge =
+        ArithmeticSponge::<Fp, PlonkSpongeConstantsKimchi>::new(Vesta::sponge_params());
+    sponge.absorb(&inputs);
+    let expected: Vec<_> = (0..3).map(|_| sponge.squeeze()).collect();
+    assert_eq!(outputs, expected);
+}
+
+#[test]
+fn test_circuit_sponge_rate_and_domain() {
+    let params = Vesta::sponge_params();
+    let permute = |state: &mut Vec<Fp>| {
+        poseidon_block_cipher::<Fp, PlonkSpongeConstantsKimchi>(params, state)
+    };
+    let inputs = [4u64, 5, 6].map(Fp::from);
+    let domain = Fp::from(42u64);
+    let outputs = prove_and_verify(&create_circuit(1, domain), inputs);
+
+    // with a rate of 1, each input and each output takes a permutation
+    let mut state = vec![Fp::zero(), Fp::zero(), domain];
+    let mut expected = vec![];
+    state[0] += inputs[0];
+    for input in &inputs[1..] {
+        permute(&mut state);
+        state[0] += input;
+    }
+    for _ in 0..3 {
+        permute(&mut state);
+        expected.push(state[0]);
+    }
+    assert_eq!(outputs, expected);
+
+    // the domain separator changes the outputs
+    let outputs_without_domain = prove_and_verify(&create_circuit(1, Fp::zero()), inputs);
+    assert_ne!(outputs, outputs_without_domain);
+}
+
+#[test]
+#[should_panic]
+fn test_circuit_sponge_invalid_rate() {
+    let mut builder = CircuitBuilder::<Fp>::new();
+    CircuitSponge::new(&mut builder, Vesta::sponge_params(), 3);
+}
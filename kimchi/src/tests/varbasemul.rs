@@ -1,4 +1,5 @@
 use crate::circuits::{
+    constraints::GateError,
     gate::{CircuitGate, GateType},
     polynomials::varbasemul,
     wires::*,
@@ -7,7 +8,7 @@ use crate::tests::framework::TestFramework;
 use ark_ec::{AffineCurve, ProjectiveCurve};
 use ark_ff::{BigInteger, BitIteratorLE, Field, One, PrimeField, UniformRand, Zero};
 use colored::Colorize;
-use mina_curves::pasta::{Fp as F, Pallas as Other};
+use mina_curves::pasta::{Fp as F, Pallas as Other, Vesta};
 use rand::{rngs::StdRng, SeedableRng};
 use std::array;
 use std::time::Instant;
@@ -87,3 +88,78 @@ fn varbase_mul_test() {
         .setup()
         .prove_and_verify();
 }
+
+#[test]
+fn varbase_mul_shared_bits_test() {
+    let num_bits = F::size_in_bits();
+    let rows_per_scalar = 2 * (num_bits / varbasemul::BITS_PER_CHUNK);
+
+    // two scalar multiplications by the same scalar, sharing its decomposition
+    let (next_row, mut gates) = CircuitGate::<F>::create_vbmul_scalar(0, num_bits);
+    let (_, mut second) = CircuitGate::<F>::create_vbmul_scalar(next_row, num_bits);
+    gates.append(&mut second);
+    varbasemul::connect_bits(&mut gates, next_row, &varbasemul::bit_cells(0, num_bits));
+
+    let mut witness: [Vec<F>; COLUMNS] = array::from_fn(|_| vec![F::zero(); 2 * rows_per_scalar]);
+
+    let rng = &mut StdRng::from_seed([0; 32]);
+    let x = F::rand(rng);
+    let bits_lsb: Vec<_> = BitIteratorLE::new(x.into_repr()).take(num_bits).collect();
+    let bits_msb: Vec<_> = bits_lsb.iter().copied().rev().collect();
+
+    let g = Other::prime_subgroup_generator().into_projective();
+    for (i, base) in [g, g + g + g].into_iter().enumerate() {
+        let acc = (base + base).into_affine();
+        let base = base.into_affine();
+        varbasemul::witness(
+            &mut witness,
+            i * rows_per_scalar,
+            (base.x, base.y),
+            &bits_msb,
+            (acc.x, acc.y),
+        );
+    }
+
+    TestFramework::default()
+        .gates(gates)
+        .witness(witness)
+        .setup()
+        .prove_and_verify();
+}
+
+#[test]
+fn varbase_mul_shared_bits_mismatch_test() {
+    let num_bits = F::size_in_bits();
+    let rows_per_scalar = 2 * (num_bits / varbasemul::BITS_PER_CHUNK);
+
+    let (next_row, mut gates) = CircuitGate::<F>::create_vbmul_scalar(0, num_bits);
+    let (_, mut second) = CircuitGate::<F>::create_vbmul_scalar(next_row, num_bits);
+    gates.append(&mut second);
+    varbasemul::connect_bits(&mut gates, next_row, &varbasemul::bit_cells(0, num_bits));
+
+    let mut witness: [Vec<F>; COLUMNS] = array::from_fn(|_| vec![F::zero(); 2 * rows_per_scalar]);
+
+    // use two different scalars: the copy constraints on the bits must catch it
+    let rng = &mut StdRng::from_seed([1; 32]);
+    let g = Other::prime_subgroup_generator().into_projective();
+    let acc = (g + g).into_affine();
+    let base = g.into_affine();
+    for i in 0..2 {
+        let x = F::rand(rng);
+        let mut bits_msb: Vec<_> = BitIteratorLE::new(x.into_repr()).take(num_bits).collect();
+        bits_msb.reverse();
+        varbasemul::witness(
+            &mut witness,
+            i * rows_per_scalar,
+            (base.x, base.y),
+            &bits_msb,
+            (acc.x, acc.y),
+        );
+    }
+
+    let runner = TestFramework::default().gates(gates).setup();
+    assert!(matches!(
+        runner.prover_index().cs.verify::<Vesta>(&witness, &[]),
+        Err(GateError::DisconnectedWires(..))
+    ));
+}
@@ -0,0 +1,74 @@
+use crate::circuits::{
+    gate::GateType,
+    polynomials::generic::testing::{create_circuit, fill_in_witness},
+    wires::COLUMNS,
+    witness_diff::{CellDiff, WitnessDiff},
+};
+use ark_ff::Zero;
+use mina_curves::pasta::Fp;
+use std::array;
+
+#[test]
+fn test_witness_diff() {
+    let public = vec![Fp::from(3u8); 5];
+    let gates = create_circuit(0, public.len());
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &public);
+
+    let diff = WitnessDiff::new(&gates, &witness, &witness);
+    assert!(diff.is_empty());
+    assert_eq!(diff.first_divergence(), None);
+    assert_eq!(diff.to_string(), "the witnesses are identical\n");
+
+    // two cells of a generic gate, and a row past the gates in a single witness
+    let mut other = witness.clone();
+    let row = public.len() + 2;
+    other[1][row] += Fp::from(1u8);
+    other[2][row] += Fp::from(1u8);
+    other[4].push(Fp::from(7u8));
+
+    let diff = WitnessDiff::new(&gates, &witness, &other);
+    assert_eq!(diff.cells(), 3);
+    assert_eq!(diff.rows.len(), 2);
+
+    let first = diff.first_divergence().unwrap();
+    assert_eq!(first.row, row);
+    assert_eq!(first.typ, Some(GateType::Generic));
+    assert_eq!(
+        first.cells[0],
+        CellDiff {
+            col: 1,
+            left: Some(witness[1][row]),
+            right: Some(other[1][row]),
+        }
+    );
+
+    let last = &diff.rows[1];
+    assert_eq!((last.row, last.typ), (gates.len(), None));
+    assert_eq!(
+        last.cells,
+        vec![CellDiff {
+            col: 4,
+            left: None,
+            right: Some(Fp::from(7u8)),
+        }]
+    );
+
+    assert_eq!(
+        diff.to_string(),
+        format!(
+            "the witnesses differ on 3 cells in 2 rows\n\
+             row {row} (Generic):\n  \
+             column 1: {} != {}\n  \
+             column 2: {} != {}\n\
+             row {} (past the gates):\n  \
+             column 4: - != {}\n",
+            witness[1][row],
+            other[1][row],
+            witness[2][row],
+            other[2][row],
+            gates.len(),
+            Fp::from(7u8)
+        )
+    );
+}
@@ -0,0 +1,490 @@
+use super::framework::TestFramework;
+use crate::{
+    circuits::{
+        builder::CircuitBuilder,
+        constraints::ConstraintSystem,
+        gate::{CircuitGate, GateType},
+        polynomials::{
+            bitwise::gadget::{BitwiseGadget, BitwiseOp},
+            chacha::{self, ChaCha20Gadget},
+            complete_add::CompleteAddGadget,
+            endomul_scalar::EndoMulScalarGadget,
+            endosclmul::EndoMulGadget,
+            generic::{testing, GenericGadget, GenericGateSpec},
+            poseidon::PoseidonGadget,
+            select::witness::MuxGadget,
+            sha256::{
+                gadget::{BLOCK_WORDS, STATE_WORDS},
+                witness::{compress, Sha256CompressionGadget, INITIAL_STATE},
+            },
+            varbasemul::VarBaseMulGadget,
+        },
+        wires::{Wire, COLUMNS},
+        witness_generator::{WitnessBuilder, WitnessGenerator},
+    },
+    curve::KimchiCurve,
+    error::{CircuitBuilderError, WitnessBuilderError},
+};
+use ark_ec::AffineCurve;
+use ark_ff::{BigInteger, BitIteratorLE, PrimeField, Zero};
+use commitment_dlog::srs::endos;
+use mina_curves::pasta::{Fp, Pallas, Vesta};
+use num_bigint::BigUint;
+use oracle::{
+    constants::PlonkSpongeConstantsKimchi, permutation::poseidon_block_cipher,
+    sponge::ScalarChallenge,
+};
+use std::array;
+
+// Checks that the witness of the gadget satisfies its gates, and returns the values
+// of its input and output cells
+fn check_gadget<G: WitnessGenerator<Fp>>(gadget: &G, input: &G::Input) -> (Vec<Fp>, Vec<Fp>) {
+    check_gadget_with_public(gadget, input, &[])
+}
+
+// Same as [check_gadget], for a gadget starting with the rows of the public inputs `public`
+fn check_gadget_with_public<G: WitnessGenerator<Fp>>(
+    gadget: &G,
+    input: &G::Input,
+    public: &[Fp],
+) -> (Vec<Fp>, Vec<Fp>) {
+    let mut gates = gadget.gates(0);
+    assert_eq!(gates.len(), gadget.rows());
+
+    let mut witness = gadget.witness(input);
+    assert!(witness.iter().all(|column| column.len() == gadget.rows()));
+
+    // a circuit has more than one row, and the last gate of the gadget may read the next row
+    gates.push(CircuitGate::zero(Wire::new(gates.len())));
+    for column in witness.iter_mut() {
+        column.push(Fp::zero());
+    }
+    let mut cs = ConstraintSystem::create(gates)
+        .public(public.len())
+        .build()
+        .unwrap();
+    // the endomorphism coefficient set by the prover index
+    cs.endo = endos::<Pallas>().0;
+    assert_eq!(cs.verify::<Vesta>(&witness, public), Ok(()));
+
+    let cells = |cells: Vec<(usize, usize)>| {
+        cells
+            .into_iter()
+            .map(|(row, col)| witness[col][row])
+            .collect()
+    };
+    (cells(gadget.input_cells()), cells(gadget.output_cells()))
+}
+
+#[test]
+fn test_witness_generator_mux() {
+    let values: Vec<Fp> = (10u64..15).map(Fp::from).collect();
+    let (inputs, outputs) = check_gadget(&MuxGadget { n: 5 }, &(3, values.clone()));
+
+    let expected_inputs: Vec<_> = std::iter::once(Fp::from(3u64)).chain(values).collect();
+    assert_eq!(inputs, expected_inputs);
+    assert_eq!(outputs, vec![Fp::from(13u64)]);
+}
+
+#[test]
+fn test_witness_generator_poseidon() {
+    let params = Vesta::sponge_params();
+    let input = [1u64, 2, 3].map(Fp::from);
+    let (inputs, outputs) = check_gadget(&PoseidonGadget { params }, &input);
+
+    let mut state = input.to_vec();
+    poseidon_block_cipher::<Fp, PlonkSpongeConstantsKimchi>(params, &mut state);
+    assert_eq!(inputs, input.to_vec());
+    assert_eq!(outputs, state);
+}
+
+#[test]
+fn test_witness_generator_sha256_compression() {
+    let block: [u32; BLOCK_WORDS] = array::from_fn(|i| 0x01020304 * i as u32);
+    let (inputs, outputs) = check_gadget(&Sha256CompressionGadget, &(INITIAL_STATE, block));
+
+    let words = |words: &[u32]| -> Vec<Fp> { words.iter().map(|w| Fp::from(*w)).collect() };
+    let expected_inputs: Vec<_> = words(&INITIAL_STATE)
+        .into_iter()
+        .chain(words(&block))
+        .collect();
+    assert_eq!(inputs.len(), STATE_WORDS + BLOCK_WORDS);
+    assert_eq!(inputs, expected_inputs);
+    assert_eq!(outputs, words(&compress(INITIAL_STATE, block)));
+}
+
+#[test]
+fn test_witness_generator_complete_add() {
+    let g = Pallas::prime_subgroup_generator();
+    let p = g + g;
+    let point = |p: Pallas| (p.x, p.y);
+    for (a, b, expected) in [(g, p, g + p), (p, p, p + p)] {
+        let (inputs, outputs) = check_gadget(&CompleteAddGadget, &(point(a), point(b)));
+        assert_eq!(inputs, vec![a.x, a.y, b.x, b.y]);
+        assert_eq!(outputs, vec![expected.x, expected.y]);
+    }
+
+    // the point at infinity is flagged, whatever the output cells
+    let witness = WitnessGenerator::<Fp>::witness(&CompleteAddGadget, &(point(p), point(-p)));
+    assert_eq!(witness[6][0], Fp::from(1u64));
+}
+
+// The bits of a random scalar of `num_bits` bits, most significant bit first, and the scalar
+fn scalar_bits(num_bits: usize) -> (Vec<bool>, Fp) {
+    let bits_lsb: Vec<_> = BitIteratorLE::new(Fp::from(0x1234_5678_9abc_def0u64).into_repr())
+        .take(num_bits)
+        .collect();
+    let scalar = Fp::from_repr(<Fp as PrimeField>::BigInt::from_bits_le(&bits_lsb)).unwrap();
+    (bits_lsb.into_iter().rev().collect(), scalar)
+}
+
+#[test]
+fn test_witness_generator_varbasemul() {
+    let num_bits = 20;
+    let (bits, scalar) = scalar_bits(num_bits);
+    let base = Pallas::prime_subgroup_generator();
+    let acc0 = base + base;
+    let input = ((base.x, base.y), bits.clone(), (acc0.x, acc0.y));
+    let (inputs, outputs) = check_gadget(&VarBaseMulGadget { num_bits }, &input);
+
+    let bits: Vec<_> = bits.into_iter().map(Fp::from).collect();
+    let expected_inputs: Vec<_> = [base.x, base.y]
+        .into_iter()
+        .chain(bits)
+        .chain([acc0.x, acc0.y])
+        .collect();
+    assert_eq!(inputs, expected_inputs);
+
+    // Acc := [2]T; Acc := (Acc + Q) + Acc for each bit, where Q is T or -T
+    let mut acc = acc0;
+    for bit in input.1 {
+        let q = if bit { base } else { -base };
+        acc = (acc + q) + acc;
+    }
+    assert_eq!(outputs, vec![acc.x, acc.y, scalar]);
+}
+
+#[test]
+fn test_witness_generator_endomul() {
+    let num_bits = 16;
+    let (bits, scalar) = scalar_bits(num_bits);
+    let (endo_q, _) = endos::<Pallas>();
+    let base = Pallas::prime_subgroup_generator();
+    let acc0 = {
+        let t = Pallas::new(endo_q * base.x, base.y, false);
+        let p = t + base;
+        p + p
+    };
+    let input = ((base.x, base.y), bits.clone(), (acc0.x, acc0.y));
+    let gadget = EndoMulGadget {
+        endo: endo_q,
+        num_bits,
+    };
+    let (inputs, outputs) = check_gadget(&gadget, &input);
+    assert_eq!(inputs, vec![base.x, base.y, acc0.x, acc0.y]);
+
+    // Acc := (Acc + Q) + Acc for each pair of bits (b1, b0), where Q is (endo^b1 x, (-1)^(1-b0) y)
+    let mut acc = acc0;
+    for pair in bits.chunks(2) {
+        let xq = if pair[0] { endo_q * base.x } else { base.x };
+        let yq = if pair[1] { base.y } else { -base.y };
+        acc = (acc + Pallas::new(xq, yq, false)) + acc;
+    }
+    assert_eq!(outputs, vec![acc.x, acc.y, scalar]);
+}
+
+#[test]
+fn test_witness_generator_endomul_scalar() {
+    // the length of the scalar challenges
+    let num_bits = 128;
+    let (_, scalar) = scalar_bits(num_bits);
+    let (_, endo_scalar) = endos::<Vesta>();
+    let gadget = EndoMulScalarGadget {
+        endo_scalar,
+        num_bits,
+    };
+    let (inputs, outputs) = check_gadget(&gadget, &scalar);
+    assert_eq!(inputs, vec![scalar]);
+    assert_eq!(
+        outputs[0] * endo_scalar + outputs[1],
+        ScalarChallenge(scalar).to_field(&endo_scalar)
+    );
+}
+
+#[test]
+fn test_witness_generator_chacha() {
+    let state: [u32; 16] = array::from_fn(|i| 0x0302_0100 * i as u32 + 0x6170_7865);
+    let (inputs, outputs) = check_gadget(&ChaCha20Gadget, &state);
+
+    let words = |words: Vec<u32>| -> Vec<Fp> { words.into_iter().map(Fp::from).collect() };
+    assert_eq!(inputs, words(state.to_vec()));
+    assert_eq!(outputs, words(chacha::testing::chacha20(state.to_vec())));
+}
+
+#[test]
+fn test_witness_generator_bitwise() {
+    let (a, b) = (0x0123_4567_89ab_cdefu64, 0xfedc_ba98_7654_3210u64);
+    let cases = [
+        (BitwiseOp::Xor, 64, a ^ b),
+        (BitwiseOp::And, 64, a & b),
+        (BitwiseOp::Or, 32, (a | b) as u32 as u64),
+        (BitwiseOp::Not, 32, !a as u32 as u64),
+        (BitwiseOp::Shl(5), 64, a << 5),
+        (BitwiseOp::Shr(5), 32, (a as u32 >> 5) as u64),
+        (BitwiseOp::Rotl(7), 32, (a as u32).rotate_left(7) as u64),
+        (BitwiseOp::Rotr(7), 64, a.rotate_right(7)),
+        (BitwiseOp::Rotl(3), 16, (a as u16).rotate_left(3) as u64),
+    ];
+    for (op, bits, expected) in cases {
+        let gadget = BitwiseGadget { op, bits };
+        let input = [a, b][..op.arity()].to_vec();
+        let (inputs, outputs) = check_gadget(&gadget, &input);
+
+        // the 64-bit words are held by their low and high 32-bit words
+        let words = |x: u64| -> Vec<Fp> {
+            match bits {
+                64 => vec![Fp::from(x as u32), Fp::from(x >> 32)],
+                16 => vec![Fp::from(x as u16)],
+                _ => vec![Fp::from(x as u32)],
+            }
+        };
+        let expected_inputs: Vec<_> = input.iter().flat_map(|x| words(*x)).collect();
+        assert_eq!(inputs, expected_inputs, "inputs of {op:?} on {bits} bits");
+        assert_eq!(outputs, words(expected), "output of {op:?} on {bits} bits");
+    }
+}
+
+// The Poseidon permutation of (a, b, 0), and the element at a private index
+// of (a, b, the first element of the permutation), as public output
+fn create_circuit(index: impl Fn(Fp) -> usize + 'static) -> CircuitBuilder<Fp> {
+    let mut builder = CircuitBuilder::new();
+    let a = builder.public_input("a").unwrap();
+    let b = builder.public_input("b").unwrap();
+    let i = builder.private_input("i").unwrap();
+    let zero = builder.constant(Fp::zero());
+
+    let params = Vesta::sponge_params();
+    let state = builder.gadget(PoseidonGadget { params }, &[a, b, zero], move |v| {
+        [v.get(a), v.get(b), Fp::zero()]
+    });
+
+    let values = [a, b, state[0]];
+    let inputs: Vec<_> = std::iter::once(i).chain(values).collect();
+    let output = builder.gadget(MuxGadget { n: 3 }, &inputs, move |v| {
+        let values = values.iter().map(|var| v.get(*var)).collect();
+        (index(v.get(i)), values)
+    });
+    builder.public_output(output[0]);
+
+    builder
+}
+
+fn to_usize(x: Fp) -> usize {
+    let x: BigUint = x.into();
+    usize::try_from(x).unwrap()
+}
+
+#[test]
+fn test_witness_generator_circuit_builder() {
+    let builder = create_circuit(to_usize);
+    let (a, b) = (Fp::from(5u64), Fp::from(7u64));
+    let inputs = [("a", a), ("b", b), ("i", Fp::from(2u64))];
+    let witness = builder.generate_witness(&inputs).unwrap();
+
+    let mut state = vec![a, b, Fp::zero()];
+    poseidon_block_cipher::<Fp, PlonkSpongeConstantsKimchi>(Vesta::sponge_params(), &mut state);
+    let public = witness[0][..builder.public()].to_vec();
+    assert_eq!(public, vec![a, b, state[0]]);
+
+    TestFramework::default()
+        .gates(builder.gates().unwrap())
+        .witness(witness)
+        .public_inputs(public)
+        .setup()
+        .prove_and_verify();
+}
+
+#[test]
+fn test_witness_generator_circuit_builder_inconsistent_input() {
+    // the multiplexer is given an index other than the value of its index variable
+    let builder = create_circuit(|i| 1 - to_usize(i));
+    let inputs = [
+        ("a", Fp::from(5u64)),
+        ("b", Fp::from(7u64)),
+        ("i", Fp::zero()),
+    ];
+    assert!(matches!(
+        builder.generate_witness(&inputs),
+        Err(CircuitBuilderError::NotEqual(_, _))
+    ));
+}
+
+#[test]
+fn test_witness_generator_generic() {
+    let gadget = GenericGadget {
+        gate1: GenericGateSpec::Add {
+            left_coeff: None,
+            right_coeff: Some(Fp::from(3u64)),
+            output_coeff: None,
+        },
+        gate2: Some(GenericGateSpec::Mul {
+            output_coeff: None,
+            mul_coeff: Some(Fp::from(2u64)),
+        }),
+    };
+    let input = [11u64, 23, 11, 23].map(Fp::from).to_vec();
+    let (inputs, outputs) = check_gadget(&gadget, &input);
+    assert_eq!(inputs, input);
+    assert_eq!(
+        outputs,
+        vec![Fp::from(11u64 + 23 * 3), Fp::from(11u64 * 23 * 2)]
+    );
+
+    let gadget = GenericGadget {
+        gate1: GenericGateSpec::Pub,
+        gate2: Some(GenericGateSpec::Const(Fp::from(5u64))),
+    };
+    let public = vec![Fp::from(7u64)];
+    let (inputs, outputs) = check_gadget_with_public(&gadget, &public, &public);
+    assert_eq!(inputs, vec![Fp::from(7u64)]);
+    assert_eq!(outputs, vec![Fp::from(7u64), Fp::from(5u64)]);
+}
+
+// The gadgets laying out the rows of the generic test circuit
+fn generic_test_gadgets(public: usize) -> Vec<GenericGadget<Fp>> {
+    let add_mul = GenericGadget {
+        gate1: GenericGateSpec::Add {
+            left_coeff: None,
+            right_coeff: Some(3u32.into()),
+            output_coeff: None,
+        },
+        gate2: Some(GenericGateSpec::Mul {
+            output_coeff: None,
+            mul_coeff: Some(2u32.into()),
+        }),
+    };
+    let consts = GenericGadget {
+        gate1: GenericGateSpec::Const(3u32.into()),
+        gate2: Some(GenericGateSpec::Const(5u32.into())),
+    };
+    let public_gadget = GenericGadget {
+        gate1: GenericGateSpec::Pub,
+        gate2: None,
+    };
+
+    std::iter::repeat(public_gadget)
+        .take(public)
+        .chain(std::iter::repeat(add_mul).take(10))
+        .chain(std::iter::repeat(consts).take(10))
+        .collect()
+}
+
+#[test]
+fn test_witness_builder_generic_circuit() {
+    let public: Vec<_> = [1u64, 2, 3].map(Fp::from).to_vec();
+    let gates = testing::create_circuit(0, public.len());
+
+    let mut builder = WitnessBuilder::new(&gates);
+    let mut public_values = public.iter();
+    for gadget in generic_test_gadgets(public.len()) {
+        let input = match gadget.gate1 {
+            GenericGateSpec::Pub => vec![*public_values.next().unwrap()],
+            GenericGateSpec::Const(_) => vec![],
+            _ => [11u64, 23, 11, 23].map(Fp::from).to_vec(),
+        };
+        builder.gadget(&gadget, &input).unwrap();
+    }
+    let witness = builder.build().unwrap();
+
+    // the same witness as the one filled in by hand
+    let mut expected: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    testing::fill_in_witness(0, &mut expected, &public);
+    assert_eq!(witness, expected);
+
+    TestFramework::default()
+        .gates(gates)
+        .witness(witness)
+        .public_inputs(public)
+        .setup()
+        .prove_and_verify();
+}
+
+#[test]
+fn test_witness_builder_chained_gadgets() {
+    // a constant index, and the multiplexer selecting the value at that index
+    let index = GenericGadget {
+        gate1: GenericGateSpec::Const(Fp::from(2u64)),
+        gate2: None,
+    };
+    let mux = MuxGadget { n: 4 };
+    let gates: Vec<_> = index
+        .gates(0)
+        .into_iter()
+        .chain(mux.gates(index.rows()))
+        .collect();
+
+    let mut builder = WitnessBuilder::new(&gates);
+    let row = builder.gadget(&index, &vec![]).unwrap();
+    assert_eq!(row, 0);
+    let (out_row, out_col) = index.output_cells()[0];
+    let selected = to_usize(builder.cell(out_row, out_col));
+    let values: Vec<_> = (10u64..14).map(Fp::from).collect();
+    let row = builder.gadget(&mux, &(selected, values)).unwrap();
+    assert_eq!(row, index.rows());
+    let witness = builder.build().unwrap();
+
+    let cs = ConstraintSystem::create(gates).build().unwrap();
+    assert_eq!(cs.verify::<Vesta>(&witness, &[]), Ok(()));
+    let (out_row, out_col) = WitnessGenerator::<Fp>::output_cells(&mux)[0];
+    assert_eq!(witness[out_col][row + out_row], Fp::from(12u64));
+}
+
+#[test]
+fn test_witness_builder_mismatch() {
+    let gates = testing::create_circuit::<Fp>(0, 0);
+    let gadgets = generic_test_gadgets(0);
+    let input = [11u64, 23, 11, 23].map(Fp::from).to_vec();
+
+    // a gadget of other gates
+    let mut builder = WitnessBuilder::new(&gates);
+    assert_eq!(
+        builder.gadget(
+            &PoseidonGadget {
+                params: Vesta::sponge_params()
+            },
+            &[Fp::zero(); 3]
+        ),
+        Err(WitnessBuilderError::GateType {
+            row: 0,
+            expected: GateType::Poseidon,
+            actual: GateType::Generic,
+        })
+    );
+
+    // generic gates with other coefficients
+    assert_eq!(
+        builder.gadget(&gadgets[10], &vec![]),
+        Err(WitnessBuilderError::Coefficients(GateType::Generic, 0))
+    );
+
+    // an incomplete witness
+    builder.gadget(&gadgets[0], &input).unwrap();
+    assert_eq!(
+        builder.build().map(|_| ()),
+        Err(WitnessBuilderError::Incomplete(1, gates.len()))
+    );
+
+    // more rows than the circuit
+    let mut builder = WitnessBuilder::new(&gates);
+    for _ in 0..gates.len() {
+        builder.row([Fp::zero(); COLUMNS]).unwrap();
+    }
+    assert_eq!(
+        builder.row([Fp::zero(); COLUMNS]),
+        Err(WitnessBuilderError::TooManyRows {
+            row: gates.len(),
+            rows: 1,
+            gates: gates.len(),
+        })
+    );
+}
@@ -0,0 +1,218 @@
+use super::framework::TestFramework;
+use crate::circuits::{
+    constraints::ConstraintSystem,
+    expr::E,
+    gate::{CircuitGate, GateType},
+    lookup::tables::{LookupTable, TableId},
+    polynomials::{
+        generic::testing::{create_circuit, fill_in_witness},
+        permutation::{
+            eval_vanishes_on_zero_knowledge_and_previous_rows, eval_zk_polynomial,
+            vanishes_on_zero_knowledge_and_previous_rows, zk_polynomial, MAX_ZK_ROWS, NON_ZK_ROWS,
+            ZK_ROWS,
+        },
+    },
+    wires::{Wire, COLUMNS},
+};
+use crate::{
+    error::SetupError,
+    proof::ProverProof,
+    prover_index::testing::{new_index_for_test, TestIndexBuilder},
+    verifier::verify,
+    verifier_index::VerifierIndex,
+};
+use ark_ff::{UniformRand, Zero};
+use ark_poly::{EvaluationDomain, Polynomial, Radix2EvaluationDomain as D};
+use commitment_dlog::commitment::CommitmentCurve;
+use groupmap::GroupMap;
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use oracle::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use rand::{rngs::StdRng, SeedableRng};
+use std::array;
+
+type BaseSponge = DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>;
+type ScalarSponge = DefaultFrSponge<Fp, PlonkSpongeConstantsKimchi>;
+
+#[test]
+fn test_zk_polynomials() {
+    let domain = D::<Fp>::new(16).unwrap();
+    let rng = &mut StdRng::from_seed([0u8; 32]);
+
+    for zk_rows in ZK_ROWS..=MAX_ZK_ROWS {
+        let zkpm = zk_polynomial(domain, zk_rows);
+        let vanishing = vanishes_on_zero_knowledge_and_previous_rows(domain, zk_rows);
+        assert_eq!(zkpm.degree(), zk_rows as usize);
+        assert_eq!(vanishing.degree(), zk_rows as usize + 1);
+        assert_eq!(
+            E::<Fp>::VanishesOnZeroKnowledgeAndPreviousRows.degree(domain.size, zk_rows),
+            zk_rows + 1
+        );
+
+        // the zero-knowledge polynomial vanishes exactly on the last `zk_rows` rows,
+        // and the other one on the row just before as well
+        for (row, x) in domain.elements().enumerate() {
+            let zk_row = row >= domain.size() - zk_rows as usize;
+            let final_row = row >= domain.size() - zk_rows as usize - 1;
+            assert_eq!(zkpm.evaluate(&x).is_zero(), zk_row);
+            assert_eq!(vanishing.evaluate(&x).is_zero(), final_row);
+        }
+
+        let pt = Fp::rand(rng);
+        assert_eq!(eval_zk_polynomial(domain, zk_rows, pt), zkpm.evaluate(&pt));
+        assert_eq!(
+            eval_vanishes_on_zero_knowledge_and_previous_rows(domain, zk_rows, pt),
+            vanishing.evaluate(&pt)
+        );
+    }
+}
+
+#[test]
+fn test_zk_rows_out_of_range() {
+    for zk_rows in [0, 1, ZK_ROWS - 1, MAX_ZK_ROWS + 1] {
+        let res = ConstraintSystem::<Fp>::create(create_circuit(0, 0))
+            .zk_rows(zk_rows)
+            .build();
+        assert!(matches!(res, Err(SetupError::ZkRows(n)) if n == zk_rows));
+    }
+}
+
+#[test]
+fn test_generic_gate_zk_rows() {
+    for zk_rows in [ZK_ROWS, 4, 5, MAX_ZK_ROWS] {
+        let public = vec![Fp::from(3u8); 5];
+        let gates = create_circuit(0, public.len());
+
+        let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+        fill_in_witness(0, &mut witness, &public);
+
+        let runner = TestFramework::default()
+            .gates(gates)
+            .witness(witness)
+            .public_inputs(public)
+            .zk_rows(zk_rows)
+            .setup();
+
+        assert_eq!(runner.prover_index().cs.zk_rows, zk_rows);
+        runner.prove_and_verify();
+    }
+}
+
+#[test]
+fn test_generic_gate_non_zk() {
+    let public = vec![Fp::from(3u8); 5];
+    let gates = create_circuit(0, public.len());
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &public);
+
+    // without zero-knowledge, a single row is reserved, whatever the number of zero-knowledge rows
+    let index = TestIndexBuilder::new(gates.clone())
+        .public(public.len())
+        .zk_rows(0)
+        .zk(false)
+        .build();
+    assert!(!index.cs.zk);
+    assert_eq!(index.cs.zk_rows, NON_ZK_ROWS);
+    assert_eq!(index.cs.random_rows(), 0);
+
+    // the commitments are not blinded, so they do not depend on the randomness of the prover
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+    let prove = |seed: u64| {
+        ProverProof::create_with_rng::<BaseSponge, ScalarSponge>(
+            &group_map,
+            witness.clone(),
+            &[],
+            &index,
+            &mut StdRng::seed_from_u64(seed),
+        )
+        .unwrap()
+    };
+    let (proof, other_proof) = (prove(0), prove(1));
+    for (comm, other_comm) in proof
+        .commitments
+        .w_comm
+        .iter()
+        .chain([&proof.commitments.z_comm, &proof.commitments.t_comm])
+        .zip(other_proof.commitments.w_comm.iter().chain([
+            &other_proof.commitments.z_comm,
+            &other_proof.commitments.t_comm,
+        ]))
+    {
+        assert_eq!(comm.unshifted, other_comm.unshifted);
+    }
+
+    let verifier_index = index.verifier_index();
+    assert!(!verifier_index.zk);
+    verify::<Vesta, BaseSponge, ScalarSponge>(&group_map, &verifier_index, &proof).unwrap();
+    verify::<Vesta, BaseSponge, ScalarSponge>(&group_map, &verifier_index, &other_proof).unwrap();
+
+    // the mode is part of the digest and of the encodings of the verifier index
+    let zk_verifier_index = new_index_for_test(gates, public.len()).verifier_index();
+    assert_ne!(
+        verifier_index.digest::<BaseSponge>(),
+        zk_verifier_index.digest::<BaseSponge>()
+    );
+    let compressed = verifier_index.compress().unwrap();
+    let de_index = VerifierIndex::<Vesta>::decompress(&compressed, None, Fp::zero()).unwrap();
+    assert!(!de_index.zk);
+    let serialized = rmp_serde::to_vec(&verifier_index).unwrap();
+    let de_index: VerifierIndex<Vesta> = rmp_serde::from_slice(&serialized).unwrap();
+    assert!(!de_index.zk);
+
+    // the proof does not verify with the zero-knowledge index of the same circuit
+    assert!(
+        verify::<Vesta, BaseSponge, ScalarSponge>(&group_map, &zk_verifier_index, &proof).is_err()
+    );
+}
+
+#[test]
+fn test_lookup_gate_zk_rows() {
+    let num_lookups = 20;
+    let table_values: Vec<Fp> = (0..16u64).map(|i| Fp::from(2 * i)).collect();
+    let table_id = TableId::new(-1);
+    let table = LookupTable {
+        id: table_id,
+        data: vec![(0..16u64).map(Into::into).collect(), table_values.clone()],
+    };
+
+    let gates: Vec<_> = (0..num_lookups)
+        .map(|i| CircuitGate {
+            typ: GateType::Lookup,
+            coeffs: vec![],
+            wires: Wire::new(i),
+        })
+        .collect();
+
+    // each row looks up the pairs (i, 2i), (i + 1, 2i + 2) and (i + 2, 2i + 4),
+    // where i is the row index modulo 14
+    let witness: [Vec<Fp>; COLUMNS] = array::from_fn(|col| {
+        (0..num_lookups)
+            .map(|row| match col {
+                0 => table_id.to_field(),
+                1 | 3 | 5 => Fp::from((row % 14 + (col - 1) / 2) as u64),
+                2 | 4 | 6 => table_values[row % 14 + (col - 2) / 2],
+                _ => Fp::zero(),
+            })
+            .collect()
+    });
+
+    for zk_rows in [4, MAX_ZK_ROWS] {
+        TestFramework::default()
+            .gates(gates.clone())
+            .witness(witness.clone())
+            .lookup_tables(vec![table.clone()])
+            .zk_rows(zk_rows)
+            .setup()
+            .prove_and_verify();
+    }
+
+    TestFramework::default()
+        .gates(gates)
+        .witness(witness)
+        .lookup_tables(vec![table])
+        .zk(false)
+        .setup()
+        .prove_and_verify();
+}
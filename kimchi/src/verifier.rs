@@ -7,25 +7,30 @@ use crate::{
         expr::{Column, Constants, PolishToken},
         gate::GateType,
         lookup::{lookups::LookupsUsed, tables::combine_table},
-        polynomials::{generic, permutation},
+        polynomials::{generic, permutation, sha256},
         scalars::RandomOracles,
         wires::{COLUMNS, PERMUTS},
     },
     curve::KimchiCurve,
     error::VerifyError,
     oracles::OraclesResult,
+    parallel::{self, prelude::*},
     plonk_sponge::FrSponge,
     proof::{ProverProof, RecursionChallenge},
     verifier_index::VerifierIndex,
 };
-use ark_ff::{Field, One, PrimeField, Zero};
-use ark_poly::{EvaluationDomain, Polynomial};
+use ark_ff::{Field, One, PrimeField, UniformRand, Zero};
+use ark_poly::{
+    univariate::DensePolynomial, EvaluationDomain, Evaluations, Polynomial,
+    Radix2EvaluationDomain as D,
+};
 use commitment_dlog::commitment::{
-    combined_inner_product, BatchEvaluationProof, Evaluation, PolyComm,
+    combined_inner_product, BatchEvaluationProof, BatchMsm, Evaluation, PolyComm,
 };
-use itertools::izip;
+use o1_utils::ExtendedDensePolynomial as _;
 use oracle::{sponge::ScalarChallenge, FqSponge};
 use rand::thread_rng;
+use std::marker::PhantomData;
 
 /// The result of a proof verification.
 pub type Result<T> = std::result::Result<T, VerifyError>;
@@ -50,6 +55,24 @@ where
         &self,
         index: &VerifierIndex<G>,
         public_comm: &PolyComm<G>,
+    ) -> Result<OraclesResult<G, EFqSponge>> {
+        self.oracles_with_digest::<EFqSponge, EFrSponge>(
+            index,
+            index.digest::<EFqSponge>(),
+            public_comm,
+        )
+    }
+
+    /// Same as [ProverProof::oracles], with the digest of the verifier index
+    /// `verifier_index_digest` (see [VerifierIndex::digest]) given rather than recomputed
+    fn oracles_with_digest<
+        EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+        EFrSponge: FrSponge<G::ScalarField>,
+    >(
+        &self,
+        index: &VerifierIndex<G>,
+        verifier_index_digest: G::BaseField,
+        public_comm: &PolyComm<G>,
     ) -> Result<OraclesResult<G, EFqSponge>> {
         //~
         //~ #### Fiat-Shamir argument
@@ -59,11 +82,31 @@ where
         let n = index.domain.size;
         let (_, endo_r) = G::endos();
 
+        //~ 1. If the index declares no lookup, ensure that the proof carries no lookup
+        //~    commitments nor lookup evaluations, so that the transcript is the one of a circuit
+        //~    without lookups and all the lookup processing below is skipped.
+        let lookup_commits = match &index.lookup_index {
+            Some(_) => self.commitments.lookup.as_ref(),
+            None => {
+                if self.commitments.lookup.is_some()
+                    || self.chunked_evals().any(|evals| evals.lookup.is_some())
+                {
+                    return Err(VerifyError::UnexpectedLookup);
+                }
+                None
+            }
+        };
+
+        //~ 1. Ensure that the proof carries the evaluations at $\zeta / \omega$
+        //~    if and only if the gates of the circuit read the previous row.
+        if self.prev_row.is_some() != index.linearization.reads_prev_row() {
+            return Err(VerifyError::IncorrectPrevRowEvaluations);
+        }
+
         //~ 1. Setup the Fq-Sponge.
         let mut fq_sponge = EFqSponge::new(G::OtherCurve::sponge_params());
 
         //~ 1. Absorb the digest of the VerifierIndex.
-        let verifier_index_digest = index.digest::<EFqSponge>();
         fq_sponge.absorb_fq(&[verifier_index_digest]);
 
         //~ 1. Absorb the commitments of the previous challenges with the Fq-sponge.
@@ -82,11 +125,7 @@ where
 
         //~ 1. If lookup is used:
         let joint_combiner = if let Some(l) = &index.lookup_index {
-            let lookup_commits = self
-                .commitments
-                .lookup
-                .as_ref()
-                .ok_or(VerifyError::LookupCommitmentMissing)?;
+            let lookup_commits = lookup_commits.ok_or(VerifyError::LookupCommitmentMissing)?;
 
             // if runtime is used, absorb the commitment
             if l.runtime_tables_selector.is_some() {
@@ -130,9 +169,9 @@ where
         let gamma = fq_sponge.challenge();
 
         //~ 1. If using lookup, absorb the commitment to the aggregation lookup polynomial.
-        self.commitments.lookup.iter().for_each(|l| {
+        if let Some(l) = lookup_commits {
             fq_sponge.absorb_g(&l.aggreg.unshifted);
-        });
+        }
 
         //~ 1. Absorb the commitment to the permutation trace with the Fq-Sponge.
         fq_sponge.absorb_g(&self.commitments.z_comm.unshifted);
@@ -143,8 +182,9 @@ where
         //~ 1. Derive $\alpha$ from $\alpha'$ using the endomorphism (TODO: details).
         let alpha = alpha_chal.to_field(endo_r);
 
-        //~ 1. Enforce that the length of the $t$ commitment is of size `PERMUTS`.
-        if self.commitments.t_comm.unshifted.len() != PERMUTS {
+        //~ 1. Enforce that the length of the $t$ commitment is of size `PERMUTS`
+        //~    times the number of chunks of the polynomials over the domain.
+        if self.commitments.t_comm.unshifted.len() != PERMUTS * index.num_chunks() {
             return Err(VerifyError::IncorrectCommitmentLength("t"));
         }
 
@@ -166,12 +206,11 @@ where
 
         // prepare some often used values
         let zeta1 = zeta.pow(&[n]);
-        let zetaw = zeta * index.domain.group_gen;
-        let evaluation_points = [zeta, zetaw];
-        let powers_of_eval_points_for_chunks = [
-            zeta.pow(&[index.max_poly_size as u64]),
-            zetaw.pow(&[index.max_poly_size as u64]),
-        ];
+        let evaluation_points = index.evaluation_points(zeta);
+        let powers_of_eval_points_for_chunks: Vec<_> = evaluation_points
+            .iter()
+            .map(|pt| pt.pow(&[index.max_poly_size as u64]))
+            .collect();
 
         //~ 1. Compute evaluations for the previous recursion challenges.
         let polys: Vec<(PolyComm<G>, _)> = self
@@ -207,44 +246,51 @@ where
         // compute Lagrange base evaluation denominators
         let w: Vec<_> = index.domain.elements().take(self.public.len()).collect();
 
-        let mut zeta_minus_x: Vec<_> = w.iter().map(|w| zeta - w).collect();
-
-        w.iter()
-            .take(self.public.len())
-            .for_each(|w| zeta_minus_x.push(zetaw - w));
+        let mut zeta_minus_x: Vec<_> = evaluation_points
+            .iter()
+            .flat_map(|pt| w.iter().map(move |w| *pt - w))
+            .collect();
 
         ark_ff::fields::batch_inversion::<G::ScalarField>(&mut zeta_minus_x);
 
-        //~ 1. Evaluate the negated public polynomial (if present) at $\zeta$ and $\zeta\omega$.
+        //~ 1. Evaluate the negated public polynomial (if present) at $\zeta$ and $\zeta\omega$
+        //~    (and at $\zeta / \omega$ if the gates of the circuit read the previous row).
         //~
-        //~    NOTE: this works only in the case when the poly segment size is not smaller than that of the domain.
-        let public_evals = if self.public.is_empty() {
-            [vec![G::ScalarField::zero()], vec![G::ScalarField::zero()]]
+        //~    NOTE: when the domain is larger than the SRS, the polynomial is chunked,
+        //~    and the verifier interpolates it to evaluate each of its chunks.
+        let public_evals: Vec<_> = if self.public.is_empty() {
+            vec![vec![G::ScalarField::zero()]; evaluation_points.len()]
+        } else if index.num_chunks() > 1 {
+            let public: Vec<_> = self.public.iter().map(|p| -*p).collect();
+            let public_poly =
+                Evaluations::<G::ScalarField, D<G::ScalarField>>::from_vec_and_domain(
+                    public,
+                    index.domain,
+                )
+                .interpolate()
+                .to_chunked_polynomial(index.max_poly_size);
+            evaluation_points
+                .iter()
+                .map(|pt| public_poly.evaluate_chunks(*pt))
+                .collect()
         } else {
-            [
-                vec![
-                    (self
-                        .public
-                        .iter()
-                        .zip(zeta_minus_x.iter())
-                        .zip(index.domain.elements())
-                        .map(|((p, l), w)| -*l * p * w)
-                        .fold(G::ScalarField::zero(), |x, y| x + y))
-                        * (zeta1 - G::ScalarField::one())
-                        * index.domain.size_inv,
-                ],
-                vec![
-                    (self
-                        .public
-                        .iter()
-                        .zip(zeta_minus_x[self.public.len()..].iter())
-                        .zip(index.domain.elements())
-                        .map(|((p, l), w)| -*l * p * w)
-                        .fold(G::ScalarField::zero(), |x, y| x + y))
-                        * index.domain.size_inv
-                        * (zetaw.pow(&[n as u64]) - G::ScalarField::one()),
-                ],
-            ]
+            evaluation_points
+                .iter()
+                .zip(zeta_minus_x.chunks(self.public.len()))
+                .map(|(pt, pt_minus_x)| {
+                    vec![
+                        (self
+                            .public
+                            .iter()
+                            .zip(pt_minus_x.iter())
+                            .zip(index.domain.elements())
+                            .map(|((p, l), w)| -*l * p * w)
+                            .fold(G::ScalarField::zero(), |x, y| x + y))
+                            * index.domain.size_inv
+                            * (pt.pow(&[n]) - G::ScalarField::one()),
+                    ]
+                })
+                .collect()
         };
 
         //~ 1. Absorb the unique evaluation of ft: $ft(\zeta\omega)$.
@@ -261,6 +307,15 @@ where
         fr_sponge.absorb_multiple(&public_evals[1]);
         fr_sponge.absorb_evaluations([&self.evals[0], &self.evals[1]]);
 
+        //~ 1. If the gates of the circuit read the previous row,
+        //~    absorb the evaluation $ft(\zeta / \omega)$,
+        //~    then all the polynomial evaluations in $\zeta / \omega$, in the same order.
+        if let Some(prev_row) = &self.prev_row {
+            fr_sponge.absorb(&prev_row.ft_eval);
+            fr_sponge.absorb_multiple(&public_evals[2]);
+            fr_sponge.absorb_evaluations([&prev_row.evals]);
+        }
+
         //~ 1. Sample $v'$ with the Fr-Sponge.
         let v_chal = fr_sponge.challenge();
 
@@ -275,10 +330,11 @@ where
 
         //~ 1. Create a list of all polynomials that have an evaluation proof.
 
-        let evals = vec![
-            self.evals[0].combine(powers_of_eval_points_for_chunks[0]),
-            self.evals[1].combine(powers_of_eval_points_for_chunks[1]),
-        ];
+        let evals: Vec<_> = self
+            .chunked_evals()
+            .zip(powers_of_eval_points_for_chunks.iter())
+            .map(|(e, pt)| e.combine(*pt))
+            .collect();
 
         //~ 1. Compute the evaluation of $ft(\zeta)$.
         let ft_eval0 = {
@@ -305,11 +361,10 @@ where
                 .map(|(w, s)| (beta * s) + w + gamma)
                 .fold(init, |x, y| x * y);
 
-            ft_eval0 -= if public_evals[0].is_empty() {
-                G::ScalarField::zero()
-            } else {
-                public_evals[0][0]
-            };
+            ft_eval0 -= DensePolynomial::eval_polynomial(
+                &public_evals[0],
+                powers_of_eval_points_for_chunks[0],
+            );
 
             ft_eval0 -= evals[0]
                 .w
@@ -335,6 +390,7 @@ where
                 endo_coefficient: index.endo,
                 mds: &G::sponge_params().mds,
                 foreign_field_modulus: index.foreign_field_modulus.clone(),
+                zk_rows: index.zk_rows,
             };
             ft_eval0 -= PolishToken::evaluate(
                 &index.linearization.constant_term,
@@ -349,27 +405,29 @@ where
         };
 
         let combined_inner_product = {
-            let ft_eval0 = vec![ft_eval0];
-            let ft_eval1 = vec![self.ft_eval1];
+            let chunked_evals: Vec<_> = self.chunked_evals().collect();
 
             #[allow(clippy::type_complexity)]
             let mut es: Vec<(Vec<Vec<G::ScalarField>>, Option<usize>)> =
                 polys.iter().map(|(_, e)| (e.clone(), None)).collect();
-            es.push((public_evals.to_vec(), None));
-            es.push((vec![ft_eval0, ft_eval1], None));
+            es.push((public_evals.clone(), None));
+            es.push((self.ft_evals(ft_eval0), None));
             es.push((
-                self.evals.iter().map(|e| e.z.clone()).collect::<Vec<_>>(),
+                chunked_evals
+                    .iter()
+                    .map(|e| e.z.clone())
+                    .collect::<Vec<_>>(),
                 None,
             ));
             es.push((
-                self.evals
+                chunked_evals
                     .iter()
                     .map(|e| e.generic_selector.clone())
                     .collect::<Vec<_>>(),
                 None,
             ));
             es.push((
-                self.evals
+                chunked_evals
                     .iter()
                     .map(|e| e.poseidon_selector.clone())
                     .collect::<Vec<_>>(),
@@ -379,7 +437,7 @@ where
                 (0..COLUMNS)
                     .map(|c| {
                         (
-                            self.evals
+                            chunked_evals
                                 .iter()
                                 .map(|e| e.w[c].clone())
                                 .collect::<Vec<_>>(),
@@ -392,7 +450,7 @@ where
                 (0..PERMUTS - 1)
                     .map(|c| {
                         (
-                            self.evals
+                            chunked_evals
                                 .iter()
                                 .map(|e| e.s[c].clone())
                                 .collect::<Vec<_>>(),
@@ -402,7 +460,7 @@ where
                     .collect::<Vec<_>>(),
             );
 
-            combined_inner_product(&evaluation_points, &v, &u, &es, index.srs().g.len())
+            combined_inner_product(&evaluation_points, &v, &u, &es, index.max_poly_size)
         };
 
         let oracles = RandomOracles {
@@ -434,8 +492,98 @@ where
     }
 }
 
-fn to_batch<'a, G, EFqSponge, EFrSponge>(
+/// A verifier index prepared for the verification of many proofs, with the data that
+/// the verification of each proof would otherwise recompute: the digest of the index,
+/// absorbed at the start of the transcript of every proof, and the commitments
+/// to the Lagrange bases of its SRS, with which the public inputs are committed to.
+///
+/// The digest depends on the Fq-sponge, so a prepared index can only be used
+/// to verify proofs with the sponge `EFqSponge` it was prepared with.
+/// See [verify_prepared] and [batch_verify_prepared].
+pub struct PreparedVerifierIndex<'a, G: KimchiCurve, EFqSponge> {
+    /// The verifier index
+    index: &'a VerifierIndex<G>,
+    /// The digest of the verifier index, with the sponge `EFqSponge`
+    digest: G::BaseField,
+    /// The commitments to the Lagrange bases of the domain of the index
+    lagrange_bases: &'a [PolyComm<G>],
+    sponge: PhantomData<EFqSponge>,
+}
+
+impl<'a, G, EFqSponge> PreparedVerifierIndex<'a, G, EFqSponge>
+where
+    G: KimchiCurve,
+    G::BaseField: PrimeField,
+    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+{
+    /// Prepares `index` for the verification of many proofs,
+    /// computing its digest and the values it computes lazily
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the SRS of the index has no commitments to the Lagrange bases of its domain.
+    pub fn new(index: &'a VerifierIndex<G>) -> Self {
+        // the values computed lazily by the index are computed once, before the proofs
+        index.zkpm();
+        index.w();
+
+        let lagrange_bases = index
+            .srs()
+            .lagrange_bases
+            .get(&index.domain.size())
+            .expect("pre-computed committed lagrange bases not found");
+
+        PreparedVerifierIndex {
+            index,
+            digest: index.digest::<EFqSponge>(),
+            lagrange_bases,
+            sponge: PhantomData,
+        }
+    }
+
+    /// The verifier index
+    pub fn index(&self) -> &'a VerifierIndex<G> {
+        self.index
+    }
+}
+
+/// Commits to the negated public input polynomial of `public`,
+/// with the commitments to the Lagrange bases of the SRS of `index`.
+pub(crate) fn public_commitment<G: KimchiCurve>(
     index: &VerifierIndex<G>,
+    public: &[G::ScalarField],
+) -> PolyComm<G>
+where
+    G::BaseField: PrimeField,
+{
+    let lgr_comm = index
+        .srs()
+        .lagrange_bases
+        .get(&index.domain.size())
+        .expect("pre-computed committed lagrange bases not found");
+    commit_public(lgr_comm, public)
+}
+
+/// Commits to the negated public input polynomial of `public`,
+/// with the commitments to the Lagrange bases `lgr_comm`
+fn commit_public<G: KimchiCurve>(
+    lgr_comm: &[PolyComm<G>],
+    public: &[G::ScalarField],
+) -> PolyComm<G> {
+    let com: Vec<_> = lgr_comm.iter().take(public.len()).collect();
+    let elm: Vec<_> = public.iter().map(|s| -*s).collect();
+    let mut public_comm = PolyComm::<G>::multi_scalar_mul(&com, &elm);
+    // the chunks of zero coefficients are committed to as the point at infinity
+    while public_comm.unshifted.len() > 1
+        && public_comm.unshifted.last().map_or(false, |c| c.is_zero())
+    {
+        public_comm.unshifted.pop();
+    }
+    public_comm
+}
+
+fn to_batch<'a, G, EFqSponge, EFrSponge>(
+    prepared: &PreparedVerifierIndex<G, EFqSponge>,
     proof: &'a ProverProof<G>,
 ) -> Result<BatchEvaluationProof<'a, G, EFqSponge>>
 where
@@ -451,6 +599,7 @@ where
     //~ This allows us to potentially batch verify a number of partially verified proofs.
     //~ Essentially, this steps verifies that $f(\zeta) = t(\zeta) * Z_H(\zeta)$.
     //~
+    let index = prepared.index;
 
     if proof.prev_challenges.len() != index.prev_challenges {
         return Err(VerifyError::IncorrectPrevChallengesLength(
@@ -460,25 +609,13 @@ where
     }
 
     //~ 1. Commit to the negated public input polynomial.
-    let lgr_comm = index
-        .srs()
-        .lagrange_bases
-        .get(&index.domain.size())
-        .expect("pre-computed committed lagrange bases not found");
-    let com: Vec<_> = lgr_comm
-        .iter()
-        .map(|c| PolyComm {
-            unshifted: vec![*c],
-            shifted: None,
-        })
-        .take(index.public)
-        .collect();
-    let com_ref: Vec<_> = com.iter().collect();
+    //~    When the domain is larger than the SRS, the commitment is chunked,
+    //~    and its trailing chunks that commit to zero coefficients are removed
+    //~    (like the prover does when it commits to the polynomial in coefficient form).
     if proof.public.len() != index.public {
         return Err(VerifyError::IncorrectPubicInputLength(index.public));
     }
-    let elm: Vec<_> = proof.public.iter().map(|s| -*s).collect();
-    let public_comm = PolyComm::<G>::multi_scalar_mul(&com_ref, &elm);
+    let public_comm = commit_public(prepared.lagrange_bases, &proof.public);
 
     //~ 1. Run the [Fiat-Shamir argument](#fiat-shamir-argument).
     let OraclesResult {
@@ -491,15 +628,18 @@ where
         zeta1: zeta_to_domain_size,
         ft_eval0,
         ..
-    } = proof.oracles::<EFqSponge, EFrSponge>(index, &public_comm)?;
+    } = proof.oracles_with_digest::<EFqSponge, EFrSponge>(index, prepared.digest, &public_comm)?;
 
     //~ 1. Combine the chunked polynomials' evaluations
     //~    (TODO: most likely only the quotient polynomial is chunked)
-    //~    with the right powers of $\zeta^n$ and $(\zeta * \omega)^n$.
-    let evals = vec![
-        proof.evals[0].combine(powers_of_eval_points_for_chunks[0]),
-        proof.evals[1].combine(powers_of_eval_points_for_chunks[1]),
-    ];
+    //~    with the right powers of $\zeta^n$ and $(\zeta * \omega)^n$
+    //~    (and $(\zeta / \omega)^n$ if the gates of the circuit read the previous row).
+    let chunked_evals: Vec<_> = proof.chunked_evals().collect();
+    let evals: Vec<_> = chunked_evals
+        .iter()
+        .zip(powers_of_eval_points_for_chunks.iter())
+        .map(|(e, pt)| e.combine(*pt))
+        .collect();
 
     //~ 4. Compute the commitment to the linearized polynomial $f$.
     //~    To do this, add the constraints of all of the gates, of the permutation,
@@ -554,6 +694,7 @@ where
                 endo_coefficient: index.endo,
                 mds: &G::sponge_params().mds,
                 foreign_field_modulus: index.foreign_field_modulus.clone(),
+                zk_rows: index.zk_rows,
             };
 
             for (col, tokens) in &index.linearization.index_terms {
@@ -629,10 +770,19 @@ where
                             Zero | Generic | Lookup => {
                                 panic!("Selector for {:?} not defined", t)
                             }
-                            CompleteAdd => &index.complete_add_comm,
-                            VarBaseMul => &index.mul_comm,
-                            EndoMul => &index.emul_comm,
-                            EndoMulScalar => &index.endomul_scalar_comm,
+                            // the selectors absent from the index are zero, as are their terms
+                            CompleteAdd | VarBaseMul | EndoMul | EndoMulScalar => {
+                                let comm = match t {
+                                    CompleteAdd => &index.complete_add_comm,
+                                    VarBaseMul => &index.mul_comm,
+                                    EndoMul => &index.emul_comm,
+                                    _ => &index.endomul_scalar_comm,
+                                };
+                                match comm {
+                                    Some(comm) => comm,
+                                    None => continue,
+                                }
+                            }
                             Poseidon => &index.psm_comm,
                             ChaCha0 => &index.chacha_comm.as_ref().unwrap()[0],
                             ChaCha1 => &index.chacha_comm.as_ref().unwrap()[1],
@@ -644,6 +794,17 @@ where
                             RangeCheck0 => &index.range_check_comm.as_ref().unwrap()[0],
                             RangeCheck1 => &index.range_check_comm.as_ref().unwrap()[1],
                             ForeignFieldAdd => index.foreign_field_add_comm.as_ref().unwrap(),
+                            Sha256Word | Sha256SmallSigma0 | Sha256SmallSigma1
+                            | Sha256BigSigma0 | Sha256BigSigma1 | Sha256Ch | Sha256Maj
+                            | Sha256Spread => &index.sha256_comm.as_ref().unwrap()
+                                [sha256::gadget::circuit_gate_selector_index(*t)],
+                            Custom0 | Custom1 | Custom2 | Custom3 | Custom4 | Custom5 | Custom6
+                            | Custom7 => t
+                                .custom_slot()
+                                .and_then(|slot| index.custom_gates_comm.get(slot))
+                                .unwrap_or_else(|| {
+                                    panic!("Selector for {:?} not found in the verifier index", t)
+                                }),
                         };
                         scalars.push(scalar);
                         commitments.push(c);
@@ -679,29 +840,28 @@ where
     //~~ - public input commitment
     evaluations.push(Evaluation {
         commitment: public_comm,
-        evaluations: public_evals.to_vec(),
+        evaluations: public_evals,
         degree_bound: None,
     });
 
     //~~ - ft commitment (chunks of it)
     evaluations.push(Evaluation {
         commitment: ft_comm,
-        evaluations: vec![vec![ft_eval0], vec![proof.ft_eval1]],
+        evaluations: proof.ft_evals(ft_eval0),
         degree_bound: None,
     });
 
     //~~ - permutation commitment
     evaluations.push(Evaluation {
         commitment: proof.commitments.z_comm.clone(),
-        evaluations: proof.evals.iter().map(|e| e.z.clone()).collect(),
+        evaluations: chunked_evals.iter().map(|e| e.z.clone()).collect(),
         degree_bound: None,
     });
 
     //~~ - index commitments that use the coefficients
     evaluations.push(Evaluation {
         commitment: index.generic_comm.clone(),
-        evaluations: proof
-            .evals
+        evaluations: chunked_evals
             .iter()
             .map(|e| e.generic_selector.clone())
             .collect(),
@@ -709,8 +869,7 @@ where
     });
     evaluations.push(Evaluation {
         commitment: index.psm_comm.clone(),
-        evaluations: proof
-            .evals
+        evaluations: chunked_evals
             .iter()
             .map(|e| e.poseidon_selector.clone())
             .collect(),
@@ -726,8 +885,7 @@ where
             .zip(
                 (0..COLUMNS)
                     .map(|i| {
-                        proof
-                            .evals
+                        chunked_evals
                             .iter()
                             .map(|e| e.w[i].clone())
                             .collect::<Vec<_>>()
@@ -749,8 +907,7 @@ where
             .zip(
                 (0..PERMUTS - 1)
                     .map(|i| {
-                        proof
-                            .evals
+                        chunked_evals
                             .iter()
                             .map(|e| e.s[i].clone())
                             .collect::<Vec<_>>()
@@ -771,30 +928,22 @@ where
             .lookup
             .as_ref()
             .ok_or(VerifyError::LookupCommitmentMissing)?;
-        let lookup_eval0 = proof.evals[0]
-            .lookup
-            .as_ref()
-            .ok_or(VerifyError::LookupEvalsMissing)?;
-        let lookup_eval1 = proof.evals[1]
-            .lookup
-            .as_ref()
-            .ok_or(VerifyError::LookupEvalsMissing)?;
+        let lookup_evals = chunked_evals
+            .iter()
+            .map(|e| e.lookup.as_ref().ok_or(VerifyError::LookupEvalsMissing))
+            .collect::<Result<Vec<_>>>()?;
 
         // check that the there's as many evals as commitments for sorted polynomials
         let sorted_len = lookup_comms.sorted.len();
-        if sorted_len != lookup_eval0.sorted.len() || sorted_len != lookup_eval1.sorted.len() {
+        if lookup_evals.iter().any(|l| l.sorted.len() != sorted_len) {
             return Err(VerifyError::ProofInconsistentLookup);
         }
 
         // add evaluations of sorted polynomials
-        for (comm, evals0, evals1) in izip!(
-            &lookup_comms.sorted,
-            lookup_eval0.sorted.clone(),
-            lookup_eval1.sorted.clone()
-        ) {
+        for (i, comm) in lookup_comms.sorted.iter().enumerate() {
             evaluations.push(Evaluation {
                 commitment: comm.clone(),
-                evaluations: vec![evals0, evals1],
+                evaluations: lookup_evals.iter().map(|l| l.sorted[i].clone()).collect(),
                 degree_bound: None,
             });
         }
@@ -802,7 +951,7 @@ where
         // add evaluations of the aggreg polynomial
         evaluations.push(Evaluation {
             commitment: lookup_comms.aggreg.clone(),
-            evaluations: vec![lookup_eval0.aggreg.clone(), lookup_eval1.aggreg.clone()],
+            evaluations: lookup_evals.iter().map(|l| l.aggreg.clone()).collect(),
             degree_bound: None,
         });
 
@@ -827,7 +976,7 @@ where
         // add evaluation of the table polynomial
         evaluations.push(Evaluation {
             commitment: table_comm,
-            evaluations: vec![lookup_eval0.table.clone(), lookup_eval1.table.clone()],
+            evaluations: lookup_evals.iter().map(|l| l.table.clone()).collect(),
             degree_bound: None,
         });
 
@@ -837,27 +986,21 @@ where
                 .runtime
                 .as_ref()
                 .ok_or(VerifyError::IncorrectRuntimeProof)?;
-            let runtime_eval0 = lookup_eval0
-                .runtime
-                .as_ref()
-                .cloned()
-                .ok_or(VerifyError::IncorrectRuntimeProof)?;
-            let runtime_eval1 = lookup_eval1
-                .runtime
-                .as_ref()
-                .cloned()
-                .ok_or(VerifyError::IncorrectRuntimeProof)?;
+            let runtime_evals = lookup_evals
+                .iter()
+                .map(|l| l.runtime.clone().ok_or(VerifyError::IncorrectRuntimeProof))
+                .collect::<Result<Vec<_>>>()?;
 
             evaluations.push(Evaluation {
                 commitment: runtime.clone(),
-                evaluations: vec![runtime_eval0, runtime_eval1],
+                evaluations: runtime_evals,
                 degree_bound: None,
             });
         }
     }
 
     // prepare for the opening proof verification
-    let evaluation_points = vec![oracles.zeta, oracles.zeta * index.domain.group_gen];
+    let evaluation_points = index.evaluation_points(oracles.zeta);
     Ok(BatchEvaluationProof {
         sponge: fq_sponge,
         evaluations,
@@ -884,8 +1027,93 @@ where
     EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
     EFrSponge: FrSponge<G::ScalarField>,
 {
-    let proofs = vec![(verifier_index, proof)];
-    batch_verify::<G, EFqSponge, EFrSponge>(group_map, &proofs)
+    let entry = BatchEntry::new(verifier_index, proof);
+    aggregate_verify::<G, EFqSponge, EFrSponge>(group_map, &[entry])
+}
+
+/// Same as [verify], returning the public outputs of the proof on success,
+/// which are the last [VerifierIndex::public_output] of its public values.
+///
+/// # Errors
+///
+/// Will give error if `proof` is not verified as valid.
+pub fn verify_with_public_output<'a, G, EFqSponge, EFrSponge>(
+    group_map: &G::Map,
+    verifier_index: &VerifierIndex<G>,
+    proof: &'a ProverProof<G>,
+) -> Result<&'a [G::ScalarField]>
+where
+    G: KimchiCurve,
+    G::BaseField: PrimeField,
+    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+    EFrSponge: FrSponge<G::ScalarField>,
+{
+    let start = verifier_index
+        .public
+        .checked_sub(verifier_index.public_output)
+        .ok_or(VerifyError::IncorrectPublicOutputLength(
+            verifier_index.public_output,
+        ))?;
+    verify::<G, EFqSponge, EFrSponge>(group_map, verifier_index, proof)?;
+
+    // the length of the public values was checked against the index
+    Ok(&proof.public[start..])
+}
+
+/// Verify a proof encoded with [ProverProof::compress], using a [`VerifierIndex`] and a `group_map`.
+///
+/// # Errors
+///
+/// Will give error if the proof cannot be decoded, or if it is not verified as valid.
+pub fn verify_compressed<G, EFqSponge, EFrSponge>(
+    group_map: &G::Map,
+    verifier_index: &VerifierIndex<G>,
+    compressed_proof: &[u8],
+) -> Result<()>
+where
+    G: KimchiCurve,
+    G::BaseField: PrimeField,
+    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+    EFrSponge: FrSponge<G::ScalarField>,
+{
+    let proof = ProverProof::decompress(compressed_proof)
+        .map_err(|_| VerifyError::MalformedCompressedProof)?;
+    verify::<G, EFqSponge, EFrSponge>(group_map, verifier_index, &proof)
+}
+
+/// A proof in a batch of proofs to verify together, with the verifier index of its circuit.
+///
+/// The proofs of a batch can be for different circuits, as long as their verifier indexes
+/// share the same SRS. See [aggregate_verify].
+pub struct BatchEntry<'a, G: KimchiCurve> {
+    /// The verifier index of the circuit of the proof
+    pub verifier_index: &'a VerifierIndex<G>,
+    /// The proof to verify
+    pub proof: &'a ProverProof<G>,
+}
+
+impl<'a, G: KimchiCurve> BatchEntry<'a, G> {
+    /// Creates an entry verifying `proof` with `verifier_index`
+    pub fn new(verifier_index: &'a VerifierIndex<G>, proof: &'a ProverProof<G>) -> Self {
+        BatchEntry {
+            verifier_index,
+            proof,
+        }
+    }
+}
+
+impl<'a, G: KimchiCurve> Clone for BatchEntry<'a, G> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, G: KimchiCurve> Copy for BatchEntry<'a, G> {}
+
+impl<'a, G: KimchiCurve> From<(&'a VerifierIndex<G>, &'a ProverProof<G>)> for BatchEntry<'a, G> {
+    fn from((verifier_index, proof): (&'a VerifierIndex<G>, &'a ProverProof<G>)) -> Self {
+        BatchEntry::new(verifier_index, proof)
+    }
 }
 
 /// This function verifies the batch of zk-proofs
@@ -893,6 +1121,8 @@ where
 ///     index: `VerifierIndex`
 ///     RETURN: verification status
 ///
+/// Same as [aggregate_verify], with the proofs given as pairs of references.
+///
 /// # Errors
 ///
 /// Will give error if `srs` of `proof` is invalid or `verify` process fails.
@@ -900,6 +1130,31 @@ pub fn batch_verify<G, EFqSponge, EFrSponge>(
     group_map: &G::Map,
     proofs: &[(&VerifierIndex<G>, &ProverProof<G>)],
 ) -> Result<()>
+where
+    G: KimchiCurve,
+    G::BaseField: PrimeField,
+    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+    EFrSponge: FrSponge<G::ScalarField>,
+{
+    let entries: Vec<_> = proofs.iter().copied().map(BatchEntry::from).collect();
+    aggregate_verify::<G, EFqSponge, EFrSponge>(group_map, &entries)
+}
+
+/// Verifies a batch of proofs, possibly for different circuits, with a single
+/// opening check: the partial verifications of all the proofs are aggregated into one
+/// batch, whose opening proofs are checked with one multi-scalar multiplication.
+///
+/// Each distinct verifier index of the batch is prepared once
+/// (see [PreparedVerifierIndex]), however many proofs it verifies.
+///
+/// # Errors
+///
+/// Will give error if the verifier indexes do not have an SRS of the same length,
+/// or if one of the proofs is not verified as valid.
+pub fn aggregate_verify<G, EFqSponge, EFrSponge>(
+    group_map: &G::Map,
+    entries: &[BatchEntry<G>],
+) -> Result<()>
 where
     G: KimchiCurve,
     G::BaseField: PrimeField,
@@ -910,32 +1165,27 @@ where
     //~
     //~ Below, we define the steps to verify a number of proofs
     //~ (each associated to a [verifier index](#verifier-index)).
+    //~ The proofs can be for different circuits, as long as their verifier indexes share the same URS.
     //~ You can, of course, use it to verify a single proof.
     //~
 
     //~ 1. If there's no proof to verify, the proof validates trivially.
-    if proofs.is_empty() {
+    if entries.is_empty() {
         return Ok(());
     }
 
     //~ 1. Ensure that all the proof's verifier index have a URS of the same length. (TODO: do they have to be the same URS though? should we check for that?)
-    // TODO: Account for the different SRS lengths
-    let srs = &proofs[0].0.srs();
-    for (index, _) in proofs.iter() {
-        if index.srs().g.len() != srs.g.len() {
-            return Err(VerifyError::DifferentSRS);
-        }
+    check_batch_srs(entries)?;
+    let srs = entries[0].verifier_index.srs();
 
-        // also make sure that the SRS is not smaller than the domain size
-        if index.srs().max_degree() < index.domain.size() {
-            return Err(VerifyError::SRSTooSmall);
-        }
-    }
+    //~ 1. Prepare each distinct verifier index once, computing its digest.
+    let prepared = prepare_batch::<G, EFqSponge>(entries);
 
     //~ 1. Validate each proof separately following the [partial verification](#partial-verification) steps.
     let mut batch = vec![];
-    for (index, proof) in proofs {
-        batch.push(to_batch::<G, EFqSponge, EFrSponge>(index, proof)?);
+    for entry in entries {
+        let prepared = find_prepared(&prepared, entry.verifier_index);
+        batch.push(to_batch::<G, EFqSponge, EFrSponge>(prepared, entry.proof)?);
     }
 
     //~ 1. Use the [`PolyCom.verify`](#polynomial-commitments) to verify the partially evaluated proofs.
@@ -945,3 +1195,173 @@ where
         Err(VerifyError::OpenProof)
     }
 }
+
+/// Verify a proof [`ProverProof`] using a [`PreparedVerifierIndex`] and a `group_map`.
+///
+/// # Errors
+///
+/// Will give error if `proof` is not verified as valid.
+pub fn verify_prepared<G, EFqSponge, EFrSponge>(
+    group_map: &G::Map,
+    prepared: &PreparedVerifierIndex<G, EFqSponge>,
+    proof: &ProverProof<G>,
+) -> Result<()>
+where
+    G: KimchiCurve,
+    G::BaseField: PrimeField,
+    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+    EFrSponge: FrSponge<G::ScalarField>,
+{
+    batch_verify_prepared::<G, EFqSponge, EFrSponge>(
+        group_map,
+        prepared,
+        std::slice::from_ref(proof),
+    )
+}
+
+/// Same as [batch_verify], for a batch of proofs of the same verifier index,
+/// prepared once with [PreparedVerifierIndex::new] rather than for every proof.
+///
+/// # Errors
+///
+/// Will give error if one of the `proofs` is not verified as valid.
+pub fn batch_verify_prepared<G, EFqSponge, EFrSponge>(
+    group_map: &G::Map,
+    prepared: &PreparedVerifierIndex<G, EFqSponge>,
+    proofs: &[ProverProof<G>],
+) -> Result<()>
+where
+    G: KimchiCurve,
+    G::BaseField: PrimeField,
+    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+    EFrSponge: FrSponge<G::ScalarField>,
+{
+    if proofs.is_empty() {
+        return Ok(());
+    }
+
+    let mut batch = vec![];
+    for proof in proofs {
+        batch.push(to_batch::<G, EFqSponge, EFrSponge>(prepared, proof)?);
+    }
+
+    let srs = prepared.index.srs();
+    if srs.verify::<EFqSponge, _>(group_map, &mut batch, &mut thread_rng()) {
+        Ok(())
+    } else {
+        Err(VerifyError::OpenProof)
+    }
+}
+
+/// Prepares each distinct verifier index of a batch once, however many proofs it verifies.
+/// The indexes are compared by address, as comparing their contents would cost more than
+/// preparing them again.
+fn prepare_batch<'a, G, EFqSponge>(
+    entries: &[BatchEntry<'a, G>],
+) -> Vec<PreparedVerifierIndex<'a, G, EFqSponge>>
+where
+    G: KimchiCurve,
+    G::BaseField: PrimeField,
+    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+{
+    let mut prepared: Vec<PreparedVerifierIndex<G, EFqSponge>> = vec![];
+    for entry in entries {
+        if !prepared
+            .iter()
+            .any(|p| std::ptr::eq(p.index, entry.verifier_index))
+        {
+            prepared.push(PreparedVerifierIndex::new(entry.verifier_index));
+        }
+    }
+    prepared
+}
+
+/// The prepared index of `index`, among the indexes prepared by [prepare_batch]
+fn find_prepared<'p, 'a, G: KimchiCurve, EFqSponge>(
+    prepared: &'p [PreparedVerifierIndex<'a, G, EFqSponge>],
+    index: &VerifierIndex<G>,
+) -> &'p PreparedVerifierIndex<'a, G, EFqSponge> {
+    prepared
+        .iter()
+        .find(|p| std::ptr::eq(p.index, index))
+        .expect("every verifier index of the batch is prepared")
+}
+
+/// Checks that all the verifier indexes of a batch have an SRS of the same length.
+/// The SRS can be smaller than the domains, in which case the commitments are chunked.
+fn check_batch_srs<G: KimchiCurve>(entries: &[BatchEntry<G>]) -> Result<()>
+where
+    G::BaseField: PrimeField,
+{
+    // TODO: Account for the different SRS lengths
+    let srs = entries[0].verifier_index.srs();
+    for entry in entries {
+        if entry.verifier_index.srs().g.len() != srs.g.len() {
+            return Err(VerifyError::DifferentSRS);
+        }
+    }
+    Ok(())
+}
+
+/// Same as [batch_verify], but with the work spread over the threads of the rayon thread pool.
+///
+/// The partial verification of the proofs (the derivation of their oracles and the checks of
+/// their evaluations) is done in parallel, then the batch is split into one chunk per thread,
+/// whose terms of the final multi-scalar multiplication are computed in parallel.
+/// The terms of all the chunks are then merged, and the whole batch is checked
+/// with a single multi-scalar multiplication, as in [batch_verify].
+/// This is meant for large batches: for a handful of proofs, [batch_verify] is as fast.
+///
+/// # Errors
+///
+/// Will give error if `srs` of `proof` is invalid or `verify` process fails.
+/// When several proofs are invalid, the error of any of them may be returned.
+pub fn verify_batch_parallel<G, EFqSponge, EFrSponge>(
+    group_map: &G::Map,
+    proofs: &[(&VerifierIndex<G>, &ProverProof<G>)],
+) -> Result<()>
+where
+    G: KimchiCurve,
+    G::BaseField: PrimeField,
+    G::Map: Sync,
+    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField> + Send + Sync,
+    EFrSponge: FrSponge<G::ScalarField>,
+{
+    if proofs.is_empty() {
+        return Ok(());
+    }
+
+    let entries: Vec<_> = proofs.iter().copied().map(BatchEntry::from).collect();
+    check_batch_srs(&entries)?;
+    let srs = proofs[0].0.srs();
+
+    // each distinct verifier index is prepared once, before the parallel verifications
+    let prepared = prepare_batch::<G, EFqSponge>(&entries);
+    let mut batch = entries
+        .par_iter()
+        .map(|entry| {
+            let prepared = find_prepared(&prepared, entry.verifier_index);
+            to_batch::<G, EFqSponge, EFrSponge>(prepared, entry.proof)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    // the proofs of all the chunks are scaled by the powers of the same random bases,
+    // as if they were checked in a single batch
+    let mut rng = thread_rng();
+    let rand_base = G::ScalarField::rand(&mut rng);
+    let sg_rand_base = G::ScalarField::rand(&mut rng);
+
+    let threads = parallel::current_num_threads();
+    let chunk_size = (batch.len() + threads - 1) / threads;
+    let msm = batch
+        .par_chunks_mut(chunk_size)
+        .enumerate()
+        .map(|(i, chunk)| srs.batch_msm(group_map, chunk, rand_base, sg_rand_base, i * chunk_size))
+        .collect::<Option<Vec<_>>>()
+        .and_then(|msms| msms.into_iter().reduce(BatchMsm::merge));
+    if msm.map_or(false, |msm| msm.check()) {
+        Ok(())
+    } else {
+        Err(VerifyError::OpenProof)
+    }
+}
@@ -1,40 +1,72 @@
 //! This module implements the verifier index as [`VerifierIndex`].
-//! You can derive this struct from the [`ProverIndex`] struct.
+//! You can derive this struct from the [`ProverIndex`](crate::prover_index::ProverIndex) struct.
 
 use crate::{
     alphas::Alphas,
     circuits::{
+        constraints::zk_default,
         expr::{Linearization, PolishToken},
         lookup::{index::LookupSelectors, lookups::LookupsUsed},
         polynomials::{
-            permutation::{zk_polynomial, zk_w3},
-            range_check,
+            permutation::{zk_polynomial, zk_w3, ZK_ROWS},
+            range_check, sha256,
         },
         wires::{COLUMNS, PERMUTS},
     },
     curve::KimchiCurve,
     error::VerifierIndexError,
-    prover_index::ProverIndex,
 };
-use ark_ff::{One, PrimeField};
-use ark_poly::{univariate::DensePolynomial, Radix2EvaluationDomain as D};
+use ark_ec::AffineCurve;
+use ark_ff::{One, PrimeField, Zero};
+use ark_poly::{univariate::DensePolynomial, EvaluationDomain, Radix2EvaluationDomain as D};
+use ark_serialize::SerializationError;
 use commitment_dlog::{
     commitment::{CommitmentCurve, PolyComm},
     srs::SRS,
 };
 use num_bigint::BigUint;
 use once_cell::sync::OnceCell;
-use oracle::FqSponge;
+use oracle::{
+    constants::PlonkSpongeConstantsKimchi,
+    poseidon::{ArithmeticSponge, Sponge},
+    FqSponge,
+};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_with::serde_as;
+#[cfg(feature = "prover")]
 use std::array;
 use std::{
     fs::{File, OpenOptions},
-    io::{BufReader, BufWriter, Seek, SeekFrom::Start},
+    io::{BufRead, BufReader, BufWriter, Read, Seek, SeekFrom::Start, Write},
     path::Path,
     sync::Arc,
 };
 
+/// The version of the format written by [VerifierIndex::to_file].
+///
+/// - version 1 is the original format, which was not prefixed by a header,
+///   and which did not contain the number of zero-knowledge rows (always 3 at the time),
+/// - version 2 adds the number of zero-knowledge rows,
+/// - version 3 adds the commitments to the selectors of the SHA-256 gates,
+/// - version 4 adds the commitments to the selectors of the custom gates,
+/// - version 5 adds the number of public outputs,
+/// - version 6 adds the boolean columns,
+/// - version 7 adds the zero-knowledge mode,
+/// - version 8 omits the commitments to the selectors of the elliptic curve gates
+///   absent from the circuit.
+///
+/// The fields added by the versions 3 to 7 are appended to the serialized index,
+/// and take their default value when reading an index of an earlier version.
+pub const VERIFIER_INDEX_VERSION: u32 = 8;
+
+/// The oldest version of the format that [VerifierIndex::from_file] still accepts.
+pub const MIN_VERIFIER_INDEX_VERSION: u32 = 1;
+
+/// The bytes starting the header of a serialized [VerifierIndex],
+/// followed by the version of the format as a big-endian `u32`.
+/// A serialized index without this header is of version 1.
+pub const VERIFIER_INDEX_MAGIC: &[u8; 4] = b"KVIX";
+
 //~spec:startcode
 #[serde_as]
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -75,6 +107,8 @@ pub struct VerifierIndex<G: KimchiCurve> {
     pub public: usize,
     /// number of previous evaluation challenges, for recursive proving
     pub prev_challenges: usize,
+    /// number of rows at the end of the domain reserved for zero-knowledge
+    pub zk_rows: u64,
 
     // index polynomial commitments
     /// permutation commitment array
@@ -92,19 +126,20 @@ pub struct VerifierIndex<G: KimchiCurve> {
     #[serde(bound = "PolyComm<G>: Serialize + DeserializeOwned")]
     pub psm_comm: PolyComm<G>,
 
-    // ECC arithmetic polynomial commitments
+    // ECC arithmetic polynomial commitments, absent when the circuit has no such gate
+    // (their selectors are then zero)
     /// EC addition selector polynomial commitment
-    #[serde(bound = "PolyComm<G>: Serialize + DeserializeOwned")]
-    pub complete_add_comm: PolyComm<G>,
+    #[serde(bound = "Option<PolyComm<G>>: Serialize + DeserializeOwned")]
+    pub complete_add_comm: Option<PolyComm<G>>,
     /// EC variable base scalar multiplication selector polynomial commitment
-    #[serde(bound = "PolyComm<G>: Serialize + DeserializeOwned")]
-    pub mul_comm: PolyComm<G>,
+    #[serde(bound = "Option<PolyComm<G>>: Serialize + DeserializeOwned")]
+    pub mul_comm: Option<PolyComm<G>>,
     /// endoscalar multiplication selector polynomial commitment
-    #[serde(bound = "PolyComm<G>: Serialize + DeserializeOwned")]
-    pub emul_comm: PolyComm<G>,
+    #[serde(bound = "Option<PolyComm<G>>: Serialize + DeserializeOwned")]
+    pub emul_comm: Option<PolyComm<G>>,
     /// endoscalar multiplication scalar computation selector polynomial commitment
-    #[serde(bound = "PolyComm<G>: Serialize + DeserializeOwned")]
-    pub endomul_scalar_comm: PolyComm<G>,
+    #[serde(bound = "Option<PolyComm<G>>: Serialize + DeserializeOwned")]
+    pub endomul_scalar_comm: Option<PolyComm<G>>,
 
     /// Chacha polynomial commitments
     #[serde(bound = "PolyComm<G>: Serialize + DeserializeOwned")]
@@ -142,11 +177,185 @@ pub struct VerifierIndex<G: KimchiCurve> {
     /// The mapping between powers of alpha and constraints
     #[serde(skip)]
     pub powers_of_alpha: Alphas<G::ScalarField>,
+
+    // SHA-256 gates polynomial commitments
+    // (serialized last, so that indexes serialized before their introduction can still be read)
+    #[serde(default)]
+    #[serde(bound = "PolyComm<G>: Serialize + DeserializeOwned")]
+    pub sha256_comm: Option<[PolyComm<G>; sha256::gadget::GATE_COUNT]>,
+
+    // Custom gates polynomial commitments, in the order of the registration of the custom gates
+    #[serde(default)]
+    #[serde(bound = "PolyComm<G>: Serialize + DeserializeOwned")]
+    pub custom_gates_comm: Vec<PolyComm<G>>,
+
+    /// number of public outputs, which are the last of the `public` public values
+    #[serde(default)]
+    pub public_output: usize,
+
+    /// witness columns whose cells are all checked to be boolean
+    #[serde(default)]
+    pub boolean_columns: Vec<usize>,
+
+    /// whether the proofs are zero-knowledge
+    /// (see [ConstraintSystem::zk](crate::circuits::constraints::ConstraintSystem::zk))
+    #[serde(default = "zk_default")]
+    pub zk: bool,
 }
 //~spec:endcode
 
-impl<G: KimchiCurve> ProverIndex<G> {
-    /// Produces the [`VerifierIndex`] from the prover's [`ProverIndex`].
+/// The version 1 of the serialized [VerifierIndex],
+/// which did not contain the number of zero-knowledge rows.
+#[serde_as]
+#[derive(Serialize, Deserialize)]
+pub(crate) struct VerifierIndexV1<G: KimchiCurve> {
+    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
+    domain: D<G::ScalarField>,
+    max_poly_size: usize,
+    max_quot_size: usize,
+    public: usize,
+    prev_challenges: usize,
+    #[serde(bound = "PolyComm<G>: Serialize + DeserializeOwned")]
+    sigma_comm: [PolyComm<G>; PERMUTS],
+    #[serde(bound = "PolyComm<G>: Serialize + DeserializeOwned")]
+    coefficients_comm: [PolyComm<G>; COLUMNS],
+    #[serde(bound = "PolyComm<G>: Serialize + DeserializeOwned")]
+    generic_comm: PolyComm<G>,
+    #[serde(bound = "PolyComm<G>: Serialize + DeserializeOwned")]
+    psm_comm: PolyComm<G>,
+    #[serde(bound = "PolyComm<G>: Serialize + DeserializeOwned")]
+    complete_add_comm: PolyComm<G>,
+    #[serde(bound = "PolyComm<G>: Serialize + DeserializeOwned")]
+    mul_comm: PolyComm<G>,
+    #[serde(bound = "PolyComm<G>: Serialize + DeserializeOwned")]
+    emul_comm: PolyComm<G>,
+    #[serde(bound = "PolyComm<G>: Serialize + DeserializeOwned")]
+    endomul_scalar_comm: PolyComm<G>,
+    #[serde(bound = "PolyComm<G>: Serialize + DeserializeOwned")]
+    chacha_comm: Option<[PolyComm<G>; 4]>,
+    #[serde(bound = "PolyComm<G>: Serialize + DeserializeOwned")]
+    range_check_comm: Option<[PolyComm<G>; range_check::gadget::GATE_COUNT]>,
+    foreign_field_modulus: Option<BigUint>,
+    #[serde(bound = "Option<PolyComm<G>>: Serialize + DeserializeOwned")]
+    foreign_field_add_comm: Option<PolyComm<G>>,
+    #[serde_as(as = "[o1_utils::serialization::SerdeAs; PERMUTS]")]
+    shift: [G::ScalarField; PERMUTS],
+    #[serde(bound = "PolyComm<G>: Serialize + DeserializeOwned")]
+    lookup_index: Option<LookupVerifierIndex<G>>,
+}
+
+/// The commitment to the selector of a gate absent from the circuit,
+/// which is the commitment to the zero polynomial, in `num_chunks` chunks
+fn zero_selector_comm<G: AffineCurve>(num_chunks: usize) -> PolyComm<G> {
+    PolyComm {
+        unshifted: vec![G::zero(); num_chunks],
+        shifted: None,
+    }
+}
+
+/// The commitment `comm` to a selector, or `None` if it is the commitment to the zero polynomial
+fn selector_comm<G: AffineCurve>(comm: PolyComm<G>) -> Option<PolyComm<G>> {
+    if comm.unshifted.iter().all(Zero::is_zero) {
+        None
+    } else {
+        Some(comm)
+    }
+}
+
+impl<G: KimchiCurve> From<VerifierIndexV1<G>> for VerifierIndex<G> {
+    fn from(index: VerifierIndexV1<G>) -> Self {
+        let VerifierIndexV1 {
+            domain,
+            max_poly_size,
+            max_quot_size,
+            public,
+            prev_challenges,
+            sigma_comm,
+            coefficients_comm,
+            generic_comm,
+            psm_comm,
+            complete_add_comm,
+            mul_comm,
+            emul_comm,
+            endomul_scalar_comm,
+            chacha_comm,
+            range_check_comm,
+            foreign_field_modulus,
+            foreign_field_add_comm,
+            shift,
+            lookup_index,
+        } = index;
+
+        VerifierIndex {
+            domain,
+            max_poly_size,
+            max_quot_size,
+            srs: OnceCell::new(),
+            public,
+            prev_challenges,
+            // the number of zero-knowledge rows was not configurable before version 2
+            zk_rows: ZK_ROWS,
+            sigma_comm,
+            coefficients_comm,
+            generic_comm,
+            psm_comm,
+            complete_add_comm: selector_comm(complete_add_comm),
+            mul_comm: selector_comm(mul_comm),
+            emul_comm: selector_comm(emul_comm),
+            endomul_scalar_comm: selector_comm(endomul_scalar_comm),
+            chacha_comm,
+            range_check_comm,
+            foreign_field_modulus,
+            foreign_field_add_comm,
+            shift,
+            zkpm: OnceCell::new(),
+            w: OnceCell::new(),
+            endo: G::ScalarField::zero(),
+            lookup_index,
+            linearization: Linearization::default(),
+            powers_of_alpha: Alphas::default(),
+            sha256_comm: None,
+            custom_gates_comm: vec![],
+            public_output: 0,
+            boolean_columns: vec![],
+            zk: true,
+        }
+    }
+}
+
+#[cfg(test)]
+impl<G: KimchiCurve> From<&VerifierIndex<G>> for VerifierIndexV1<G> {
+    fn from(index: &VerifierIndex<G>) -> Self {
+        let num_chunks = index.num_chunks();
+        let zero_selector_comm = || zero_selector_comm(num_chunks);
+        let index = index.clone();
+        VerifierIndexV1 {
+            domain: index.domain,
+            max_poly_size: index.max_poly_size,
+            max_quot_size: index.max_quot_size,
+            public: index.public,
+            prev_challenges: index.prev_challenges,
+            sigma_comm: index.sigma_comm,
+            coefficients_comm: index.coefficients_comm,
+            generic_comm: index.generic_comm,
+            psm_comm: index.psm_comm,
+            complete_add_comm: index.complete_add_comm.unwrap_or_else(zero_selector_comm),
+            mul_comm: index.mul_comm.unwrap_or_else(zero_selector_comm),
+            emul_comm: index.emul_comm.unwrap_or_else(zero_selector_comm),
+            endomul_scalar_comm: index.endomul_scalar_comm.unwrap_or_else(zero_selector_comm),
+            chacha_comm: index.chacha_comm,
+            range_check_comm: index.range_check_comm,
+            foreign_field_modulus: index.foreign_field_modulus,
+            foreign_field_add_comm: index.foreign_field_add_comm,
+            shift: index.shift,
+            lookup_index: index.lookup_index,
+        }
+    }
+}
+
+#[cfg(feature = "prover")]
+impl<G: KimchiCurve> crate::prover_index::ProverIndex<G> {
+    /// Produces the [`VerifierIndex`] from the prover's [`ProverIndex`](crate::prover_index::ProverIndex).
     ///
     /// # Panics
     ///
@@ -166,6 +375,15 @@ impl<G: KimchiCurve> ProverIndex<G> {
 
         let domain = self.cs.domain.d1;
 
+        // the selectors of the gates absent from the circuit are zero, and not committed to
+        let commit_selector = |evals: &ark_poly::Evaluations<G::ScalarField, D<G::ScalarField>>| {
+            if evals.evals.iter().all(Zero::is_zero) {
+                None
+            } else {
+                Some(self.srs.commit_evaluations_non_hiding(domain, evals, None))
+            }
+        };
+
         let lookup_index = {
             self.cs
                 .lookup_constraint_system
@@ -200,7 +418,11 @@ impl<G: KimchiCurve> ProverIndex<G> {
             max_quot_size: self.max_quot_size,
             powers_of_alpha: self.powers_of_alpha.clone(),
             public: self.cs.public,
+            public_output: self.cs.public_output,
+            boolean_columns: self.cs.boolean_columns.clone(),
             prev_challenges: self.cs.prev_challenges,
+            zk_rows: self.cs.zk_rows,
+            zk: self.cs.zk,
             srs: {
                 let cell = OnceCell::new();
                 cell.set(Arc::clone(&self.srs)).unwrap();
@@ -216,23 +438,10 @@ impl<G: KimchiCurve> ProverIndex<G> {
 
             psm_comm: mask_fixed(self.srs.commit_non_hiding(&self.cs.psm, None)),
 
-            complete_add_comm: self.srs.commit_evaluations_non_hiding(
-                domain,
-                &self.cs.complete_addl4,
-                None,
-            ),
-            mul_comm: self
-                .srs
-                .commit_evaluations_non_hiding(domain, &self.cs.mull8, None),
-            emul_comm: self
-                .srs
-                .commit_evaluations_non_hiding(domain, &self.cs.emull, None),
-
-            endomul_scalar_comm: self.srs.commit_evaluations_non_hiding(
-                domain,
-                &self.cs.endomul_scalar8,
-                None,
-            ),
+            complete_add_comm: commit_selector(&self.cs.complete_addl4),
+            mul_comm: commit_selector(&self.cs.mull8),
+            emul_comm: commit_selector(&self.cs.emull),
+            endomul_scalar_comm: commit_selector(&self.cs.endomul_scalar8),
 
             chacha_comm: self.cs.chacha8.as_ref().map(|c| {
                 array::from_fn(|i| self.srs.commit_evaluations_non_hiding(domain, &c[i], None))
@@ -262,13 +471,30 @@ impl<G: KimchiCurve> ProverIndex<G> {
             },
             w: {
                 let cell = OnceCell::new();
-                cell.set(zk_w3(self.cs.domain.d1)).unwrap();
+                cell.set(zk_w3(self.cs.domain.d1, self.cs.zk_rows)).unwrap();
                 cell
             },
             endo: self.cs.endo,
             lookup_index,
             linearization: self.linearization.clone(),
             foreign_field_modulus: self.cs.foreign_field_modulus.clone(),
+
+            sha256_comm: self.cs.sha256_selector_polys.as_ref().map(|poly| {
+                array::from_fn(|i| {
+                    self.srs
+                        .commit_evaluations_non_hiding(domain, &poly[i].eval8, None)
+                })
+            }),
+
+            custom_gates_comm: self
+                .cs
+                .custom_selector_polys
+                .iter()
+                .map(|poly| {
+                    self.srs
+                        .commit_evaluations_non_hiding(domain, &poly.eval8, None)
+                })
+                .collect(),
         }
     }
 }
@@ -286,21 +512,40 @@ impl<G: KimchiCurve> VerifierIndex<G> {
         })
     }
 
+    /// The number of chunks of the commitments to polynomials over the domain,
+    /// which is more than one when the domain is larger than the SRS.
+    pub fn num_chunks(&self) -> usize {
+        (self.domain.size() + self.max_poly_size - 1) / self.max_poly_size
+    }
+
+    /// The points at which the polynomials of a proof are opened, given the challenge `zeta`:
+    /// `zeta` and `zeta * omega`, and `zeta / omega` if the gates of the circuit read the previous row
+    pub fn evaluation_points(&self, zeta: G::ScalarField) -> Vec<G::ScalarField> {
+        let mut points = vec![zeta, zeta * self.domain.group_gen];
+        if self.linearization.reads_prev_row() {
+            points.push(zeta * self.domain.group_gen_inv);
+        }
+        points
+    }
+
     /// Gets zkpm from [`VerifierIndex`] lazily
     pub fn zkpm(&self) -> &DensePolynomial<G::ScalarField> {
-        self.zkpm.get_or_init(|| zk_polynomial(self.domain))
+        self.zkpm
+            .get_or_init(|| zk_polynomial(self.domain, self.zk_rows))
     }
 
     /// Gets w from [`VerifierIndex`] lazily
     pub fn w(&self) -> &G::ScalarField {
-        self.w.get_or_init(|| zk_w3(self.domain))
+        self.w.get_or_init(|| zk_w3(self.domain, self.zk_rows))
     }
 
     /// Deserializes a [`VerifierIndex`] from a file, given a pointer to an SRS and an optional offset in the file.
+    /// All the versions of the format from [MIN_VERIFIER_INDEX_VERSION] to [VERIFIER_INDEX_VERSION] are accepted.
     ///
     /// # Errors
     ///
-    /// Will give error if it fails to deserialize from file or unable to set `srs` in `verifier_index`.
+    /// Will give error if it fails to deserialize from file, if the version of the format is not supported,
+    /// or if it is unable to set `srs` in `verifier_index`.
     pub fn from_file(
         srs: Option<Arc<SRS<G>>>,
         path: &Path,
@@ -317,9 +562,43 @@ impl<G: KimchiCurve> VerifierIndex<G> {
             reader.seek(Start(offset)).map_err(|e| e.to_string())?;
         }
 
+        // version of the format (an index without header is of version 1)
+        let version = if reader
+            .fill_buf()
+            .map_err(|e| e.to_string())?
+            .starts_with(VERIFIER_INDEX_MAGIC)
+        {
+            let mut header = [0u8; 8];
+            reader.read_exact(&mut header).map_err(|e| e.to_string())?;
+            u32::from_be_bytes([header[4], header[5], header[6], header[7]])
+        } else {
+            1
+        };
+
         // deserialize
-        let mut verifier_index = Self::deserialize(&mut rmp_serde::Deserializer::new(reader))
-            .map_err(|e| e.to_string())?;
+        let mut verifier_index = match version {
+            1 => VerifierIndexV1::deserialize(&mut rmp_serde::Deserializer::new(reader))
+                .map(Into::into),
+            // the fields appended after version 2 are read with their default value when absent
+            2..=VERIFIER_INDEX_VERSION => {
+                Self::deserialize(&mut rmp_serde::Deserializer::new(reader))
+            }
+            _ => return Err(VerifierIndexError::UnsupportedVersion(version).to_string()),
+        }
+        .map_err(|e| e.to_string())?;
+
+        // the commitments to the selectors of the elliptic curve gates are all present
+        // before version 8, including the commitments to zero of the absent gates
+        if version < 8 {
+            for comm in [
+                &mut verifier_index.complete_add_comm,
+                &mut verifier_index.mul_comm,
+                &mut verifier_index.emul_comm,
+                &mut verifier_index.endomul_scalar_comm,
+            ] {
+                *comm = comm.take().and_then(selector_comm);
+            }
+        }
 
         // fill in the rest
         if let Some(srs) = srs {
@@ -335,6 +614,7 @@ impl<G: KimchiCurve> VerifierIndex<G> {
     }
 
     /// Writes a [`VerifierIndex`] to a file, potentially appending it to the already-existing content (if append is set to true)
+    /// The index is prefixed by a header containing the version [VERIFIER_INDEX_VERSION] of the format.
     // TODO: append should be a bool, not an option
     /// # Errors
     ///
@@ -350,7 +630,12 @@ impl<G: KimchiCurve> VerifierIndex<G> {
             .open(path)
             .map_err(|e| e.to_string())?;
 
-        let writer = BufWriter::new(file);
+        let mut writer = BufWriter::new(file);
+
+        writer
+            .write_all(VERIFIER_INDEX_MAGIC)
+            .and_then(|_| writer.write_all(&VERIFIER_INDEX_VERSION.to_be_bytes()))
+            .map_err(|e| e.to_string())?;
 
         self.serialize(&mut rmp_serde::Serializer::new(writer))
             .map_err(|e| e.to_string())
@@ -369,7 +654,11 @@ impl<G: KimchiCurve> VerifierIndex<G> {
             max_quot_size: _,
             srs: _,
             public: _,
+            public_output: _,
+            boolean_columns: _,
             prev_challenges: _,
+            zk_rows: _,
+            zk,
 
             // Always present
             sigma_comm,
@@ -386,6 +675,10 @@ impl<G: KimchiCurve> VerifierIndex<G> {
             range_check_comm,
             foreign_field_add_comm,
             foreign_field_modulus: _,
+            sha256_comm,
+
+            // Custom gates
+            custom_gates_comm,
 
             // Lookup index; optional
             lookup_index,
@@ -409,10 +702,15 @@ impl<G: KimchiCurve> VerifierIndex<G> {
         }
         fq_sponge.absorb_g(&generic_comm.unshifted);
         fq_sponge.absorb_g(&psm_comm.unshifted);
-        fq_sponge.absorb_g(&complete_add_comm.unshifted);
-        fq_sponge.absorb_g(&mul_comm.unshifted);
-        fq_sponge.absorb_g(&emul_comm.unshifted);
-        fq_sponge.absorb_g(&endomul_scalar_comm.unshifted);
+        // the absent selectors are absorbed as the commitment to zero they stand for,
+        // so that the digest does not depend on whether they are committed to
+        let num_chunks = self.num_chunks();
+        for comm in [complete_add_comm, mul_comm, emul_comm, endomul_scalar_comm] {
+            match comm {
+                Some(comm) => fq_sponge.absorb_g(&comm.unshifted),
+                None => fq_sponge.absorb_g(&zero_selector_comm::<G>(num_chunks).unshifted),
+            }
+        }
 
         // Optional gates
 
@@ -429,6 +727,17 @@ impl<G: KimchiCurve> VerifierIndex<G> {
         if let Some(foreign_field_add_comm) = foreign_field_add_comm {
             fq_sponge.absorb_g(&foreign_field_add_comm.unshifted);
         }
+        if let Some(sha256_comm) = sha256_comm {
+            for sha256_comm in sha256_comm {
+                fq_sponge.absorb_g(&sha256_comm.unshifted);
+            }
+        }
+
+        // Custom gates
+
+        for custom_gate_comm in custom_gates_comm {
+            fq_sponge.absorb_g(&custom_gate_comm.unshifted);
+        }
 
         // Lookup index; optional
 
@@ -444,6 +753,7 @@ impl<G: KimchiCurve> VerifierIndex<G> {
                     chacha_final,
                     lookup_gate,
                     range_check_gate,
+                    sha256,
                 },
 
             max_joint_size: _,
@@ -471,7 +781,58 @@ impl<G: KimchiCurve> VerifierIndex<G> {
             if let Some(range_check_gate) = range_check_gate {
                 fq_sponge.absorb_g(&range_check_gate.unshifted);
             }
+            if let Some(sha256) = sha256 {
+                fq_sponge.absorb_g(&sha256.unshifted);
+            }
+        }
+
+        // The mode is only absorbed without zero-knowledge,
+        // so that the digests of the zero-knowledge indexes are unchanged
+        if !zk {
+            fq_sponge.absorb_fq(&[G::BaseField::one()]);
         }
         fq_sponge.digest_fq()
     }
+
+    /// Computes a canonical digest of the [`VerifierIndex`], identifying its circuit,
+    /// for example to bind proofs to a specific version of a circuit on-chain
+    /// or in a recursive verifier.
+    ///
+    /// Unlike [`VerifierIndex::digest`], which only absorbs the commitments of the index
+    /// for the Fiat-Shamir transformation, it covers all the parameters of the circuit:
+    /// it is the Poseidon hash, over the base field, of the compressed encoding of the index
+    /// (see [`VerifierIndex::compress`]) followed by an encoding of its linearization.
+    /// As this encoding, it does not depend on the SRS,
+    /// and it changes with [`crate::proof_compression::COMPRESSED_VERIFIER_INDEX_VERSION`].
+    ///
+    /// The linearization covers the constraints of the gates, including those of the custom gates
+    /// (see [`crate::circuits::custom_gate`]), whose selectors alone do not identify the circuit.
+    /// It is computed in a deterministic order, so that the digest is the same for the same circuit.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the index cannot be encoded (see [`VerifierIndex::compress`]),
+    /// or if its linearization is missing, as after deserialization.
+    pub fn circuit_digest(&self) -> Result<G::BaseField, SerializationError>
+    where
+        G::BaseField: PrimeField,
+    {
+        if self.linearization.index_terms.is_empty() {
+            return Err(SerializationError::InvalidData);
+        }
+        let mut bytes = self.compress()?;
+        bytes.extend(self.compress_linearization()?);
+
+        // the bytes are packed in field elements of fewer bits than the modulus,
+        // after the number of bytes
+        let chunk_size = (G::BaseField::size_in_bits() - 1) / 8;
+        let mut sponge = ArithmeticSponge::<G::BaseField, PlonkSpongeConstantsKimchi>::new(
+            G::OtherCurve::sponge_params(),
+        );
+        sponge.absorb(&[G::BaseField::from(bytes.len() as u64)]);
+        for chunk in bytes.chunks(chunk_size) {
+            sponge.absorb(&[G::BaseField::from_le_bytes_mod_order(chunk)]);
+        }
+        Ok(sponge.squeeze())
+    }
 }
@@ -3,6 +3,7 @@ pub mod pasta;
 pub mod permutation;
 pub mod poseidon;
 pub mod sponge;
+pub mod validation;
 
 #[cfg(test)]
 mod tests;
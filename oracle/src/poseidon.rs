@@ -2,7 +2,8 @@
 
 use crate::constants::SpongeConstants;
 use crate::permutation::{full_round, poseidon_block_cipher};
-use ark_ff::Field;
+use crate::validation::{validate, SpongeParamsError};
+use ark_ff::{Field, PrimeField};
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
@@ -41,6 +42,37 @@ pub struct ArithmeticSpongeParams<F: Field> {
     pub mds: Vec<Vec<F>>,
 }
 
+impl<F: PrimeField> ArithmeticSpongeParams<F> {
+    /// Creates the parameters of the permutation given by `SC`,
+    /// rejecting them if they are not sound (see [crate::validation]).
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the parameters do not pass [ArithmeticSpongeParams::validate].
+    pub fn new<SC: SpongeConstants>(
+        round_constants: Vec<Vec<F>>,
+        mds: Vec<Vec<F>>,
+    ) -> Result<Self, SpongeParamsError> {
+        let params = ArithmeticSpongeParams {
+            round_constants,
+            mds,
+        };
+        params.validate::<SC>()?;
+        Ok(params)
+    }
+
+    /// Checks that these parameters are sound for the permutation given by `SC`:
+    /// that they match its round split, that its S-box and MDS matrix are sound,
+    /// and that it has enough rounds against the known attacks.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if any of these checks fails.
+    pub fn validate<SC: SpongeConstants>(&self) -> Result<(), SpongeParamsError> {
+        validate::<F, SC>(self)
+    }
+}
+
 #[derive(Clone)]
 pub struct ArithmeticSponge<F: Field, SC: SpongeConstants> {
     pub sponge_state: SpongeState,
@@ -1,10 +1,13 @@
 use crate::{
-    constants::{PlonkSpongeConstantsKimchi, PlonkSpongeConstantsLegacy},
+    constants::{PlonkSpongeConstantsKimchi, PlonkSpongeConstantsLegacy, SpongeConstants},
     pasta::fp_kimchi as SpongeParametersKimchi,
     pasta::fp_legacy as SpongeParametersLegacy,
+    pasta::{fp_kimchi, fp_legacy, fq_kimchi, fq_legacy},
     poseidon::ArithmeticSponge as Poseidon,
-    poseidon::Sponge as _,
+    poseidon::{ArithmeticSpongeParams, Sponge as _},
+    validation::SpongeParamsError,
 };
+use ark_ff::{One, Zero};
 use mina_curves::pasta::Fp;
 use o1_utils::FieldHelpers;
 use serde::Deserialize;
@@ -81,3 +84,115 @@ fn poseidon_test_vectors_kimchi() {
     }
     test_vectors("kimchi.json", hash);
 }
+
+#[test]
+fn poseidon_params_validation() {
+    fp_kimchi::static_params()
+        .validate::<PlonkSpongeConstantsKimchi>()
+        .unwrap();
+    fq_kimchi::static_params()
+        .validate::<PlonkSpongeConstantsKimchi>()
+        .unwrap();
+    fp_legacy::static_params()
+        .validate::<PlonkSpongeConstantsLegacy>()
+        .unwrap();
+    fq_legacy::static_params()
+        .validate::<PlonkSpongeConstantsLegacy>()
+        .unwrap();
+}
+
+#[test]
+fn poseidon_params_validation_rejects_unsound_params() {
+    macro_rules! sponge_constants {
+        ($name:ident, $full:expr, $partial:expr, $half:expr, $sbox:expr) => {
+            struct $name;
+
+            impl SpongeConstants for $name {
+                const PERM_ROUNDS_FULL: usize = $full;
+                const PERM_ROUNDS_PARTIAL: usize = $partial;
+                const PERM_HALF_ROUNDS_FULL: usize = $half;
+                const PERM_SBOX: u32 = $sbox;
+                const PERM_FULL_MDS: bool = true;
+                const PERM_INITIAL_ARK: bool = false;
+            }
+        };
+    }
+    sponge_constants!(UnusedPartialRounds, 55, 10, 0, 7);
+    sponge_constants!(UnevenFullRounds, 55, 10, 4, 7);
+    sponge_constants!(CubicSbox, 55, 0, 0, 3);
+    sponge_constants!(FewRounds, 20, 0, 0, 7);
+
+    let kimchi = fp_kimchi::params();
+    let params = |round_constants: &[Vec<Fp>], mds: Vec<Vec<Fp>>| ArithmeticSpongeParams {
+        round_constants: round_constants.to_vec(),
+        mds,
+    };
+    let validate =
+        |params: ArithmeticSpongeParams<Fp>| params.validate::<PlonkSpongeConstantsKimchi>();
+
+    // round split
+    assert_eq!(
+        kimchi.validate::<UnusedPartialRounds>(),
+        Err(SpongeParamsError::UnusedPartialRounds(10))
+    );
+    assert_eq!(
+        kimchi.validate::<UnevenFullRounds>(),
+        Err(SpongeParamsError::UnevenFullRounds { full: 55, half: 4 })
+    );
+    assert_eq!(
+        validate(params(&kimchi.round_constants[..54], kimchi.mds.clone())),
+        Err(SpongeParamsError::RoundConstants {
+            expected: 55,
+            width: 3
+        })
+    );
+
+    // S-box, as 3 divides p - 1
+    assert_eq!(
+        kimchi.validate::<CubicSbox>(),
+        Err(SpongeParamsError::Sbox(3))
+    );
+
+    // MDS matrix
+    let (zero, one) = (Fp::zero(), Fp::one());
+    assert_eq!(
+        validate(params(&kimchi.round_constants, vec![vec![one; 2]; 2])),
+        Err(SpongeParamsError::MdsShape(3))
+    );
+    assert_eq!(
+        validate(params(&kimchi.round_constants, vec![vec![one; 3]; 3])),
+        Err(SpongeParamsError::SingularMds)
+    );
+    let identity = vec![
+        vec![one, zero, zero],
+        vec![zero, one, zero],
+        vec![zero, zero, one],
+    ];
+    assert_eq!(
+        validate(params(&kimchi.round_constants, identity)),
+        Err(SpongeParamsError::MdsEigenvalue)
+    );
+
+    // security margin
+    assert_eq!(
+        kimchi.validate::<FewRounds>(),
+        Err(SpongeParamsError::Rounds {
+            found: 20,
+            required: 49
+        })
+    );
+
+    // the checked constructor
+    assert!(ArithmeticSpongeParams::new::<PlonkSpongeConstantsKimchi>(
+        kimchi.round_constants.clone(),
+        kimchi.mds.clone()
+    )
+    .is_ok());
+    assert_eq!(
+        ArithmeticSpongeParams::new::<FewRounds>(kimchi.round_constants, kimchi.mds).err(),
+        Some(SpongeParamsError::Rounds {
+            found: 20,
+            required: 49
+        })
+    );
+}
@@ -0,0 +1,314 @@
+//! This module implements the validation of Poseidon parameters,
+//! against the round split given by their [SpongeConstants] and the known attacks on Poseidon.
+//!
+//! The bounds on the number of rounds are the ones of section 5.5 of the
+//! [Poseidon paper](https://eprint.iacr.org/2019/458.pdf), for a security level of [SECURITY_LEVEL] bits,
+//! to which [ROUNDS_MARGIN] rounds are added as a security margin.
+
+use crate::{constants::SpongeConstants, poseidon::ArithmeticSpongeParams};
+use ark_ff::{BigInteger, FpParameters, PrimeField};
+use thiserror::Error;
+
+/// The security level, in bits, targeted by the parameters
+pub const SECURITY_LEVEL: u32 = 128;
+
+/// The number of rounds added to the bounds of the attacks
+pub const ROUNDS_MARGIN: usize = 2;
+
+/// Errors of the validation of Poseidon parameters
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum SpongeParamsError {
+    #[error("the rate {rate} and the capacity {capacity} do not make up the width {width}")]
+    Width {
+        width: usize,
+        rate: usize,
+        capacity: usize,
+    },
+
+    #[error(
+        "{0} partial rounds are configured without half full rounds, so they are never applied"
+    )]
+    UnusedPartialRounds(usize),
+
+    #[error("the {full} full rounds are not split in two halves of {half} rounds")]
+    UnevenFullRounds { full: usize, half: usize },
+
+    #[error("expected at least {expected} rows of {width} round constants")]
+    RoundConstants { expected: usize, width: usize },
+
+    #[error("the MDS matrix is not a {0}x{0} matrix")]
+    MdsShape(usize),
+
+    #[error("the S-box x^{0} is not a non-linear permutation of the field")]
+    Sbox(u32),
+
+    #[error("the MDS matrix is not invertible")]
+    SingularMds,
+
+    #[error("the MDS matrix has an eigenvalue in the field")]
+    MdsEigenvalue,
+
+    #[error("{found} full rounds, below the {required} required against statistical attacks")]
+    FullRounds { found: usize, required: usize },
+
+    #[error("{found} rounds, below the {required} required against algebraic attacks")]
+    Rounds { found: usize, required: usize },
+}
+
+/// Checks that the parameters `params` are sound for the permutation given by `SC`.
+///
+/// # Errors
+///
+/// Will give error if the round split of `SC` is inconsistent, if `params` do not match it,
+/// or if the permutation is not secure.
+pub fn validate<F: PrimeField, SC: SpongeConstants>(
+    params: &ArithmeticSpongeParams<F>,
+) -> Result<(), SpongeParamsError> {
+    let width = SC::SPONGE_WIDTH;
+    if SC::SPONGE_RATE == 0
+        || SC::SPONGE_CAPACITY == 0
+        || SC::SPONGE_RATE + SC::SPONGE_CAPACITY != width
+    {
+        return Err(SpongeParamsError::Width {
+            width,
+            rate: SC::SPONGE_RATE,
+            capacity: SC::SPONGE_CAPACITY,
+        });
+    }
+
+    // the round split, as implemented by [crate::permutation::poseidon_block_cipher]
+    let full = SC::PERM_ROUNDS_FULL;
+    let partial = SC::PERM_ROUNDS_PARTIAL;
+    let half = SC::PERM_HALF_ROUNDS_FULL;
+    let rounds = if half == 0 {
+        if partial != 0 {
+            return Err(SpongeParamsError::UnusedPartialRounds(partial));
+        }
+        full + usize::from(SC::PERM_INITIAL_ARK)
+    } else {
+        if full != 2 * half {
+            return Err(SpongeParamsError::UnevenFullRounds { full, half });
+        }
+        full + partial
+    };
+
+    if params.round_constants.len() < rounds
+        || params.round_constants.iter().any(|row| row.len() != width)
+    {
+        return Err(SpongeParamsError::RoundConstants {
+            expected: rounds,
+            width,
+        });
+    }
+
+    // the MDS matrix actually applied, see [crate::permutation::apply_mds_matrix]
+    let mds = if SC::PERM_FULL_MDS {
+        if params.mds.len() != width || params.mds.iter().any(|row| row.len() != width) {
+            return Err(SpongeParamsError::MdsShape(width));
+        }
+        params.mds.clone()
+    } else {
+        if width != 3 {
+            return Err(SpongeParamsError::MdsShape(width));
+        }
+        let (zero, one) = (F::zero(), F::one());
+        vec![
+            vec![one, zero, one],
+            vec![one, one, zero],
+            vec![zero, one, one],
+        ]
+    };
+
+    validate_sbox::<F>(SC::PERM_SBOX)?;
+    validate_mds(&mds)?;
+    validate_rounds::<F>(SC::PERM_SBOX, width, full, partial)
+}
+
+/// The S-box $x^\alpha$ is a non-linear permutation iff $\alpha > 1$ and $\gcd(\alpha, p - 1) = 1$.
+fn validate_sbox<F: PrimeField>(alpha: u32) -> Result<(), SpongeParamsError> {
+    if alpha < 3 {
+        return Err(SpongeParamsError::Sbox(alpha));
+    }
+
+    // p - 1 must not be divisible by any prime factor of alpha
+    let mut rest = alpha;
+    let mut factor = 2;
+    while rest > 1 {
+        if rest % factor == 0 {
+            if modulus_minus_one_mod::<F>(factor) == 0 {
+                return Err(SpongeParamsError::Sbox(alpha));
+            }
+            while rest % factor == 0 {
+                rest /= factor;
+            }
+        }
+        factor += 1;
+    }
+
+    Ok(())
+}
+
+/// Computes $(p - 1) \bmod q$
+fn modulus_minus_one_mod<F: PrimeField>(q: u32) -> u64 {
+    let q = u128::from(q);
+    let p_mod_q = F::Params::MODULUS
+        .as_ref()
+        .iter()
+        .rev()
+        .fold(0u128, |acc, limb| ((acc << 64) + u128::from(*limb)) % q);
+    ((p_mod_q + q - 1) % q) as u64
+}
+
+/// The MDS matrix must be invertible, and must have no eigenvalue in the field.
+///
+/// For a width of 3, the characteristic polynomial of the matrix is then irreducible,
+/// so that no subspace is invariant under the matrix,
+/// which rules out the invariant subspace trails through the partial rounds.
+fn validate_mds<F: PrimeField>(mds: &[Vec<F>]) -> Result<(), SpongeParamsError> {
+    let charpoly = characteristic_polynomial(mds);
+
+    // the determinant is the constant term of the characteristic polynomial, up to sign
+    if charpoly[0].is_zero() {
+        return Err(SpongeParamsError::SingularMds);
+    }
+
+    // the eigenvalues are the roots of the characteristic polynomial,
+    // which are shared with $x^p - x$
+    let mut x_to_p = vec![F::one()];
+    for bit in F::Params::MODULUS.to_bits_be() {
+        x_to_p = mul_mod(&x_to_p, &x_to_p, &charpoly);
+        if bit {
+            x_to_p = mul_mod(&x_to_p, &[F::zero(), F::one()], &charpoly);
+        }
+    }
+    x_to_p.resize(std::cmp::max(x_to_p.len(), 2), F::zero());
+    x_to_p[1] -= F::one();
+
+    if degree(&gcd(charpoly, x_to_p)) > 0 {
+        return Err(SpongeParamsError::MdsEigenvalue);
+    }
+
+    Ok(())
+}
+
+/// The number of rounds must be above the bounds of the statistical and algebraic attacks,
+/// plus the security margin.
+fn validate_rounds<F: PrimeField>(
+    alpha: u32,
+    width: usize,
+    full: usize,
+    partial: usize,
+) -> Result<(), SpongeParamsError> {
+    let alpha = f64::from(alpha);
+    let log2_p = f64::from(F::Params::MODULUS_BITS - 1);
+    let security = f64::from(SECURITY_LEVEL);
+    let t = width as f64;
+    let log_alpha_2 = 1. / alpha.log2();
+
+    // statistical attacks
+    let statistical = if security <= (log2_p - (alpha - 1.).log2()) * (t + 1.) {
+        6
+    } else {
+        10
+    };
+    if full < statistical + ROUNDS_MARGIN {
+        return Err(SpongeParamsError::FullRounds {
+            found: full,
+            required: statistical + ROUNDS_MARGIN,
+        });
+    }
+
+    // interpolation and Gröbner basis attacks
+    let interpolation = log_alpha_2 * security.min(log2_p) + t.ln() / alpha.ln();
+    let groebner_1 = log_alpha_2 * (security / 3.).min(log2_p / 2.);
+    let groebner_2 = t - 1. + log_alpha_2 * (security / (t + 1.)).min(log2_p / 2.);
+    let algebraic = interpolation.max(groebner_1).max(groebner_2).ceil() as usize;
+    if full + partial < algebraic + ROUNDS_MARGIN {
+        return Err(SpongeParamsError::Rounds {
+            found: full + partial,
+            required: algebraic + ROUNDS_MARGIN,
+        });
+    }
+
+    Ok(())
+}
+
+//
+// Helpers on square matrices and polynomials, the latter given by their coefficients
+// from the lowest degree
+//
+
+/// Computes the characteristic polynomial $\det(x I - M)$ of `matrix` with the Faddeev-LeVerrier
+/// algorithm, which is fine for the small matrices of Poseidon.
+fn characteristic_polynomial<F: PrimeField>(matrix: &[Vec<F>]) -> Vec<F> {
+    let n = matrix.len();
+    let mul = |a: &[Vec<F>], b: &[Vec<F>]| -> Vec<Vec<F>> {
+        (0..n)
+            .map(|i| {
+                (0..n)
+                    .map(|j| (0..n).fold(F::zero(), |acc, k| acc + a[i][k] * b[k][j]))
+                    .collect()
+            })
+            .collect()
+    };
+
+    let mut coeffs = vec![F::zero(); n + 1];
+    coeffs[n] = F::one();
+    let mut m = vec![vec![F::zero(); n]; n];
+    for k in 1..=n {
+        // M_k = A M_{k-1} + c_{n-k+1} I
+        m = mul(matrix, &m);
+        for (i, row) in m.iter_mut().enumerate() {
+            row[i] += coeffs[n - k + 1];
+        }
+        // c_{n-k} = -tr(A M_k) / k
+        let am = mul(matrix, &m);
+        let trace = (0..n).fold(F::zero(), |acc, i| acc + am[i][i]);
+        coeffs[n - k] = -trace / F::from(k as u64);
+    }
+    coeffs
+}
+
+/// The degree of `poly`, with the zero polynomial of degree 0
+fn degree<F: PrimeField>(poly: &[F]) -> usize {
+    poly.iter().rposition(|c| !c.is_zero()).unwrap_or(0)
+}
+
+/// Computes the remainder of `a` by the non-zero polynomial `b`
+fn rem<F: PrimeField>(mut a: Vec<F>, b: &[F]) -> Vec<F> {
+    let db = degree(b);
+    let lead_inv = b[db].inverse().expect("division by the zero polynomial");
+    if db == 0 {
+        return vec![F::zero()];
+    }
+    while degree(&a) >= db {
+        let da = degree(&a);
+        let factor = a[da] * lead_inv;
+        for (i, c) in b[..=db].iter().enumerate() {
+            a[da - db + i] -= factor * c;
+        }
+    }
+    a.truncate(db);
+    a
+}
+
+/// Computes $a b \bmod m$
+fn mul_mod<F: PrimeField>(a: &[F], b: &[F], m: &[F]) -> Vec<F> {
+    let mut res = vec![F::zero(); a.len() + b.len() - 1];
+    for (i, x) in a.iter().enumerate() {
+        for (j, y) in b.iter().enumerate() {
+            res[i + j] += *x * y;
+        }
+    }
+    rem(res, m)
+}
+
+/// Computes a greatest common divisor of `a` and `b`
+fn gcd<F: PrimeField>(mut a: Vec<F>, mut b: Vec<F>) -> Vec<F> {
+    while !b.iter().all(F::is_zero) {
+        let r = rem(a, &b);
+        a = b;
+        b = r;
+    }
+    a
+}
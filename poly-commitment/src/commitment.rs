@@ -6,7 +6,10 @@
 //!     producing the batched opening proof
 //! 3. Verify batch of batched opening proofs
 
-use crate::{error::CommitmentError, srs::SRS};
+use crate::{
+    error::CommitmentError,
+    srs::{GroupMapKind, SRS},
+};
 use ark_ec::{
     models::short_weierstrass_jacobian::GroupAffine as SWJAffine, msm::VariableBaseMSM,
     AffineCurve, ProjectiveCurve, SWModelParameters,
@@ -324,6 +327,8 @@ pub fn squeeze_challenge<
 pub trait CommitmentCurve: AffineCurve {
     type Params: SWModelParameters;
     type Map: GroupMap<Self::BaseField>;
+    /// The kind of [CommitmentCurve::Map], recorded in the SRS
+    const GROUP_MAP: GroupMapKind;
 
     fn to_coordinates(&self) -> Option<(Self::BaseField, Self::BaseField)>;
     fn of_coordinates(x: Self::BaseField, y: Self::BaseField) -> Self;
@@ -360,6 +365,7 @@ where
 {
     type Params = P;
     type Map = BWParameters<P>;
+    const GROUP_MAP: GroupMapKind = GroupMapKind::SvdW;
 
     fn to_coordinates(&self) -> Option<(Self::BaseField, Self::BaseField)> {
         if self.infinity {
@@ -419,7 +425,12 @@ pub fn combined_inner_product<F: PrimeField>(
     let mut res = F::zero();
     let mut xi_i = F::one();
 
-    for (evals_tr, shifted) in polys.iter().filter(|(evals_tr, _)| !evals_tr[0].is_empty()) {
+    // polynomials without evaluations (either because there are no evaluation points,
+    // or because the polynomial has no chunk) do not contribute to the combined inner product
+    for (evals_tr, shifted) in polys
+        .iter()
+        .filter(|(evals_tr, _)| evals_tr.first().map_or(false, |e| !e.is_empty()))
+    {
         // transpose the evaluations
         let evals = (0..evals_tr[0].len())
             .map(|i| evals_tr.iter().map(|v| v[i]).collect::<Vec<_>>())
@@ -461,7 +472,8 @@ where
     /// The commitment of the polynomial being evaluated
     pub commitment: PolyComm<G>,
 
-    /// Contains an evaluation table
+    /// Contains an evaluation table, with one row per evaluation point
+    /// and one column per chunk of the commitment
     pub evaluations: Vec<Vec<G::ScalarField>>,
 
     /// optional degree bound
@@ -600,6 +612,10 @@ impl<G: CommitmentCurve> SRS<G> {
         PolyComm::<G> { unshifted, shifted }
     }
 
+    /// Commits to a polynomial in evaluation form, using the commitments to the Lagrange bases
+    /// of `domain` (see [SRS::add_lagrange_basis]).
+    /// If the domain is larger than the SRS, the commitment is chunked like [SRS::commit_non_hiding] would,
+    /// without the trailing chunks of zero coefficients.
     pub fn commit_evaluations_non_hiding(
         &self,
         domain: D<G::ScalarField>,
@@ -611,18 +627,48 @@ impl<G: CommitmentCurve> SRS<G> {
             None => panic!("lagrange bases for size {} not found", domain.size()),
             Some(v) => &v[..],
         };
-        match domain.size.cmp(&plnm.domain().size) {
+        let subsampled: Vec<_>;
+        let evals = match domain.size.cmp(&plnm.domain().size) {
             std::cmp::Ordering::Less => {
                 let s = (plnm.domain().size / domain.size) as usize;
-                let v: Vec<_> = (0..(domain.size())).map(|i| plnm.evals[s * i]).collect();
-                Self::commit_helper(&v[..], basis, None, is_zero, max)
-            }
-            std::cmp::Ordering::Equal => {
-                Self::commit_helper(&plnm.evals[..], basis, None, is_zero, max)
+                subsampled = (0..(domain.size())).map(|i| plnm.evals[s * i]).collect();
+                &subsampled[..]
             }
+            std::cmp::Ordering::Equal => &plnm.evals[..],
             std::cmp::Ordering::Greater => {
                 panic!("desired commitment domain size greater than evaluations' domain size")
             }
+        };
+
+        let num_chunks = basis.first().map_or(1, |b| b.unshifted.len());
+        if num_chunks == 1 {
+            let basis: Vec<_> = basis.iter().map(|b| b.unshifted[0]).collect();
+            return Self::commit_helper(evals, &basis, None, is_zero, max);
+        }
+
+        // degree bounds are only supported when the domain fits in the SRS
+        assert!(max.is_none(), "cannot shift a chunked commitment");
+        if is_zero {
+            return PolyComm {
+                unshifted: vec![G::zero()],
+                shifted: None,
+            };
+        }
+        let scalars: Vec<_> = evals.iter().map(|e| e.into_repr()).collect();
+        let mut unshifted: Vec<_> = (0..num_chunks)
+            .into_par_iter()
+            .map(|k| {
+                let basis: Vec<_> = basis.iter().map(|b| b.unshifted[k]).collect();
+                VariableBaseMSM::multi_scalar_mul(&basis, &scalars).into_affine()
+            })
+            .collect();
+        // the chunks of zero coefficients are committed to as the point at infinity
+        while unshifted.len() > 1 && unshifted.last().map_or(false, |c| c.is_zero()) {
+            unshifted.pop();
+        }
+        PolyComm {
+            unshifted,
+            shifted: None,
         }
     }
 
@@ -646,6 +692,10 @@ impl<G: CommitmentCurve> SRS<G> {
     ///     oracle_params: parameters for the random oracle argument
     ///     randomness source context
     ///     RETURN: verification status
+    ///
+    /// A claim is rejected if one of its evaluation tables doesn't have exactly one row per evaluation point.
+    /// Zero commitments, evaluation points in the domain and empty claim lists are accepted,
+    /// see [SRS::open] for their semantics.
     pub fn verify<EFqSponge, RNG>(
         &self,
         group_map: &G::Map,
@@ -656,6 +706,35 @@ impl<G: CommitmentCurve> SRS<G> {
         EFqSponge: FqSponge<G::BaseField, G, G::ScalarField>,
         RNG: RngCore + CryptoRng,
         G::BaseField: PrimeField,
+    {
+        // sample randomiser to scale the proofs with
+        let rand_base = G::ScalarField::rand(rng);
+        let sg_rand_base = G::ScalarField::rand(rng);
+
+        self.batch_msm(group_map, batch, rand_base, sg_rand_base, 0)
+            .map_or(false, |msm| msm.check())
+    }
+
+    /// Computes the terms of the multi-scalar multiplication checking the batch of batched
+    /// opening proofs `batch`, as done by [SRS::verify], the `i`-th proof of the batch being
+    /// scaled by `rand_base^(first + i)`, and the check of its `sg` by `sg_rand_base^(first + i)`.
+    ///
+    /// The terms of the consecutive slices of a batch can thus be computed separately,
+    /// with the same random bases, then merged with [BatchMsm::merge] and checked
+    /// with a single multi-scalar multiplication.
+    ///
+    /// Returns `None` if one of the claims is malformed (see [SRS::verify]).
+    pub fn batch_msm<EFqSponge>(
+        &self,
+        group_map: &G::Map,
+        batch: &mut [BatchEvaluationProof<G, EFqSponge>],
+        rand_base: G::ScalarField,
+        sg_rand_base: G::ScalarField,
+        first: usize,
+    ) -> Option<BatchMsm<G>>
+    where
+        EFqSponge: FqSponge<G::BaseField, G, G::ScalarField>,
+        G::BaseField: PrimeField,
     {
         // Verifier checks for all i,
         // c_i Q_i + delta_i = z1_i (G_i + b_i U_i) + z2_i H
@@ -692,12 +771,19 @@ impl<G: CommitmentCurve> SRS<G> {
         let mut scalars = vec![G::ScalarField::zero(); padded_length + 1];
         assert_eq!(scalars.len(), points.len());
 
-        // sample randomiser to scale the proofs with
-        let rand_base = G::ScalarField::rand(rng);
-        let sg_rand_base = G::ScalarField::rand(rng);
+        let mut rand_base_i = rand_base.pow([first as u64]);
+        let mut sg_rand_base_i = sg_rand_base.pow([first as u64]);
 
-        let mut rand_base_i = G::ScalarField::one();
-        let mut sg_rand_base_i = G::ScalarField::one();
+        // reject malformed claims instead of silently truncating their evaluations
+        let well_formed = batch.iter().all(|proof| {
+            proof
+                .evaluations
+                .iter()
+                .all(|e| e.evaluations.len() == proof.evaluation_points.len())
+        });
+        if !well_formed {
+            return None;
+        }
 
         for BatchEvaluationProof {
             sponge,
@@ -854,9 +940,41 @@ impl<G: CommitmentCurve> SRS<G> {
             sg_rand_base_i *= &sg_rand_base;
         }
 
-        // verify the equation
-        let scalars: Vec<_> = scalars.iter().map(|x| x.into_repr()).collect();
-        VariableBaseMSM::multi_scalar_mul(&points, &scalars) == G::Projective::zero()
+        Some(BatchMsm {
+            points,
+            scalars,
+            shared: padded_length + 1,
+        })
+    }
+}
+
+/// The terms of the multi-scalar multiplication checking a batch of opening proofs,
+/// see [SRS::batch_msm].
+pub struct BatchMsm<G: AffineCurve> {
+    points: Vec<G>,
+    scalars: Vec<G::ScalarField>,
+    /// The number of leading points, `H` and the points of the SRS, shared by all the batches
+    shared: usize,
+}
+
+impl<G: AffineCurve> BatchMsm<G> {
+    /// Merges the terms of two slices of a batch, computed with the same SRS and random bases
+    pub fn merge(mut self, other: BatchMsm<G>) -> BatchMsm<G> {
+        assert_eq!(self.shared, other.shared);
+        for (scalar, other) in self.scalars.iter_mut().zip(&other.scalars[..other.shared]) {
+            *scalar += other;
+        }
+        self.points.extend_from_slice(&other.points[other.shared..]);
+        self.scalars
+            .extend_from_slice(&other.scalars[other.shared..]);
+        self
+    }
+
+    /// Returns `true` if the multi-scalar multiplication vanishes,
+    /// which is the case if all the opening proofs of the batch are valid
+    pub fn check(&self) -> bool {
+        let scalars: Vec<_> = self.scalars.iter().map(|x| x.into_repr()).collect();
+        VariableBaseMSM::multi_scalar_mul(&self.points, &scalars) == G::Projective::zero()
     }
 }
 
@@ -907,12 +1025,42 @@ mod tests {
         let computed_lagrange_commitments = srs.lagrange_bases.get(&domain.size()).unwrap();
         for i in 0..n {
             assert_eq!(
-                computed_lagrange_commitments[i],
-                expected_lagrange_commitments[i]
+                computed_lagrange_commitments[i].unshifted,
+                vec![expected_lagrange_commitments[i]]
             );
         }
     }
 
+    #[test]
+    fn test_chunked_lagrange_commitments() {
+        let n = 64;
+        let domain = D::<Fp>::new(n).unwrap();
+
+        // an SRS a quarter of the size of the domain
+        let mut srs = SRS::<VestaG>::create(n / 4);
+        srs.add_lagrange_basis(domain);
+
+        let computed_lagrange_commitments = srs.lagrange_bases.get(&domain.size()).unwrap();
+        for (i, computed) in computed_lagrange_commitments.iter().enumerate() {
+            let mut e = vec![Fp::zero(); n];
+            e[i] = Fp::one();
+            let p = Evaluations::<Fp, D<Fp>>::from_vec_and_domain(e, domain).interpolate();
+            let expected = srs.commit_non_hiding(&p, None);
+            assert_eq!(expected.unshifted.len(), 4);
+            assert_eq!(computed.unshifted, expected.unshifted);
+        }
+
+        // committing in evaluation form agrees with committing in coefficient form
+        let evals: Vec<_> = (0..n).map(|i| Fp::from(i as u64)).collect();
+        let evals = Evaluations::<Fp, D<Fp>>::from_vec_and_domain(evals, domain);
+        assert_eq!(
+            srs.commit_evaluations_non_hiding(domain, &evals, None)
+                .unshifted,
+            srs.commit_non_hiding(&evals.interpolate_by_ref(), None)
+                .unshifted
+        );
+    }
+
     #[test]
     fn test_opening_proof() {
         // create two polynomials
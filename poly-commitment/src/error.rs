@@ -1,3 +1,4 @@
+use crate::srs::GroupMapKind;
 use thiserror::Error;
 
 #[derive(Error, Debug, Clone, Copy)]
@@ -7,3 +8,22 @@ pub enum CommitmentError {
     )]
     BlindersDontMatch(usize, usize),
 }
+
+#[derive(Error, Debug, Clone)]
+pub enum SrsError {
+    #[error("the SRS could not be serialized or deserialized: {0}")]
+    Serialization(String),
+    #[error("the point {0} of the SRS is not on the curve")]
+    NotOnCurve(String),
+    #[error("the point {0} of the SRS is not in the prime-order subgroup")]
+    NotInSubgroup(String),
+    #[error("the points of the SRS are not all in the prime-order subgroup")]
+    BatchNotInSubgroup,
+    #[error("the group map {0} of the SRS is not supported")]
+    UnsupportedGroupMap(u8),
+    #[error("the SRS was derived with the group map {found:?}, but the curve uses {expected:?}")]
+    GroupMapMismatch {
+        expected: GroupMapKind,
+        found: GroupMapKind,
+    },
+}
@@ -69,10 +69,16 @@ impl<G: CommitmentCurve> SRS<G> {
     /// This function opens polynomial commitments in batch
     ///     plnms: batch of polynomials to open commitments for with, optionally, max degrees
     ///     elm: evaluation point vector to open the commitments at
+    ///     (the evaluation points can be arbitrary, including elements of the domain)
     ///     polyscale: polynomial scaling factor for opening commitments in batch
     ///     evalscale: eval scaling factor for opening commitments in batch
     ///     oracle_params: parameters for the random oracle argument
     ///     RETURN: commitment opening proof
+    ///
+    /// Degenerate claims are handled consistently with [SRS::verify]:
+    /// the zero polynomial can be opened like any other polynomial,
+    /// and an empty list of polynomials or of evaluation points is a vacuous claim
+    /// for which a valid proof is still produced.
     #[allow(clippy::too_many_arguments)]
     #[allow(clippy::type_complexity)]
     #[allow(clippy::many_single_char_names)]
@@ -121,8 +127,9 @@ impl<G: CommitmentCurve> SRS<G> {
                     assert!(omegas.shifted.is_none());
                 }
                 for j in 0..omegas.unshifted.len() {
-                    let segment =
-                        &p_i.coeffs[offset..std::cmp::min(offset + self.g.len(), p_i.coeffs.len())];
+                    // the polynomial may have fewer segments than its commitment has chunks
+                    let segment = &p_i.coeffs[std::cmp::min(offset, p_i.coeffs.len())
+                        ..std::cmp::min(offset + self.g.len(), p_i.coeffs.len())];
                     // always mixing in the unshifted segments
                     plnm.add_unshifted(scale, segment);
 
@@ -131,12 +138,23 @@ impl<G: CommitmentCurve> SRS<G> {
                     offset += self.g.len();
                     if let Some(m) = degree_bound {
                         if offset >= *m {
-                            if offset > *m {
-                                // mixing in the shifted segment since degree is bounded
-                                plnm.add_shifted(scale, self.g.len() - m % self.g.len(), segment);
+                            let shifted_blinder = omegas.shifted.unwrap();
+                            // a zero shifted segment with a zero blinder is committed to as the point at infinity,
+                            // which the verifier ignores, so it must not consume a power of `polyscale` either
+                            let shifted_is_zero = shifted_blinder.is_zero()
+                                && (offset == *m || segment.iter().all(|c| c.is_zero()));
+                            if !shifted_is_zero {
+                                if offset > *m {
+                                    // mixing in the shifted segment since degree is bounded
+                                    plnm.add_shifted(
+                                        scale,
+                                        self.g.len() - m % self.g.len(),
+                                        segment,
+                                    );
+                                }
+                                omega += &(shifted_blinder * scale);
+                                scale *= &polyscale;
                             }
-                            omega += &(omegas.shifted.unwrap() * scale);
-                            scale *= &polyscale;
                         }
                     }
                 }
@@ -1,15 +1,22 @@
 //! This module implements the Marlin structured reference string primitive
 
-use crate::commitment::CommitmentCurve;
-use ark_ec::{AffineCurve, ProjectiveCurve};
-use ark_ff::{BigInteger, PrimeField};
+use crate::{
+    commitment::{CommitmentCurve, PolyComm},
+    error::SrsError,
+};
+use ark_ec::{msm::VariableBaseMSM, AffineCurve, ProjectiveCurve, SWModelParameters};
+use ark_ff::{BigInteger, Field, One, PrimeField, UniformRand, Zero};
 use ark_poly::{EvaluationDomain, Radix2EvaluationDomain as D};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use blake2::{Blake2b512, Digest};
 use groupmap::GroupMap;
+use rand_core::{CryptoRng, RngCore};
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use std::array;
 use std::collections::HashMap;
+use std::io::{Read, Write};
 
 #[serde_as]
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -21,10 +28,15 @@ pub struct SRS<G: CommitmentCurve> {
     #[serde_as(as = "o1_utils::serialization::SerdeAs")]
     pub h: G,
 
+    /// The map from the base field to the curve used to derive the points
+    #[serde(default)]
+    pub group_map: GroupMapKind,
+
     // TODO: the following field should be separated, as they are optimization values
-    /// Commitments to Lagrange bases, per domain size
+    /// Commitments to Lagrange bases, per domain size.
+    /// When the domain is larger than the SRS, each commitment is split into chunks.
     #[serde(skip)]
-    pub lagrange_bases: HashMap<usize, Vec<G>>,
+    pub lagrange_bases: HashMap<usize, Vec<PolyComm<G>>>,
     /// Coefficient for the curve endomorphism
     #[serde(skip)]
     pub endo_r: G::ScalarField,
@@ -52,6 +64,72 @@ where
     (endo_q, endo_r)
 }
 
+/// The maps from the base field to the curve that can be used to derive the points of an SRS,
+/// see [CommitmentCurve::Map].
+/// The map is recorded in the SRS, so that SRS artifacts remain unambiguous as maps are added.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum GroupMapKind {
+    /// The map of Shallue and van de Woestijne, implemented by [groupmap::BWParameters]
+    #[default]
+    SvdW,
+}
+
+impl GroupMapKind {
+    /// The byte that encodes the map in the files written by [SRS::save]
+    pub fn to_byte(self) -> u8 {
+        match self {
+            GroupMapKind::SvdW => 0,
+        }
+    }
+
+    /// Decodes a byte written by [GroupMapKind::to_byte], if it encodes a supported map
+    pub fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(GroupMapKind::SvdW),
+            _ => None,
+        }
+    }
+}
+
+/// How thoroughly the points of an SRS are checked when it is loaded with [SRS::load].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SrsValidation {
+    /// The points are trusted as they are, this should only be used for SRS files
+    /// coming from a trusted source (for example, files written by this process).
+    None,
+    /// Every point is checked to be on the curve, and all the points are checked
+    /// to be in the prime-order subgroup at once, with a random linear combination.
+    /// A point outside of the subgroup goes undetected with probability at most `1/q`,
+    /// where `q` is the smallest prime factor of the cofactor of the curve.
+    Batch,
+    /// Every point is checked to be on the curve and in the prime-order subgroup.
+    Strict,
+}
+
+/// Checks that a point satisfies the short Weierstrass equation of its curve.
+/// The point at infinity is rejected, as it is never part of a valid SRS.
+fn is_on_curve<G: CommitmentCurve>(p: &G) -> bool
+where
+    G::Params: SWModelParameters<BaseField = G::BaseField>,
+{
+    match p.to_coordinates() {
+        None => false,
+        Some((x, y)) => {
+            let a = <G::Params as SWModelParameters>::COEFF_A;
+            let b = <G::Params as SWModelParameters>::COEFF_B;
+            y.square() == (x.square() + a) * x + b
+        }
+    }
+}
+
+/// Checks that a point on the curve is in the prime-order subgroup, that is `r * p = 0`.
+/// As scalars are reduced modulo `r`, this is computed as `(r - 1) * p + p`.
+fn is_in_prime_subgroup<G: AffineCurve>(p: &G) -> bool {
+    let mut rp = p.mul(-G::ScalarField::one());
+    rp.add_assign_mixed(p);
+    rp.is_zero()
+}
+
 fn point_of_random_bytes<G: CommitmentCurve>(map: &G::Map, random_bytes: &[u8]) -> G
 where
     G::BaseField: PrimeField,
@@ -80,16 +158,11 @@ where
     }
 
     /// Compute commitments to the lagrange basis corresponding to the given domain and
-    /// cache them in the SRS
+    /// cache them in the SRS.
+    /// If the domain is larger than the SRS, the commitments are split into
+    /// `domain.size() / self.g.len()` (rounded up) chunks.
     pub fn add_lagrange_basis(&mut self, domain: D<G::ScalarField>) {
         let n = domain.size();
-        if n > self.g.len() {
-            panic!(
-                "add_lagrange_basis: Domain size {} larger than SRS size {}",
-                n,
-                self.g.len()
-            );
-        }
 
         if self.lagrange_bases.contains_key(&n) {
             return;
@@ -150,13 +223,35 @@ where
         // because the commitment to the polynomial x^i is just self.g[i], we can obtain
         // commitments to the normalized Lagrange polynomials by applying IFFT to the
         // vector self.g[0..n].
-        let mut lg: Vec<<G as AffineCurve>::Projective> =
-            self.g[0..n].iter().map(|g| g.into_projective()).collect();
-        domain.ifft_in_place(&mut lg);
+        //
+        // When the domain is larger than the SRS, the Lagrange polynomials have more
+        // coefficients than there are elements in self.g, so their commitments are chunked:
+        // the k-th chunk commits to the coefficients of x^{k m}, ..., x^{(k + 1) m - 1}
+        // with self.g[0..m], where m is the size of the SRS.
+        // Applying IFFT to the vector that holds self.g[0..m] at the positions of these monomials
+        // (and zero elsewhere) thus yields the k-th chunks of the commitments.
+        let m = self.g.len();
+        let num_chunks = (n + m - 1) / m;
+        let chunks: Vec<Vec<G>> = (0..num_chunks)
+            .map(|k| {
+                let mut lg = vec![<G as AffineCurve>::Projective::zero(); n];
+                for (i, g) in self.g.iter().take(n - k * m).enumerate() {
+                    lg[k * m + i] = g.into_projective();
+                }
+                domain.ifft_in_place(&mut lg);
+
+                <G as AffineCurve>::Projective::batch_normalization(lg.as_mut_slice());
+                lg.iter().map(|g| g.into_affine()).collect()
+            })
+            .collect();
 
-        <G as AffineCurve>::Projective::batch_normalization(lg.as_mut_slice());
-        self.lagrange_bases
-            .insert(n, lg.iter().map(|g| g.into_affine()).collect());
+        let bases = (0..n)
+            .map(|i| PolyComm {
+                unshifted: chunks.iter().map(|chunk| chunk[i]).collect(),
+                shifted: None,
+            })
+            .collect();
+        self.lagrange_bases.insert(n, bases);
     }
 
     /// This function creates SRS instance for circuits with number of rows up to `depth`.
@@ -184,9 +279,145 @@ where
         SRS {
             g,
             h,
+            group_map: G::GROUP_MAP,
+            lagrange_bases: HashMap::new(),
+            endo_r,
+            endo_q,
+        }
+    }
+
+    /// Writes the points of the SRS (`g` and `h`) to `writer`, uncompressed,
+    /// followed by the byte of its [GroupMapKind].
+    /// The cached Lagrange bases are not written.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the SRS cannot be written to `writer`.
+    pub fn save<W: Write>(&self, mut writer: W) -> Result<(), SrsError> {
+        self.g
+            .serialize_unchecked(&mut writer)
+            .and_then(|_| self.h.serialize_unchecked(&mut writer))
+            .map_err(|e| SrsError::Serialization(e.to_string()))?;
+        writer
+            .write_all(&[self.group_map.to_byte()])
+            .map_err(|e| SrsError::Serialization(e.to_string()))
+    }
+
+    /// Reads an SRS written with [SRS::save], and checks its points according to `validation`.
+    /// The group map of the SRS must be the one of the curve, whatever the validation.
+    ///
+    /// Decoding the points does not check them, so that loading a trusted SRS is cheap.
+    /// SRS files from an untrusted source should be loaded with [SrsValidation::Batch]
+    /// (or [SrsValidation::Strict]), see [SRS::validate].
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the SRS cannot be read from `reader`, or if it fails the validation.
+    pub fn load<R: Read>(
+        mut reader: R,
+        validation: SrsValidation,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> Result<Self, SrsError>
+    where
+        G::Params: SWModelParameters<BaseField = G::BaseField>,
+    {
+        let g = Vec::<G>::deserialize_unchecked(&mut reader)
+            .map_err(|e| SrsError::Serialization(e.to_string()))?;
+        let h = G::deserialize_unchecked(&mut reader)
+            .map_err(|e| SrsError::Serialization(e.to_string()))?;
+        let mut group_map = [0u8];
+        reader
+            .read_exact(&mut group_map)
+            .map_err(|e| SrsError::Serialization(e.to_string()))?;
+        let group_map = GroupMapKind::from_byte(group_map[0])
+            .ok_or(SrsError::UnsupportedGroupMap(group_map[0]))?;
+
+        let (endo_q, endo_r) = endos::<G>();
+        let srs = SRS {
+            g,
+            h,
+            group_map,
             lagrange_bases: HashMap::new(),
             endo_r,
             endo_q,
+        };
+
+        srs.check_group_map()?;
+        srs.validate(validation, rng)?;
+        Ok(srs)
+    }
+
+    /// Checks that the points of the SRS were derived with the group map of the curve,
+    /// which is also the one used to create and verify opening proofs.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the group map of the SRS is not [CommitmentCurve::GROUP_MAP].
+    pub fn check_group_map(&self) -> Result<(), SrsError> {
+        if self.group_map == G::GROUP_MAP {
+            Ok(())
+        } else {
+            Err(SrsError::GroupMapMismatch {
+                expected: G::GROUP_MAP,
+                found: self.group_map,
+            })
+        }
+    }
+
+    /// Checks that the points of the SRS are on the curve and in the prime-order subgroup.
+    ///
+    /// With [SrsValidation::Batch], a single scalar multiplication by the group order is
+    /// performed on a random linear combination of the points, instead of one per point.
+    /// The randomness must not be predictable by whoever produced the SRS.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if a point is not on the curve or not in the prime-order subgroup.
+    pub fn validate(
+        &self,
+        validation: SrsValidation,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> Result<(), SrsError>
+    where
+        G::Params: SWModelParameters<BaseField = G::BaseField>,
+    {
+        if validation == SrsValidation::None {
+            return Ok(());
+        }
+
+        let name = |i: usize| {
+            if i < self.g.len() {
+                format!("g[{i}]")
+            } else {
+                "h".to_string()
+            }
+        };
+        let points: Vec<G> = self.g.iter().chain([&self.h]).copied().collect();
+
+        if let Some(i) = points.par_iter().position_any(|p| !is_on_curve(p)) {
+            return Err(SrsError::NotOnCurve(name(i)));
+        }
+
+        match validation {
+            SrsValidation::None => Ok(()),
+            SrsValidation::Batch => {
+                let scalars: Vec<_> = points
+                    .iter()
+                    .map(|_| G::ScalarField::rand(rng).into_repr())
+                    .collect();
+                let combined = VariableBaseMSM::multi_scalar_mul(&points, &scalars).into_affine();
+                if is_in_prime_subgroup(&combined) {
+                    Ok(())
+                } else {
+                    Err(SrsError::BatchNotInSubgroup)
+                }
+            }
+            SrsValidation::Strict => {
+                match points.par_iter().position_any(|p| !is_in_prime_subgroup(p)) {
+                    None => Ok(()),
+                    Some(i) => Err(SrsError::NotInSubgroup(name(i))),
+                }
+            }
         }
     }
 }
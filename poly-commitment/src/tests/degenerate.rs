@@ -0,0 +1,216 @@
+//! This module tests degenerate openings:
+//! the zero polynomial, evaluation points that are elements of the domain,
+//! and empty lists of polynomials or evaluation points.
+
+use crate::{
+    commitment::{BatchEvaluationProof, BlindedCommitment, CommitmentCurve, Evaluation},
+    srs::SRS,
+};
+use ark_ff::{One, UniformRand, Zero};
+use ark_poly::{
+    univariate::DensePolynomial, EvaluationDomain, Radix2EvaluationDomain as D, UVPolynomial,
+};
+use groupmap::GroupMap;
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use o1_utils::ExtendedDensePolynomial as _;
+use oracle::constants::PlonkSpongeConstantsKimchi as SC;
+use oracle::sponge::DefaultFqSponge;
+use oracle::FqSponge as _;
+use rand::{rngs::StdRng, SeedableRng};
+
+type Sponge = DefaultFqSponge<VestaParameters, SC>;
+
+const SRS_SIZE: usize = 1 << 7;
+
+/// A polynomial to open, with its optional degree bound and whether its commitment is hiding
+struct Claim {
+    poly: DensePolynomial<Fp>,
+    bound: Option<usize>,
+    hiding: bool,
+}
+
+impl Claim {
+    fn new(poly: DensePolynomial<Fp>, bound: Option<usize>, hiding: bool) -> Self {
+        Claim {
+            poly,
+            bound,
+            hiding,
+        }
+    }
+}
+
+/// Commits to the claims, opens them at the evaluation points,
+/// lets `tamper` modify the evaluations, and verifies the resulting proof.
+fn open_and_verify(
+    claims: &[Claim],
+    eval_points: &[Fp],
+    tamper: impl FnOnce(&mut Vec<Evaluation<Vesta>>),
+) -> bool {
+    let rng = &mut StdRng::from_seed([0u8; 32]);
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+    let sponge = Sponge::new(oracle::pasta::fq_kimchi::static_params());
+    let srs = SRS::<Vesta>::create(SRS_SIZE);
+
+    let commitments: Vec<BlindedCommitment<Vesta>> = claims
+        .iter()
+        .map(|claim| {
+            if claim.hiding {
+                srs.commit(&claim.poly, claim.bound, rng)
+            } else {
+                let commitment = srs.commit_non_hiding(&claim.poly, claim.bound);
+                let blinders = commitment.map(|_| Fp::zero());
+                BlindedCommitment {
+                    commitment,
+                    blinders,
+                }
+            }
+        })
+        .collect();
+
+    let polynomials: Vec<_> = claims
+        .iter()
+        .zip(&commitments)
+        .map(|(claim, comm)| (&claim.poly, claim.bound, comm.blinders.clone()))
+        .collect();
+
+    let polyscale = Fp::rand(rng);
+    let evalscale = Fp::rand(rng);
+
+    let proof = srs.open::<Sponge, _>(
+        &group_map,
+        &polynomials,
+        eval_points,
+        polyscale,
+        evalscale,
+        sponge.clone(),
+        rng,
+    );
+
+    let mut evaluations: Vec<_> = claims
+        .iter()
+        .zip(commitments)
+        .map(|(claim, comm)| Evaluation {
+            commitment: comm.commitment,
+            evaluations: eval_points
+                .iter()
+                .map(|pt| {
+                    claim
+                        .poly
+                        .to_chunked_polynomial(SRS_SIZE)
+                        .evaluate_chunks(*pt)
+                })
+                .collect(),
+            degree_bound: claim.bound,
+        })
+        .collect();
+
+    tamper(&mut evaluations);
+
+    let mut batch = vec![BatchEvaluationProof {
+        sponge,
+        evaluations,
+        evaluation_points: eval_points.to_vec(),
+        polyscale,
+        evalscale,
+        opening: &proof,
+    }];
+
+    srs.verify::<Sponge, _>(&group_map, &mut batch, rng)
+}
+
+fn random_poly(degree: usize) -> DensePolynomial<Fp> {
+    let rng = &mut StdRng::from_seed([1u8; 32]);
+    DensePolynomial::rand(degree, rng)
+}
+
+fn random_points(n: usize) -> Vec<Fp> {
+    let rng = &mut StdRng::from_seed([2u8; 32]);
+    (0..n).map(|_| Fp::rand(rng)).collect()
+}
+
+#[test]
+fn test_open_zero_polynomial() {
+    for hiding in [true, false] {
+        let claims = [
+            Claim::new(DensePolynomial::zero(), None, hiding),
+            Claim::new(random_poly(200), None, hiding),
+        ];
+        assert!(open_and_verify(&claims, &random_points(2), |_| ()));
+    }
+}
+
+#[test]
+fn test_open_zero_polynomial_with_degree_bound() {
+    // the shifted commitment of a non-hiding zero polynomial is the point at infinity,
+    // the polynomial following it must be scaled identically by the prover and the verifier
+    for hiding in [true, false] {
+        for bound in [5, SRS_SIZE] {
+            let claims = [
+                Claim::new(DensePolynomial::zero(), Some(bound), hiding),
+                Claim::new(random_poly(100), None, hiding),
+            ];
+            assert!(open_and_verify(&claims, &random_points(2), |_| ()));
+        }
+    }
+}
+
+#[test]
+fn test_open_zero_polynomial_wrong_evaluation() {
+    let claims = [Claim::new(DensePolynomial::zero(), None, false)];
+    assert!(!open_and_verify(&claims, &random_points(2), |evals| {
+        evals[0].evaluations[1][0] = Fp::one();
+    }));
+}
+
+#[test]
+fn test_open_at_domain_elements() {
+    let domain = D::<Fp>::new(SRS_SIZE).unwrap();
+    let eval_points = vec![
+        Fp::zero(),
+        Fp::one(),
+        domain.group_gen,
+        domain.element(SRS_SIZE - 1),
+    ];
+    let claims = [
+        Claim::new(random_poly(SRS_SIZE - 1), None, true),
+        Claim::new(random_poly(300), None, true),
+    ];
+    assert!(open_and_verify(&claims, &eval_points, |_| ()));
+}
+
+#[test]
+fn test_open_empty_claims() {
+    // no polynomial
+    assert!(open_and_verify(&[], &random_points(2), |_| ()));
+
+    // no evaluation point
+    let claims = [Claim::new(random_poly(200), None, true)];
+    assert!(open_and_verify(&claims, &[], |_| ()));
+
+    // neither
+    assert!(open_and_verify(&[], &[], |_| ()));
+}
+
+#[test]
+fn test_reject_malformed_evaluation_table() {
+    let claims = [Claim::new(random_poly(200), None, true)];
+    assert!(!open_and_verify(&claims, &random_points(2), |evals| {
+        evals[0].evaluations.pop();
+    }));
+}
+
+#[test]
+fn test_commit_zero_polynomial() {
+    let srs = SRS::<Vesta>::create(SRS_SIZE);
+    let zero = DensePolynomial::<Fp>::zero();
+
+    // the zero polynomial is committed to as a single chunk at infinity
+    let comm = srs.commit_non_hiding(&zero, None);
+    assert_eq!(comm.unshifted, vec![Vesta::zero()]);
+    assert!(comm.shifted.is_none());
+
+    // with a degree bound, the shifted commitment is at infinity as well
+    let comm = srs.commit_non_hiding(&zero, Some(5));
+    assert_eq!(comm.unshifted, vec![Vesta::zero()]);
+    assert_eq!(comm.shifted, Some(Vesta::zero()));
+}
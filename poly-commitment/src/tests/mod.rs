@@ -1,2 +1,4 @@
 mod batch_15_wires;
 mod commitment;
+mod degenerate;
+mod srs;
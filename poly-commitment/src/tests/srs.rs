@@ -0,0 +1,95 @@
+//! This module tests saving and loading an SRS, and the validation of its points.
+
+use crate::{
+    error::SrsError,
+    srs::{GroupMapKind, SrsValidation, SRS},
+};
+use mina_curves::pasta::Vesta;
+use rand::{rngs::StdRng, SeedableRng};
+
+const SRS_SIZE: usize = 1 << 5;
+
+/// The size of an uncompressed Vesta point, with its infinity flag
+const POINT_SIZE: usize = 65;
+
+/// The size of the length prefix of the vector `g`
+const LEN_SIZE: usize = 8;
+
+fn saved_srs() -> (SRS<Vesta>, Vec<u8>) {
+    let srs = SRS::<Vesta>::create(SRS_SIZE);
+    let mut bytes = vec![];
+    srs.save(&mut bytes).unwrap();
+    (srs, bytes)
+}
+
+#[test]
+fn test_srs_save_load() {
+    let rng = &mut StdRng::from_seed([0u8; 32]);
+    let (srs, bytes) = saved_srs();
+
+    for validation in [
+        SrsValidation::None,
+        SrsValidation::Batch,
+        SrsValidation::Strict,
+    ] {
+        let loaded = SRS::<Vesta>::load(bytes.as_slice(), validation, rng).unwrap();
+        assert_eq!(loaded.g, srs.g);
+        assert_eq!(loaded.h, srs.h);
+        assert_eq!(loaded.endo_q, srs.endo_q);
+        assert_eq!(loaded.endo_r, srs.endo_r);
+        assert_eq!(loaded.group_map, GroupMapKind::SvdW);
+    }
+}
+
+#[test]
+fn test_srs_load_group_map() {
+    let rng = &mut StdRng::from_seed([0u8; 32]);
+    let (_, mut bytes) = saved_srs();
+
+    // the group map is the last byte
+    assert_eq!(bytes.len(), LEN_SIZE + (SRS_SIZE + 1) * POINT_SIZE + 1);
+    *bytes.last_mut().unwrap() = 7;
+    assert!(matches!(
+        SRS::<Vesta>::load(bytes.as_slice(), SrsValidation::None, rng),
+        Err(SrsError::UnsupportedGroupMap(7))
+    ));
+}
+
+#[test]
+fn test_srs_load_truncated() {
+    let rng = &mut StdRng::from_seed([0u8; 32]);
+    let (_, bytes) = saved_srs();
+
+    let truncated = &bytes[..bytes.len() - 1];
+    assert!(matches!(
+        SRS::<Vesta>::load(truncated, SrsValidation::None, rng),
+        Err(SrsError::Serialization(_))
+    ));
+}
+
+#[test]
+fn test_srs_load_point_not_on_curve() {
+    let rng = &mut StdRng::from_seed([0u8; 32]);
+    let (_, mut bytes) = saved_srs();
+
+    // flip the lowest bit of the x-coordinate of g[1]
+    bytes[LEN_SIZE + POINT_SIZE] ^= 1;
+
+    // without validation, the corrupted point goes unnoticed
+    assert!(SRS::<Vesta>::load(bytes.as_slice(), SrsValidation::None, rng).is_ok());
+
+    for validation in [SrsValidation::Batch, SrsValidation::Strict] {
+        match SRS::<Vesta>::load(bytes.as_slice(), validation, rng) {
+            Err(SrsError::NotOnCurve(point)) => assert_eq!(point, "g[1]"),
+            res => panic!("unexpected result: {:?}", res.map(|_| ())),
+        }
+    }
+
+    // same for h, the last point
+    let (_, mut bytes) = saved_srs();
+    bytes[LEN_SIZE + SRS_SIZE * POINT_SIZE] ^= 1;
+    match SRS::<Vesta>::load(bytes.as_slice(), SrsValidation::Batch, rng) {
+        Err(SrsError::NotOnCurve(point)) => assert_eq!(point, "h"),
+        res => panic!("unexpected result: {:?}", res.map(|_| ())),
+    }
+}
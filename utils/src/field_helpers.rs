@@ -15,6 +15,16 @@ pub enum FieldHelpersError {
     DecodeHex,
     #[error("failed to convert BigUint into field element")]
     FromBigToField,
+    #[error("the integer is not smaller than the modulus of the field")]
+    NotReduced,
+    #[error("the field element does not fit in {0} bytes")]
+    TooManyBytes(usize),
+    #[error("the bits {start}..{end} are not within the {size} bits of the field element")]
+    BitsOutOfRange {
+        start: usize,
+        end: usize,
+        size: usize,
+    },
 }
 
 /// Result alias using [FieldHelpersError]
@@ -41,6 +51,68 @@ pub trait FieldHelpers<F> {
     /// Serialize to bits
     fn to_bits(&self) -> Vec<bool>;
 
+    /// Serialize to exactly `len` bytes in little-endian order,
+    /// failing if the element does not fit rather than truncating it
+    fn to_le_bytes_checked(&self, len: usize) -> Result<Vec<u8>> {
+        let mut bytes = self.to_bytes();
+        if bytes.len() > len {
+            if bytes[len..].iter().any(|byte| *byte != 0) {
+                return Err(FieldHelpersError::TooManyBytes(len));
+            }
+            bytes.truncate(len);
+        }
+        bytes.resize(len, 0);
+        Ok(bytes)
+    }
+
+    /// Serialize to exactly `len` bytes in big-endian order,
+    /// failing if the element does not fit rather than truncating it
+    fn to_be_bytes_checked(&self, len: usize) -> Result<Vec<u8>> {
+        let mut bytes = self.to_le_bytes_checked(len)?;
+        bytes.reverse();
+        Ok(bytes)
+    }
+
+    /// Serialize the bits `start..end` (in little-endian order),
+    /// failing if they are not within the bits of the element
+    fn to_bits_range(&self, start: usize, end: usize) -> Result<Vec<bool>> {
+        let bits = self.to_bits();
+        if start > end || end > bits.len() {
+            return Err(FieldHelpersError::BitsOutOfRange {
+                start,
+                end,
+                size: bits.len(),
+            });
+        }
+        Ok(bits[start..end].to_vec())
+    }
+
+    /// Deserialize from little-endian bytes of any length,
+    /// failing if they encode an integer not smaller than the modulus rather than reducing it
+    fn from_le_bytes_checked(bytes: &[u8]) -> Result<F>
+    where
+        F: Field,
+    {
+        let mut padded = F::zero().to_bytes();
+        let (bytes, excess) = bytes.split_at(std::cmp::min(bytes.len(), padded.len()));
+        if excess.iter().any(|byte| *byte != 0) {
+            return Err(FieldHelpersError::NotReduced);
+        }
+        padded[..bytes.len()].copy_from_slice(bytes);
+        F::from_bytes(&padded).map_err(|_| FieldHelpersError::NotReduced)
+    }
+
+    /// Deserialize from big-endian bytes of any length,
+    /// failing if they encode an integer not smaller than the modulus rather than reducing it
+    fn from_be_bytes_checked(bytes: &[u8]) -> Result<F>
+    where
+        F: Field,
+    {
+        let mut bytes = bytes.to_vec();
+        bytes.reverse();
+        Self::from_le_bytes_checked(&bytes)
+    }
+
     /// Field size in bytes
     fn size_in_bytes() -> usize
     where
@@ -131,7 +203,7 @@ mod tests {
     use super::*;
 
     use ark_ec::AffineCurve;
-    use ark_ff::One;
+    use ark_ff::{One, Zero};
     use mina_curves::pasta::Pallas as CurvePoint;
 
     /// Base field element type
@@ -249,6 +321,57 @@ mod tests {
         );
     }
 
+    #[test]
+    fn field_bytes_checked() {
+        let fe = BaseField::from(0x0102_0304u32);
+        assert_eq!(fe.to_le_bytes_checked(4), Ok(vec![4, 3, 2, 1]));
+        assert_eq!(fe.to_be_bytes_checked(5), Ok(vec![0, 1, 2, 3, 4]));
+        assert_eq!(
+            fe.to_le_bytes_checked(3),
+            Err(FieldHelpersError::TooManyBytes(3))
+        );
+        assert_eq!(fe.to_le_bytes_checked(40).unwrap().len(), 40);
+
+        assert_eq!(BaseField::from_le_bytes_checked(&[4, 3, 2, 1]), Ok(fe));
+        assert_eq!(BaseField::from_be_bytes_checked(&[0, 1, 2, 3, 4]), Ok(fe));
+        assert_eq!(BaseField::from_le_bytes_checked(&[]), Ok(BaseField::zero()));
+
+        // the modulus and larger integers are not reduced
+        let modulus = BaseField::modulus_biguint();
+        assert_eq!(
+            BaseField::from_le_bytes_checked(&modulus.to_bytes_le()),
+            Err(FieldHelpersError::NotReduced)
+        );
+        assert_eq!(
+            BaseField::from_be_bytes_checked(&[0xff; 33]),
+            Err(FieldHelpersError::NotReduced)
+        );
+        let max = modulus - 1u32;
+        assert_eq!(
+            BaseField::from_be_bytes_checked(&max.to_bytes_be()),
+            Ok(-BaseField::one())
+        );
+    }
+
+    #[test]
+    fn field_bits_range() {
+        let fe = BaseField::from(0b1101u32);
+        assert_eq!(fe.to_bits_range(0, 4), Ok(vec![true, false, true, true]));
+        assert_eq!(fe.to_bits_range(2, 2), Ok(vec![]));
+
+        let size = fe.to_bits().len();
+        assert!(fe.to_bits_range(0, size).is_ok());
+        assert_eq!(
+            fe.to_bits_range(1, size + 1),
+            Err(FieldHelpersError::BitsOutOfRange {
+                start: 1,
+                end: size + 1,
+                size
+            })
+        );
+        assert!(fe.to_bits_range(3, 2).is_err());
+    }
+
     #[test]
     fn field_big() {
         let fe_1024 = BaseField::from(1024u32);
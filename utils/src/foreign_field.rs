@@ -114,8 +114,10 @@ impl<F: Field, const N: usize> ForeignElement<F, N> {
         let mut bytes = vec![];
         // limbs are stored in little endian
         for limb in self.limbs {
-            let crumb = &limb.to_bytes()[0..LIMB_BITS / 8];
-            bytes.extend_from_slice(crumb);
+            let crumb = limb
+                .to_le_bytes_checked(LIMB_BITS / 8)
+                .expect("limb larger than LIMB_BITS");
+            bytes.extend_from_slice(&crumb);
         }
         BigUint::from_bytes_le(&bytes)
     }
@@ -127,7 +129,7 @@ impl<F: Field, const N: usize> ForeignElement<F, N> {
         let chunks: Vec<&[u8]> = bytes.chunks(LIMB_BITS / 8).collect();
         chunks
             .iter()
-            .map(|chunk| F::from_random_bytes(chunk).expect("failed to deserialize"))
+            .map(|chunk| F::from_le_bytes_checked(chunk).expect("failed to deserialize"))
             .collect()
     }
 }